@@ -9,10 +9,11 @@ use ropey::{self, Rope};
 use std::{
     borrow::Borrow,
     cell::{self, RefCell},
-    cmp, fmt, io,
+    cmp, fmt, fs, io,
     iter::FromIterator,
     mem,
     ops::RangeBounds,
+    path,
     rc::{self, Rc},
     result,
     sync::Mutex,
@@ -20,21 +21,37 @@ use std::{
 };
 
 use crate::{
-    event::{Event, Mod, Mto, DP},
+    event::{Cud, Event, Mod, Mto, Opr, TextObject, DP},
     location::Location,
-    mark,
+    mark, register,
+    search::{Search, SearchOpts},
     term::{Span, Spanline},
     text,
     window::WinBuffer,
     {err_at, Error, Result},
 };
 
+/// Leading bytes of a persistent undo file, with its format version.
+const UNDO_MAGIC: &[u8] = b"TEDUNDO1";
+
 /// Maximum number of lines supported by this buffer implementation.
 pub const MAX_LINES: usize = 1_000_000_000;
 
 /// Number of spaces to use for each step of indent.
 pub const SHIFT_WIDTH: usize = 4;
 
+/// Number of columns that a tab character counts for, in indentation.
+pub const TAB_STOP: usize = 8;
+
+/// Maximum width of a line, in columns, when formatting text.
+pub const TEXT_WIDTH: usize = 80;
+
+/// Comment leaders that are repeated on each line when formatting text.
+pub const COMMENT_LEADERS: [&str; 6] = ["///", "//!", "//", "#", "--", ";"];
+
+/// Abbreviations that do not end a sentence, for sentence motions.
+pub const ABBREVIATIONS: [&str; 8] = ["Mr.", "Mrs.", "Ms.", "Dr.", "St.", "e.g.", "i.e.", "vs."];
+
 lazy_static! {
     static ref BUFFER_NUM: Mutex<usize> = Mutex::new(0);
 }
@@ -86,24 +103,71 @@ impl Eq for Cursor {}
 
 impl PartialOrd for Cursor {
     fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
-        if self.row == other.row {
-            self.col.partial_cmp(&other.col)
-        } else {
-            self.row.partial_cmp(&other.row)
-        }
+        Some(self.cmp(other))
     }
 }
 
 impl Ord for Cursor {
     fn cmp(&self, other: &Self) -> cmp::Ordering {
         if self.row == other.row {
-            self.row.cmp(&other.row)
-        } else {
             self.col.cmp(&other.col)
+        } else {
+            self.row.cmp(&other.row)
         }
     }
 }
 
+/// Count of words, lines, sentences and paragraphs in buffer, each as
+/// `(nth, total)`, where `nth` counts upto the one under the cursor.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct TextCount {
+    pub words: (usize, usize),
+    pub lines: (usize, usize),
+    pub sentences: (usize, usize),
+    pub paras: (usize, usize),
+}
+
+impl fmt::Display for TextCount {
+    fn fmt(&self, f: &mut fmt::Formatter) -> result::Result<(), fmt::Error> {
+        write!(
+            f,
+            "Line {} of {}; Word {} of {}; Sentence {} of {}; Para {} of {}",
+            self.lines.0,
+            self.lines.1,
+            self.words.0,
+            self.words.1,
+            self.sentences.0,
+            self.sentences.1,
+            self.paras.0,
+            self.paras.1
+        )
+    }
+}
+
+/// Summary of a change in buffer's undo history, refer [Buffer::history].
+#[derive(Clone, Copy, Default, Debug, Eq, PartialEq)]
+pub struct ChangeInfo {
+    /// Sequence id of change, in pre-order of the change tree, with the
+    /// oldest change numbered as 0.
+    pub seq: usize,
+    /// Sequence id of the change this change was made on.
+    pub parent: Option<usize>,
+    /// Last cursor position for this change.
+    pub cursor: usize,
+    /// Number of characters added and removed, from its parent.
+    pub added: usize,
+    pub removed: usize,
+    /// Whether this is the buffer's current change.
+    pub current: bool,
+}
+
+impl fmt::Display for ChangeInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> result::Result<(), fmt::Error> {
+        let curr = if_else!(self.current, "*", "");
+        write!(f, "{}{} +{} -{}", self.seq, curr, self.added, self.removed)
+    }
+}
+
 /// All bits and pieces of content is managed by buffer.
 ///
 /// Content is to be found in location, refer [Location] for details. Use
@@ -116,19 +180,68 @@ pub struct Buffer {
     pub format: text::Format,
     /// Shift-width, number of spaces to use for each step of indent.
     pub shift_width: usize,
+    /// Use spaces for indentation, instead of tabs.
+    pub expand_tab: bool,
+    /// Number of columns that a tab character counts for.
+    pub tab_stop: usize,
+    /// Along with expand_tab, tab and backspace in insert mode work with
+    /// shift_width columns of spaces, as if they were a single tab.
+    pub soft_tab_stop: bool,
+    /// Copy indentation of the current line when opening a new line.
+    pub auto_indent: bool,
+    /// Along with auto_indent, indent by another shift_width after lines
+    /// ending in `{`.
+    pub brace_indent: bool,
+    /// Move left and right by grapheme clusters, instead of characters.
+    pub grapheme_motion: bool,
+    /// Maximum width of a line, when formatting text.
+    pub text_width: usize,
+    /// Options for pattern search.
+    pub search_opts: SearchOpts,
+    /// Pattern search wraps around the end, or the beginning, of buffer.
+    pub wrapscan: bool,
+    /// Derive folds from the syntax tree, refer [Buffer::set_syntax_folds],
+    /// instead of creating them via `zf`.
+    pub fold_syntax: bool,
+    /// Abbreviations that do not end a sentence, refer [text::is_sentence_end].
+    pub abbreviations: Vec<String>,
+    /// Insert-mode abbreviations, as (trigger-word, replacement). Trigger
+    /// word is expanded when followed by a non-word character.
+    pub iabbrevs: Vec<(String, String)>,
+    /// File type for this buffer, refer [crate::syntax] for supported types.
+    /// Empty string means the file type is yet to be detected.
+    pub file_type: String,
     /// Buffer number, for easy picking. Make sure to set unique numbers
     /// for each buffer.
     pub num: usize, // buffer number
 
     // Buffer states
     inner: Inner,
+    // root of the change tree, keeps the undo history alive.
+    _root: Rc<RefCell<Change>>,
 
     // current tab-completion state
     tab_state: TabState,
     // mark-list [a-z]
     marks: mark::Marks,
+    // jump-list, positions before large motions.
+    jumps: mark::Jumps,
+    // change-list, positions of recent edits, along with the change that
+    // made the latest entry.
+    changes: mark::Changes,
+    changes_of: rc::Weak<RefCell<Change>>,
+    // last visual selection, reselected via `gv`.
+    last_visual: Option<mark::Selection>,
+    // folds over ranges of lines, in the order they were created.
+    folds: Vec<mark::Fold>,
+    // text registers, for delete, change, yank and put commands.
+    registers: register::Registers,
+    // register selected, via `"x`, for the next delete, change, yank or put.
+    register: Option<char>,
     // sticky state for cursor column.
     sticky_col: StickyCol,
+    // last change, replayed by dot command.
+    last_change: LastChange,
     // Last search command applied on this buffer.
     mto_pattern: Mto,
     // Last find character command (within the line) applied on this buffer.
@@ -139,6 +252,7 @@ pub struct Buffer {
 enum Inner {
     Normal(NormalBuffer),
     Insert(InsertBuffer),
+    Visual(VisualBuffer),
     Replace(ReplaceBuffer),
     None,
 }
@@ -161,6 +275,12 @@ impl From<InsertBuffer> for Inner {
     }
 }
 
+impl From<VisualBuffer> for Inner {
+    fn from(vb: VisualBuffer) -> Inner {
+        Inner::Visual(vb)
+    }
+}
+
 impl From<ReplaceBuffer> for Inner {
     fn from(rb: ReplaceBuffer) -> Inner {
         Inner::Replace(rb)
@@ -171,10 +291,30 @@ impl Inner {
     fn do_repeat(buf: &mut Buffer) -> Result<Event> {
         match &mut buf.inner {
             Inner::Normal(_) => err_at!(Fatal, msg: format!("not supported")),
-            Inner::Insert(ib) => {
-                let i_evnts = ib.i_evnts.clone();
-                InsertBuffer::do_repeat(ib.repeat, i_evnts, buf)
+            Inner::Visual(_) => err_at!(Fatal, msg: format!("not supported")),
+            Inner::Insert(InsertBuffer {
+                block: Some((start, row, pad)),
+                ..
+            }) => {
+                let (start, row, pad) = (*start, *row, *pad);
+                insert_block(buf, start, row, pad)
             }
+            Inner::Insert(ib) if ib.repeat > 0 => {
+                // repeat edits typed after the last cursor motion, from
+                // where the last edit ended.
+                let i_evnts: Vec<Event> = ib.i_evnts.clone().collect();
+                let i_evnts = {
+                    let mut iter = i_evnts.split(|e| matches!(e, Event::Mt(_)));
+                    let edits = iter.rfind(|es| !es.is_empty()).unwrap_or(&[]);
+                    Event::from(edits.to_vec())
+                };
+                let (repeat, open, cursor) = (ib.repeat, ib.open, ib.edit_cursor);
+                if let Some(cursor) = cursor {
+                    buf.set_cursor(cursor);
+                }
+                InsertBuffer::do_repeat(repeat, open, i_evnts, buf)
+            }
+            Inner::Insert(_) => Ok(Event::Noop),
             Inner::Replace(_) => todo!(),
             Inner::None => err_at!(Fatal, msg: format!("unreachable"))?,
         }
@@ -184,6 +324,7 @@ impl Inner {
         match self {
             inner @ Inner::Normal(_) => Ok(inner),
             Inner::Insert(ib) => Ok(Inner::Normal(ib.into())),
+            Inner::Visual(vb) => Ok(Inner::Normal(vb.into())),
             Inner::Replace(_) => todo!(),
             Inner::None => err_at!(Fatal, msg: format!("unreachable"))?,
         }
@@ -191,7 +332,7 @@ impl Inner {
 
     fn insert_event(&mut self, evnt: Event) -> Result<()> {
         match self {
-            Inner::Normal(_) => Ok(()),
+            Inner::Normal(_) | Inner::Visual(_) => Ok(()),
             Inner::Insert(ib) => ib.insert_event(evnt),
             Inner::Replace(_) => todo!(),
             Inner::None => err_at!(Fatal, msg: format!("unreachable"))?,
@@ -203,6 +344,7 @@ impl Inner {
         match self {
             Inner::Normal(nb) => nb.cud_newlines(cursor, nl, n),
             Inner::Insert(ib) => ib.cud_newlines(cursor, nl, n),
+            Inner::Visual(vb) => vb.cud_newlines(cursor, nl, n),
             Inner::Replace(rb) => rb.cud_newlines(cursor, nl, n),
             Inner::None => err_at!(Fatal, msg: format!("unreachable")),
         }
@@ -213,6 +355,7 @@ impl Inner {
         match self {
             Inner::Normal(nb) => nb.cud_char(cursor, ch),
             Inner::Insert(ib) => ib.cud_char(cursor, ch),
+            Inner::Visual(vb) => vb.cud_char(cursor, ch),
             Inner::Replace(rb) => rb.cud_char(cursor, ch),
             Inner::None => err_at!(Fatal, msg: format!("unreachable")),
         }
@@ -223,6 +366,7 @@ impl Inner {
         match self {
             Inner::Normal(nb) => nb.cud_str(cursor, txt),
             Inner::Insert(ib) => ib.cud_str(cursor, txt),
+            Inner::Visual(vb) => vb.cud_str(cursor, txt),
             Inner::Replace(rb) => rb.cud_str(cursor, txt),
             Inner::None => err_at!(Fatal, msg: format!("unreachable")),
         }
@@ -236,6 +380,7 @@ impl Inner {
         match self {
             Inner::Normal(nb) => nb.cud_delete(range),
             Inner::Insert(ib) => ib.cud_delete(range),
+            Inner::Visual(vb) => vb.cud_delete(range),
             Inner::Replace(rb) => rb.cud_delete(range),
             Inner::None => err_at!(Fatal, msg: format!("unreachable")),
         }
@@ -251,19 +396,42 @@ impl Buffer {
             let bytes = loc.to_bytes()?;
             err_at!(FailBuffer, Rope::from_reader(bytes.as_slice()))?
         };
+        let root = Change::start(buf);
         let mut num = BUFFER_NUM.lock().unwrap();
         *num = *num + 1;
         let b = Buffer {
             location: loc,
             format: text::Format::default(),
             shift_width: SHIFT_WIDTH,
+            expand_tab: true,
+            tab_stop: TAB_STOP,
+            soft_tab_stop: false,
+            auto_indent: false,
+            brace_indent: false,
+            grapheme_motion: false,
+            text_width: TEXT_WIDTH,
+            search_opts: SearchOpts::default(),
+            wrapscan: true,
+            fold_syntax: false,
+            abbreviations: ABBREVIATIONS.iter().map(|s| s.to_string()).collect(),
+            iabbrevs: Vec::default(),
+            file_type: String::default(),
             num: *num,
 
-            inner: Inner::Normal(NormalBuffer::new(buf)),
+            inner: Inner::Normal(NormalBuffer::new(Rc::clone(&root))),
+            _root: root,
 
             tab_state: TabState::default(),
             marks: mark::new_marks(),
+            jumps: mark::Jumps::default(),
+            changes: mark::Changes::default(),
+            changes_of: rc::Weak::new(),
+            last_visual: None,
+            folds: Vec::default(),
+            registers: register::new_registers(),
+            register: None,
             sticky_col: StickyCol::default(),
+            last_change: LastChange::default(),
             mto_pattern: Mto::default(),
             mto_find_char: Mto::default(),
         };
@@ -271,7 +439,8 @@ impl Buffer {
         Ok(b)
     }
 
-    /// Create an empty buffer backed by transient in-memory location.
+    /// Create an empty buffer backed by transient in-memory location. Its
+    /// id is generated from the buffer number, refer [Buffer::to_id].
     pub fn empty() -> Buffer {
         Self::from_reader(Location::default()).unwrap()
     }
@@ -281,12 +450,42 @@ impl Buffer {
         match &mut self.inner {
             Inner::Normal(val) => val.set_cursor(cursor),
             Inner::Insert(val) => val.set_cursor(cursor),
+            Inner::Visual(val) => val.set_cursor(cursor),
             Inner::Replace(val) => val.set_cursor(cursor),
             Inner::None => (),
         };
         self
     }
 
+    /// Set cursor to the character at `byte` offset, useful for jumping to
+    /// locations reported as byte offset, like in compiler errors. Offsets
+    /// beyond the buffer saturate to its last character. Return the cursor.
+    pub fn goto_offset(&mut self, byte: usize) -> usize {
+        let cursor = {
+            let change = self.to_change();
+            let byte = cmp::min(byte, change.rope.len_bytes());
+            change.rope.byte_to_char(byte)
+        };
+        let cursor = saturate_cursor(self, cursor);
+        self.set_cursor(cursor).clear_sticky_col();
+        cursor
+    }
+
+    /// Set cursor to column `col` of line `line`, both starting from 1,
+    /// useful for jumping to locations reported as line and column, like
+    /// in compiler errors and grep matches. Line and column beyond the
+    /// buffer saturate to its last line and last column. Return the cursor.
+    pub fn goto_line_col(&mut self, line: usize, col: usize) -> usize {
+        let cursor = {
+            let row = cmp::min(line.saturating_sub(1), self.to_last_line_idx());
+            let n = line_chars(self, row).saturating_sub(1);
+            xy_to_cursor(self, (row, cmp::min(col.saturating_sub(1), n)))
+        };
+        let cursor = saturate_cursor(self, cursor);
+        self.set_cursor(cursor).clear_sticky_col();
+        cursor
+    }
+
     /// Configure buffer as read-only.
     pub fn set_read_only(&mut self, read_only: bool) -> &mut Self {
         self.location.set_read_only(read_only);
@@ -305,6 +504,89 @@ impl Buffer {
         self
     }
 
+    /// Configure whether to indent using spaces instead of tabs.
+    pub fn set_expand_tab(&mut self, expand_tab: bool) -> &mut Self {
+        self.expand_tab = expand_tab;
+        self
+    }
+
+    /// Configure number of columns that a tab character counts for.
+    pub fn set_tab_stop(&mut self, tab_stop: usize) -> &mut Self {
+        self.tab_stop = tab_stop;
+        self
+    }
+
+    /// Configure whether tab and backspace, in insert mode, shall work with
+    /// shift_width columns of spaces.
+    pub fn set_soft_tab_stop(&mut self, soft_tab_stop: bool) -> &mut Self {
+        self.soft_tab_stop = soft_tab_stop;
+        self
+    }
+
+    /// Configure whether new lines shall copy the current line's indentation.
+    pub fn set_auto_indent(&mut self, auto_indent: bool) -> &mut Self {
+        self.auto_indent = auto_indent;
+        self
+    }
+
+    /// Configure whether new lines after a `{` shall be indented further.
+    pub fn set_brace_indent(&mut self, brace_indent: bool) -> &mut Self {
+        self.brace_indent = brace_indent;
+        self
+    }
+
+    /// Configure whether left and right motions step by grapheme clusters.
+    pub fn set_grapheme_motion(&mut self, grapheme_motion: bool) -> &mut Self {
+        self.grapheme_motion = grapheme_motion;
+        self
+    }
+
+    /// Configure maximum line width for formatting text.
+    pub fn set_text_width(&mut self, text_width: usize) -> &mut Self {
+        self.text_width = text_width;
+        self
+    }
+
+    /// Configure abbreviations that do not end a sentence.
+    pub fn set_abbreviations(&mut self, abbreviations: Vec<String>) -> &mut Self {
+        self.abbreviations = abbreviations;
+        self
+    }
+
+    /// Configure insert-mode abbreviations, as (trigger-word, replacement).
+    pub fn set_iabbrevs(&mut self, iabbrevs: Vec<(String, String)>) -> &mut Self {
+        self.iabbrevs = iabbrevs;
+        self
+    }
+
+    /// Configure case sensitivity for pattern search.
+    pub fn set_search_opts(&mut self, search_opts: SearchOpts) -> &mut Self {
+        self.search_opts = search_opts;
+        self
+    }
+
+    /// Configure whether pattern search shall wrap around the buffer.
+    pub fn set_wrapscan(&mut self, wrapscan: bool) -> &mut Self {
+        self.wrapscan = wrapscan;
+        self
+    }
+
+    /// Configure whether folds are derived from the syntax tree.
+    pub fn set_fold_syntax(&mut self, fold_syntax: bool) -> &mut Self {
+        // folds derived from syntax tree are of no use for manual folding.
+        if self.fold_syntax && !fold_syntax {
+            self.folds = vec![];
+        }
+        self.fold_syntax = fold_syntax;
+        self
+    }
+
+    /// Configure buffer's file type, used for syntax highlighting.
+    pub fn set_file_type(&mut self, file_type: &str) -> &mut Self {
+        self.file_type = file_type.to_string();
+        self
+    }
+
     /// Clear sticky-column for this buffer. Certian buffer commands can
     /// make the cursor stick to the end-of-the-line or beginning-of-the-line.
     /// Refer [Buffer::set_sticky_col] for details.
@@ -330,8 +612,10 @@ impl Buffer {
 
     /// Switch buffer to `Normal` mode.
     pub fn set_normal_mode(&mut self) {
+        self.set_last_visual();
         self.inner = match mem::replace(&mut self.inner, Inner::default()) {
             Inner::Insert(ib) => Inner::Normal(ib.into()),
+            Inner::Visual(vb) => Inner::Normal(vb.into()),
             Inner::Replace(_rb) => todo!(),
             inner @ Inner::Normal(_) => inner,
             inner @ Inner::None => inner,
@@ -340,11 +624,13 @@ impl Buffer {
 
     /// Switch buffer to `Insert` mode.
     pub fn set_insert_mode(&mut self) {
+        self.set_last_visual();
         self.inner = match mem::replace(&mut self.inner, Inner::default()) {
             Inner::Normal(mut nb) => {
                 let change = mem::replace(&mut nb.change, Default::default());
                 Inner::Insert(InsertBuffer::new(1, change))
             }
+            Inner::Visual(vb) => Inner::Insert(InsertBuffer::new(1, vb.change)),
             Inner::Replace(_rb) => todo!(),
             inner @ Inner::Insert(_) => inner,
             inner @ Inner::None => inner,
@@ -379,16 +665,46 @@ impl WinBuffer for Buffer {
                 _change: change,
                 iter,
                 reverse: false,
+                n: usize::MAX,
             })),
             DP::Left => Ok(Box::new(IterLine {
                 _change: change,
                 iter,
                 reverse: true,
+                n: usize::MAX,
             })),
             _ => err_at!(Fatal, msg: format!("unreachable")),
         }
     }
 
+    fn lines_between<'a>(
+        &'a self,
+        a: usize,
+        z: usize,
+        dp: DP,
+    ) -> Result<Box<dyn Iterator<Item = String> + 'a>> {
+        let change = self.to_change();
+        let z = cmp::min(change.rope.len_lines(), z);
+        let a = cmp::min(a, z);
+        let line_idx = match dp {
+            DP::Right => a,
+            DP::Left => z,
+            _ => err_at!(Fatal, msg: format!("unreachable"))?,
+        };
+        let iter = unsafe {
+            let cref: &Change = change.borrow();
+            let cref = (cref as *const Change).as_ref().unwrap();
+            cref.rope.lines_at(line_idx)
+        };
+
+        Ok(Box::new(IterLine {
+            _change: change,
+            iter,
+            reverse: dp == DP::Left,
+            n: z - a,
+        }))
+    }
+
     fn chars_at<'a>(
         &'a self,
         char_idx: usize,
@@ -472,6 +788,15 @@ impl WinBuffer for Buffer {
         let change = &self.to_change();
         change.rope.line(line_idx).len_chars()
     }
+
+    fn to_closed_fold(&self, line_idx: usize) -> Option<(usize, usize)> {
+        let iter = self.to_folds().into_iter();
+        let iter = iter.filter(|(a, z, closed)| *closed && *a <= line_idx && line_idx <= *z);
+        iter.fold(None, |acc, (a, z, _)| match acc {
+            Some((x, y)) => Some((cmp::min(x, a), cmp::max(y, z))),
+            None => Some((a, z)),
+        })
+    }
 }
 
 impl Buffer {
@@ -493,21 +818,24 @@ impl Buffer {
         match &self.inner {
             Inner::Normal(_) => "normal",
             Inner::Insert(_) => "insert",
+            Inner::Visual(_) => "visual",
             Inner::Replace(_) => "replace",
             Inner::None => "none",
         }
     }
 
-    /// Return buffer id, constructed from its location string.
+    /// Return buffer id, unique across the session. Disk buffers are
+    /// identified by their file path, while memory and ted buffers, that
+    /// can share the same name, are identified by name and buffer number.
     #[inline]
     pub fn to_id(&self) -> String {
         match self.to_location() {
-            Location::Memory { name, .. } => name.clone(),
+            Location::Memory { name, .. } => format!("{}#{}", name, self.num),
             Location::Disk { path_file, .. } => match path_file.to_str() {
                 Some(s) => s.to_string(),
                 None => format!("{:?}", path_file),
             },
-            Location::Ted { name, .. } => name.clone(),
+            Location::Ted { name, .. } => format!("{}#{}", name, self.num),
         }
     }
 
@@ -549,6 +877,7 @@ impl Buffer {
         match &self.inner {
             Inner::Normal(val) => val.to_change(),
             Inner::Insert(val) => val.to_change(),
+            Inner::Visual(val) => val.to_change(),
             Inner::Replace(val) => val.to_change(),
             Inner::None => unreachable!(),
         }
@@ -559,6 +888,7 @@ impl Buffer {
         match &mut self.inner {
             Inner::Normal(val) => val.as_mut_change(),
             Inner::Insert(val) => val.as_mut_change(),
+            Inner::Visual(val) => val.as_mut_change(),
             Inner::Replace(val) => val.as_mut_change(),
             Inner::None => unreachable!(),
         }
@@ -606,374 +936,1012 @@ impl Buffer {
         match &self.inner {
             Inner::Normal(_) => NormalBuffer::on_event(self, evnts),
             Inner::Insert(_) => InsertBuffer::on_event(self, evnts),
+            Inner::Visual(_) => VisualBuffer::on_event(self, evnts),
             Inner::Replace(_) => todo!(),
             Inner::None => err_at!(Fatal, msg: format!("unreachable")),
         }
     }
 }
 
-/// Create-Update-Delete operations on buffer.
+/// Visual selection on buffer.
 impl Buffer {
-    /// Insert `n` newlines, specified by `nl`, at `cursor` position.
-    /// If cursor is `None`, use the current cursor position. Return
-    /// the new cursor postion.
-    #[inline]
-    pub fn cud_newlines(&mut self, cursor: Option<usize>, nl: &str, n: usize) -> Result<usize> {
-        self.inner.cud_newlines(cursor, nl, n)
+    /// Return the selected range, as (from, to) char-index where `to` is
+    /// exclusive, if buffer is in visual mode. For linewise selection, the
+    /// range covers whole lines.
+    pub fn to_selection(&self) -> Option<(usize, usize)> {
+        match &self.inner {
+            Inner::Visual(vb) if vb.linewise => {
+                let cursor = self.to_char_cursor();
+                let from = self.to_line_home(Some(cmp::min(vb.anchor, cursor)));
+                let row = self.char_to_line(cmp::max(vb.anchor, cursor));
+                Some((from, self.line_to_char(row + 1)))
+            }
+            Inner::Visual(_) => self.to_visual_range().map(|(from, to, _)| (from, to)),
+            _ => None,
+        }
     }
 
-    /// Insert character `ch` at `cursor` position. If cursor is None, use the
-    /// current cursor position. Return the new cursor position.
-    #[inline]
-    pub fn cud_char(&mut self, cursor: Option<usize>, ch: char) -> Result<usize> {
-        self.inner.cud_char(cursor, ch)
+    /// Return the last visual selection made on this buffer, as
+    /// (anchor, cursor, linewise). Selection shifts along with subsequent
+    /// edits to the buffer.
+    pub fn to_last_visual(&self) -> Option<(usize, usize, bool)> {
+        self.last_visual
+            .map(|sel| (sel.anchor, sel.cursor, sel.linewise))
     }
 
-    /// Insert string `txt` at `cursor position. If cursor is None, use the
-    /// current cursor position. Return the new cursor position.
-    #[inline]
-    pub fn cud_str(&mut self, cursor: Option<usize>, txt: &str) -> Result<usize> {
-        self.inner.cud_str(cursor, txt)
+    // remember the selection, if buffer is in visual mode, for `gv`.
+    fn set_last_visual(&mut self) {
+        if let Inner::Visual(vb) = &self.inner {
+            self.last_visual = Some(mark::Selection {
+                anchor: vb.anchor,
+                cursor: self.to_char_cursor(),
+                linewise: vb.linewise,
+                block: vb.block,
+            });
+        }
     }
 
-    /// Delete text between the specified range. Range shall be specified
-    /// as character-index.
-    #[inline]
-    pub fn cud_delete<R>(&mut self, range: R) -> Result<()>
-    where
-        R: RangeBounds<usize>,
-    {
-        self.inner.cud_delete(range)
+    /// Return the selected block, as (from-row, to-row, from-col, to-col),
+    /// if buffer is in visual-block mode. Rows and columns are inclusive,
+    /// columns are char offsets within the line.
+    pub fn to_visual_block(&self) -> Option<(usize, usize, usize, usize)> {
+        match &self.inner {
+            Inner::Visual(vb) if vb.block => {
+                let cursor = self.to_char_cursor();
+                let (ar, cr) = (self.char_to_line(vb.anchor), self.char_to_line(cursor));
+                let ac = vb.anchor - self.line_to_char(ar);
+                let cc = cursor - self.line_to_char(cr);
+                let (rf, rt) = (cmp::min(ar, cr), cmp::max(ar, cr));
+                let (cf, ct) = (cmp::min(ac, cc), cmp::max(ac, cc));
+                Some((rf, rt, cf, ct))
+            }
+            _ => None,
+        }
     }
-}
 
-#[derive(Clone)]
-struct NormalBuffer {
-    i_evnts: Event,
-    change: Rc<RefCell<Change>>,
+    fn to_visual_range(&self) -> Option<(usize, usize, bool)> {
+        match &self.inner {
+            Inner::Visual(vb) if vb.block => {
+                // span of the block, from its top-left to bottom-right.
+                let (rf, rt, cf, ct) = self.to_visual_block()?;
+                let from = self.line_to_char(rf) + cmp::min(cf, line_chars(self, rf));
+                let to = self.line_to_char(rt) + cmp::min(ct + 1, line_chars(self, rt));
+                Some((from, cmp::max(from, to), false))
+            }
+            Inner::Visual(vb) => {
+                let cursor = self.to_char_cursor();
+                let (from, to) = (cmp::min(vb.anchor, cursor), cmp::max(vb.anchor, cursor));
+                if vb.linewise {
+                    let (from, to) = linewise_range(self, from, to);
+                    Some((from, to, true))
+                } else {
+                    Some((from, cmp::min(to + 1, self.n_chars()), false))
+                }
+            }
+            _ => None,
+        }
+    }
 }
 
-impl From<InsertBuffer> for NormalBuffer {
-    fn from(ib: InsertBuffer) -> NormalBuffer {
-        NormalBuffer {
-            i_evnts: ib.i_evnts,
-            change: ib.change,
+/// Programmatic edits on buffer, without synthesizing key events.
+impl Buffer {
+    /// Insert `txt` at char-index `char_idx` as a new change, that can be
+    /// undone. Cursor stays with the text it was on. Return the edit
+    /// descriptor, that shall be applied on the syntax tree.
+    pub fn insert_str(&mut self, char_idx: usize, txt: &str) -> Result<crate::event::Edit> {
+        use crate::event::Edit;
+
+        if char_idx > self.n_chars() {
+            err_at!(Invalid, msg: format!("insert at {} > {}", char_idx, self.n_chars()))?
+        }
+
+        let cursor = self.to_char_cursor();
+        let change = Change::fork(self.as_mut_change());
+        *self.as_mut_change() = change;
+        self.cud_str(Some(char_idx), txt)?;
+
+        let n = txt.chars().count();
+        self.set_cursor(if_else!(cursor >= char_idx, cursor + n, cursor));
+
+        Ok(Edit::new_ins(char_idx, txt.to_string()))
+    }
+
+    /// Delete text between [a, z), specified as char-index, as a new
+    /// change, that can be undone. Cursor stays with the text it was on, or
+    /// moves to `a` if it was within the deleted text. Return the deleted
+    /// text and the edit descriptor, that shall be applied on the syntax
+    /// tree.
+    pub fn delete_range(&mut self, a: usize, z: usize) -> Result<(String, crate::event::Edit)> {
+        use crate::event::Edit;
+
+        if a > z || z > self.n_chars() {
+            err_at!(Invalid, msg: format!("delete range {}..{} > {}", a, z, self.n_chars()))?
         }
+
+        let (cursor, txt) = (self.to_char_cursor(), self.slice(a..z));
+        let change = Change::fork(self.as_mut_change());
+        *self.as_mut_change() = change;
+        self.cud_delete(a..z)?;
+
+        let cursor = match cursor {
+            cursor if cursor >= z => cursor - (z - a),
+            cursor if cursor >= a => a,
+            cursor => cursor,
+        };
+        self.set_cursor(cursor);
+
+        Ok((txt.clone(), Edit::new_del(a, txt)))
     }
 }
 
-impl NormalBuffer {
-    fn new(buf: Rope) -> NormalBuffer {
-        NormalBuffer {
-            i_evnts: Event::default(),
-            change: Change::start(buf),
+/// Search operations on buffer.
+impl Buffer {
+    /// Return the char-index of the next match for `patt`, from cursor in
+    /// direction `dp`, without moving the cursor. Useful for previewing
+    /// the match while the pattern is being typed. Return None if pattern
+    /// is invalid or there is no match.
+    pub fn search_preview(&self, patt: &str, dp: DP) -> Option<usize> {
+        let search = Search::new(patt, self.search_opts).ok()?;
+        match find_pattern(self, 1, &search, dp).ok()? {
+            Some((_, true)) if !self.wrapscan => None,
+            Some((cursor, _)) => Some(cursor),
+            None => None,
         }
     }
 
-    fn set_cursor(&mut self, cursor: usize) {
-        self.to_mut_change().set_cursor(cursor);
+    /// Substitute matches of `patt` with `rep` for lines between
+    /// [from, to), specified as line-index. Only the first match in each
+    /// line is replaced, unless `global` is true. `rep` can refer to
+    /// captured groups using `$n` syntax. Cursor is moved to the last
+    /// substituted line. Return [Event::Noop] if nothing was substituted.
+    pub fn substitute(
+        &mut self,
+        from: usize,
+        to: usize,
+        patt: &regex::Regex,
+        rep: &str,
+        global: bool,
+    ) -> Result<Event> {
+        use crate::event::Edit;
+
+        let to = cmp::min(to, self.n_lines());
+        if from >= to {
+            return Ok(Event::Noop);
+        }
+
+        let (mut newt, mut last) = (String::default(), None);
+        for row in from..to {
+            let line = self.line(row);
+            let n = line.trim_end_matches(&['\n', '\r'][..]).len();
+            let (txt, nl) = line.split_at(n);
+            if patt.is_match(txt) {
+                last = Some(row);
+            }
+            let txt = if_else!(global, patt.replace_all(txt, rep), patt.replace(txt, rep));
+            newt.push_str(&txt);
+            newt.push_str(nl);
+        }
+
+        let row = match last {
+            Some(row) => row,
+            None => return Ok(Event::Noop),
+        };
+
+        let (a, z) = (self.line_to_char(from), self.line_to_char(to));
+        let oldt = self.slice(a..z);
+
+        let change = Change::fork(self.as_mut_change());
+        *self.as_mut_change() = change;
+        self.cud_delete(a..z)?;
+        self.cud_str(Some(a), &newt)?;
+
+        self.set_cursor(self.line_to_char(row));
+        let cursor = mto_line_home(self, DP::TextCol)?;
+        self.set_cursor(cursor).clear_sticky_col();
+
+        Ok(Event::Edit(Edit::new_chg(a, oldt, newt)))
     }
 
-    #[inline]
-    fn cud_newlines(&mut self, cursor: Option<usize>, nl: &str, n: usize) -> Result<usize> {
-        self.to_mut_change().cud_newlines(cursor, nl, n)
+    /// Replace all matches of `patt`, in the entire buffer, with `rep`, as
+    /// a single change. `rep` can refer to captured groups using `$n` or
+    /// `${name}` syntax. Return the number of replacements.
+    pub fn replace_all(&mut self, patt: &str, rep: &str, opts: SearchOpts) -> Result<usize> {
+        let search = Search::new(patt, opts)?;
+        let txt = self.to_string();
+
+        // collect replacements as char-index range, in order.
+        let mut items = vec![];
+        let (mut at, mut ch_at) = (0, 0);
+        for caps in search.as_regex().captures_iter(&txt) {
+            let m = caps.get(0).unwrap();
+            let a = ch_at + txt[at..m.start()].chars().count();
+            let z = a + m.as_str().chars().count();
+            let mut newt = String::default();
+            caps.expand(rep, &mut newt);
+            items.push((a, z, newt));
+            at = m.end();
+            ch_at = z;
+        }
+        if items.is_empty() {
+            return Ok(0);
+        }
+
+        let change = Change::fork(self.as_mut_change());
+        *self.as_mut_change() = change;
+        for (a, z, newt) in items.iter().rev() {
+            self.cud_delete(*a..*z)?;
+            self.cud_str(Some(*a), newt)?;
+        }
+        let cursor = saturate_cursor(self, self.to_char_cursor());
+        self.set_cursor(cursor).clear_sticky_col();
+
+        Ok(items.len())
     }
 
-    #[inline]
-    fn cud_char(&mut self, cursor: Option<usize>, ch: char) -> Result<usize> {
-        self.to_mut_change().cud_char(cursor, ch)
+    /// Remove trailing spaces and tabs from every line in the buffer, as a
+    /// single change. Cursor stays on its line.
+    pub fn strip_whitespace(&mut self) -> Result<Event> {
+        use crate::event::Edit;
+
+        let strip = |line: String| -> String {
+            let n = line.trim_end_matches(&['\n', '\r'][..]).len();
+            let (txt, nl) = line.split_at(n);
+            txt.trim_end_matches(&[' ', '\t'][..]).to_string() + nl
+        };
+
+        let rows: Vec<usize> = {
+            let iter = 0..self.n_lines();
+            iter.filter(|row| strip(self.line(*row)).len() < self.line(*row).len())
+                .collect()
+        };
+        let (from, to) = match (rows.first(), rows.last()) {
+            (Some(from), Some(to)) => (*from, to + 1),
+            _ => return Ok(Event::Noop),
+        };
+
+        let newt: String = (from..to).map(|row| strip(self.line(row))).collect();
+        let (a, z) = (self.line_to_char(from), self.line_to_char(to));
+        let oldt = self.slice(a..z);
+
+        let Cursor { row, col } = self.to_xy_cursor(None);
+        let change = Change::fork(self.as_mut_change());
+        *self.as_mut_change() = change;
+        self.cud_delete(a..z)?;
+        self.cud_str(Some(a), &newt)?;
+
+        let col = cmp::min(col, line_chars(self, row).saturating_sub(1));
+        let cursor = saturate_cursor(self, xy_to_cursor(self, (row, col)));
+        self.set_cursor(cursor).clear_sticky_col();
+
+        Ok(Event::Edit(Edit::new_chg(a, oldt, newt)))
     }
+}
 
-    #[inline]
-    fn cud_str(&mut self, cursor: Option<usize>, text: &str) -> Result<usize> {
-        self.to_mut_change().cud_str(cursor, text)
+/// Line operations on buffer.
+impl Buffer {
+    /// Join the current line with the next `n` lines, by removing the
+    /// newlines between them. Leading whitespace of each joined line is
+    /// collapsed into a single space, unless the line starts with `)` or
+    /// the previous line already ends with whitespace. Cursor is moved to
+    /// the last joint. Joining on the last line is a no-op.
+    pub fn join_lines(&mut self, n: usize) -> Result<Event> {
+        use crate::event::Edit;
+
+        let row = self.char_to_line(self.to_char_cursor());
+        let end = cmp::min(row.saturating_add(n), self.to_last_line_idx());
+        if row >= end {
+            return Ok(Event::Noop);
+        }
+
+        let mut newt = self
+            .line(row)
+            .trim_end_matches(&['\n', '\r'][..])
+            .to_string();
+        let mut col = 0;
+        for row in (row + 1)..=end {
+            let line = self.line(row);
+            let txt = line.trim_end_matches(&['\n', '\r'][..]).trim_start();
+            col = newt.chars().count();
+            let space = match newt.chars().last() {
+                Some(ch) if ch.is_whitespace() => false,
+                Some(_) => !txt.is_empty() && !txt.starts_with(')'),
+                None => false,
+            };
+            if space {
+                newt.push(' ');
+            }
+            newt.push_str(txt);
+        }
+
+        let a = self.line_to_char(row);
+        let z = {
+            let line = self.line(end);
+            let n = line.trim_end_matches(&['\n', '\r'][..]).chars().count();
+            self.line_to_char(end) + n
+        };
+        let oldt = self.slice(a..z);
+
+        let change = Change::fork(self.as_mut_change());
+        *self.as_mut_change() = change;
+        self.cud_delete(a..z)?;
+        self.cud_str(Some(a), &newt)?;
+        self.set_cursor(a + col).clear_sticky_col();
+
+        Ok(Event::Edit(Edit::new_chg(a, oldt, newt)))
     }
 
-    #[inline]
-    fn cud_delete<R>(&mut self, range: R) -> Result<()>
-    where
-        R: RangeBounds<usize>,
-    {
-        self.to_mut_change().cud_delete(range)
+    /// Format lines between [from, to), specified as line-index, by
+    /// re-wrapping each paragraph to `text_width` columns. Lines are broken
+    /// only at whitespace and wrapped lines get the indentation of the
+    /// paragraph's first line. Comment leaders, refer [COMMENT_LEADERS],
+    /// are repeated on each wrapped line. Blank lines separate paragraphs
+    /// and are left as is. Cursor is moved to the first non-blank
+    /// character of the last formatted line.
+    pub fn format_lines(&mut self, from: usize, to: usize) -> Result<Event> {
+        use crate::event::Edit;
+
+        let to = cmp::min(to, self.n_lines());
+        if from >= to {
+            return Ok(Event::Noop);
+        }
+
+        let (a, z) = (self.line_to_char(from), self.line_to_char(to));
+        let oldt = self.slice(a..z);
+        let newt = format_text(&oldt, self.text_width, self.tab_stop);
+        if oldt == newt {
+            return Ok(Event::Noop);
+        }
+
+        let change = Change::fork(self.as_mut_change());
+        *self.as_mut_change() = change;
+        self.cud_delete(a..z)?;
+        self.cud_str(Some(a), &newt)?;
+
+        let row = from + newt.lines().count().saturating_sub(1);
+        self.set_cursor(self.line_to_char(row));
+        let cursor = mto_line_home(self, DP::TextCol)?;
+        self.set_cursor(cursor).clear_sticky_col();
+
+        Ok(Event::Edit(Edit::new_chg(a, oldt, newt)))
     }
 }
 
-impl NormalBuffer {
-    fn to_change(&self) -> cell::Ref<Change> {
-        self.change.as_ref().borrow()
+/// Indentation operations on buffer.
+impl Buffer {
+    /// Shift lines between [from, to), specified as line-index, by `n`
+    /// steps of shift-width, to the right or left as specified by `dp`.
+    /// Blank lines are left as is, and shifting left stops at column
+    /// zero. Cursor is not moved.
+    pub fn shift_lines(&mut self, from: usize, to: usize, n: usize, dp: DP) -> Result<Event> {
+        shift_text(self, from, to, n, dp, true /*fork*/)
     }
+}
 
-    fn to_mut_change(&mut self) -> cell::RefMut<Change> {
-        self.change.as_ref().borrow_mut()
+/// Repeat operations on buffer.
+impl Buffer {
+    /// Repeat the last change made in normal mode, along with the text
+    /// inserted, if the change had switched to insert mode.
+    pub fn repeat_last_change(&mut self) -> Result<Event> {
+        let evnts = self.last_change.to_events();
+        self.on_event(evnts)
     }
+}
 
-    fn as_mut_change(&mut self) -> &mut Rc<RefCell<Change>> {
-        &mut self.change
+/// Persist buffer's content.
+impl Buffer {
+    /// Write buffer's content to its location and mark the buffer as
+    /// unmodified. Return the number of bytes written.
+    ///
+    /// Change tree is collapsed into the written content, hence undo
+    /// history prior to the write is lost.
+    pub fn write(&mut self) -> Result<usize> {
+        if self.is_read_only() {
+            err_at!(IOError, msg: format!("read-only buffer {}", self.to_id()))?
+        }
+        let n = self.location.write(&self.to_string())?;
+
+        let root = {
+            let change = self.to_change();
+            let root = Change::start(change.as_ref().clone());
+            root.borrow_mut().set_cursor(change.to_char_cursor());
+            root
+        };
+        *self.as_mut_change() = Rc::clone(&root);
+        self._root = root;
+
+        Ok(n)
     }
 }
 
-impl NormalBuffer {
-    fn on_event(buf: &mut Buffer, mut evnts: Event) -> Result<Event> {
-        let mut res_evnts = Event::Noop;
-        loop {
-            match evnts.next() {
-                Some(evnt) => {
-                    res_evnts.push(Self::do_on_event(buf, evnt)?);
-                    match &buf.inner {
-                        Inner::Normal(_) => (),
-                        Inner::Insert(_) => {
-                            res_evnts.push(buf.on_event(evnts)?);
-                            break;
-                        }
-                        Inner::Replace(_) => todo!(),
-                        Inner::None => err_at!(Fatal, msg: format!("unreachable"))?,
-                    }
-                }
-                None => break,
+/// Persistent undo for buffer.
+///
+/// Change tree is saved in pre-order, each change as a difference from its
+/// past, along with its cursor and redo path. Undo file is keyed by the
+/// content hash of the current change, so that an undo file that is stale
+/// with respect to buffer's content is not loaded.
+impl Buffer {
+    /// Save the change tree into undo file `path`. Return the number of
+    /// bytes written.
+    pub fn save_undo<P: AsRef<path::Path>>(&self, path: P) -> Result<usize> {
+        let curr = self.to_change();
+        let tree = self.to_change_tree();
+
+        let mut out = UNDO_MAGIC.to_vec();
+        out.extend_from_slice(&to_content_hash(&self.to_string()).to_le_bytes());
+        put_varint(&mut out, tree.len());
+        let seq = {
+            let mut iter = tree.iter();
+            iter.position(|(c, _)| std::ptr::eq(c.as_ptr(), &*curr))
+                .unwrap_or(0)
+        };
+        put_varint(&mut out, seq);
+
+        for (change, past) in tree.iter() {
+            let cc: &Change = &change.as_ref().borrow();
+            let old = match past {
+                Some(p) => tree[*p].0.as_ref().borrow().rope.to_string(),
+                None => String::default(),
             };
+            let (at, removed, txt) = to_diff(&old, &cc.rope.to_string());
+            put_varint(&mut out, past.map(|p| p + 1).unwrap_or(0));
+            put_varint(&mut out, cc.redo.map(|off| off + 1).unwrap_or(0));
+            put_varint(&mut out, cc.cursor);
+            put_varint(&mut out, at);
+            put_varint(&mut out, removed);
+            put_varint(&mut out, txt.len());
+            out.extend_from_slice(txt.as_bytes());
         }
-        Ok(res_evnts)
+
+        fs::write(path, &out)?;
+        Ok(out.len())
     }
 
-    fn do_on_event(buf: &mut Buffer, evnt: Event) -> Result<Event> {
-        debug!("{}", evnt);
+    /// Load the change tree from undo file `path`, replacing buffer's
+    /// change tree. Fail if the undo file was saved for a different content.
+    pub fn load_undo<P: AsRef<path::Path>>(&mut self, path: P) -> Result<()> {
+        let bytes = fs::read(path)?;
+        let mut off = UNDO_MAGIC.len();
 
-        let evnt = match evnt {
-            // first, try switching to insert mode, if event is insert.
-            Event::Md(Mod::Insert(n, pos)) if n > 0 => mod_insert(buf, n, pos)?,
-            Event::Md(Mod::Append(n, pos)) if n > 0 => mod_append(buf, n, pos)?,
-            Event::Md(Mod::Open(n, pos)) if n > 0 => mod_open(buf, n, pos)?,
-            Event::Md(Mod::Insert(_, _)) => Event::Noop,
-            Event::Md(Mod::Append(_, _)) => Event::Noop,
-            Event::Md(Mod::Open(_, _)) => Event::Noop,
-            // motion command - characterwise.
-            Event::Mt(Mto::Left(n, dp)) => {
-                let cursor = mto_left(buf, n, dp)?;
-                buf.set_cursor(cursor).clear_sticky_col();
-                Event::Noop
-            }
-            Event::Mt(Mto::Right(n, dp)) => {
-                let cursor = mto_right(buf, n, dp)?;
-                buf.set_cursor(cursor).clear_sticky_col();
-                Event::Noop
-            }
-            Event::Mt(Mto::LineHome(dp)) => {
-                let cursor = mto_line_home(buf, dp)?;
-                buf.set_cursor(cursor).set_sticky_col(dp, "home");
-                Event::Noop
-            }
-            Event::Mt(Mto::LineEnd(n, dp)) => {
-                let cursor = mto_line_end(buf, n, dp)?;
-                buf.set_cursor(cursor).set_sticky_col(dp, "end");
-                Event::Noop
-            }
-            Event::Mt(Mto::LineMiddle(p, _)) if p < 1 => {
-                let cursor = mto_line_middle(buf, 50)?;
-                buf.set_cursor(cursor).clear_sticky_col();
-                Event::Noop
-            }
-            Event::Mt(Mto::LineMiddle(p, _)) => {
-                let cursor = mto_line_middle(buf, p)?;
-                buf.set_cursor(cursor).clear_sticky_col();
-                Event::Noop
-            }
-            Event::Mt(Mto::Col(n)) => {
-                let cursor = mto_column(buf, n)?;
-                buf.set_cursor(cursor).clear_sticky_col();
-                Event::Noop
-            }
-            Event::Mt(e @ Mto::CharF(_, _, _)) => {
-                buf.mto_find_char = e.clone();
-                let cursor = mto_char(buf, e)?;
-                buf.set_cursor(cursor).clear_sticky_col();
-                Event::Noop
-            }
-            Event::Mt(e @ Mto::CharT(_, _, _)) => {
-                buf.mto_find_char = e.clone();
-                let cursor = mto_char(buf, e)?;
-                buf.set_cursor(cursor).clear_sticky_col();
-                Event::Noop
-            }
-            Event::Mt(Mto::CharR(n, dir)) => {
-                let e = buf.mto_find_char.clone();
-                let cursor = mto_char(buf, e.dir_xor(n, dir)?)?;
-                buf.set_cursor(cursor).clear_sticky_col();
-                Event::Noop
-            }
-            // motion command - linewise.
-            Event::Mt(Mto::Up(n, dp)) => {
-                let cursor = mto_up(buf, n, dp)?;
-                buf.set_cursor(cursor);
-                Event::Noop
-            }
-            Event::Mt(Mto::Down(n, dp)) => {
-                let cursor = mto_down(buf, n, dp)?;
-                buf.set_cursor(cursor);
-                Event::Noop
-            }
-            Event::Mt(Mto::Row(n, dp)) => {
-                let n = n.saturating_sub(1);
-                let cursor = mto_row(buf, n, dp)?;
-                buf.set_cursor(cursor);
-                Event::Noop
-            }
-            Event::Mt(Mto::Percent(n, dp)) => {
-                let cursor = mto_percent(buf, n, dp)?;
-                buf.set_cursor(cursor);
-                Event::Noop
-            }
-            Event::Mt(Mto::Cursor(n)) => {
-                let cursor = mto_cursor(buf, n)?;
-                buf.set_cursor(cursor);
-                Event::Noop
-            }
-            // motion command - word/sentence/para wise
-            Event::Mt(Mto::Word(n, DP::Left, pos)) => {
-                let cursor = mto_words_left(buf, n, pos)?;
-                buf.set_cursor(cursor).clear_sticky_col();
-                Event::Noop
-            }
-            Event::Mt(Mto::Word(n, DP::Right, pos)) => {
-                let cursor = mto_words_right(buf, n, pos)?;
-                buf.set_cursor(cursor).clear_sticky_col();
-                Event::Noop
-            }
-            Event::Mt(Mto::WWord(n, DP::Left, pos)) => {
-                let cursor = mto_wwords_left(buf, n, pos)?;
-                buf.set_cursor(cursor).clear_sticky_col();
-                Event::Noop
-            }
-            Event::Mt(Mto::WWord(n, DP::Right, pos)) => {
-                let cursor = mto_wwords_right(buf, n, pos)?;
-                buf.set_cursor(cursor).clear_sticky_col();
-                Event::Noop
-            }
-            Event::Mt(Mto::Sentence(n, DP::Left)) => {
-                let cursor = mto_sentence_left(buf, n)?;
-                buf.set_cursor(cursor).clear_sticky_col();
-                Event::Noop
-            }
-            Event::Mt(Mto::Sentence(n, DP::Right)) => {
-                let cursor = mto_sentence_right(buf, n)?;
-                buf.set_cursor(cursor).clear_sticky_col();
-                Event::Noop
-            }
-            Event::Mt(Mto::Para(n, DP::Left)) => {
-                let cursor = mto_paras_left(buf, n)?;
-                buf.set_cursor(cursor).clear_sticky_col();
-                Event::Noop
-            }
-            Event::Mt(Mto::Para(n, DP::Right)) => {
-                let cursor = mto_paras_right(buf, n)?;
-                buf.set_cursor(cursor).clear_sticky_col();
-                Event::Noop
-            }
-            // motion command, other motions.
-            Event::Mt(Mto::MatchPair) => {
-                let cursor = mto_match_pair(buf)?;
-                buf.set_cursor(cursor).clear_sticky_col();
-                Event::Noop
-            }
-            Event::Mt(Mto::UnmatchPair(n, ch, dir)) => {
-                let cursor = mto_unmatch_pair(buf, ch, n, dir)?;
-                buf.set_cursor(cursor).clear_sticky_col();
-                Event::Noop
-            }
-            // motion command marks and jumps
-            Event::Mt(Mto::Jump(typ, mindex)) => {
-                let mrk = mark::get_mark(&buf.marks, mindex);
-                match mrk {
-                    Some(mrk) if typ == '`' => {
-                        let cursor = mrk.to_cursor();
-                        buf.set_cursor(cursor).clear_sticky_col();
-                        Event::Noop
-                    }
-                    Some(mrk) if typ == '\'' => {
-                        buf.set_cursor(mrk.to_cursor());
-                        let cursor = mto_line_home(buf, DP::TextCol)?;
-                        buf.set_cursor(cursor).clear_sticky_col();
-                        Event::Noop
+        if !bytes.starts_with(UNDO_MAGIC) || bytes.len() < off + 8 {
+            err_at!(Invalid, msg: format!("not an undo file"))?
+        }
+        let hash = {
+            let mut hash = [0_u8; 8];
+            hash.copy_from_slice(&bytes[off..off + 8]);
+            off += 8;
+            u64::from_le_bytes(hash)
+        };
+        let content = self.to_string();
+        if hash != to_content_hash(&content) {
+            err_at!(Invalid, msg: format!("stale undo file"))?
+        }
+
+        let n = get_varint(&bytes, &mut off)?;
+        let curr = get_varint(&bytes, &mut off)?;
+        let mut tree: Vec<Rc<RefCell<Change>>> = vec![];
+        for seq in 0..n {
+            let past = match get_varint(&bytes, &mut off)? {
+                0 if seq == 0 => None,
+                p if p > 0 && p <= seq => Some(Rc::clone(&tree[p - 1])),
+                _ => err_at!(Invalid, msg: format!("corrupt undo file, change {}", seq))?,
+            };
+            let redo = get_varint(&bytes, &mut off)?.checked_sub(1);
+            let cursor = get_varint(&bytes, &mut off)?;
+            let (at, removed) = (get_varint(&bytes, &mut off)?, get_varint(&bytes, &mut off)?);
+            let txt = {
+                let n = get_varint(&bytes, &mut off)?;
+                match bytes.get(off..off.saturating_add(n)) {
+                    Some(txt) => {
+                        off += n;
+                        err_at!(Invalid, std::str::from_utf8(txt))?
                     }
-                    _ => Event::Mt(Mto::Jump(typ, mindex)),
+                    None => err_at!(Invalid, msg: format!("truncated undo file"))?,
                 }
+            };
+
+            let mut rope = match past.as_ref() {
+                Some(past) => past.as_ref().borrow().rope.clone(),
+                None => Rope::new(),
+            };
+            if at.saturating_add(removed) > rope.len_chars() {
+                err_at!(Invalid, msg: format!("corrupt undo file, change {}", seq))?
             }
-            Event::Mr(mrk) => match mrk.to_index() {
-                'a'..='z' | '\'' | '`' => {
-                    let mrk = mrk.into_mark(buf);
-                    mark::set_mark(&mut buf.marks, mrk);
-                    Event::Noop
-                }
-                'A'..='Z' => Event::Mr(mrk.into_mark(buf)),
-                _ => Event::Noop,
-            },
-            Event::Mt(e @ Mto::Bracket(_, _, _, _)) => mto_bracket(buf, e)?,
-            Event::Mt(e @ Mto::Pattern(_, Some(_), _)) => {
-                buf.mto_pattern = e.clone();
+            rope.remove(at..(at + removed));
+            rope.insert(at, txt);
 
-                let cursor = mto_pattern(buf, e)?;
-                buf.set_cursor(cursor).clear_sticky_col();
-                Event::Noop
+            let change = Rc::new(RefCell::new(Change {
+                rope,
+                past: past.as_ref().map(Rc::downgrade),
+                news: Vec::default(),
+                redo,
+                cuds: Event::Noop,
+                cursor,
+            }));
+            if let Some(past) = past {
+                past.as_ref().borrow_mut().news.push(Rc::clone(&change));
             }
-            Event::Mt(Mto::PatternR(n, dir)) => {
-                let e = buf.mto_pattern.clone();
+            tree.push(change);
+        }
 
-                let cursor = mto_pattern(buf, e.dir_xor(n, dir)?)?;
-                buf.set_cursor(cursor).clear_sticky_col();
-                Event::Noop
+        match tree.get(curr) {
+            Some(change) if change.as_ref().borrow().rope == content.as_str() => {
+                *self.as_mut_change() = Rc::clone(change);
+                self._root = Rc::clone(&tree[0]);
             }
-            evnt => evnt,
-        };
+            _ => err_at!(Invalid, msg: format!("corrupt undo file, current {}", curr))?,
+        }
+        let cursor = saturate_cursor(self, self.to_char_cursor());
+        self.set_cursor(cursor).clear_sticky_col();
 
-        Ok(evnt)
+        Ok(())
     }
 }
 
-#[derive(Clone)]
-struct InsertBuffer {
-    repeat: usize,
-    i_evnts: Event,
-    change: Rc<RefCell<Change>>,
-}
+/// Text statistics on buffer.
+impl Buffer {
+    /// Count words, lines, sentences and paragraphs in buffer, refer
+    /// [TextCount] for details. Words and sentences are bounded the same
+    /// way as `W` and `)` motions, paragraphs are separated by blank lines
+    /// same as `}` motion.
+    pub fn to_text_count(&mut self) -> Result<TextCount> {
+        let cursor = self.to_char_cursor();
+        let words = self.count_text(cursor, |b| mto_wwords_right(b, 1, DP::Start));
+        let sentences = self.count_text(cursor, |b| mto_sentence(b, 1, DP::Right));
+        self.set_cursor(cursor);
+
+        let row = self.to_xy_cursor(None).row;
+        let n_lines = self.to_last_line_idx() + 1;
+        let paras = {
+            let (mut nth, mut total, mut blank) = (0, 0, true);
+            let iter = self.lines_between(0, n_lines, DP::Right)?.enumerate();
+            for (r, line) in iter {
+                let ok = text::visual_line_n(&line) == 0;
+                if blank && !ok {
+                    total += 1;
+                    nth += if_else!(r <= row, 1, 0);
+                }
+                blank = ok;
+            }
+            (nth, total)
+        };
 
-impl InsertBuffer {
-    fn new(repeat: usize, change: Rc<RefCell<Change>>) -> Self {
-        InsertBuffer {
-            repeat: repeat.saturating_sub(1),
-            i_evnts: Event::default(),
-            change,
+        Ok(TextCount {
+            words: words?,
+            lines: (cmp::min(row + 1, n_lines), n_lines),
+            sentences: sentences?,
+            paras,
+        })
+    }
+
+    // Count text objects, starting from the first non-blank character and
+    // moving over them using `mto`. Return (nth, total), where nth counts
+    // text objects starting at or before `cursor`. Moves the cursor.
+    fn count_text<F>(&mut self, cursor: usize, mto: F) -> Result<(usize, usize)>
+    where
+        F: Fn(&mut Buffer) -> Result<usize>,
+    {
+        // motions can stop at blank lines and saturate at the last
+        // character, count only when moved to the beginning of a text
+        // object.
+        let is_start = |buf: &Buffer, at: usize| -> bool {
+            let change = buf.to_change();
+            let (ch, prev) = match at {
+                at if at >= change.rope.len_chars() => return false,
+                0 => (change.rope.char(0), ' '),
+                at => (change.rope.char(at), change.rope.char(at - 1)),
+            };
+            let prev = prev.is_whitespace() || matches!(prev, ')' | ']' | '"' | '\'');
+            !ch.is_whitespace() && prev
+        };
+
+        let mut at = {
+            let mut iter = self.chars_at(0, DP::Right)?;
+            match iter.position(|ch| !ch.is_whitespace()) {
+                Some(at) => at,
+                None => return Ok((0, 0)),
+            }
+        };
+        let (mut nth, mut total) = (if_else!(at <= cursor, 1, 0), 1);
+        loop {
+            self.set_cursor(at);
+            match mto(self)? {
+                next if next <= at => break Ok((nth, total)),
+                next if is_start(self, next) => {
+                    total += 1;
+                    nth += if_else!(next <= cursor, 1, 0);
+                    at = next;
+                }
+                next => at = next,
+            }
         }
     }
+}
 
-    fn set_cursor(&mut self, cursor: usize) {
-        self.to_mut_change().set_cursor(cursor)
+/// Undo/Redo operations on buffer.
+impl Buffer {
+    /// Undo the last change-session, by moving to its past in the change
+    /// tree, and restore the cursor. Return [Event::Noop] if there is no
+    /// past to undo.
+    pub fn undo(&mut self) -> Result<Event> {
+        let past = {
+            let change = self.to_change();
+            change.past.as_ref().and_then(|past| past.upgrade())
+        };
+        match past {
+            Some(past) => {
+                let curr = Rc::clone(self.as_mut_change());
+                {
+                    // remember the redo path back to current change.
+                    let pc: &mut Change = &mut past.as_ref().borrow_mut();
+                    pc.redo = pc.news.iter().position(|c| Rc::ptr_eq(c, &curr));
+                }
+                Ok(self.switch_change(past))
+            }
+            None => Ok(Event::Noop),
+        }
     }
 
-    #[inline]
-    fn cud_newlines(&mut self, cursor: Option<usize>, nl: &str, n: usize) -> Result<usize> {
-        self.to_mut_change().cud_newlines(cursor, nl, n)
+    /// Redo the most recent change-session undone from current change,
+    /// and restore the cursor. Return [Event::Noop] if there is no future
+    /// to redo.
+    pub fn redo(&mut self) -> Result<Event> {
+        let new = {
+            let change = self.to_change();
+            match change.redo {
+                Some(off) => change.news.get(off).map(Rc::clone),
+                None => change.news.last().map(Rc::clone),
+            }
+        };
+        match new {
+            Some(new) => Ok(self.switch_change(new)),
+            None => Ok(Event::Noop),
+        }
     }
 
-    #[inline]
-    fn cud_char(&mut self, cursor: Option<usize>, ch: char) -> Result<usize> {
-        self.to_mut_change().cud_char(cursor, ch)
+    /// Iterate over the change tree, in pre-order starting from the oldest
+    /// change, for visualizing undo history.
+    pub fn history(&self) -> impl Iterator<Item = ChangeInfo> {
+        let curr = self.to_change();
+        let tree = self.to_change_tree();
+
+        let mut infos: Vec<ChangeInfo> = vec![];
+        for (seq, (change, parent)) in tree.iter().enumerate() {
+            let cc: &Change = &change.as_ref().borrow();
+            let (added, removed) = match parent {
+                Some(p) => {
+                    let old = tree[*p].0.as_ref().borrow().rope.to_string();
+                    let (_, removed, txt) = to_diff(&old, &cc.rope.to_string());
+                    (txt.chars().count(), removed)
+                }
+                None => (0, 0),
+            };
+            infos.push(ChangeInfo {
+                seq,
+                parent: *parent,
+                cursor: cc.cursor,
+                added,
+                removed,
+                current: std::ptr::eq(cc, &*curr),
+            });
+        }
+
+        infos.into_iter()
     }
 
-    #[inline]
-    fn cud_str(&mut self, cursor: Option<usize>, text: &str) -> Result<usize> {
-        self.to_mut_change().cud_str(cursor, text)
+    // Return the change tree in pre-order, starting from the oldest change,
+    // along with the offset of each change's past.
+    fn to_change_tree(&self) -> Vec<(Rc<RefCell<Change>>, Option<usize>)> {
+        let mut tree = vec![];
+        let mut stack = vec![(Rc::clone(&self._root), None)];
+        while let Some((change, past)) = stack.pop() {
+            let seq = tree.len();
+            {
+                let cc: &Change = &change.as_ref().borrow();
+                let iter = cc.news.iter().rev();
+                stack.extend(iter.map(|new| (Rc::clone(new), Some(seq))));
+            }
+            tree.push((change, past));
+        }
+        tree
     }
 
-    #[inline]
-    fn cud_delete<R>(&mut self, range: R) -> Result<()>
-    where
-        R: RangeBounds<usize>,
-    {
-        self.to_mut_change().cud_delete(range)
+    fn switch_change(&mut self, change: Rc<RefCell<Change>>) -> Event {
+        use crate::event::Edit;
+
+        let oldt = self.to_string();
+        *self.as_mut_change() = change;
+        let cursor = saturate_cursor(self, self.to_char_cursor());
+        self.set_cursor(cursor).clear_sticky_col();
+
+        Event::Edit(Edit::new_chg(0, oldt, self.to_string()))
     }
 }
 
-impl InsertBuffer {
-    fn to_change(&self) -> cell::Ref<Change> {
+/// Create-Update-Delete operations on buffer.
+impl Buffer {
+    /// Insert `n` newlines, specified by `nl`, at `cursor` position.
+    /// If cursor is `None`, use the current cursor position. Return
+    /// the new cursor postion.
+    #[inline]
+    pub fn cud_newlines(&mut self, cursor: Option<usize>, nl: &str, n: usize) -> Result<usize> {
+        let at = cursor.unwrap_or(self.to_char_cursor());
+        mark::shift_marks(&mut self.marks, at, 0, nl.chars().count() * n);
+        self.jumps.shift(at, 0, nl.chars().count() * n);
+        self.shift_last_visual(at, 0, nl.chars().count() * n);
+        self.shift_folds(at, 0, nl.chars().count() * n);
+        self.set_change_at(at, 0, nl.chars().count() * n);
+        self.inner.cud_newlines(cursor, nl, n)
+    }
+
+    /// Insert character `ch` at `cursor` position. If cursor is None, use the
+    /// current cursor position. Return the new cursor position.
+    #[inline]
+    pub fn cud_char(&mut self, cursor: Option<usize>, ch: char) -> Result<usize> {
+        let at = cursor.unwrap_or(self.to_char_cursor());
+        mark::shift_marks(&mut self.marks, at, 0, 1);
+        self.jumps.shift(at, 0, 1);
+        self.shift_last_visual(at, 0, 1);
+        self.shift_folds(at, 0, 1);
+        self.set_change_at(at, 0, 1);
+        self.inner.cud_char(cursor, ch)
+    }
+
+    /// Insert string `txt` at `cursor position. If cursor is None, use the
+    /// current cursor position. Return the new cursor position.
+    #[inline]
+    pub fn cud_str(&mut self, cursor: Option<usize>, txt: &str) -> Result<usize> {
+        let at = cursor.unwrap_or(self.to_char_cursor());
+        mark::shift_marks(&mut self.marks, at, 0, txt.chars().count());
+        self.jumps.shift(at, 0, txt.chars().count());
+        self.shift_last_visual(at, 0, txt.chars().count());
+        self.shift_folds(at, 0, txt.chars().count());
+        self.set_change_at(at, 0, txt.chars().count());
+        self.inner.cud_str(cursor, txt)
+    }
+
+    /// Delete text between the specified range. Range shall be specified
+    /// as character-index.
+    #[inline]
+    pub fn cud_delete<R>(&mut self, range: R) -> Result<()>
+    where
+        R: RangeBounds<usize>,
+    {
+        let (from, to) = to_char_range(range, self.n_chars());
+        mark::shift_marks(&mut self.marks, from, to.saturating_sub(from), 0);
+        self.jumps.shift(from, to.saturating_sub(from), 0);
+        self.shift_last_visual(from, to.saturating_sub(from), 0);
+        self.shift_folds(from, to.saturating_sub(from), 0);
+        self.set_change_at(from, to.saturating_sub(from), 0);
+        self.inner.cud_delete(from..to)
+    }
+}
+
+/// Marks on buffer.
+impl Buffer {
+    /// Set mark `index` at `cursor`, as char-index. Marks shift along with
+    /// subsequent edits to the buffer.
+    pub fn set_mark(&mut self, index: char, cursor: usize) {
+        let mrk = mark::Mark::new(index, self.to_id(), cursor);
+        mark::set_mark(&mut self.marks, mrk);
+    }
+
+    // remember `cursor` as the position before a jump, in the jump-list
+    // and as the `'` and `` ` `` marks.
+    fn set_jump(&mut self, cursor: usize) {
+        self.jumps.push(cursor);
+        self.set_mark('\'', cursor);
+        self.set_mark('`', cursor);
+    }
+
+    /// Return positions, as char-index, of recent edits, one entry for each
+    /// change, oldest first.
+    pub fn changelist(&self) -> Vec<usize> {
+        self.changes.to_list()
+    }
+
+    // shift the change-list for `n` chars removed and `m` chars inserted
+    // at `at`, and remember `at` as the position of the current change.
+    fn set_change_at(&mut self, at: usize, n: usize, m: usize) {
+        self.changes.shift(at, n, m);
+
+        let change = Rc::downgrade(self.as_mut_change());
+        match self.changes_of.ptr_eq(&change) {
+            true => self.changes.set_latest(at),
+            false => self.changes.push(at),
+        }
+        self.changes_of = change;
+    }
+
+    fn shift_last_visual(&mut self, at: usize, n: usize, m: usize) {
+        if let Some(sel) = self.last_visual.as_mut() {
+            sel.shift(at, n, m)
+        }
+    }
+}
+
+/// Folds on buffer.
+impl Buffer {
+    /// Create a closed fold over lines `[from, to]`, specified as line-index.
+    /// Existing folds partially overlapping the range are included in the
+    /// new fold, so that folds are always nested.
+    pub fn add_fold(&mut self, from: usize, to: usize) {
+        let (mut from, mut to) = (cmp::min(from, to), cmp::max(from, to));
+        let folds = self.to_fold_list();
+        loop {
+            let (x, y) = folds.iter().fold((from, to), |(x, y), (_, (a, z, _))| {
+                let nested = (x <= *a && *z <= y) || (*a <= x && y <= *z);
+                match *a <= y && x <= *z && !nested {
+                    true => (cmp::min(x, *a), cmp::max(y, *z)),
+                    false => (x, y),
+                }
+            });
+            if (x, y) == (from, to) {
+                break;
+            }
+            from = x;
+            to = y;
+        }
+
+        let item = folds
+            .into_iter()
+            .find(|(_, (a, z, _))| (*a, *z) == (from, to));
+        match item {
+            Some((i, _)) => self.folds[i].closed = true,
+            None => {
+                let (start, end) = (self.line_to_char(from), self.line_to_char(to));
+                self.folds.push(mark::Fold {
+                    start,
+                    end,
+                    closed: true,
+                })
+            }
+        }
+    }
+
+    /// Open the outermost closed fold containing line `row`. Return false
+    /// if `row` is not within a closed fold.
+    pub fn open_fold(&mut self, row: usize) -> bool {
+        match self.to_closed_fold(row) {
+            Some((x, y)) => {
+                for (i, (a, z, _)) in self.to_fold_list().into_iter() {
+                    if (a, z) == (x, y) {
+                        self.folds[i].closed = false;
+                    }
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Close the innermost open fold containing line `row`. If `row` is
+    /// within a closed fold, the open fold containing that is closed.
+    /// Return false if there is no such fold.
+    pub fn close_fold(&mut self, row: usize) -> bool {
+        let (x, y) = self.to_closed_fold(row).unwrap_or((row, row));
+        let item = self
+            .to_fold_list()
+            .into_iter()
+            .filter(|(_, (a, z, closed))| !closed && *a <= x && y <= *z)
+            .min_by_key(|(_, (a, z, _))| z - a);
+        match item {
+            Some((i, _)) => {
+                self.folds[i].closed = true;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Replace folds with `ranges` derived from the syntax tree, specified
+    /// as (first-line, last-line) in line-index. Folds that were closed
+    /// remain closed, matched by their first line, which shifts along with
+    /// edits. New folds are open.
+    pub fn set_syntax_folds(&mut self, ranges: Vec<(usize, usize)>) {
+        let closed: Vec<usize> = {
+            let iter = self.to_folds().into_iter().filter(|(_, _, closed)| *closed);
+            iter.map(|(a, _, _)| a).collect()
+        };
+        let n = self.n_lines();
+        let iter = ranges.into_iter().filter(|(a, z)| a <= z && *z < n);
+        self.folds = iter
+            .map(|(a, z)| mark::Fold {
+                start: self.line_to_char(a),
+                end: self.line_to_char(z),
+                closed: closed.contains(&a),
+            })
+            .collect();
+    }
+
+    /// Return folds as (first-line, last-line, closed), specified as
+    /// line-index, in the order they were created.
+    pub fn to_folds(&self) -> Vec<(usize, usize, bool)> {
+        let folds = self.to_fold_list();
+        folds.into_iter().map(|(_, fold)| fold).collect()
+    }
+
+    // folds along with their index in `self.folds`. Folds beyond the end
+    // of buffer, possible after undo/redo, are skipped.
+    fn to_fold_list(&self) -> Vec<(usize, (usize, usize, bool))> {
+        let n = self.n_chars();
+        let iter = self.folds.iter().enumerate().filter(|(_, f)| f.end <= n);
+        iter.map(|(i, f)| {
+            let (a, z) = (self.char_to_line(f.start), self.char_to_line(f.end));
+            (i, (a, z, f.closed))
+        })
+        .collect()
+    }
+
+    // shift folds for `n` chars removed and `m` chars inserted at `at`.
+    // Folds whose lines are removed entirely are dropped.
+    fn shift_folds(&mut self, at: usize, n: usize, m: usize) {
+        let n_chars = self.n_chars();
+        let mut folds = mem::take(&mut self.folds);
+        folds.retain(|fold| {
+            let removed = fold.end <= n_chars && n > 0 && at <= fold.start && {
+                let row = self.char_to_line(fold.end);
+                (self.line_to_char(row) + self.len_line(row)) <= (at + n)
+            };
+            !removed
+        });
+        folds.iter_mut().for_each(|fold| fold.shift(at, n, m));
+        self.folds = folds;
+    }
+}
+
+#[derive(Clone)]
+struct NormalBuffer {
+    i_evnts: Event,
+    change: Rc<RefCell<Change>>,
+}
+
+impl From<InsertBuffer> for NormalBuffer {
+    fn from(ib: InsertBuffer) -> NormalBuffer {
+        NormalBuffer {
+            i_evnts: ib.i_evnts,
+            change: ib.change,
+        }
+    }
+}
+
+impl NormalBuffer {
+    fn new(change: Rc<RefCell<Change>>) -> NormalBuffer {
+        NormalBuffer {
+            i_evnts: Event::default(),
+            change,
+        }
+    }
+
+    fn set_cursor(&mut self, cursor: usize) {
+        self.to_mut_change().set_cursor(cursor);
+    }
+
+    #[inline]
+    fn cud_newlines(&mut self, cursor: Option<usize>, nl: &str, n: usize) -> Result<usize> {
+        self.to_mut_change().cud_newlines(cursor, nl, n)
+    }
+
+    #[inline]
+    fn cud_char(&mut self, cursor: Option<usize>, ch: char) -> Result<usize> {
+        self.to_mut_change().cud_char(cursor, ch)
+    }
+
+    #[inline]
+    fn cud_str(&mut self, cursor: Option<usize>, text: &str) -> Result<usize> {
+        self.to_mut_change().cud_str(cursor, text)
+    }
+
+    #[inline]
+    fn cud_delete<R>(&mut self, range: R) -> Result<()>
+    where
+        R: RangeBounds<usize>,
+    {
+        self.to_mut_change().cud_delete(range)
+    }
+}
+
+impl NormalBuffer {
+    fn to_change(&self) -> cell::Ref<Change> {
         self.change.as_ref().borrow()
     }
 
@@ -986,984 +1954,3088 @@ impl InsertBuffer {
     }
 }
 
-impl InsertBuffer {
-    fn do_repeat(repeat: usize, i_evnts: Event, buf: &mut Buffer) -> Result<Event> {
+impl NormalBuffer {
+    fn on_event(buf: &mut Buffer, mut evnts: Event) -> Result<Event> {
         let mut res_evnts = Event::Noop;
-        // repeat insert, if any, before exiting the insert-mode.
-        for _ in 0..repeat {
-            for evnt in i_evnts.clone().into_iter() {
-                res_evnts.push(Self::do_on_event(buf, evnt)?);
-            }
+        loop {
+            match evnts.next() {
+                Some(evnt) => {
+                    let from = buf.to_char_cursor();
+                    let motion = matches!(&evnt, Event::Mt(_));
+                    let jump = matches!(&evnt, Event::Mt(mto) if mto.is_jump());
+                    res_evnts.push(Self::do_on_event(buf, evnt)?);
+                    if motion {
+                        skip_closed_fold(buf, from);
+                    }
+                    if jump && from != buf.to_char_cursor() {
+                        res_evnts.push(Self::do_on_event(buf, Event::JumpFrom(from))?);
+                    }
+                    match &buf.inner {
+                        Inner::Normal(_) => (),
+                        Inner::Insert(_) | Inner::Visual(_) => {
+                            res_evnts.push(buf.on_event(evnts)?);
+                            break;
+                        }
+                        Inner::Replace(_) => todo!(),
+                        Inner::None => err_at!(Fatal, msg: format!("unreachable"))?,
+                    }
+                }
+                None => break,
+            };
         }
         Ok(res_evnts)
     }
 
-    fn insert_event(&mut self, evnt: Event) -> Result<()> {
-        self.i_evnts.push(evnt);
-        Ok(())
-    }
+    fn do_on_event(buf: &mut Buffer, evnt: Event) -> Result<Event> {
+        debug!("{}", evnt);
 
-    fn on_event(buf: &mut Buffer, mut evnts: Event) -> Result<Event> {
-        use crate::event::Event::*;
+        let dot = if_else!(LastChange::is_change(&evnt), Some(evnt.clone()), None);
 
-        let mut res_evnts = Event::Noop;
-        let evnt = loop {
-            let evnt = match evnts.next() {
-                // first, handle mode events.
-                Some(Md(Mod::Esc)) => {
-                    let evnt = Inner::do_repeat(buf)?;
-                    let cursor = mto_left(buf, 1, DP::LineBound)?;
-                    buf.set_cursor(cursor).clear_sticky_col();
-                    buf.inner = {
-                        let inner = mem::replace(&mut buf.inner, Inner::default());
-                        inner.into_normal_buffer()?
-                    };
-                    break evnt;
+        let evnt = match evnt {
+            // first, try switching to insert mode, if event is insert.
+            Event::Md(Mod::Insert(n, pos)) if n > 0 => mod_insert(buf, n, pos)?,
+            Event::Md(Mod::Append(n, pos)) if n > 0 => mod_append(buf, n, pos)?,
+            Event::Md(Mod::Open(n, pos)) if n > 0 => mod_open(buf, n, pos)?,
+            Event::Md(Mod::Insert(_, _)) => Event::Noop,
+            Event::Md(Mod::Append(_, _)) => Event::Noop,
+            Event::Md(Mod::Open(_, _)) => Event::Noop,
+            Event::Md(Mod::Visual(linewise)) => mod_visual(buf, linewise)?,
+            Event::Md(Mod::VisualBlock) => mod_visual_block(buf)?,
+            Event::Md(Mod::Reselect) => mod_reselect(buf)?,
+            // operator commands.
+            Event::Op(Opr::Delete(n, mto)) => opr_delete(buf, n, mto)?,
+            Event::Op(Opr::Change(n, mto)) => opr_change(buf, n, mto)?,
+            Event::Op(Opr::Yank(n, mto)) => opr_yank(buf, n, mto)?,
+            Event::Op(opr @ Opr::Swapcase(_, _)) => opr_case(buf, opr)?,
+            Event::Op(opr @ Opr::Lowercase(_, _)) => opr_case(buf, opr)?,
+            Event::Op(opr @ Opr::Uppercase(_, _)) => opr_case(buf, opr)?,
+            Event::Op(opr @ Opr::RShift(_, _)) => opr_shift(buf, opr)?,
+            Event::Op(opr @ Opr::LShift(_, _)) => opr_shift(buf, opr)?,
+            Event::Op(opr @ Opr::Format(_, _)) => opr_format(buf, opr)?,
+            Event::Op(opr @ Opr::Fold(_, _)) => opr_fold(buf, opr)?,
+            Event::Put(n, index, dp) => put_register(buf, n, index, dp)?,
+            // delete characters under and before the cursor.
+            Event::Wr(Cud::Delete(n)) => delete_chars(buf, n, DP::Right)?,
+            Event::Wr(Cud::Backspace(n)) => delete_chars(buf, n, DP::Left)?,
+            // replace `n` characters under the cursor.
+            Event::ReplaceChar(n, ch) => replace_chars(buf, n, ch)?,
+            // join `n` lines, and atleast two lines.
+            Event::Join(n) => buf.join_lines(cmp::max(n, 2) - 1)?,
+            // open or close the fold under cursor.
+            Event::Fold(open) => fold_cursor(buf, open)?,
+            // repeat last change.
+            Event::Dot(n) => {
+                let mut evnt = Event::Noop;
+                for _ in 0..n {
+                    evnt.push(buf.repeat_last_change()?);
                 }
-                // then other evnts
-                Some(Event::Noop) => Event::Noop,
-                Some(evnt @ TabInsert(_)) => Self::do_on_event(buf, evnt)?,
-                Some(evnt @ TabClear) => Self::do_on_event(buf, evnt)?,
-                Some(evnt) => {
-                    // save into this insert-session.
-                    buf.inner.insert_event(evnt.clone())?;
-                    Self::do_on_event(buf, evnt)?
+                evnt
+            }
+            // undo and redo command.
+            Event::Undo(n) => {
+                let mut evnt = Event::Noop;
+                for _ in 0..n {
+                    match buf.undo()? {
+                        Event::Noop => break,
+                        e => evnt.push(e),
+                    }
+                }
+                evnt
+            }
+            Event::Redo(n) => {
+                let mut evnt = Event::Noop;
+                for _ in 0..n {
+                    match buf.redo()? {
+                        Event::Noop => break,
+                        e => evnt.push(e),
+                    }
+                }
+                evnt
+            }
+            // motion command - characterwise.
+            Event::Mt(Mto::Left(n, dp)) => {
+                let cursor = mto_left(buf, n, dp)?;
+                buf.set_cursor(cursor).clear_sticky_col();
+                Event::Noop
+            }
+            Event::Mt(Mto::Right(n, dp)) => {
+                let cursor = mto_right(buf, n, dp)?;
+                buf.set_cursor(cursor).clear_sticky_col();
+                Event::Noop
+            }
+            Event::Mt(Mto::LineHome(dp)) => {
+                let cursor = mto_line_home(buf, dp)?;
+                buf.set_cursor(cursor).set_sticky_col(dp, "home");
+                Event::Noop
+            }
+            Event::Mt(Mto::LineEnd(n, dp)) => {
+                let cursor = mto_line_end(buf, n, dp)?;
+                buf.set_cursor(cursor).set_sticky_col(dp, "end");
+                Event::Noop
+            }
+            Event::Mt(Mto::LineMiddle(p, _)) if p < 1 => {
+                let cursor = mto_line_middle(buf, 50)?;
+                buf.set_cursor(cursor).clear_sticky_col();
+                Event::Noop
+            }
+            Event::Mt(Mto::LineMiddle(p, _)) => {
+                let cursor = mto_line_middle(buf, p)?;
+                buf.set_cursor(cursor).clear_sticky_col();
+                Event::Noop
+            }
+            Event::Mt(Mto::Col(n)) => {
+                let cursor = mto_column(buf, n)?;
+                buf.set_cursor(cursor).clear_sticky_col();
+                Event::Noop
+            }
+            Event::Mt(e @ Mto::CharF(_, _, _)) => {
+                buf.mto_find_char = e.clone();
+                let cursor = mto_char(buf, e, false)?;
+                buf.set_cursor(cursor).clear_sticky_col();
+                Event::Noop
+            }
+            Event::Mt(e @ Mto::CharT(_, _, _)) => {
+                buf.mto_find_char = e.clone();
+                let cursor = mto_char(buf, e, false)?;
+                buf.set_cursor(cursor).clear_sticky_col();
+                Event::Noop
+            }
+            Event::Mt(Mto::CharR(n, dir)) => {
+                let e = buf.mto_find_char.clone();
+                let cursor = mto_char(buf, e.dir_xor(n, dir)?, true)?;
+                buf.set_cursor(cursor).clear_sticky_col();
+                Event::Noop
+            }
+            // motion command - linewise.
+            Event::Mt(Mto::Up(n, dp)) => {
+                let cursor = mto_up(buf, n, dp)?;
+                buf.set_cursor(cursor);
+                Event::Noop
+            }
+            Event::Mt(Mto::Down(n, dp)) => {
+                let cursor = mto_down(buf, n, dp)?;
+                buf.set_cursor(cursor);
+                Event::Noop
+            }
+            Event::Mt(Mto::Row(n, dp)) => {
+                let n = n.saturating_sub(1);
+                let cursor = mto_row(buf, n, dp)?;
+                buf.set_cursor(cursor).clear_sticky_col();
+                Event::Noop
+            }
+            Event::Mt(Mto::Percent(n, dp)) => {
+                let cursor = mto_percent(buf, n, dp)?;
+                buf.set_cursor(cursor);
+                Event::Noop
+            }
+            Event::Mt(Mto::Cursor(n)) => {
+                let cursor = mto_cursor(buf, n)?;
+                buf.set_cursor(cursor);
+                Event::Noop
+            }
+            // motion command - word/sentence/para wise
+            Event::Mt(Mto::Word(n, DP::Left, pos)) => {
+                let cursor = mto_words_left(buf, n, pos)?;
+                buf.set_cursor(cursor).clear_sticky_col();
+                Event::Noop
+            }
+            Event::Mt(Mto::Word(n, DP::Right, pos)) => {
+                let cursor = mto_words_right(buf, n, pos)?;
+                buf.set_cursor(cursor).clear_sticky_col();
+                Event::Noop
+            }
+            Event::Mt(Mto::WWord(n, DP::Left, pos)) => {
+                let cursor = mto_wwords_left(buf, n, pos)?;
+                buf.set_cursor(cursor).clear_sticky_col();
+                Event::Noop
+            }
+            Event::Mt(Mto::WWord(n, DP::Right, pos)) => {
+                let cursor = mto_wwords_right(buf, n, pos)?;
+                buf.set_cursor(cursor).clear_sticky_col();
+                Event::Noop
+            }
+            Event::Mt(Mto::Sentence(n, dp)) => {
+                let cursor = mto_sentence(buf, n, dp)?;
+                buf.set_cursor(cursor).clear_sticky_col();
+                Event::Noop
+            }
+            Event::Mt(Mto::Para(n, DP::Left)) => {
+                let cursor = mto_paras_left(buf, n)?;
+                buf.set_cursor(cursor).clear_sticky_col();
+                Event::Noop
+            }
+            Event::Mt(Mto::Para(n, DP::Right)) => {
+                let cursor = mto_paras_right(buf, n)?;
+                buf.set_cursor(cursor).clear_sticky_col();
+                Event::Noop
+            }
+            // motion command, other motions.
+            Event::Mt(Mto::MatchPair) => {
+                let cursor = mto_match_pair(buf)?;
+                buf.set_cursor(cursor).clear_sticky_col();
+                Event::Noop
+            }
+            Event::Mt(Mto::UnmatchPair(n, ch, dir)) => {
+                let cursor = mto_unmatch_pair(buf, ch, n, dir)?;
+                buf.set_cursor(cursor).clear_sticky_col();
+                Event::Noop
+            }
+            // motion command marks and jumps
+            Event::Mt(Mto::Jump(typ, mindex)) => {
+                let mrk = mark::get_mark(&buf.marks, mindex);
+                match mrk {
+                    Some(mrk) if typ == '`' => {
+                        let cursor = mrk.to_cursor();
+                        buf.set_cursor(cursor).clear_sticky_col();
+                        Event::Noop
+                    }
+                    Some(mrk) if typ == '\'' => {
+                        buf.set_cursor(mrk.to_cursor());
+                        let cursor = mto_line_home(buf, DP::TextCol)?;
+                        buf.set_cursor(cursor).clear_sticky_col();
+                        Event::Noop
+                    }
+                    _ => Event::Mt(Mto::Jump(typ, mindex)),
+                }
+            }
+            Event::Mt(Mto::JumpList(n, dp)) => {
+                let cursor = buf.to_char_cursor();
+                let cursor = match dp {
+                    DP::Left => buf.jumps.backward(cursor, n),
+                    _ => buf.jumps.forward(n),
+                };
+                if let Some(cursor) = cursor {
+                    let cursor = cmp::min(cursor, buf.n_chars());
+                    buf.set_cursor(cursor).clear_sticky_col();
+                }
+                Event::Noop
+            }
+            Event::Mt(Mto::ChangeList(n, dp)) => {
+                let cursor = match dp {
+                    DP::Left => buf.changes.backward(n),
+                    _ => buf.changes.forward(n),
+                };
+                if let Some(cursor) = cursor {
+                    let cursor = cmp::min(cursor, buf.n_chars());
+                    buf.set_cursor(cursor).clear_sticky_col();
+                }
+                Event::Noop
+            }
+            Event::JumpFrom(cursor) => {
+                buf.set_jump(cursor);
+                Event::Noop
+            }
+            Event::Reg(index) => {
+                buf.register = Some(index);
+                Event::Noop
+            }
+            Event::Mr(mrk) => match mrk.to_index() {
+                'a'..='z' | '\'' | '`' => {
+                    let mrk = mrk.into_mark(buf);
+                    mark::set_mark(&mut buf.marks, mrk);
+                    Event::Noop
+                }
+                'A'..='Z' => Event::Mr(mrk.into_mark(buf)),
+                _ => Event::Noop,
+            },
+            Event::Mt(e @ Mto::Bracket(_, _, _, _)) => mto_bracket(buf, e)?,
+            Event::Mt(e @ Mto::Pattern(_, Some(_), _)) => {
+                buf.mto_pattern = e.clone();
+
+                let (cursor, evnt) = mto_pattern(buf, e)?;
+                buf.set_cursor(cursor).clear_sticky_col();
+                evnt
+            }
+            Event::Mt(Mto::PatternR(n, dir)) => {
+                let e = buf.mto_pattern.clone();
+
+                let (cursor, evnt) = mto_pattern(buf, e.dir_xor(n, dir)?)?;
+                buf.set_cursor(cursor).clear_sticky_col();
+                evnt
+            }
+            Event::Mt(Mto::SearchWord(n, dp)) => {
+                let (cursor, evnt) = mto_search_word(buf, n, dp)?;
+                buf.set_cursor(cursor).clear_sticky_col();
+                evnt
+            }
+            evnt => evnt,
+        };
+
+        // remember the change for dot command, changes that switched to
+        // insert mode are remembered when the insert session completes.
+        match (dot, &mut buf.inner) {
+            (Some(dot), Inner::Insert(ib)) => ib.dot = dot,
+            (Some(dot), _) => buf.last_change = LastChange::new(dot),
+            (None, _) => (),
+        }
+
+        Ok(evnt)
+    }
+}
+
+#[derive(Clone)]
+struct InsertBuffer {
+    repeat: usize,
+    // event that started this insert-session, for dot command.
+    dot: Event,
+    i_evnts: Event,
+    // cursor after the last edit in this insert-session, to repeat from.
+    edit_cursor: Option<usize>,
+    // insert-session opened a new line, each repeat shall open another.
+    open: bool,
+    // insert-session on a visual-block, as (start, last-row, pad), refer
+    // [mod_insert_block].
+    block: Option<(usize, usize, bool)>,
+    // cursor was moved within this insert-session, subsequent edits shall
+    // go into a new change, so that they can be undone separately.
+    moved: bool,
+    change: Rc<RefCell<Change>>,
+}
+
+impl InsertBuffer {
+    fn new(repeat: usize, change: Rc<RefCell<Change>>) -> Self {
+        InsertBuffer {
+            repeat: repeat.saturating_sub(1),
+            dot: Event::default(),
+            i_evnts: Event::default(),
+            edit_cursor: None,
+            open: false,
+            block: None,
+            moved: false,
+            change,
+        }
+    }
+
+    fn set_cursor(&mut self, cursor: usize) {
+        self.to_mut_change().set_cursor(cursor)
+    }
+
+    #[inline]
+    fn cud_newlines(&mut self, cursor: Option<usize>, nl: &str, n: usize) -> Result<usize> {
+        self.to_mut_change().cud_newlines(cursor, nl, n)
+    }
+
+    #[inline]
+    fn cud_char(&mut self, cursor: Option<usize>, ch: char) -> Result<usize> {
+        self.to_mut_change().cud_char(cursor, ch)
+    }
+
+    #[inline]
+    fn cud_str(&mut self, cursor: Option<usize>, text: &str) -> Result<usize> {
+        self.to_mut_change().cud_str(cursor, text)
+    }
+
+    #[inline]
+    fn cud_delete<R>(&mut self, range: R) -> Result<()>
+    where
+        R: RangeBounds<usize>,
+    {
+        self.to_mut_change().cud_delete(range)
+    }
+}
+
+impl InsertBuffer {
+    fn to_change(&self) -> cell::Ref<Change> {
+        self.change.as_ref().borrow()
+    }
+
+    fn to_mut_change(&mut self) -> cell::RefMut<Change> {
+        self.change.as_ref().borrow_mut()
+    }
+
+    fn as_mut_change(&mut self) -> &mut Rc<RefCell<Change>> {
+        &mut self.change
+    }
+}
+
+impl InsertBuffer {
+    fn do_repeat(repeat: usize, open: bool, i_evnts: Event, buf: &mut Buffer) -> Result<Event> {
+        let mut res_evnts = Event::Noop;
+        // repeat insert, if any, before exiting the insert-mode.
+        for _ in 0..repeat {
+            if open {
+                res_evnts.push(open_below(buf)?);
+            }
+            for evnt in i_evnts.clone().into_iter() {
+                res_evnts.push(Self::do_on_event(buf, evnt)?);
+            }
+        }
+        Ok(res_evnts)
+    }
+
+    fn insert_event(&mut self, evnt: Event) -> Result<()> {
+        // characters typed within an insert-session are undone together,
+        // upto an explicit cursor motion.
+        match (&evnt, self.moved) {
+            (Event::Mt(_), _) => self.moved = true,
+            (_, true) => {
+                self.moved = false;
+                self.change = Change::fork(&mut self.change);
+            }
+            (_, false) => (),
+        }
+        self.i_evnts.push(evnt);
+        Ok(())
+    }
+
+    fn on_event(buf: &mut Buffer, mut evnts: Event) -> Result<Event> {
+        use crate::event::Event::*;
+
+        let mut res_evnts = Event::Noop;
+        let evnt = loop {
+            let evnt = match evnts.next() {
+                // first, handle mode events.
+                Some(Md(Mod::Esc)) => {
+                    if let Inner::Insert(ib) = &buf.inner {
+                        if ib.dot != Event::Noop {
+                            let (dot, inserts) = (ib.dot.clone(), ib.i_evnts.clone());
+                            buf.last_change = LastChange::with_inserts(dot, inserts);
+                        }
+                    }
+                    let evnt = Inner::do_repeat(buf)?;
+                    let cursor = mto_left(buf, 1, DP::LineBound)?;
+                    buf.set_cursor(cursor).clear_sticky_col();
+                    buf.inner = {
+                        let inner = mem::replace(&mut buf.inner, Inner::default());
+                        inner.into_normal_buffer()?
+                    };
+                    break evnt;
+                }
+                // then other evnts
+                Some(Event::Noop) => Event::Noop,
+                Some(evnt @ TabInsert(_)) => Self::do_on_event(buf, evnt)?,
+                Some(evnt @ TabClear) => Self::do_on_event(buf, evnt)?,
+                Some(evnt) => {
+                    // save into this insert-session.
+                    buf.inner.insert_event(evnt.clone())?;
+                    let motion = matches!(evnt, Mt(_));
+                    let evnt = Self::do_on_event(buf, evnt)?;
+                    let cursor = buf.to_char_cursor();
+                    match &mut buf.inner {
+                        Inner::Insert(ib) if !motion => ib.edit_cursor = Some(cursor),
+                        _ => (),
+                    }
+                    evnt
+                }
+                None => break Event::Noop,
+            };
+            res_evnts.push(evnt)
+        };
+        res_evnts.push(evnt);
+        Ok(res_evnts)
+    }
+
+    fn do_on_event(buf: &mut Buffer, evnt: Event) -> Result<Event> {
+        use crate::event::{self, Cud, Event::*};
+
+        debug!("{}", evnt);
+
+        // handle tab-rotation for completion.
+        let evnt = match (buf.tab_state.clone(), evnt) {
+            (TabState::None, TabInsert(newt)) => {
+                let cursor = {
+                    let cursor = buf.to_char_cursor();
+                    buf.cud_str(Some(cursor), newt.as_str())?
+                };
+                buf.set_cursor(cursor).clear_sticky_col();
+
+                buf.tab_state = TabState::Active(newt.clone());
+                Edit(event::Edit::new_ins(cursor, newt))
+            }
+            (TabState::None, TabClear) => Event::Noop,
+            (TabState::None, evnt) => evnt,
+            (TabState::Active(oldt), TabInsert(newt)) => {
+                let cursor = {
+                    let cursor = buf.to_char_cursor();
+                    cursor.saturating_sub(text::width(oldt.chars()))
+                };
+                let to = cursor + oldt.chars().count();
+                buf.cud_delete(cursor..to)?;
+                let cursor = buf.cud_str(Some(cursor), newt.as_str())?;
+                buf.set_cursor(cursor).clear_sticky_col();
+
+                buf.tab_state = TabState::Active(newt.clone());
+                Edit(event::Edit::new_chg(cursor, oldt, newt))
+            }
+            (TabState::Active(oldt), TabClear) => {
+                let cursor = {
+                    let cursor = buf.to_char_cursor();
+                    cursor.saturating_sub(text::width(oldt.chars()))
+                };
+                let to = cursor + oldt.chars().count();
+                buf.cud_delete(cursor..to)?;
+                buf.set_cursor(cursor);
+
+                buf.tab_state = TabState::default();
+                Edit(event::Edit::new_del(cursor, oldt))
+            }
+            (TabState::Active(_), evnt) => {
+                buf.tab_state = TabState::default();
+                evnt
+            }
+        };
+
+        let evnt = match evnt {
+            // cursor movement
+            Mt(Mto::Left(n, dp)) => {
+                let cursor = mto_left(buf, n, dp)?;
+                buf.set_cursor(cursor).clear_sticky_col();
+                Event::Noop
+            }
+            Mt(Mto::Right(n, dp)) => {
+                let cursor = mto_right(buf, n, dp)?;
+                buf.set_cursor(cursor).clear_sticky_col();
+                Event::Noop
+            }
+            Mt(Mto::Up(n, dp)) => {
+                let cursor = mto_up(buf, n, dp)?;
+                buf.set_cursor(cursor);
+                Event::Noop
+            }
+            Mt(Mto::Down(n, dp)) => {
+                let cursor = mto_down(buf, n, dp)?;
+                buf.set_cursor(cursor);
+                Event::Noop
+            }
+            Mt(Mto::LineHome(dp)) => {
+                let cursor = mto_line_home(buf, dp)?;
+                buf.set_cursor(cursor).set_sticky_col(dp, "home");
+                Event::Noop
+            }
+            Mt(Mto::LineEnd(n, dp)) => {
+                let cursor = mto_line_end(buf, n, dp)?;
+                buf.set_cursor(cursor).set_sticky_col(dp, "end");
+                Event::Noop
+            }
+            // insert session
+            Wr(Cud::Char(ch)) => {
+                let mut evnt = match ch.is_alphanumeric() || ch == '_' {
+                    true => Event::Noop,
+                    false => expand_iabbrev(buf)?,
+                };
+                let cursor = buf.to_char_cursor();
+                {
+                    let cursor = buf.cud_char(None, ch)?;
+                    buf.set_cursor(cursor).clear_sticky_col();
+                }
+                evnt.push(Edit(event::Edit::new_ins(cursor, ch.into())));
+                evnt
+            }
+            // pasted text is inserted as is, without auto-indent and
+            // abbreviations, only newlines are normalized.
+            Paste(txt) => {
+                let cursor = buf.to_char_cursor();
+                let txt = {
+                    let txt = txt.replace("\r\n", "\n").replace('\r', "\n");
+                    txt.replace('\n', buf.format.newline())
+                };
+                {
+                    let cursor = buf.cud_str(None, &txt)?;
+                    buf.set_cursor(cursor).clear_sticky_col();
+                }
+                Edit(event::Edit::new_ins(cursor, txt))
+            }
+            Wr(Cud::Tab(n)) => {
+                let mut evnt = expand_iabbrev(buf)?;
+                let cursor = buf.to_char_cursor();
+                let txt = match buf.expand_tab {
+                    true => {
+                        let n = to_soft_tab(buf, cursor, n);
+                        " ".repeat(n)
+                    }
+                    false => "\t".repeat(n),
+                };
+                {
+                    let cursor = buf.cud_str(None, &txt)?;
+                    buf.set_cursor(cursor).clear_sticky_col();
+                }
+                evnt.push(Edit(event::Edit::new_ins(cursor, txt)));
+                evnt
+            }
+            Wr(Cud::Enter(n)) => {
+                let mut evnt = expand_iabbrev(buf)?;
+                let cursor = buf.to_char_cursor();
+                let indent = {
+                    let xy = buf.to_xy_cursor(Some(cursor));
+                    let line: String = buf.line(xy.row).chars().take(xy.col).collect();
+                    to_auto_indent(buf, &line)
+                };
+                let to = buf.cud_newlines(Some(cursor), buf.format.newline(), n)?;
+                let to = buf.cud_str(Some(to), &indent)?;
+                buf.set_cursor(to).clear_sticky_col();
+                evnt.push(Edit(event::Edit::new_ins(cursor, buf.slice(cursor..to))));
+                evnt
+            }
+            Wr(Cud::Backspace(n)) if buf.to_char_cursor() > 0 => {
+                let cursor = buf.to_char_cursor();
+                let from = match buf.expand_tab && buf.soft_tab_stop {
+                    true => (0..n).fold(cursor, |from, _| from - to_soft_tab_back(buf, from)),
+                    false => cursor.saturating_sub(n),
+                };
+                let txt = buf.slice(from..cursor);
+                buf.cud_delete(from..cursor)?;
+                buf.set_cursor(from);
+                Edit(event::Edit::new_del(from, txt))
+            }
+            Wr(Cud::Delete(n)) => {
+                let cursor = buf.to_char_cursor();
+                let to = cmp::min(cursor.saturating_add(n), buf.n_chars());
+                let txt = buf.slice(cursor..to);
+                buf.cud_delete(cursor..to)?;
+                Edit(event::Edit::new_del(cursor, txt))
+            }
+            Wr(Cud::RShift(n)) | Wr(Cud::LShift(n)) => {
+                let dp = if_else!(matches!(evnt, Wr(Cud::RShift(_))), DP::Right, DP::Left);
+                let cursor = buf.to_char_cursor();
+                let (row, n_old) = {
+                    let row = buf.char_to_line(cursor);
+                    (row, line_chars(buf, row))
+                };
+                // part of the insert session, don't fork the change.
+                let evnt = shift_text(buf, row, row + 1, n, dp, false)?;
+                // keep the cursor on the same text.
+                let home = buf.line_to_char(row);
+                let cursor = (cursor + line_chars(buf, row)).saturating_sub(n_old);
+                buf.set_cursor(cmp::max(cursor, home)).clear_sticky_col();
+                evnt
+            }
+            evnt => evnt,
+        };
+
+        Ok(evnt)
+    }
+}
+
+#[derive(Clone)]
+struct VisualBuffer {
+    // selection anchor, as char-index.
+    anchor: usize,
+    linewise: bool,
+    // rectangular selection, between the anchor's and cursor's columns.
+    block: bool,
+    change: Rc<RefCell<Change>>,
+}
+
+impl From<VisualBuffer> for NormalBuffer {
+    fn from(vb: VisualBuffer) -> NormalBuffer {
+        NormalBuffer::new(vb.change)
+    }
+}
+
+impl VisualBuffer {
+    fn new(anchor: usize, linewise: bool, change: Rc<RefCell<Change>>) -> Self {
+        VisualBuffer {
+            anchor,
+            linewise,
+            block: false,
+            change,
+        }
+    }
+
+    fn set_cursor(&mut self, cursor: usize) {
+        self.to_mut_change().set_cursor(cursor)
+    }
+
+    #[inline]
+    fn cud_newlines(&mut self, cursor: Option<usize>, nl: &str, n: usize) -> Result<usize> {
+        self.to_mut_change().cud_newlines(cursor, nl, n)
+    }
+
+    #[inline]
+    fn cud_char(&mut self, cursor: Option<usize>, ch: char) -> Result<usize> {
+        self.to_mut_change().cud_char(cursor, ch)
+    }
+
+    #[inline]
+    fn cud_str(&mut self, cursor: Option<usize>, text: &str) -> Result<usize> {
+        self.to_mut_change().cud_str(cursor, text)
+    }
+
+    #[inline]
+    fn cud_delete<R>(&mut self, range: R) -> Result<()>
+    where
+        R: RangeBounds<usize>,
+    {
+        self.to_mut_change().cud_delete(range)
+    }
+}
+
+impl VisualBuffer {
+    fn to_change(&self) -> cell::Ref<Change> {
+        self.change.as_ref().borrow()
+    }
+
+    fn to_mut_change(&mut self) -> cell::RefMut<Change> {
+        self.change.as_ref().borrow_mut()
+    }
+
+    fn as_mut_change(&mut self) -> &mut Rc<RefCell<Change>> {
+        &mut self.change
+    }
+}
+
+impl VisualBuffer {
+    fn on_event(buf: &mut Buffer, mut evnts: Event) -> Result<Event> {
+        let mut res_evnts = Event::Noop;
+        loop {
+            match evnts.next() {
+                Some(evnt) => {
+                    let from = buf.to_char_cursor();
+                    let motion = matches!(&evnt, Event::Mt(_));
+                    res_evnts.push(Self::do_on_event(buf, evnt)?);
+                    if motion {
+                        skip_closed_fold(buf, from);
+                    }
+                    match &buf.inner {
+                        Inner::Visual(_) => (),
+                        Inner::Normal(_) | Inner::Insert(_) => {
+                            res_evnts.push(buf.on_event(evnts)?);
+                            break;
+                        }
+                        Inner::Replace(_) | Inner::None => {
+                            err_at!(Fatal, msg: format!("unreachable"))?
+                        }
+                    }
+                }
+                None => break,
+            };
+        }
+        Ok(res_evnts)
+    }
+
+    fn do_on_event(buf: &mut Buffer, evnt: Event) -> Result<Event> {
+        debug!("{}", evnt);
+
+        let (from, to, linewise) = match buf.to_visual_range() {
+            Some(range) => range,
+            None => err_at!(Fatal, msg: format!("unreachable"))?,
+        };
+        let block = buf.to_visual_block();
+
+        if let Some(blk) = block {
+            match &evnt {
+                Event::Op(Opr::Delete(_, _))
+                | Event::Op(Opr::Change(_, _))
+                | Event::Op(Opr::Yank(_, _))
+                | Event::Md(Mod::Insert(_, _))
+                | Event::Md(Mod::Append(_, _)) => return Self::do_block_event(buf, blk, evnt),
+                _ => (),
+            }
+        }
+
+        let evnt = match evnt {
+            Event::Md(Mod::Esc) => {
+                buf.set_normal_mode();
+                Event::Noop
+            }
+            Event::Md(Mod::Visual(lw)) if lw == linewise && block.is_none() => {
+                buf.set_normal_mode();
+                Event::Noop
+            }
+            Event::Md(Mod::Visual(lw)) => {
+                if let Inner::Visual(vb) = &mut buf.inner {
+                    vb.linewise = lw;
+                    vb.block = false;
+                }
+                Event::Noop
+            }
+            Event::Md(Mod::VisualBlock) if block.is_some() => {
+                buf.set_normal_mode();
+                Event::Noop
+            }
+            Event::Md(Mod::VisualBlock) => {
+                if let Inner::Visual(vb) = &mut buf.inner {
+                    vb.linewise = false;
+                    vb.block = true;
+                }
+                Event::Noop
+            }
+            // inserts are only on visual-block.
+            Event::Md(Mod::Insert(_, _)) | Event::Md(Mod::Append(_, _)) => Event::Noop,
+            // operator commands, on the selected range.
+            Event::Op(Opr::Delete(_, _)) => {
+                buf.set_normal_mode();
+                delete_text(buf, from, to, linewise)?
+            }
+            Event::Op(Opr::Change(_, _)) => {
+                buf.set_normal_mode();
+                change_text(buf, from, to, linewise)?
+            }
+            Event::Op(Opr::Yank(_, _)) => {
+                buf.set_normal_mode();
+                yank_text(buf, from, to, linewise)?
+            }
+            Event::Op(opr @ Opr::Swapcase(_, _))
+            | Event::Op(opr @ Opr::Lowercase(_, _))
+            | Event::Op(opr @ Opr::Uppercase(_, _)) => {
+                buf.set_normal_mode();
+                let evnt = case_text(buf, from, to, &opr)?;
+                buf.set_cursor(from).clear_sticky_col();
+                evnt
+            }
+            Event::Op(opr @ Opr::RShift(_, _)) | Event::Op(opr @ Opr::LShift(_, _)) => {
+                buf.set_normal_mode();
+                shift_range(buf, from, to, &opr)?
+            }
+            Event::Op(Opr::Format(_, _)) => {
+                buf.set_normal_mode();
+                format_range(buf, from, to)?
+            }
+            Event::Op(Opr::Fold(_, _)) => {
+                buf.set_normal_mode();
+                fold_range(buf, from, to)?
+            }
+            // motion commands, extend the selection.
+            evnt @ Event::Mt(_) => NormalBuffer::do_on_event(buf, evnt)?,
+            evnt @ Event::Reg(_) => NormalBuffer::do_on_event(buf, evnt)?,
+            evnt => evnt,
+        };
+
+        Ok(evnt)
+    }
+
+    fn do_block_event(
+        buf: &mut Buffer,
+        blk: (usize, usize, usize, usize),
+        evnt: Event,
+    ) -> Result<Event> {
+        let (rf, rt, cf, ct) = blk;
+
+        buf.set_normal_mode();
+        match evnt {
+            Event::Op(Opr::Delete(_, _)) => delete_block(buf, blk),
+            Event::Op(Opr::Change(_, _)) => change_block(buf, blk),
+            Event::Op(Opr::Yank(_, _)) => yank_block(buf, blk),
+            Event::Md(Mod::Insert(_, _)) => mod_insert_block(buf, rf, rt, cf, false),
+            Event::Md(Mod::Append(_, _)) => mod_insert_block(buf, rf, rt, ct + 1, true),
+            Event::Op(Opr::Fold(_, _)) => fold_lines(buf, rf, rt),
+            evnt => Ok(evnt),
+        }
+    }
+}
+
+#[derive(Clone)]
+struct ReplaceBuffer {
+    change: Rc<RefCell<Change>>,
+}
+
+impl From<NormalBuffer> for ReplaceBuffer {
+    fn from(nb: NormalBuffer) -> ReplaceBuffer {
+        ReplaceBuffer { change: nb.change }
+    }
+}
+
+impl ReplaceBuffer {
+    fn set_cursor(&mut self, cursor: usize) {
+        self.to_mut_change().set_cursor(cursor)
+    }
+
+    #[inline]
+    fn cud_newlines(&mut self, cursor: Option<usize>, nl: &str, n: usize) -> Result<usize> {
+        self.to_mut_change().cud_newlines(cursor, nl, n)
+    }
+
+    #[inline]
+    fn cud_char(&mut self, _cursor: Option<usize>, _ch: char) -> Result<usize> {
+        todo!()
+    }
+
+    #[inline]
+    fn cud_str(&mut self, _cursor: Option<usize>, _text: &str) -> Result<usize> {
+        todo!()
+    }
+
+    #[inline]
+    fn cud_delete<R>(&mut self, _range: R) -> Result<()>
+    where
+        R: RangeBounds<usize>,
+    {
+        todo!()
+    }
+}
+
+impl ReplaceBuffer {
+    fn to_change(&self) -> cell::Ref<Change> {
+        self.change.as_ref().borrow()
+    }
+
+    fn to_mut_change(&mut self) -> cell::RefMut<Change> {
+        self.change.as_ref().borrow_mut()
+    }
+
+    fn as_mut_change(&mut self) -> &mut Rc<RefCell<Change>> {
+        &mut self.change
+    }
+}
+
+// A change captures a single session of CUD commands. The main
+// purpose is to implement undo/redo and few other associated features.
+//
+// NOTE: Visualize the change tree, maintained via `past` and `news`
+// references, as inverted tree of changes, with root being the oldest
+// change.
+#[derive(Clone)]
+struct Change {
+    // persistent clone shared among all previous changes. Cloning a rope
+    // is O(1), and clones share their unmodified nodes, so a change only
+    // costs the nodes touched by its edits.
+    rope: Rope,
+    // a change always come from a single past.
+    past: Option<rc::Weak<RefCell<Change>>>,
+    // and it can have ZERO or more futures.
+    news: Vec<Rc<RefCell<Change>>>,
+    // redo path, offset into `news`.
+    redo: Option<usize>,
+    // list of events for this change-session.
+    cuds: Event,
+    // last and latest cursor position for this change.
+    cursor: usize,
+}
+
+impl Default for Change {
+    fn default() -> Change {
+        Change {
+            rope: Rope::from_reader(io::empty()).unwrap(),
+            past: None,
+            news: Vec::default(),
+            redo: None,
+            cuds: Event::Noop,
+            cursor: 0,
+        }
+    }
+}
+
+impl AsRef<Rope> for Change {
+    fn as_ref(&self) -> &Rope {
+        &self.rope
+    }
+}
+
+impl Change {
+    // Start from the first change.
+    fn start(rope: Rope) -> Rc<RefCell<Change>> {
+        Rc::new(RefCell::new(Change {
+            rope,
+            past: None,
+            news: Vec::default(),
+            redo: None,
+            cuds: Event::Noop,
+            cursor: 0,
+        }))
+    }
+
+    // fork from current `change`, where current change can be the
+    // leaf node of the inverted tree, or an intermediate node
+    // of the inverted tree.
+    //
+    // a fork always create a new leaf.
+    fn fork(change: &mut Rc<RefCell<Change>>) -> Rc<RefCell<Change>> {
+        let leaf = {
+            let cc: &Change = &change.as_ref().borrow();
+            Rc::new(RefCell::new(Change {
+                rope: cc.as_ref().clone(),
+                past: Some(Rc::downgrade(change)),
+                news: Vec::default(),
+                redo: None,
+                cuds: Event::Noop,
+                cursor: cc.cursor, // inherit the cursor position.
+            }))
+        };
+        {
+            let cc: &mut Change = &mut change.as_ref().borrow_mut();
+            let redo = cc.news.len();
+            cc.news.push(Rc::clone(&leaf));
+            cc.redo = Some(redo);
+        }
+        leaf
+    }
+
+    #[inline]
+    fn to_char_cursor(&self) -> usize {
+        self.cursor
+    }
+
+    fn to_xy_cursor(&self, cursor: Option<usize>) -> Cursor {
+        let cursor = cursor.unwrap_or(self.cursor);
+        let row_at = self.rope.char_to_line(cursor);
+        let col_at = cursor.saturating_sub(self.rope.line_to_char(row_at));
+        (col_at, row_at).into()
+    }
+
+    fn is_modified(&self) -> bool {
+        let mut modified = match self.cuds {
+            Event::Noop => false,
+            _ => true,
+        };
+        modified = modified || self.past.is_some();
+        modified = modified || self.news.len() > 0;
+        modified
+    }
+}
+
+impl Change {
+    fn set_cursor(&mut self, cursor: usize) {
+        self.cursor = cursor;
+    }
+
+    fn cud_newlines(&mut self, cursor: Option<usize>, nl: &str, n: usize) -> Result<usize> {
+        let cursor = cursor.unwrap_or(self.cursor);
+
+        let mut new_line = String::default();
+        (0..n).for_each(|_| new_line.push_str(nl));
+        self.rope.insert(cursor, &new_line);
+
+        Ok(cursor + new_line.chars().count())
+    }
+
+    fn cud_char(&mut self, cursor: Option<usize>, ch: char) -> Result<usize> {
+        let cursor = cursor.unwrap_or(self.cursor);
+        self.rope.insert_char(cursor, ch);
+        Ok(cursor + 1)
+    }
+
+    fn cud_str(&mut self, cursor: Option<usize>, txt: &str) -> Result<usize> {
+        let cursor = cursor.unwrap_or(self.cursor);
+        self.rope.insert(cursor, txt);
+        Ok(cursor + txt.chars().count())
+    }
+
+    fn cud_delete<R>(&mut self, range: R) -> Result<()>
+    where
+        R: RangeBounds<usize>,
+    {
+        let (from, to) = to_char_range(range, self.rope.len_chars());
+        if from < to {
+            self.rope.remove(from..to);
+        }
+        Ok(())
+    }
+}
+
+fn mto_left(buf: &Buffer, n: usize, dp: DP) -> Result<usize> {
+    let cursor = buf.to_char_cursor();
+    match buf.grapheme_motion {
+        true => (0..n).try_fold(cursor, |cursor, _| {
+            let cursor = mto_left_chars(buf, cursor, 1, dp)?;
+            Ok(to_grapheme_start(buf, cursor))
+        }),
+        false => mto_left_chars(buf, cursor, n, dp),
+    }
+}
+
+fn mto_left_chars(buf: &Buffer, cursor: usize, mut n: usize, dp: DP) -> Result<usize> {
+    let home = buf.to_line_home(Some(cursor));
+    let new_cursor = cursor.saturating_sub(n);
+
+    let cursor = match dp {
+        DP::LineBound if new_cursor >= home => new_cursor,
+        DP::LineBound => home,
+        DP::Nobound if new_cursor >= home => new_cursor,
+        DP::Nobound => {
+            n = n - (cursor - home);
+            let mut iter = (0..buf.char_to_line(cursor)).rev();
+            loop {
+                match iter.next() {
+                    Some(line_idx) => {
+                        let s = buf.line(line_idx);
+                        let home = buf.line_to_char(line_idx);
+                        match text::visual_line_n(&s) {
+                            m if m == n => break home,
+                            m if m > n => break home + (m - n),
+                            m => n = n - m,
+                        }
+                    }
+                    None => break 0,
+                }
+            }
+        }
+        DP::None => new_cursor,
+        dp => err_at!(Fatal, msg: format!("invalid direction: {}", dp))?,
+    };
+    Ok(cursor)
+}
+
+fn mto_right(buf: &Buffer, n: usize, dp: DP) -> Result<usize> {
+    let cursor = buf.to_char_cursor();
+    match buf.grapheme_motion {
+        true => (0..n).try_fold(cursor, |cursor, _| {
+            let n = {
+                let xy = buf.to_xy_cursor(Some(cursor));
+                text::grapheme_len(&buf.line(xy.row), xy.col)
+            };
+            let cursor = mto_right_chars(buf, cursor, n, dp)?;
+            Ok(to_grapheme_start(buf, cursor))
+        }),
+        false => mto_right_chars(buf, cursor, n, dp),
+    }
+}
+
+fn mto_right_chars(buf: &Buffer, cursor: usize, mut n: usize, dp: DP) -> Result<usize> {
+    let line_idx = buf.char_to_line(cursor);
+    let home = buf.to_line_home(Some(cursor));
+    let end = {
+        let s = buf.line(line_idx);
+        home + text::visual_line_n(&s)
+    };
+    let new_cursor = cursor + n;
+
+    let cursor = match dp {
+        DP::LineBound if new_cursor < end => new_cursor,
+        DP::LineBound if home < end => end.saturating_sub(1),
+        DP::LineBound => end,
+        DP::Nobound if new_cursor < end => new_cursor,
+        DP::Nobound => {
+            let mut iter = buf.lines_at(line_idx, DP::Right)?.enumerate();
+            loop {
+                match iter.next() {
+                    Some((0, _)) => n = n - (end - cursor),
+                    Some((i, line)) => {
+                        let m = text::visual_line_n(&line);
+                        match buf.line_to_char(line_idx + i) {
+                            home if n <= m => break home + n.saturating_sub(1),
+                            _ => n = n - m,
+                        }
+                    }
+                    None => break mto_end(buf)?,
+                }
+            }
+        }
+        dp => err_at!(Fatal, msg: format!("invalid direction: {}", dp))?,
+    };
+    Ok(cursor)
+}
+
+// Return the start of grapheme cluster containing `cursor`.
+fn to_grapheme_start(buf: &Buffer, cursor: usize) -> usize {
+    let xy = buf.to_xy_cursor(Some(cursor));
+    let home = buf.line_to_char(xy.row);
+    home + text::grapheme_start(&buf.line(xy.row), xy.col)
+}
+
+fn mto_line_home(buf: &Buffer, pos: DP) -> Result<usize> {
+    let cursor = buf.to_line_home(None);
+    let cursor = match pos {
+        DP::TextCol => {
+            let xy = buf.to_change().to_xy_cursor(Some(cursor));
+            let n = skip_whitespace(&buf.line(xy.row), xy.col, DP::Right)?;
+            cursor + n
+        }
+        DP::StickyCol | DP::None => cursor,
+        dp => err_at!(Fatal, msg: format!("invalid direction: {}", dp))?,
+    };
+    Ok(cursor)
+}
+
+fn mto_line_end(buf: &Buffer, n: usize, dp: DP) -> Result<usize> {
+    // When a `n` is given also go `n-1` lines downward.
+    let cursor = {
+        let cursor = mto_down(buf, n.saturating_sub(1), DP::None)?;
+        let s = buf.line(buf.char_to_line(cursor));
+        let m = text::visual_line_n(&s).saturating_sub(1);
+        buf.to_line_home(Some(cursor)) + m
+    };
+
+    let cursor = match dp {
+        DP::TextCol => {
+            let xy = buf.to_change().to_xy_cursor(Some(cursor));
+            let n = skip_whitespace(&buf.line(xy.row), xy.col, DP::Left)?;
+            cursor.saturating_sub(n)
+        }
+        DP::StickyCol | DP::None => cursor,
+        dp => err_at!(Fatal, msg: format!("invalid direction: {}", dp))?,
+    };
+
+    Ok(cursor)
+}
+
+fn mto_line_middle(buf: &Buffer, p: usize) -> Result<usize> {
+    let n = {
+        let s = buf.line(buf.char_to_line(buf.to_char_cursor()));
+        text::visual_line_n(&s)
+    };
+    // cursor stays within the line, never on its newline.
+    let cursor = {
+        let m = (((p as f64) / 100.0) * (n as f64)) as usize;
+        buf.to_line_home(None) + cmp::min(m, n.saturating_sub(1))
+    };
+    Ok(cursor)
+}
+
+fn mto_column(buf: &Buffer, n: usize) -> Result<usize> {
+    let home = buf.to_line_home(None);
+    let n = {
+        let s = buf.line(buf.char_to_line(buf.to_char_cursor()));
+        cmp::min(text::visual_line_n(&s), n)
+    };
+    Ok(home + n.saturating_sub(1))
+}
+
+// Find the `n`th occurence of character. `repeat` is true for `;` and `,`
+// commands, which after a `t` or `T` shall skip the character that cursor
+// is already sitting next to, otherwise they never advance.
+fn mto_char(buf: &Buffer, evnt: Mto, repeat: bool) -> Result<usize> {
+    let cursor = buf.to_char_cursor();
+
+    let (n, ch, dp, pos) = match evnt {
+        Mto::CharF(n, Some(ch), dp) => (n, ch, dp, 'f'),
+        Mto::CharT(n, Some(ch), dp) => (n, ch, dp, 't'),
+        Mto::CharT(_, None, _) | Mto::None => return Ok(cursor),
+        mto => err_at!(Fatal, msg: format!("unexpected {}", mto))?,
+    };
+
+    // in the right direction, iteration starts from the cursor character.
+    let skip = match (dp, pos) {
+        (DP::Right, 't') if repeat => 2,
+        (DP::Right, _) => 1,
+        (DP::Left, 't') if repeat => 1,
+        (_, _) => 0,
+    };
+    let iter = buf.chars_at(cursor, dp)?.enumerate().skip(skip);
+    let item = iter
+        .filter_map(|(i, a)| if_else!(a == ch, Some(i), None))
+        .skip(n.saturating_sub(1))
+        .next()
+        .clone();
+
+    let cursor = match (item, dp, pos) {
+        (Some(i), DP::Right, 'f') => cursor + i,
+        (Some(i), DP::Right, 't') => (cursor + i).saturating_sub(1),
+        (Some(i), DP::Left, 'f') => cursor.saturating_sub(i + 1),
+        (Some(i), DP::Left, 't') => cursor.saturating_sub(i),
+        (None, _, _) => cursor,
+        (_, dp, pos) => err_at!(Fatal, msg: format!("bad {} {}", dp, pos))?,
+    };
+    Ok(cursor)
+}
+
+fn mto_up(buf: &Buffer, n: usize, dp: DP) -> Result<usize> {
+    let bc_xy = buf.to_xy_cursor(None);
+    let row = fold_up(buf, bc_xy.row, n);
+    let line = &buf.line(row);
+    let char_end = text::visual_line_n(&line).saturating_sub(1);
+    let col = cmp::min(bc_xy.col, char_end);
+
+    let home = buf.line_to_char(row);
+    let cursor = match dp {
+        DP::StickyCol => match buf.sticky_col {
+            StickyCol::Home => home,
+            StickyCol::End => home + char_end,
+            StickyCol::None => home + col,
+        },
+        DP::TextCol => home + skip_whitespace(line, 0, DP::Right)?,
+        DP::None => home + col,
+        dp => err_at!(Fatal, msg: format!("invalid direction: {}", dp))?,
+    };
+    Ok(cursor)
+}
+
+// Return the line `n` lines above `row`, a closed fold counts as a single
+// line and is entered on its first line.
+fn fold_up(buf: &Buffer, row: usize, n: usize) -> usize {
+    if buf.folds.is_empty() {
+        return row.saturating_sub(n);
+    }
+    let to_row = |row| buf.to_closed_fold(row).map(|(a, _)| a).unwrap_or(row);
+
+    let mut row = to_row(row);
+    for _ in 0..n {
+        match row {
+            0 => break,
+            _ => row = to_row(row - 1),
+        }
+    }
+    row
+}
+
+// Return the line `n` lines below `row`, a closed fold counts as a single
+// line and is entered on its first line.
+fn fold_down(buf: &Buffer, row: usize, n: usize) -> usize {
+    let last = buf.to_last_line_idx();
+    if buf.folds.is_empty() {
+        return cmp::min(row.saturating_add(n), last);
+    }
+
+    let mut row = cmp::min(row, last);
+    for _ in 0..n {
+        match buf.to_closed_fold(row).map(|(_, z)| z).unwrap_or(row) + 1 {
+            next if next > last => break,
+            next => row = next,
+        }
+    }
+    buf.to_closed_fold(row).map(|(a, _)| a).unwrap_or(row)
+}
+
+// Cursor moved from `from`, by a motion, shall not land on a hidden line
+// of a closed fold, lest edits apply on hidden text. It is moved to the
+// fold's first line, or past the fold when moving forward from within the
+// fold, which otherwise can't be moved past.
+fn skip_closed_fold(buf: &mut Buffer, from: usize) {
+    if buf.folds.is_empty() {
+        return;
+    }
+    let cursor = buf.to_char_cursor();
+    let row = buf.char_to_line(cursor);
+    let (a, z) = match buf.to_closed_fold(row) {
+        Some((a, z)) if a < row => (a, z),
+        _ => return,
+    };
+    let forward = cursor > from && buf.char_to_line(from) >= a;
+    let row = if_else!(forward && z < buf.to_last_line_idx(), z + 1, a);
+    buf.set_cursor(buf.line_to_char(row)).clear_sticky_col();
+}
+
+fn mto_down(buf: &Buffer, n: usize, dp: DP) -> Result<usize> {
+    let row = fold_down(buf, buf.char_to_line(buf.to_char_cursor()), n);
+    let char_end = text::visual_line_n(&buf.line(row)).saturating_sub(1);
+    let col = cmp::min(buf.to_xy_cursor(None).col, char_end);
+
+    let home = buf.line_to_char(row);
+    let cursor = match dp {
+        DP::StickyCol => match buf.sticky_col {
+            StickyCol::Home => home,
+            StickyCol::End => home + char_end,
+            StickyCol::None => home + col,
+        },
+        DP::TextCol => {
+            let xy = buf.to_change().to_xy_cursor(Some(home));
+            home + skip_whitespace(&buf.line(xy.row), xy.col, DP::Right)?
+        }
+        DP::None => home + col,
+        dp => err_at!(Fatal, msg: format!("invalid direction: {}", dp))?,
+    };
+    Ok(cursor)
+}
+
+fn mto_row(buf: &Buffer, n: usize, dp: DP) -> Result<usize> {
+    let cursor = buf.line_to_char(cmp::min(n, buf.to_last_line_idx()));
+    let cursor = match dp {
+        DP::TextCol => {
+            let xy = buf.to_xy_cursor(Some(cursor));
+            cursor + skip_whitespace(&buf.line(xy.row), xy.col, DP::Right)?
+        }
+        _ => cursor,
+    };
+    Ok(cursor)
+}
+
+fn mto_percent(buf: &Buffer, n: usize, dp: DP) -> Result<usize> {
+    // like vim, `N%` goes to line `(N * n_lines + 99) / 100`, starting from 1.
+    let row = {
+        let n_lines = buf.to_last_line_idx() + 1;
+        let row = (n * n_lines).div_ceil(100).saturating_sub(1);
+        cmp::min(row, n_lines.saturating_sub(1))
+    };
+    let cursor = buf.line_to_char(row);
+    let cursor = match dp {
+        DP::TextCol => {
+            let xy = buf.to_change().to_xy_cursor(Some(cursor));
+            cursor + skip_whitespace(&buf.line(xy.row), xy.col, DP::Right)?
+        }
+        _ => cursor,
+    };
+    Ok(cursor)
+}
+
+fn mto_end(buf: &Buffer) -> Result<usize> {
+    let line_idx = buf.to_last_line_idx();
+    let n = text::visual_line_n(&buf.line(line_idx));
+    Ok(buf.line_to_char(line_idx) + n.saturating_sub(1))
+}
+
+// `{n}go`, jump to the `n`th character in the buffer, first character being
+// 1. Beyond the last character, cursor saturates to the last character, and
+// on a newline, cursor saturates to the last character of that line.
+fn mto_cursor(buf: &Buffer, n: usize) -> Result<usize> {
+    let cursor = cmp::min(n.saturating_sub(1), buf.last_char_idx());
+    let xy = buf.to_xy_cursor(Some(cursor));
+    let col = cmp::min(xy.col, line_chars(buf, xy.row).saturating_sub(1));
+    Ok(xy_to_cursor(buf, (xy.row, col)))
+}
+
+macro_rules! mto_text_left {
+    ($buf:ident, $n:ident, $pos:ident, $start:expr, $state:ident) => {{
+        let bc_xy = $buf.to_xy_cursor(None);
+        let to_chars = |line: String| -> Vec<char> {
+            let line = text::visual_line(&line);
+            let mut chars: Vec<char> = line.chars().collect();
+            chars.reverse();
+            chars
+        };
+
+        let (mut iter, row, col) = {
+            let chars: Vec<char> = {
+                let line = $buf.line(bc_xy.row);
+                text::visual_line(&line).chars().collect()
+            };
+            let col = {
+                let n = cmp::min(bc_xy.col + 1, chars.len());
+                if_else!($pos == DP::Start, n, bc_xy.col)
+            };
+            let rem_chars = chars[..col].len();
+            let chars: Vec<(usize, char)> = {
+                let mut chars = chars[..col].to_vec();
+                chars.reverse();
+                chars.into_iter().enumerate().collect()
+            };
+            let iter = $buf.lines_at(bc_xy.row, DP::Left)?.map(to_chars);
+            (
+                WIterChar::new(iter, rem_chars, chars),
+                bc_xy.row,
+                col.saturating_sub(1),
+            )
+        };
+
+        let mut state = $start;
+        let cursor = loop {
+            state = match iter.next() {
+                Some(item) => match state.push(DP::Left, $pos, item) {
+                    $state::Fin(r, _, None) => {
+                        break xy_to_cursor($buf, (row - r, 0));
+                    }
+                    $state::Fin(r, rc, Some(c)) => {
+                        let col = {
+                            let this = col.saturating_sub(c);
+                            if_else!(r == 0, this, rc.saturating_sub(c + 1))
+                        };
+                        break xy_to_cursor($buf, (row - r, col));
+                    }
+                    state => state,
+                },
+                None if $pos == DP::Start => break $buf.last_char_idx(),
+                None => break 0, // DP::End
+            };
+        };
+        Ok::<usize, Error>(saturate_cursor($buf, cursor))
+    }};
+}
+
+macro_rules! mto_text_right {
+    ($buf:ident, $n:ident, $pos:ident, $start:expr, $state:ident) => {{
+        let bc_xy = $buf.to_xy_cursor(None);
+        let to_chars = |s: String| -> Vec<char> { text::visual_line(&s).chars().collect() };
+
+        let (mut iter, row, col) = {
+            let chars: Vec<char> = {
+                let line = $buf.line(bc_xy.row);
+                text::visual_line(&line).chars().collect()
+            };
+            let col = if_else!(
+                $pos == DP::Start,
+                bc_xy.col,
+                cmp::min(chars.len(), bc_xy.col + 1)
+            );
+            let rem_chars = chars[col..].len();
+            let chars: Vec<(usize, char)> = {
+                let iter = chars[col..].to_vec().into_iter().enumerate();
+                iter.collect()
+            };
+            let iter = $buf.lines_at(bc_xy.row + 1, DP::Right)?.map(to_chars);
+            (WIterChar::new(iter, rem_chars, chars), bc_xy.row, col)
+        };
+
+        let mut state = $start;
+        let cursor = loop {
+            state = match iter.next() {
+                Some(item) => match state.push(DP::Right, $pos, item) {
+                    $state::Fin(r, _, None) => {
+                        break xy_to_cursor($buf, (row + r, 0));
+                    }
+                    $state::Fin(r, _, Some(c)) => {
+                        let col = {
+                            let n = line_chars($buf, row + r).saturating_sub(1);
+                            let col = if_else!(r == 0, col.saturating_add(c), c);
+                            cmp::min(n, col)
+                        };
+                        break xy_to_cursor($buf, (row + r, col));
+                    }
+                    state => state,
+                },
+                None => break $buf.last_char_idx(),
+            };
+        };
+        Ok::<usize, Error>(saturate_cursor($buf, cursor))
+    }};
+}
+
+fn mto_words_left(buf: &Buffer, n: usize, pos: DP) -> Result<usize> {
+    mto_text_left!(buf, n, pos, MtoWord::St(n), MtoWord)
+}
+
+fn mto_words_right(buf: &Buffer, n: usize, pos: DP) -> Result<usize> {
+    mto_text_right!(buf, n, pos, MtoWord::St(n), MtoWord)
+}
+
+fn mto_wwords_left(buf: &Buffer, n: usize, pos: DP) -> Result<usize> {
+    mto_text_left!(buf, n, pos, MtoWWord::St(n), MtoWWord)
+}
+
+fn mto_wwords_right(buf: &Buffer, n: usize, pos: DP) -> Result<usize> {
+    mto_text_right!(buf, n, pos, MtoWWord::St(n), MtoWWord)
+}
+
+fn mto_sentence_left(buf: &Buffer, n: usize) -> Result<usize> {
+    let pos = DP::None;
+    let start = MtoSentence::St(n);
+    let cursor = mto_text_left!(buf, n, pos, start, MtoSentence)?;
+    let is_skip = |(_, ch): &(usize, char)| -> bool {
+        match ch {
+            ch if ch.is_whitespace() => true,
+            '.' | ')' | ']' | '"' | '\'' => true,
+            _ => false,
+        }
+    };
+
+    let i = if cursor == 0 {
+        0
+    } else {
+        let iter = buf.chars_at(cursor, DP::Right)?.enumerate();
+        iter.skip_while(is_skip)
+            .next()
+            .clone()
+            .map(|(i, _)| i)
+            .unwrap_or(buf.n_chars().saturating_sub(1))
+    };
+    Ok(cursor + if_else!(buf.to_char_cursor() <= cursor + i, 0, i))
+}
+
+fn mto_sentence_right(buf: &Buffer, n: usize) -> Result<usize> {
+    let pos = DP::None;
+    let start = {
+        let ln = {
+            let bc_xy = buf.to_xy_cursor(None);
+            text::visual_line_n(&buf.line(bc_xy.row))
+        };
+        if_else!(ln == 0, MtoSentence::Ws(1, 0, n), MtoSentence::St(n))
+    };
+    mto_text_right!(buf, n, pos, start, MtoSentence)
+}
+
+// Move `n` sentences in direction `dp`, skipping over sentence ends that
+// are abbreviations, refer [text::is_sentence_end].
+fn mto_sentence(buf: &mut Buffer, mut n: usize, dp: DP) -> Result<usize> {
+    let cursor = buf.to_char_cursor();
+    let mut target = cursor;
+    while n > 0 {
+        let next = match dp {
+            DP::Left => mto_sentence_left(buf, 1)?,
+            DP::Right => mto_sentence_right(buf, 1)?,
+            _ => err_at!(Fatal, msg: format!("unreachable"))?,
+        };
+        if next == target {
+            break;
+        }
+        buf.set_cursor(next);
+        target = next;
+        n -= if_else!(is_sentence_start(buf, next), 1, 0);
+    }
+    buf.set_cursor(cursor);
+    Ok(target)
+}
+
+// Empty lines, and the first line of a paragraph, start a sentence.
+// Otherwise check the word preceding `at`, skipping whitespace and closing
+// punctuations, whether it ends a sentence. Abbreviations only suppress
+// a sentence end, refer [text::is_sentence_end].
+fn is_sentence_start(buf: &Buffer, at: usize) -> bool {
+    let row = buf.char_to_line(at);
+    if is_para_boundary(&buf.line(row)) {
+        return true;
+    }
+    let n_nls = match buf.chars_at(at, DP::Left) {
+        Ok(iter) => {
+            let iter = iter.take_while(|ch| ch.is_whitespace());
+            iter.filter(|ch| *ch == '\n').count()
+        }
+        Err(_) => return true,
+    };
+    if n_nls > 1 {
+        return true;
+    }
+
+    let is_skip = |ch: &char| ch.is_whitespace() || matches!(ch, ')' | ']' | '"' | '\'');
+    let mut word: Vec<char> = match buf.chars_at(at, DP::Left) {
+        Ok(iter) => {
+            let iter = iter.skip_while(is_skip);
+            iter.take_while(|ch| !ch.is_whitespace()).collect()
+        }
+        Err(_) => return true,
+    };
+    word.reverse();
+    match String::from_iter(word) {
+        word if word.is_empty() => true,
+        word => text::is_sentence_end(&word, &buf.abbreviations),
+    }
+}
+
+// Empty lines separate paragraphs.
+fn is_para_boundary(line: &str) -> bool {
+    text::visual_line_n(line) == 0
+}
+
+fn mto_paras_left(buf: &Buffer, mut n: usize) -> Result<usize> {
+    let row = buf.to_xy_cursor(None).row;
+    let mut iter = buf.lines_at(row, DP::Left)?.enumerate();
+    let row = loop {
+        match iter.next() {
+            Some((r, line)) if is_para_boundary(&line) && n == 1 => {
+                break row.saturating_sub(r + 1)
+            }
+            Some((_, line)) if is_para_boundary(&line) => n -= 1,
+            Some(_) => (),
+            None => break 0,
+        }
+    };
+    Ok(saturate_cursor(buf, buf.line_to_char(row)))
+}
+
+fn mto_paras_right(buf: &Buffer, mut n: usize) -> Result<usize> {
+    let row = buf.to_xy_cursor(None).row;
+    let mut iter = buf.lines_at(row, DP::Right)?.enumerate();
+    iter.next();
+    let row = loop {
+        match iter.next() {
+            Some((r, line)) if is_para_boundary(&line) && n == 1 => break row.saturating_add(r),
+            Some((_, line)) if is_para_boundary(&line) => n -= 1,
+            Some(_) => (),
+            None => break 0,
+        }
+    };
+    Ok(saturate_cursor(buf, buf.line_to_char(row)))
+}
+
+fn mto_match_pair(buf: &Buffer) -> Result<usize> {
+    use crate::match_pair;
+
+    let cursor = buf.to_char_cursor();
+    Ok(match_pair::match_on_line(buf).unwrap_or(cursor))
+}
+
+fn mto_unmatch_pair(buf: &Buffer, ch: char, n: usize, dir: DP) -> Result<usize> {
+    use crate::match_pair;
+
+    let cursor = buf.to_char_cursor();
+    let cursor = match dir {
+        DP::Left => match_pair::unmatch_before(buf, ch, n).unwrap_or(cursor),
+        DP::Right => match_pair::unmatch_after(buf, ch, n).unwrap_or(cursor),
+        _ => cursor,
+    };
+    Ok(cursor)
+}
+
+fn mto_bracket(buf: &mut Buffer, e: Mto) -> Result<Event> {
+    let mut m = 0;
+    let mut cursor = buf.to_char_cursor();
+    match e {
+        Mto::Bracket(mut n, yin, yan, DP::Left) => {
+            let mut iter = buf.chars_at(cursor, DP::Left)?.enumerate();
+            cursor -= loop {
+                match iter.next() {
+                    Some((_, ch)) if ch == yin && m > 0 => m -= 1,
+                    Some((i, ch)) if ch == yin && n == 0 => break i + 1,
+                    Some((_, ch)) if ch == yin => n -= 1,
+                    Some((_, ch)) if ch == yan => m += 1,
+                    Some(_) => (),
+                    None => break 0,
                 }
-                None => break Event::Noop,
             };
-            res_evnts.push(evnt)
-        };
-        res_evnts.push(evnt);
-        Ok(res_evnts)
+        }
+        Mto::Bracket(mut n, yin, yan, DP::Right) => {
+            let mut iter = buf.chars_at(cursor, DP::Right)?.enumerate();
+            cursor += {
+                loop {
+                    match iter.next() {
+                        Some((_, ch)) if ch == yin && m > 0 => m -= 1,
+                        Some((i, ch)) if ch == yin && n == 0 => break i,
+                        Some((_, ch)) if ch == yin => n -= 1,
+                        Some((_, ch)) if ch == yan => m += 1,
+                        Some(_) => (),
+                        None => break 0,
+                    }
+                }
+            };
+        }
+        _ => err_at!(Fatal, msg: format!("unreachable"))?,
     }
 
-    fn do_on_event(buf: &mut Buffer, evnt: Event) -> Result<Event> {
-        use crate::event::{self, Cud, Event::*};
-        use std::iter::repeat;
+    buf.set_cursor(cursor);
+    Ok(Event::Noop)
+}
 
-        debug!("{}", evnt);
+// Return the cursor for the `n`th match, along with a notification event
+// when the search wrapped around the buffer, or would have, had wrapscan
+// been enabled.
+fn mto_pattern(buf: &mut Buffer, evnt: Mto) -> Result<(usize, Event)> {
+    use crate::pubsub::Notify;
 
-        // handle tab-rotation for completion.
-        let evnt = match (buf.tab_state.clone(), evnt) {
-            (TabState::None, TabInsert(newt)) => {
-                let cursor = {
-                    let cursor = buf.to_char_cursor();
-                    buf.cud_str(Some(cursor), newt.as_str())?
-                };
-                buf.set_cursor(cursor).clear_sticky_col();
+    let (n, search, dp) = match evnt.clone() {
+        Mto::Pattern(n, Some(patt), dp) => (n, Search::new(&patt, buf.search_opts)?, dp),
+        _ => err_at!(Fatal, msg: format!("unreachable"))?,
+    };
 
-                buf.tab_state = TabState::Active(newt.clone());
-                Edit(event::Edit::new_ins(cursor, newt))
-            }
-            (TabState::None, TabClear) => Event::Noop,
-            (TabState::None, evnt) => evnt,
-            (TabState::Active(oldt), TabInsert(newt)) => {
-                let cursor = {
-                    let cursor = buf.to_char_cursor();
-                    cursor.saturating_sub(text::width(oldt.chars()))
-                };
-                let to = cursor + oldt.chars().count();
-                buf.cud_delete(cursor..to)?;
-                let cursor = buf.cud_str(Some(cursor), newt.as_str())?;
-                buf.set_cursor(cursor).clear_sticky_col();
+    let (edge, other) = if_else!(dp == DP::Left, ("TOP", "BOTTOM"), ("BOTTOM", "TOP"));
+    let cursor = buf.to_char_cursor();
+    let (cursor, span) = match find_pattern(buf, n, &search, dp)? {
+        Some((_, true)) if !buf.wrapscan => {
+            let patt = search.to_pattern();
+            let span = format!("search hit {} without match for: {}", edge, patt);
+            (cursor, Some(span))
+        }
+        Some((cursor, true)) => {
+            let span = format!("search hit {}, continuing at {}", edge, other);
+            (cursor, Some(span))
+        }
+        Some((cursor, false)) => (cursor, None),
+        None => (cursor, None),
+    };
 
-                buf.tab_state = TabState::Active(newt.clone());
-                Edit(event::Edit::new_chg(cursor, oldt, newt))
-            }
-            (TabState::Active(oldt), TabClear) => {
-                let cursor = {
-                    let cursor = buf.to_char_cursor();
-                    cursor.saturating_sub(text::width(oldt.chars()))
-                };
-                let to = cursor + oldt.chars().count();
-                buf.cud_delete(cursor..to)?;
-                buf.set_cursor(cursor);
+    let evnt = match span {
+        Some(span) => Event::Notify(Notify::Status(vec![span.into()])),
+        None => Event::Noop,
+    };
+    Ok((cursor, evnt))
+}
 
-                buf.tab_state = TabState::default();
-                Edit(event::Edit::new_del(cursor, oldt))
-            }
-            (TabState::Active(_), evnt) => {
-                buf.tab_state = TabState::default();
-                evnt
-            }
-        };
+// Search for the `n`th occurence of the word under or after the cursor,
+// matching only whole words. The search is remembered for `n` and `N`.
+fn mto_search_word(buf: &mut Buffer, n: usize, dp: DP) -> Result<(usize, Event)> {
+    let cursor = buf.to_char_cursor();
+    let row = buf.char_to_line(cursor);
+    let home = buf.line_to_char(row);
+    let line = buf.line(row);
+
+    let chars: Vec<char> = text::visual_line(&line).chars().collect();
+    let col = cursor - home;
+    let off = {
+        let mut iter = chars.iter().skip(col);
+        match iter.position(|ch| ch.is_alphanumeric() || *ch == '_') {
+            Some(i) => col + i,
+            None => return Ok((cursor, Event::Noop)),
+        }
+    };
+    let a = off - skip_alphanumeric(&line, off, DP::Left)?;
+    let z = off + skip_alphanumeric(&line, off, DP::Right)?;
 
-        let evnt = match evnt {
-            // cursor movement
-            Mt(Mto::Left(n, dp)) => {
-                let cursor = mto_left(buf, n, dp)?;
-                buf.set_cursor(cursor).clear_sticky_col();
-                Event::Noop
-            }
-            Mt(Mto::Right(n, dp)) => {
-                let cursor = mto_right(buf, n, dp)?;
-                buf.set_cursor(cursor).clear_sticky_col();
-                Event::Noop
-            }
-            Mt(Mto::Up(n, dp)) => {
-                let cursor = mto_up(buf, n, dp)?;
-                buf.set_cursor(cursor);
-                Event::Noop
-            }
-            Mt(Mto::Down(n, dp)) => {
-                let cursor = mto_down(buf, n, dp)?;
-                buf.set_cursor(cursor);
-                Event::Noop
-            }
-            Mt(Mto::LineHome(dp)) => {
-                let cursor = mto_line_home(buf, dp)?;
-                buf.set_cursor(cursor).set_sticky_col(dp, "home");
-                Event::Noop
-            }
-            Mt(Mto::LineEnd(n, dp)) => {
-                let cursor = mto_line_end(buf, n, dp)?;
-                buf.set_cursor(cursor).set_sticky_col(dp, "end");
-                Event::Noop
-            }
-            // insert session
-            Wr(Cud::Char(ch)) => {
-                let cursor = buf.to_char_cursor();
-                {
-                    let cursor = buf.cud_char(None, ch)?;
-                    buf.set_cursor(cursor).clear_sticky_col();
+    let word: String = chars[a..z].iter().collect();
+    let e = {
+        let patt = format!(r"\b{}\b", regex::escape(&word));
+        Mto::Pattern(n, Some(patt), dp)
+    };
+    buf.mto_pattern = e.clone();
+
+    // search from the start of the word.
+    buf.set_cursor(home + a);
+    mto_pattern(buf, e)
+}
+
+// Find the `n`th match for `search` from cursor, in direction `dp`,
+// wrapping around the buffer if need be. Return the char-index of the
+// match's start, and whether the search wrapped.
+fn find_pattern(buf: &Buffer, n: usize, search: &Search, dp: DP) -> Result<Option<(usize, bool)>> {
+    let matches = search.to_matches(&buf.to_string());
+    if matches.is_empty() {
+        return Ok(None);
+    }
+
+    let cursor = buf.to_char_cursor();
+    let mut wrapped = false;
+    let i = match dp {
+        DP::Left => {
+            let mut i = matches.partition_point(|(s, _)| *s < cursor);
+            for _ in 0..cmp::max(n, 1) {
+                if i == 0 {
+                    wrapped = true;
+                    i = matches.len();
                 }
-                Edit(event::Edit::new_ins(cursor, ch.into()))
+                i -= 1;
             }
-            Wr(Cud::Tab(n)) => {
-                let cursor = buf.to_char_cursor();
-                let n = buf.shift_width * n;
-                let txt = String::from_iter(repeat(' ').take(n));
-                {
-                    let cursor = buf.cud_str(None, &txt)?;
-                    buf.set_cursor(cursor).clear_sticky_col();
+            i
+        }
+        DP::Right => {
+            let mut i = match Search::find(&matches, cursor.saturating_add(1)) {
+                Some(i) => i,
+                None => return Ok(None),
+            };
+            wrapped = matches[i].0 <= cursor;
+            for _ in 1..n {
+                i += 1;
+                if i == matches.len() {
+                    wrapped = true;
+                    i = 0;
                 }
-                Edit(event::Edit::new_ins(cursor, txt))
-            }
-            Wr(Cud::Enter(n)) => {
-                let cursor = buf.to_char_cursor();
-                let to = buf.cud_newlines(Some(cursor), buf.format.newline(), n)?;
-                buf.set_cursor(cursor).clear_sticky_col();
-                Edit(event::Edit::new_ins(cursor, buf.slice(cursor..to)))
-            }
-            Wr(Cud::Backspace(n)) if buf.to_char_cursor() > 0 => {
-                let cursor = buf.to_char_cursor();
-                let from = cursor.saturating_sub(n);
-                buf.cud_delete(from..cursor)?;
-                buf.set_cursor(from);
-                Edit(event::Edit::new_del(cursor, buf.slice(from..cursor)))
-            }
-            Wr(Cud::Delete(n)) => {
-                let cursor = buf.to_char_cursor();
-                let to = cmp::min(cursor.saturating_add(n), buf.n_chars());
-                buf.cud_delete(cursor..to)?;
-                Edit(event::Edit::new_del(cursor, buf.slice(cursor..to)))
             }
-            evnt => evnt,
-        };
+            i
+        }
+        _ => err_at!(Fatal, msg: format!("unreachable"))?,
+    };
 
-        Ok(evnt)
-    }
+    Ok(Some((matches[i].0, wrapped)))
 }
 
-#[derive(Clone)]
-struct ReplaceBuffer {
-    change: Rc<RefCell<Change>>,
+fn mod_insert(buf: &mut Buffer, repeat: usize, pos: DP) -> Result<Event> {
+    if pos == DP::TextCol {
+        let cursor = mto_line_home(buf, pos)?;
+        buf.set_cursor(cursor).set_sticky_col(pos, "home");
+    }
+    buf.inner = {
+        let ib = InsertBuffer::new(repeat, Change::fork(buf.as_mut_change()));
+        ib.into()
+    };
+    Ok(Event::Noop)
 }
 
-impl From<NormalBuffer> for ReplaceBuffer {
-    fn from(nb: NormalBuffer) -> ReplaceBuffer {
-        ReplaceBuffer { change: nb.change }
-    }
+fn mod_append(buf: &mut Buffer, repeat: usize, pos: DP) -> Result<Event> {
+    let cursor = match pos {
+        DP::End => {
+            let cursor = buf.to_char_cursor();
+            let n = text::visual_line_n(&buf.line(buf.char_to_line(cursor)));
+            buf.to_line_home(None) + n
+        }
+        DP::Right => buf.to_char_cursor().saturating_add(1),
+        _ => err_at!(Fatal, msg: format!("unreachable"))?,
+    };
+    buf.set_cursor(cursor).clear_sticky_col();
+
+    buf.inner = {
+        let ib = InsertBuffer::new(repeat, Change::fork(buf.as_mut_change()));
+        ib.into()
+    };
+    Ok(Event::Noop)
 }
 
-impl ReplaceBuffer {
-    fn set_cursor(&mut self, cursor: usize) {
-        self.to_mut_change().set_cursor(cursor)
-    }
+fn mod_visual(buf: &mut Buffer, linewise: bool) -> Result<Event> {
+    let anchor = buf.to_char_cursor();
+    buf.inner = match mem::replace(&mut buf.inner, Inner::default()) {
+        Inner::Normal(nb) => VisualBuffer::new(anchor, linewise, nb.change).into(),
+        inner => inner,
+    };
+    Ok(Event::Noop)
+}
 
-    #[inline]
-    fn cud_newlines(&mut self, cursor: Option<usize>, nl: &str, n: usize) -> Result<usize> {
-        self.to_mut_change().cud_newlines(cursor, nl, n)
+fn mod_visual_block(buf: &mut Buffer) -> Result<Event> {
+    mod_visual(buf, false)?;
+    if let Inner::Visual(vb) = &mut buf.inner {
+        vb.block = true;
     }
+    Ok(Event::Noop)
+}
 
-    #[inline]
-    fn cud_char(&mut self, _cursor: Option<usize>, _ch: char) -> Result<usize> {
-        todo!()
-    }
+// Switch to insert mode at column `col` of line `rf`, for visual-block
+// `I`, `A` and `c`. On exit, text typed on line `rf` is inserted at the
+// same column on lines upto `rt`, refer [insert_block]. With `pad`, lines
+// shorter than `col` are padded with spaces.
+fn mod_insert_block(
+    buf: &mut Buffer,
+    rf: usize,
+    rt: usize,
+    col: usize,
+    pad: bool,
+) -> Result<Event> {
+    use crate::event::Edit;
+
+    buf.set_normal_mode();
+    buf.inner = {
+        let ib = InsertBuffer::new(1, Change::fork(buf.as_mut_change()));
+        ib.into()
+    };
 
-    #[inline]
-    fn cud_str(&mut self, _cursor: Option<usize>, _text: &str) -> Result<usize> {
-        todo!()
-    }
+    let (home, n) = (buf.line_to_char(rf), line_chars(buf, rf));
+    let evnt = if pad && n < col {
+        let txt = " ".repeat(col - n);
+        buf.cud_str(Some(home + n), &txt)?;
+        Event::Edit(Edit::new_ins(home + n, txt))
+    } else {
+        Event::Noop
+    };
 
-    #[inline]
-    fn cud_delete<R>(&mut self, _range: R) -> Result<()>
-    where
-        R: RangeBounds<usize>,
-    {
-        todo!()
+    let start = home + cmp::min(col, line_chars(buf, rf));
+    buf.set_cursor(start).clear_sticky_col();
+    if let Inner::Insert(ib) = &mut buf.inner {
+        ib.block = Some((start, rt, pad));
     }
+    Ok(evnt)
 }
 
-impl ReplaceBuffer {
-    fn to_change(&self) -> cell::Ref<Change> {
-        self.change.as_ref().borrow()
-    }
+// Insert text typed from `start`, in a visual-block insert-session, at the
+// same column on following lines upto row `rt`. Nothing is inserted if
+// cursor has moved away from the line of `start`.
+fn insert_block(buf: &mut Buffer, start: usize, rt: usize, pad: bool) -> Result<Event> {
+    use crate::event::Edit;
 
-    fn to_mut_change(&mut self) -> cell::RefMut<Change> {
-        self.change.as_ref().borrow_mut()
+    let (cursor, row) = (buf.to_char_cursor(), buf.char_to_line(start));
+    if cursor <= start || buf.char_to_line(cursor) != row {
+        return Ok(Event::Noop);
     }
 
-    fn as_mut_change(&mut self) -> &mut Rc<RefCell<Change>> {
-        &mut self.change
+    let (txt, col) = (buf.slice(start..cursor), start - buf.line_to_char(row));
+    let mut evnt = Event::Noop;
+    for row in (row + 1)..=rt {
+        let (home, n) = (buf.line_to_char(row), line_chars(buf, row));
+        let (at, txt) = match n {
+            n if n >= col => (home + col, txt.clone()),
+            n if pad => (home + n, " ".repeat(col - n) + &txt),
+            _ => continue,
+        };
+        buf.cud_str(Some(at), &txt)?;
+        evnt.push(Event::Edit(Edit::new_ins(at, txt)));
     }
+    buf.set_cursor(cursor);
+
+    Ok(evnt)
 }
 
-// A change captures a single session of CUD commands. The main
-// purpose is to implement undo/redo and few other associated features.
-//
-// NOTE: Visualize the change tree, maintained via `past` and `news`
-// references, as inverted tree of changes, with root being the oldest
-// change.
-#[derive(Clone)]
-struct Change {
-    // persistent clone shared among all previous changes.
-    rope: Rope,
-    // a change always come from a single past.
-    past: Option<rc::Weak<RefCell<Change>>>,
-    // and it can have ZERO or more futures.
-    news: Vec<Rc<RefCell<Change>>>,
-    // redo path, offset into `news`.
-    redo: Option<usize>,
-    // list of events for this change-session.
-    cuds: Event,
-    // last and latest cursor position for this change.
-    cursor: usize,
-}
-
-impl Default for Change {
-    fn default() -> Change {
-        Change {
-            rope: Rope::from_reader(io::empty()).unwrap(),
-            past: None,
-            news: Vec::default(),
-            redo: None,
-            cuds: Event::Noop,
-            cursor: 0,
+fn mod_reselect(buf: &mut Buffer) -> Result<Event> {
+    let sel = match buf.last_visual {
+        Some(sel) => sel,
+        None => return Ok(Event::Noop),
+    };
+    let n = buf.n_chars().saturating_sub(1);
+    let (anchor, cursor) = (cmp::min(sel.anchor, n), cmp::min(sel.cursor, n));
+
+    buf.inner = match mem::replace(&mut buf.inner, Inner::default()) {
+        Inner::Normal(nb) => {
+            let mut vb = VisualBuffer::new(anchor, sel.linewise, nb.change);
+            vb.block = sel.block;
+            vb.into()
         }
-    }
+        inner => inner,
+    };
+    buf.set_cursor(cursor).clear_sticky_col();
+    Ok(Event::Noop)
 }
 
-impl AsRef<Rope> for Change {
-    fn as_ref(&self) -> &Rope {
-        &self.rope
+fn mod_open(buf: &mut Buffer, repeat: usize, pos: DP) -> Result<Event> {
+    buf.inner = {
+        let mut ib = InsertBuffer::new(repeat, Change::fork(buf.as_mut_change()));
+        ib.open = true;
+        ib.into()
+    };
+    match pos {
+        DP::Left => {
+            let cursor = {
+                let row = buf.to_xy_cursor(None).row;
+                // only the indentation is copied, when opening above.
+                let indent = {
+                    let line = buf.line(row);
+                    let txt = line.trim_start_matches(&[' ', '\t'][..]);
+                    to_auto_indent(buf, &line[..line.len() - txt.len()])
+                };
+                let cursor = buf.line_to_char(row);
+                buf.cud_newlines(Some(cursor), buf.format.newline(), 1)?;
+                buf.cud_str(Some(cursor), &indent)?
+            };
+            buf.set_cursor(cursor).clear_sticky_col();
+        }
+        DP::Right => {
+            open_below(buf)?;
+        }
+        _ => err_at!(Fatal, msg: format!("unreachable"))?,
     }
+    Ok(Event::Noop)
 }
 
-impl Change {
-    // Start from the first change.
-    fn start(rope: Rope) -> Rc<RefCell<Change>> {
-        Rc::new(RefCell::new(Change {
-            rope,
-            past: None,
-            news: Vec::default(),
-            redo: None,
-            cuds: Event::Noop,
-            cursor: 0,
-        }))
+// Open a new line below the cursor line, with auto-indent, and move the
+// cursor to the new line.
+fn open_below(buf: &mut Buffer) -> Result<Event> {
+    use crate::event;
+
+    let (from, indent) = {
+        let cursor = buf.to_char_cursor();
+        let line = buf.line(buf.char_to_line(cursor));
+        let n = text::visual_line_n(&line);
+        (
+            buf.to_line_home(None) + n,
+            to_auto_indent(buf, text::visual_line(&line)),
+        )
+    };
+    let cursor = buf.cud_newlines(Some(from), buf.format.newline(), 1)?;
+    let cursor = buf.cud_str(Some(cursor), &indent)?;
+    buf.set_cursor(cursor).clear_sticky_col();
+    Ok(Event::Edit(event::Edit::new_ins(
+        from,
+        buf.slice(from..cursor),
+    )))
+}
+
+// Resolve the text range, as char-index, for motion `mto` repeated `n`
+// times from cursor. Return (from, to, linewise) where `to` is exclusive,
+// and None if motion cannot be applied on the buffer.
+fn opr_range(buf: &mut Buffer, n: usize, mto: Mto) -> Result<Option<(usize, usize, bool)>> {
+    if let Mto::Object(m, obj) = &mto {
+        return text_object(buf, n.saturating_mul(*m), obj);
     }
 
-    // fork from current `change`, where current change can be the
-    // leaf node of the inverted tree, or an intermediate node
-    // of the inverted tree.
-    //
-    // a fork always create a new leaf.
-    fn fork(change: &mut Rc<RefCell<Change>>) -> Rc<RefCell<Change>> {
-        let leaf = {
-            let cc: &Change = &change.as_ref().borrow();
-            Rc::new(RefCell::new(Change {
-                rope: cc.as_ref().clone(),
-                past: Some(Rc::downgrade(change)),
-                news: Vec::default(),
-                redo: None,
-                cuds: Event::Noop,
-                cursor: cc.cursor, // inherit the cursor position.
-            }))
-        };
-        {
-            let cc: &mut Change = &mut change.as_ref().borrow_mut();
-            let redo = cc.news.len();
-            cc.news.push(Rc::clone(&leaf));
-            cc.redo = Some(redo);
+    let cursor = buf.to_char_cursor();
+    let sticky_col = buf.sticky_col;
+
+    let mut target = Some(cursor);
+    for _ in 0..n {
+        match NormalBuffer::do_on_event(buf, Event::Mt(mto.clone()))? {
+            Event::Noop | Event::Notify(_) => target = Some(buf.to_char_cursor()),
+            _ => {
+                target = None;
+                break;
+            }
         }
-        leaf
     }
+    buf.set_cursor(cursor);
+    buf.sticky_col = sticky_col;
 
-    #[inline]
-    fn to_char_cursor(&self) -> usize {
-        self.cursor
-    }
+    let target = match target {
+        Some(target) => target,
+        None => return Ok(None),
+    };
+    let (from, to) = (cmp::min(cursor, target), cmp::max(cursor, target));
 
-    fn to_xy_cursor(&self, cursor: Option<usize>) -> Cursor {
-        let cursor = cursor.unwrap_or(self.cursor);
-        let row_at = self.rope.char_to_line(cursor);
-        let col_at = cursor.saturating_sub(self.rope.line_to_char(row_at));
-        (col_at, row_at).into()
-    }
+    let linewise = match &mto {
+        Mto::Up(_, _) | Mto::Down(_, _) => true,
+        Mto::Row(_, _) | Mto::Percent(_, _) => true,
+        Mto::Jump('\'', _) => true,
+        _ => false,
+    };
+    let inclusive = match &mto {
+        Mto::LineEnd(_, _) | Mto::MatchPair => true,
+        Mto::CharF(_, _, _) | Mto::CharT(_, _, _) | Mto::CharR(_, _) => target > cursor,
+        Mto::Word(_, DP::Right, DP::End) => true,
+        Mto::WWord(_, DP::Right, DP::End) => true,
+        _ => false,
+    };
 
-    fn is_modified(&self) -> bool {
-        let mut modified = match self.cuds {
-            Event::Noop => false,
-            _ => true,
-        };
-        modified = modified || self.past.is_some();
-        modified = modified || self.news.len() > 0;
-        modified
-    }
-}
+    let range = if linewise {
+        let (from, to) = linewise_range(buf, from, to);
+        (from, to, true)
+    } else {
+        let mut to = if_else!(inclusive, to + 1, to);
+        match &mto {
+            // word motion does not cross over to next line.
+            Mto::Word(_, DP::Right, DP::Start) | Mto::WWord(_, DP::Right, DP::Start) => {
+                let row = buf.char_to_line(to);
+                let home = buf.line_to_char(row);
+                if row > buf.char_to_line(from) && buf.slice(home..to).trim().is_empty() {
+                    let eol = buf.line_to_char(row - 1) + line_chars(buf, row - 1);
+                    to = if_else!(eol > from, eol, to);
+                }
+            }
+            _ => (),
+        }
+        (from, cmp::min(to, buf.n_chars()), false)
+    };
 
-impl Change {
-    fn set_cursor(&mut self, cursor: usize) {
-        self.cursor = cursor;
-    }
+    Ok(Some(range))
+}
 
-    fn cud_newlines(&mut self, cursor: Option<usize>, nl: &str, n: usize) -> Result<usize> {
-        let cursor = cursor.unwrap_or(self.cursor);
+// Expand [from, to] to cover whole lines, `to` is exclusive. When range
+// covers the last line, include the previous newline.
+fn linewise_range(buf: &Buffer, from: usize, to: usize) -> (usize, usize) {
+    let row = buf.char_to_line(to);
+    let to = buf.line_to_char(row + 1);
+    let from = match buf.to_line_home(Some(from)) {
+        home if to == buf.n_chars() && home > 0 && !buf.slice(..to).ends_with('\n') => {
+            let row = buf.char_to_line(home) - 1;
+            buf.line_to_char(row) + line_chars(buf, row)
+        }
+        home => home,
+    };
+    (from, to)
+}
 
-        let mut new_line = String::default();
-        (0..n).for_each(|_| new_line.push_str(nl));
-        self.rope.insert(cursor, &new_line);
+// Return the range [from, to) for `n` text objects around the cursor,
+// and whether it is linewise, None if there is no such object.
+fn text_object(buf: &Buffer, n: usize, obj: &TextObject) -> Result<Option<(usize, usize, bool)>> {
+    let range = match obj {
+        TextObject::Word(around) => object_word(buf, n, *around, false),
+        TextObject::WWord(around) => object_word(buf, n, *around, true),
+        TextObject::Quote(quote, around) => object_quote(buf, *quote, *around),
+        TextObject::Sentence(around) => object_sentence(buf, n, *around)?,
+        TextObject::Block(yin, yan, around) => {
+            return object_block(buf, n, *yin, *yan, *around);
+        }
+        TextObject::Para(around) => return Ok(object_para(buf, n, *around)),
+    };
+    Ok(range.map(|(from, to)| (from, to, false)))
+}
 
-        Ok(cursor + n)
+// Words, and white-space between them, are counted as objects, within
+// the line. Around object includes trailing white-space, or leading
+// white-space if there is no trailing white-space.
+fn object_word(buf: &Buffer, n: usize, around: bool, big: bool) -> Option<(usize, usize)> {
+    let cursor = buf.to_char_cursor();
+    let row = buf.char_to_line(cursor);
+    let home = buf.line_to_char(row);
+    let chars: Vec<char> = buf.line(row).chars().take(line_chars(buf, row)).collect();
+    if chars.is_empty() {
+        return None;
     }
 
-    fn cud_char(&mut self, cursor: Option<usize>, ch: char) -> Result<usize> {
-        let cursor = cursor.unwrap_or(self.cursor);
-        self.rope.insert_char(cursor, ch);
-        Ok(cursor + 1)
-    }
+    let class = |ch: char| match ch {
+        ch if ch.is_whitespace() => 0,
+        ch if big || ch.is_alphanumeric() || ch == '_' => 1,
+        _ => 2,
+    };
+    let at = cmp::min(cursor - home, chars.len() - 1);
 
-    fn cud_str(&mut self, cursor: Option<usize>, txt: &str) -> Result<usize> {
-        let cursor = cursor.unwrap_or(self.cursor);
-        self.rope.insert(cursor, txt);
-        Ok(cursor + text::width(txt.chars()))
+    let mut from = at;
+    while from > 0 && class(chars[from - 1]) == class(chars[at]) {
+        from -= 1;
+    }
+    let mut to = at;
+    for _ in 0..if_else!(around, n.saturating_mul(2), n) {
+        match chars.get(to) {
+            Some(ch) => {
+                let c = class(*ch);
+                to += chars[to..].iter().take_while(|ch| class(**ch) == c).count();
+            }
+            None => break,
+        }
+    }
+    if around && !chars[at].is_whitespace() && !chars[to - 1].is_whitespace() {
+        while from > 0 && chars[from - 1].is_whitespace() {
+            from -= 1;
+        }
     }
 
-    fn cud_delete<R>(&mut self, range: R) -> Result<()>
-    where
-        R: RangeBounds<usize>,
-    {
-        use std::ops::Bound::{Excluded, Included, Unbounded};
+    Some((home + from, home + to))
+}
 
-        let n_chars = self.rope.len_chars();
+// Sentences, and white-space between them, are counted as objects, within
+// the paragraph. Around object includes trailing white-space, or leading
+// white-space if there is no trailing white-space. Sentence boundaries
+// follow [is_sentence_start], skipping abbreviations.
+fn object_sentence(buf: &Buffer, n: usize, around: bool) -> Result<Option<(usize, usize)>> {
+    let cursor = buf.to_char_cursor();
+    let (from, to) = {
+        let (last, row) = (buf.to_last_line_idx(), buf.char_to_line(cursor));
+        let blank = |row: usize| is_para_boundary(&buf.line(row));
+        if row > last || blank(row) {
+            return Ok(None);
+        }
+        let (mut top, mut bot) = (row, row);
+        while top > 0 && !blank(top - 1) {
+            top -= 1;
+        }
+        while bot < last && !blank(bot + 1) {
+            bot += 1;
+        }
+        let to = buf.line_to_char(bot) + line_chars(buf, bot);
+        (buf.line_to_char(top), to)
+    };
+    let chars: Vec<char> = buf.chars_at(from, DP::Right)?.take(to - from).collect();
+
+    // split paragraph into (from, to, is-whitespace) units.
+    let starts: Vec<usize> = {
+        let ws = |i: usize| chars[i].is_whitespace();
+        let iter = (0..chars.len()).filter(|i| !ws(*i) && (*i == 0 || ws(*i - 1)));
+        let iter = iter
+            .enumerate()
+            .filter(|(k, i)| *k == 0 || is_sentence_start(buf, from + i));
+        iter.map(|(_, i)| i).collect()
+    };
+    let mut units: Vec<(usize, usize, bool)> = vec![];
+    match starts.first() {
+        Some(s) if *s > 0 => units.push((0, *s, true)),
+        _ => (),
+    }
+    for (k, s) in starts.iter().enumerate() {
+        let next = starts.get(k + 1).cloned().unwrap_or(chars.len());
+        let ws = chars[*s..next]
+            .iter()
+            .rev()
+            .take_while(|ch| ch.is_whitespace());
+        let e = next - ws.count();
+        units.push((*s, e, false));
+        if e < next {
+            units.push((e, next, true));
+        }
+    }
 
-        let from = match range.start_bound() {
-            Included(from) => cmp::min(*from, n_chars.saturating_sub(1)),
-            Excluded(from) => cmp::min(from.saturating_add(1), n_chars),
-            Unbounded => 0,
-        };
+    let at = cursor.saturating_sub(from);
+    let u = match units.iter().position(|(_, z, _)| at < *z) {
+        Some(u) => u,
+        None => return Ok(None),
+    };
+    let v = cmp::min(u + if_else!(around, n.saturating_mul(2), n), units.len()) - 1;
+    let (mut a, z) = (units[u].0, units[v].1);
+    if around && !units[u].2 && !units[v].2 && u > 0 && units[u - 1].2 {
+        a = units[u - 1].0;
+    }
 
-        let to = match range.end_bound() {
-            Included(to) => cmp::min((*to).saturating_add(1), n_chars),
-            Excluded(to) => cmp::min(*to, n_chars),
-            Unbounded => n_chars,
-        };
+    Ok(Some((from + a, from + z)))
+}
 
-        if from < to {
-            self.rope.remove(from..to);
+// Paragraphs, and blank lines between them, are counted as objects,
+// linewise. Around object includes trailing blank lines, or leading
+// blank lines if there are no trailing blank lines.
+fn object_para(buf: &Buffer, n: usize, around: bool) -> Option<(usize, usize, bool)> {
+    let last = buf.to_last_line_idx();
+    let blank = |row: usize| is_para_boundary(&buf.line(row));
+    let row = cmp::min(buf.char_to_line(buf.to_char_cursor()), last);
+
+    let mut from = row;
+    while from > 0 && blank(from - 1) == blank(row) {
+        from -= 1;
+    }
+    let mut to = row;
+    for _ in 0..if_else!(around, n.saturating_mul(2), n) {
+        if to > last {
+            break;
+        }
+        let b = blank(to);
+        while to <= last && blank(to) == b {
+            to += 1;
+        }
+    }
+    if around && !blank(row) && !blank(to - 1) {
+        while from > 0 && blank(from - 1) {
+            from -= 1;
         }
-        Ok(())
     }
+
+    Some((buf.line_to_char(from), buf.line_to_char(to), true))
 }
 
-fn mto_left(buf: &Buffer, mut n: usize, dp: DP) -> Result<usize> {
+// Quoted string within the line, quotes are paired from the start of the
+// line, skipping escaped quotes. Pick the pair under the cursor, or the
+// first pair after the cursor.
+fn object_quote(buf: &Buffer, quote: char, around: bool) -> Option<(usize, usize)> {
     let cursor = buf.to_char_cursor();
-    let home = buf.to_line_home(Some(cursor));
-    let new_cursor = cursor.saturating_sub(n);
+    let row = buf.char_to_line(cursor);
+    let home = buf.line_to_char(row);
+    let chars: Vec<char> = buf.line(row).chars().take(line_chars(buf, row)).collect();
+    let at = cursor - home;
 
-    let cursor = match dp {
-        DP::LineBound if new_cursor >= home => new_cursor,
-        DP::LineBound => home,
-        DP::Nobound if new_cursor >= home => new_cursor,
-        DP::Nobound => {
-            n = n - (cursor - home);
-            let mut iter = (0..buf.char_to_line(cursor)).rev();
-            loop {
-                match iter.next() {
-                    Some(line_idx) => {
-                        let s = buf.line(line_idx);
-                        let home = buf.line_to_char(line_idx);
-                        match text::visual_line_n(&s) {
-                            m if m == n => break home,
-                            m if m > n => break home + (m - n),
-                            m => n = n - m,
-                        }
-                    }
-                    None => break 0,
+    let mut quotes = vec![];
+    let mut escaped = false;
+    for (i, ch) in chars.iter().enumerate() {
+        match ch {
+            ch if *ch == quote && !escaped => quotes.push(i),
+            _ => (),
+        }
+        escaped = *ch == '\\' && !escaped;
+    }
+
+    let (a, z) = quotes
+        .chunks(2)
+        .filter(|q| q.len() == 2)
+        .map(|q| (q[0], q[1]))
+        .find(|(_, z)| at <= *z)?;
+
+    let (from, to) = match around {
+        true => {
+            let (mut from, mut to) = (a, z + 1);
+            let n = chars[to..]
+                .iter()
+                .take_while(|ch| ch.is_whitespace())
+                .count();
+            to += n;
+            if n == 0 {
+                while from > 0 && chars[from - 1].is_whitespace() {
+                    from -= 1;
                 }
             }
+            (from, to)
         }
-        DP::None => new_cursor,
-        dp => err_at!(Fatal, msg: format!("invalid direction: {}", dp))?,
+        false => (a + 1, z),
     };
-    Ok(cursor)
+    Some((home + from, home + to))
 }
 
-fn mto_right(buf: &Buffer, mut n: usize, dp: DP) -> Result<usize> {
+// `n`-th enclosing block between `yin` and `yan`, handling nested blocks.
+// Inner object of a multi-line block leaves out the newline after `yin`
+// and the indentation before `yan`, and covers whole lines.
+fn object_block(
+    buf: &Buffer,
+    mut n: usize,
+    yin: char,
+    yan: char,
+    around: bool,
+) -> Result<Option<(usize, usize, bool)>> {
     let cursor = buf.to_char_cursor();
-    let line_idx = buf.char_to_line(cursor);
-    let home = buf.to_line_home(Some(cursor));
-    let end = {
-        let s = buf.line(line_idx);
-        home + text::visual_line_n(&s)
+    let start = match buf.chars_at(cursor, DP::Right)?.next() {
+        Some(ch) if ch == yin => cursor + 1,
+        _ => cursor,
     };
-    let new_cursor = cursor + n;
 
-    let cursor = match dp {
-        DP::LineBound if new_cursor < end => new_cursor,
-        DP::LineBound if home < end => end.saturating_sub(1),
-        DP::LineBound => end,
-        DP::Nobound if new_cursor < end => new_cursor,
-        DP::Nobound => {
-            let mut iter = buf.lines_at(line_idx, DP::Right)?.enumerate();
-            loop {
-                match iter.next() {
-                    Some((0, _)) => n = n - (end - cursor),
-                    Some((i, line)) => {
-                        let m = text::visual_line_n(&line);
-                        match buf.line_to_char(line_idx + i) {
-                            home if n <= m => break home + n.saturating_sub(1),
-                            _ => n = n - m,
-                        }
-                    }
-                    None => break mto_end(buf)?,
-                }
+    let mut open = None;
+    let mut depth = 0_usize;
+    for (i, ch) in buf.chars_at(start, DP::Left)?.enumerate() {
+        match ch {
+            ch if ch == yan => depth += 1,
+            ch if ch == yin && depth > 0 => depth -= 1,
+            ch if ch == yin && n > 1 => n -= 1,
+            ch if ch == yin => {
+                open = Some(start - i - 1);
+                break;
             }
+            _ => (),
         }
-        dp => err_at!(Fatal, msg: format!("invalid direction: {}", dp))?,
+    }
+    let open = match open {
+        Some(open) => open,
+        None => return Ok(None),
     };
-    Ok(cursor)
-}
 
-fn mto_line_home(buf: &Buffer, pos: DP) -> Result<usize> {
-    let cursor = buf.to_line_home(None);
-    let cursor = match pos {
-        DP::TextCol => {
-            let xy = buf.to_change().to_xy_cursor(Some(cursor));
-            let n = skip_whitespace(&buf.line(xy.row), xy.col, DP::Right)?;
-            cursor + n
+    let mut close = None;
+    let mut depth = 0_usize;
+    for (i, ch) in buf.chars_at(open + 1, DP::Right)?.enumerate() {
+        match ch {
+            ch if ch == yin => depth += 1,
+            ch if ch == yan && depth > 0 => depth -= 1,
+            ch if ch == yan => {
+                close = Some(open + 1 + i);
+                break;
+            }
+            _ => (),
         }
-        DP::StickyCol | DP::None => cursor,
-        dp => err_at!(Fatal, msg: format!("invalid direction: {}", dp))?,
+    }
+    let close = match close {
+        Some(close) => close,
+        None => return Ok(None),
     };
-    Ok(cursor)
-}
 
-fn mto_line_end(buf: &Buffer, n: usize, dp: DP) -> Result<usize> {
-    // When a `n` is given also go `n-1` lines downward.
-    let cursor = {
-        let cursor = mto_down(buf, n.saturating_sub(1), DP::None)?;
-        let s = buf.line(buf.char_to_line(cursor));
-        let m = text::visual_line_n(&s).saturating_sub(1);
-        buf.to_line_home(Some(cursor)) + m
+    if around {
+        return Ok(Some((open, close + 1, false)));
+    }
+    let (from, nl) = match buf.chars_at(open + 1, DP::Right)?.next() {
+        Some('\n') if open + 1 < close => (open + 2, true),
+        _ => (open + 1, false),
     };
+    let range = match buf.to_line_home(Some(close)) {
+        home if home > from && buf.slice(home..close).trim().is_empty() => (from, home, nl),
+        _ => (from, cmp::max(from, close), false),
+    };
+    Ok(Some(range))
+}
 
-    let cursor = match dp {
-        DP::TextCol => {
-            let xy = buf.to_change().to_xy_cursor(Some(cursor));
-            let n = skip_whitespace(&buf.line(xy.row), xy.col, DP::Left)?;
-            cursor.saturating_sub(n)
+fn opr_delete(buf: &mut Buffer, n: usize, mto: Mto) -> Result<Event> {
+    match opr_range(buf, n, mto)? {
+        Some((from, to, linewise)) if from < to => delete_text(buf, from, to, linewise),
+        _ => Ok(Event::Noop),
+    }
+}
+
+fn opr_change(buf: &mut Buffer, n: usize, mto: Mto) -> Result<Event> {
+    let cursor = buf.to_char_cursor();
+    let (from, to, linewise) = match opr_range(buf, n, mto.clone())? {
+        Some(range) => range,
+        None => return Ok(Event::Noop),
+    };
+    let blank = match buf.chars_at(cursor, DP::Right)?.next() {
+        Some(ch) => ch.is_whitespace(),
+        None => true,
+    };
+
+    let to = match &mto {
+        // `cw` on a non-blank behaves like `ce`.
+        Mto::Word(_, DP::Right, DP::Start) | Mto::WWord(_, DP::Right, DP::Start) if !blank => {
+            from + buf.slice(from..to).trim_end().chars().count()
         }
-        DP::StickyCol | DP::None => cursor,
-        dp => err_at!(Fatal, msg: format!("invalid direction: {}", dp))?,
+        _ => to,
     };
 
-    Ok(cursor)
+    change_text(buf, from, to, linewise)
 }
 
-fn mto_line_middle(buf: &Buffer, p: usize) -> Result<usize> {
-    let n = {
-        let s = buf.line(buf.char_to_line(buf.to_char_cursor()));
-        text::visual_line_n(&s)
-    };
-    let cursor = {
-        let n = (((p as f64) / 100.0) * (n as f64)) as usize;
-        buf.to_line_home(None) + n
+fn opr_yank(buf: &mut Buffer, n: usize, mto: Mto) -> Result<Event> {
+    match opr_range(buf, n, mto)? {
+        Some((from, to, linewise)) if from < to => yank_text(buf, from, to, linewise),
+        _ => Ok(Event::Noop),
+    }
+}
+
+// Delete text between [from, to) and save it in unnamed register.
+fn delete_text(buf: &mut Buffer, from: usize, to: usize, linewise: bool) -> Result<Event> {
+    use crate::event::Edit;
+
+    let txt = buf.slice(from..to);
+    save_register(buf, txt.clone(), linewise);
+
+    let change = Change::fork(buf.as_mut_change());
+    *buf.as_mut_change() = change;
+    buf.cud_delete(from..to)?;
+
+    buf.set_cursor(saturate_cursor(buf, from));
+    let cursor = if linewise {
+        mto_line_home(buf, DP::TextCol)?
+    } else {
+        mto_right(buf, 0, DP::LineBound)?
     };
-    Ok(cursor)
+    buf.set_cursor(cursor).clear_sticky_col();
+
+    Ok(Event::Edit(Edit::new_del(from, txt)))
 }
 
-fn mto_column(buf: &Buffer, n: usize) -> Result<usize> {
+// Delete `n` characters from cursor in direction `dp`, without crossing
+// the current line, and save it in unnamed register.
+fn delete_chars(buf: &mut Buffer, n: usize, dp: DP) -> Result<Event> {
+    let cursor = buf.to_char_cursor();
     let home = buf.to_line_home(None);
-    let n = {
-        let s = buf.line(buf.char_to_line(buf.to_char_cursor()));
-        cmp::min(text::visual_line_n(&s), n)
+    let (from, to) = match dp {
+        DP::Right => {
+            let row = buf.char_to_line(cursor);
+            let end = home + line_chars(buf, row);
+            (cursor, cmp::min(cursor.saturating_add(n), end))
+        }
+        DP::Left => (cmp::max(cursor.saturating_sub(n), home), cursor),
+        _ => err_at!(Fatal, msg: format!("unreachable"))?,
     };
-    Ok(home + n.saturating_sub(1))
+    match from < to {
+        true => delete_text(buf, from, to, false),
+        false => Ok(Event::Noop),
+    }
 }
 
-fn mto_char(buf: &Buffer, evnt: Mto) -> Result<usize> {
+// Change the case of text, for operators `~`, `g~`, `gu` and `gU`. Without
+// a motion, `n` characters from cursor are changed and cursor advances
+// past them, within the line.
+fn opr_case(buf: &mut Buffer, opr: Opr) -> Result<Event> {
     let cursor = buf.to_char_cursor();
+    let (n, mto) = (opr.to_count(), opr.to_motion());
+    match mto {
+        Mto::None => {
+            let end = {
+                let row = buf.char_to_line(cursor);
+                buf.to_line_home(None) + line_chars(buf, row)
+            };
+            let to = cmp::min(cursor.saturating_add(n), end);
+            let evnt = case_text(buf, cursor, to, &opr)?;
+            let cursor = cmp::min(to, end.saturating_sub(1));
+            buf.set_cursor(cmp::max(cursor, buf.to_line_home(None)));
+            buf.clear_sticky_col();
+            Ok(evnt)
+        }
+        mto => match opr_range(buf, n, mto)? {
+            Some((from, to, linewise)) if from < to => {
+                let from = match buf.to_line_home(Some(from)) {
+                    // range might include the newline previous to the first line.
+                    home if linewise && home != from => {
+                        buf.line_to_char(buf.char_to_line(from) + 1)
+                    }
+                    _ => from,
+                };
+                let evnt = case_text(buf, from, to, &opr)?;
+                buf.set_cursor(from).clear_sticky_col();
+                Ok(evnt)
+            }
+            _ => Ok(Event::Noop),
+        },
+    }
+}
 
-    let (n, ch, dp, pos) = match evnt {
-        Mto::CharF(n, Some(ch), dp) => (n, ch, dp, 'f'),
-        Mto::CharT(n, Some(ch), dp) => (n, ch, dp, 't'),
-        Mto::CharT(_, None, _) | Mto::None => return Ok(cursor),
-        mto => err_at!(Fatal, msg: format!("unexpected {}", mto))?,
+// Transform the case of text between [from, to) based on the operator,
+// as a single change.
+fn case_text(buf: &mut Buffer, from: usize, to: usize, opr: &Opr) -> Result<Event> {
+    use crate::event::Edit;
+
+    let oldt = buf.slice(from..to);
+    let newt: String = match opr {
+        Opr::Swapcase(_, _) => oldt
+            .chars()
+            .map(|ch| match ch {
+                ch if ch.is_uppercase() => ch.to_lowercase().to_string(),
+                ch => ch.to_uppercase().to_string(),
+            })
+            .collect(),
+        Opr::Lowercase(_, _) => oldt.to_lowercase(),
+        Opr::Uppercase(_, _) => oldt.to_uppercase(),
+        _ => err_at!(Fatal, msg: format!("unreachable"))?,
     };
+    if newt == oldt {
+        return Ok(Event::Noop);
+    }
+
+    let change = Change::fork(buf.as_mut_change());
+    *buf.as_mut_change() = change;
+    buf.cud_delete(from..to)?;
+    buf.cud_str(Some(from), &newt)?;
+
+    Ok(Event::Edit(Edit::new_chg(from, oldt, newt)))
+}
 
-    let mut iter = buf.chars_at(cursor, dp)?.enumerate();
-    if let DP::Right = dp {
-        iter.next();
+fn opr_shift(buf: &mut Buffer, opr: Opr) -> Result<Event> {
+    match opr_range(buf, opr.to_count(), opr.to_motion())? {
+        Some((from, to, _)) if from < to => shift_range(buf, from, to, &opr),
+        _ => Ok(Event::Noop),
     }
-    let item = iter
-        .filter_map(|(i, a)| if_else!(a == ch, Some(i), None))
-        .skip(n.saturating_sub(1))
-        .next()
-        .clone();
+}
 
-    let cursor = match (item, dp, pos) {
-        (Some(i), DP::Right, 'f') => cursor + i,
-        (Some(i), DP::Right, 't') => (cursor + i).saturating_sub(1),
-        (Some(i), DP::Left, 'f') => cursor.saturating_sub(i + 1),
-        (Some(i), DP::Left, 't') => cursor.saturating_sub(i),
-        (None, _, _) => cursor,
-        (_, dp, pos) => err_at!(Fatal, msg: format!("bad {} {}", dp, pos))?,
-    };
-    Ok(cursor)
+// Shift lines spanning [from, to), for operators `>` and `<`, by one
+// shift-width and move the cursor to the first non-blank character of the
+// first line.
+fn shift_range(buf: &mut Buffer, from: usize, to: usize, opr: &Opr) -> Result<Event> {
+    let dp = if_else!(matches!(opr, Opr::RShift(_, _)), DP::Right, DP::Left);
+    let (from, to) = to_line_range(buf, from, to);
+
+    let evnt = buf.shift_lines(from, to, 1, dp)?;
+    buf.set_cursor(buf.line_to_char(from));
+    let cursor = mto_line_home(buf, DP::TextCol)?;
+    buf.set_cursor(cursor).clear_sticky_col();
+    Ok(evnt)
 }
 
-fn mto_up(buf: &Buffer, n: usize, dp: DP) -> Result<usize> {
-    let bc_xy = buf.to_xy_cursor(None);
-    let row = bc_xy.row.saturating_sub(n);
-    let line = &buf.line(row);
-    let char_end = text::visual_line_n(&line).saturating_sub(1);
-    let col = cmp::min(bc_xy.col, char_end);
+// Shift lines between [from, to) by `n` steps of shift-width, refer
+// [Buffer::shift_lines] for details. If `fork` is true, the shift is
+// recorded as a new change.
+fn shift_text(
+    buf: &mut Buffer,
+    from: usize,
+    to: usize,
+    n: usize,
+    dp: DP,
+    fork: bool,
+) -> Result<Event> {
+    use crate::event::Edit;
+
+    let to = cmp::min(to, buf.n_lines());
+    if from >= to {
+        return Ok(Event::Noop);
+    }
 
-    let home = buf.line_to_char(row);
-    let cursor = match dp {
-        DP::StickyCol => match buf.sticky_col {
-            StickyCol::Home => home,
-            StickyCol::End => home + char_end,
-            StickyCol::None => home + col,
-        },
-        DP::TextCol => home + skip_whitespace(&line, bc_xy.col, DP::Right)?,
-        DP::None => home + col,
-        dp => err_at!(Fatal, msg: format!("invalid direction: {}", dp))?,
-    };
-    Ok(cursor)
+    let width = buf.shift_width.saturating_mul(n);
+    let mut newt = String::default();
+    for row in from..to {
+        let line = buf.line(row);
+        let txt = line.trim_start_matches(&[' ', '\t'][..]);
+        if txt.trim_end_matches(&['\n', '\r'][..]).is_empty() {
+            newt.push_str(&line);
+            continue;
+        }
+        let indent = to_indent_width(&line[..line.len() - txt.len()], buf.tab_stop);
+        let indent = match dp {
+            DP::Right => indent.saturating_add(width),
+            DP::Left => indent.saturating_sub(width),
+            _ => err_at!(Fatal, msg: format!("unreachable"))?,
+        };
+        newt.push_str(&to_indent(indent, buf.expand_tab, buf.tab_stop));
+        newt.push_str(txt);
+    }
+
+    let (a, z) = (buf.line_to_char(from), buf.line_to_char(to));
+    let oldt = buf.slice(a..z);
+    if oldt == newt {
+        return Ok(Event::Noop);
+    }
+
+    if fork {
+        let change = Change::fork(buf.as_mut_change());
+        *buf.as_mut_change() = change;
+    }
+    let cursor = buf.to_char_cursor();
+    buf.cud_delete(a..z)?;
+    buf.cud_str(Some(a), &newt)?;
+    buf.set_cursor(saturate_cursor(buf, cursor));
+
+    Ok(Event::Edit(Edit::new_chg(a, oldt, newt)))
 }
 
-fn mto_down(buf: &Buffer, n: usize, dp: DP) -> Result<usize> {
-    let row = {
-        let row = buf.char_to_line(buf.to_char_cursor()) + n;
-        cmp::min(row, buf.to_last_line_idx())
+// Return the number of spaces to insert at `cursor`, to reach the `n`th
+// next tab-stop, or the shift-width boundary for soft-tab-stop.
+fn to_soft_tab(buf: &Buffer, cursor: usize, n: usize) -> usize {
+    let vcol = {
+        let xy = buf.to_xy_cursor(Some(cursor));
+        text::to_vcol(&buf.line(xy.row), xy.col, buf.tab_stop)
     };
-    let char_end = text::visual_line_n(&buf.line(row)).saturating_sub(1);
-    let col = cmp::min(buf.to_xy_cursor(None).col, char_end);
+    let ts = cmp::max(
+        if_else!(buf.soft_tab_stop, buf.shift_width, buf.tab_stop),
+        1,
+    );
+    ((vcol / ts) + n) * ts - vcol
+}
 
-    let home = buf.line_to_char(row);
-    let cursor = match dp {
-        DP::StickyCol => match buf.sticky_col {
-            StickyCol::Home => home,
-            StickyCol::End => home + char_end,
-            StickyCol::None => home + col,
-        },
-        DP::TextCol => {
-            let xy = buf.to_change().to_xy_cursor(Some(home));
-            home + skip_whitespace(&buf.line(xy.row), xy.col, DP::Right)?
-        }
-        DP::None => home + col,
-        dp => err_at!(Fatal, msg: format!("invalid direction: {}", dp))?,
+// Return the number of characters to delete on backspace at `cursor`.
+// Spaces upto the previous shift-width boundary are deleted as a single
+// soft-tab, otherwise a single character.
+fn to_soft_tab_back(buf: &Buffer, cursor: usize) -> usize {
+    let xy = buf.to_xy_cursor(Some(cursor));
+    let line = buf.line(xy.row);
+    let vcol = text::to_vcol(&line, xy.col, buf.tab_stop);
+    let sw = cmp::max(buf.shift_width, 1);
+    let n_spaces = {
+        let chars: Vec<char> = line.chars().take(xy.col).collect();
+        chars.into_iter().rev().take_while(|ch| *ch == ' ').count()
     };
-    Ok(cursor)
+    let m = cmp::min(n_spaces, vcol - ((vcol.saturating_sub(1) / sw) * sw));
+    cmp::min(cmp::max(m, 1), cursor)
 }
 
-fn mto_row(buf: &Buffer, n: usize, dp: DP) -> Result<usize> {
-    let cursor = buf.line_to_char(cmp::min(n, buf.to_last_line_idx()));
-    let cursor = match dp {
-        DP::TextCol => {
-            let xy = buf.to_xy_cursor(Some(cursor));
-            cursor + skip_whitespace(&buf.line(xy.row), xy.col, DP::Right)?
+// Return indentation for a new line opened after `line`, when auto_indent
+// is enabled. With brace_indent, lines ending in `{` are indented further.
+fn to_auto_indent(buf: &Buffer, line: &str) -> String {
+    if !buf.auto_indent {
+        return String::default();
+    }
+    let txt = line.trim_start_matches(&[' ', '\t'][..]);
+    let indent = &line[..line.len() - txt.len()];
+    match buf.brace_indent && txt.trim_end().ends_with('{') {
+        true => {
+            let n = to_indent_width(indent, buf.tab_stop) + buf.shift_width;
+            to_indent(n, buf.expand_tab, buf.tab_stop)
         }
-        _ => cursor,
-    };
-    Ok(cursor)
+        false => indent.to_string(),
+    }
 }
 
-fn mto_percent(buf: &Buffer, n: usize, dp: DP) -> Result<usize> {
-    let row = {
-        let n_lines = buf.to_last_line_idx() + 1;
-        cmp::min(((n * n_lines) + 99) / 100, n_lines.saturating_sub(1))
+// Expand insert-mode abbreviation, for the word since the last white-space
+// upto cursor, on typing a non-word character. Expansion is part of the
+// insert session's change, hence undone along with the session.
+fn expand_iabbrev(buf: &mut Buffer) -> Result<Event> {
+    use crate::event::{self, Event::Edit};
+
+    if buf.iabbrevs.is_empty() {
+        return Ok(Event::Noop);
+    }
+
+    let cursor = buf.to_char_cursor();
+    let word = {
+        let home = buf.line_to_char(buf.char_to_line(cursor));
+        let chars: Vec<char> = buf.slice(home..cursor).chars().collect();
+        let n = chars
+            .iter()
+            .rev()
+            .take_while(|ch| !ch.is_whitespace())
+            .count();
+        String::from_iter(&chars[(chars.len() - n)..])
     };
-    let cursor = buf.line_to_char(row);
-    let cursor = match dp {
-        DP::TextCol => {
-            let xy = buf.to_change().to_xy_cursor(Some(cursor));
-            cursor + skip_whitespace(&buf.line(xy.row), xy.col, DP::Right)?
-        }
-        _ => cursor,
+    let newt = match buf
+        .iabbrevs
+        .iter()
+        .find(|(w, _)| !w.is_empty() && w == &word)
+    {
+        Some((_, newt)) => newt.clone(),
+        None => return Ok(Event::Noop),
     };
-    Ok(cursor)
+
+    let from = cursor - word.chars().count();
+    buf.cud_delete(from..cursor)?;
+    let cursor = buf.cud_str(Some(from), &newt)?;
+    buf.set_cursor(cursor).clear_sticky_col();
+    Ok(Edit(event::Edit::new_chg(from, word, newt)))
 }
 
-fn mto_end(buf: &Buffer) -> Result<usize> {
-    let line_idx = buf.to_last_line_idx();
-    let n = text::visual_line_n(&buf.line(line_idx));
-    Ok(buf.line_to_char(line_idx) + n.saturating_sub(1))
+// Return the difference between `old` and `new` as (at, removed, txt),
+// where `removed` characters at char-index `at` in `old` are replaced by
+// `txt`, leaving out the common prefix and suffix.
+fn to_diff(old: &str, new: &str) -> (usize, usize, String) {
+    let (old, new): (Vec<char>, Vec<char>) = (old.chars().collect(), new.chars().collect());
+    let prefix = {
+        let iter = old.iter().zip(new.iter());
+        iter.take_while(|(a, b)| a == b).count()
+    };
+    let suffix = {
+        let iter = old[prefix..].iter().rev().zip(new[prefix..].iter().rev());
+        iter.take_while(|(a, b)| a == b).count()
+    };
+    let txt = String::from_iter(&new[prefix..(new.len() - suffix)]);
+    (prefix, old.len() - prefix - suffix, txt)
 }
 
-fn mto_cursor(buf: &Buffer, n: usize) -> Result<usize> {
-    let cursor = buf.to_char_cursor();
-    Ok(limite!(cursor + n, buf.n_chars().saturating_sub(1)))
+// FNV-1a hash of `txt`, stable across runs and platforms.
+fn to_content_hash(txt: &str) -> u64 {
+    let iter = txt.bytes();
+    iter.fold(0xcbf29ce484222325, |h, b| {
+        (h ^ (b as u64)).wrapping_mul(0x100000001b3)
+    })
 }
 
-macro_rules! mto_text_left {
-    ($buf:ident, $n:ident, $pos:ident, $start:expr, $state:ident) => {{
-        let bc_xy = $buf.to_xy_cursor(None);
-        let to_chars = |line: String| -> Vec<char> {
-            let line = text::visual_line(&line);
-            let mut chars: Vec<char> = line.chars().collect();
-            chars.reverse();
-            chars
-        };
+// Append `n` to `out` as LEB128 variable length integer.
+fn put_varint(out: &mut Vec<u8>, mut n: usize) {
+    loop {
+        let b = (n & 0x7f) as u8;
+        n >>= 7;
+        match n {
+            0 => break out.push(b),
+            _ => out.push(b | 0x80),
+        }
+    }
+}
 
-        let (mut iter, row, col) = {
-            let chars: Vec<char> = {
-                let line = $buf.line(bc_xy.row);
-                text::visual_line(&line).chars().collect()
-            };
-            let col = {
-                let n = cmp::min(bc_xy.col + 1, chars.len());
-                if_else!($pos == DP::Start, n, bc_xy.col)
-            };
-            let rem_chars = chars[..col].len();
-            let chars: Vec<(usize, char)> = {
-                let mut chars = chars[..col].to_vec();
-                chars.reverse();
-                chars.into_iter().enumerate().collect()
-            };
-            let iter = $buf.lines_at(bc_xy.row, DP::Left)?.map(to_chars);
-            (
-                WIterChar::new(iter, rem_chars, chars),
-                bc_xy.row,
-                col.saturating_sub(1),
-            )
-        };
+// Read LEB128 variable length integer from `bytes` at `off`, and move
+// `off` past it.
+fn get_varint(bytes: &[u8], off: &mut usize) -> Result<usize> {
+    let (mut n, mut shift) = (0_usize, 0);
+    loop {
+        match bytes.get(*off) {
+            Some(b) if shift < 64 => {
+                *off += 1;
+                n |= ((b & 0x7f) as usize) << shift;
+                if b & 0x80 == 0 {
+                    break Ok(n);
+                }
+                shift += 7;
+            }
+            _ => err_at!(Invalid, msg: format!("truncated undo file"))?,
+        }
+    }
+}
 
-        let mut state = $start;
-        let cursor = loop {
-            state = match iter.next() {
-                Some(item) => match state.push(DP::Left, $pos, item) {
-                    $state::Fin(r, _, None) => {
-                        break xy_to_cursor($buf, (row - r, 0));
-                    }
-                    $state::Fin(r, rc, Some(c)) => {
-                        let col = {
-                            let this = col.saturating_sub(c);
-                            if_else!(r == 0, this, rc.saturating_sub(c + 1))
-                        };
-                        break xy_to_cursor($buf, (row - r, col));
-                    }
-                    state => state,
-                },
-                None if $pos == DP::Start => break $buf.last_char_idx(),
-                None => break 0, // DP::End
-            };
-        };
-        Ok(saturate_cursor($buf, cursor))
-    }};
+// Return leading whitespace for indentation upto column `n`, tabs expand
+// to `tab_stop` columns.
+fn to_indent(n: usize, expand_tab: bool, tab_stop: usize) -> String {
+    let ts = cmp::max(tab_stop, 1);
+    match expand_tab {
+        true => " ".repeat(n),
+        false => "\t".repeat(n / ts) + &" ".repeat(n % ts),
+    }
 }
 
-macro_rules! mto_text_right {
-    ($buf:ident, $n:ident, $pos:ident, $start:expr, $state:ident) => {{
-        let bc_xy = $buf.to_xy_cursor(None);
-        let to_chars = |s: String| -> Vec<char> { text::visual_line(&s).chars().collect() };
+// Return the width of leading whitespace `indent`, in columns, tabs expand
+// to `tab_stop` columns.
+fn to_indent_width(indent: &str, tab_stop: usize) -> usize {
+    let ts = cmp::max(tab_stop, 1);
+    indent.chars().fold(0, |col, ch| {
+        if_else!(ch == '\t', (col / ts + 1) * ts, col + 1)
+    })
+}
 
-        let (mut iter, row, col) = {
-            let chars: Vec<char> = {
-                let line = $buf.line(bc_xy.row);
-                text::visual_line(&line).chars().collect()
-            };
-            let col = if_else!(
-                $pos == DP::Start,
-                bc_xy.col,
-                cmp::min(chars.len(), bc_xy.col + 1)
-            );
-            let rem_chars = chars[col..].len();
-            let chars: Vec<(usize, char)> = {
-                let iter = chars[col..].to_vec().into_iter().enumerate();
-                iter.collect()
-            };
-            let iter = $buf.lines_at(bc_xy.row + 1, DP::Right)?.map(to_chars);
-            (WIterChar::new(iter, rem_chars, chars), bc_xy.row, col)
-        };
+// Return line-range [from, to) spanning the char-range [from, to).
+fn to_line_range(buf: &Buffer, from: usize, to: usize) -> (usize, usize) {
+    let from = match buf.to_line_home(Some(from)) {
+        // range might include the newline previous to the first line.
+        home if home != from && buf.slice(from..from + 1) == "\n" => from + 1,
+        _ => from,
+    };
+    (buf.char_to_line(from), buf.char_to_line(to - 1) + 1)
+}
 
-        let mut state = $start;
-        let cursor = loop {
-            state = match iter.next() {
-                Some(item) => match state.push(DP::Right, $pos, item) {
-                    $state::Fin(r, _, None) => {
-                        break xy_to_cursor($buf, (row + r, 0));
-                    }
-                    $state::Fin(r, _, Some(c)) => {
-                        let col = {
-                            let n = line_chars($buf, row + r).saturating_sub(1);
-                            let col = if_else!(r == 0, col.saturating_add(c), c);
-                            cmp::min(n, col)
-                        };
-                        break xy_to_cursor($buf, (row + r, col));
-                    }
-                    state => state,
-                },
-                None => break $buf.last_char_idx(),
-            };
-        };
-        Ok(saturate_cursor($buf, cursor))
-    }};
+fn opr_format(buf: &mut Buffer, opr: Opr) -> Result<Event> {
+    match opr_range(buf, opr.to_count(), opr.to_motion())? {
+        Some((from, to, _)) if from < to => format_range(buf, from, to),
+        _ => Ok(Event::Noop),
+    }
 }
 
-fn mto_words_left(buf: &Buffer, n: usize, pos: DP) -> Result<usize> {
-    mto_text_left!(buf, n, pos, MtoWord::St(n), MtoWord)
+// Format lines spanning [from, to), for operators `gq` and `gw`.
+fn format_range(buf: &mut Buffer, from: usize, to: usize) -> Result<Event> {
+    let (from, to) = to_line_range(buf, from, to);
+    buf.format_lines(from, to)
 }
 
-fn mto_words_right(buf: &Buffer, n: usize, pos: DP) -> Result<usize> {
-    mto_text_right!(buf, n, pos, MtoWord::St(n), MtoWord)
+fn opr_fold(buf: &mut Buffer, opr: Opr) -> Result<Event> {
+    match opr_range(buf, opr.to_count(), opr.to_motion())? {
+        Some((from, to, _)) if from < to => fold_range(buf, from, to),
+        _ => Ok(Event::Noop),
+    }
 }
 
-fn mto_wwords_left(buf: &Buffer, n: usize, pos: DP) -> Result<usize> {
-    mto_text_left!(buf, n, pos, MtoWWord::St(n), MtoWWord)
+// Create a closed fold over lines spanning [from, to), for operator `zf`.
+fn fold_range(buf: &mut Buffer, from: usize, to: usize) -> Result<Event> {
+    let (from, to) = to_line_range(buf, from, to);
+    fold_lines(buf, from, to - 1)
 }
 
-fn mto_wwords_right(buf: &Buffer, n: usize, pos: DP) -> Result<usize> {
-    mto_text_right!(buf, n, pos, MtoWWord::St(n), MtoWWord)
+// Create a closed fold over lines `[from, to]`, cursor is moved to the
+// first line of the fold. Folds derived from the syntax tree cannot be
+// created manually.
+fn fold_lines(buf: &mut Buffer, from: usize, to: usize) -> Result<Event> {
+    use crate::pubsub::Notify;
+
+    if buf.fold_syntax {
+        let span = "cannot create fold with foldmethod=syntax".to_string();
+        return Ok(Event::Notify(Notify::Status(vec![span.into()])));
+    }
+    buf.add_fold(from, to);
+    buf.set_cursor(buf.line_to_char(from)).clear_sticky_col();
+    Ok(Event::Noop)
 }
 
-fn mto_sentence_left(buf: &Buffer, n: usize) -> Result<usize> {
-    let pos = DP::None;
-    let start = MtoSentence::St(n);
-    let cursor = mto_text_left!(buf, n, pos, start, MtoSentence)?;
-    let is_skip = |(_, ch): &(usize, char)| -> bool {
-        match ch {
-            ch if ch.is_whitespace() => true,
-            '.' | ')' | ']' | '"' | '\'' => true,
-            _ => false,
+// Open, or close, the fold under cursor, for `zo` and `zc`. When the fold
+// is closed, cursor is moved to its first line.
+fn fold_cursor(buf: &mut Buffer, open: bool) -> Result<Event> {
+    let row = buf.to_xy_cursor(None).row;
+    if_else!(open, buf.open_fold(row), buf.close_fold(row));
+    if let Some((a, _)) = buf.to_closed_fold(row) {
+        buf.set_cursor(buf.line_to_char(a)).clear_sticky_col();
+    }
+    Ok(Event::Noop)
+}
+
+// Re-wrap paragraphs in `txt` to `width` columns, refer
+// [Buffer::format_lines] for details.
+fn format_text(txt: &str, width: usize, tab_stop: usize) -> String {
+    // paragraph being collected, (indent, comment-leader, words)
+    let mut para: Option<(&str, &str, Vec<&str>)> = None;
+    let mut lines: Vec<String> = vec![];
+    for line in txt.lines() {
+        let line = line.trim_end_matches('\r');
+        let body = line.trim_start();
+        let indent = &line[..line.len() - body.len()];
+        let leader = COMMENT_LEADERS
+            .iter()
+            .find(|l| match body.strip_prefix(*l) {
+                Some(rest) => rest.is_empty() || rest.starts_with(char::is_whitespace),
+                None => false,
+            })
+            .copied()
+            .unwrap_or("");
+        let words: Vec<&str> = body[leader.len()..].split_whitespace().collect();
+
+        match para.as_mut() {
+            Some((_, l, ws)) if *l == leader && !words.is_empty() => ws.extend(words),
+            _ => {
+                if let Some((indent, leader, words)) = para.take() {
+                    wrap_words(indent, leader, words, width, tab_stop, &mut lines);
+                }
+                match words.is_empty() {
+                    true => lines.push(line.to_string()),
+                    false => para = Some((indent, leader, words)),
+                }
+            }
         }
-    };
+    }
+    if let Some((indent, leader, words)) = para.take() {
+        wrap_words(indent, leader, words, width, tab_stop, &mut lines);
+    }
 
-    let i = if cursor == 0 {
-        0
-    } else {
-        let iter = buf.chars_at(cursor, DP::Right)?.enumerate();
-        iter.skip_while(is_skip)
-            .next()
-            .clone()
-            .map(|(i, _)| i)
-            .unwrap_or(buf.n_chars().saturating_sub(1))
-    };
-    Ok(cursor + if_else!(buf.to_char_cursor() <= cursor + i, 0, i))
+    let mut newt = lines.join("\n");
+    if txt.ends_with('\n') {
+        newt.push('\n');
+    }
+    newt
 }
 
-fn mto_sentence_right(buf: &Buffer, n: usize) -> Result<usize> {
-    let pos = DP::None;
-    let start = {
-        let ln = {
-            let bc_xy = buf.to_xy_cursor(None);
-            text::visual_line_n(&buf.line(bc_xy.row))
-        };
-        if_else!(ln == 0, MtoSentence::Ws(1, 0, n), MtoSentence::St(n))
+// Fill `words` into lines of `width` columns, each line prefixed with
+// `indent` and comment `leader`. Words longer than `width` are not split,
+// tabs in `indent` expand to `tab_stop` columns.
+fn wrap_words(
+    indent: &str,
+    leader: &str,
+    words: Vec<&str>,
+    width: usize,
+    tab_stop: usize,
+    lines: &mut Vec<String>,
+) {
+    let prefix = match leader {
+        "" => indent.to_string(),
+        leader => format!("{}{} ", indent, leader),
     };
-    mto_text_right!(buf, n, pos, start, MtoSentence)
+    let start = to_indent_width(indent, tab_stop) + prefix.chars().count() - indent.chars().count();
+
+    let (mut line, mut col) = (prefix.clone(), start);
+    for word in words.into_iter() {
+        let n = word.chars().count();
+        if col > start && col + 1 + n > width {
+            lines.push(mem::replace(&mut line, prefix.clone()));
+            col = start;
+        }
+        if col > start {
+            line.push(' ');
+            col += 1;
+        }
+        line.push_str(word);
+        col += n;
+    }
+    lines.push(line);
 }
 
-fn mto_paras_left(buf: &Buffer, mut n: usize) -> Result<usize> {
-    let row = buf.to_xy_cursor(None).row;
-    let mut iter = buf.lines_at(row, DP::Left)?.enumerate();
-    let row = loop {
-        match iter.next() {
-            Some((r, line)) => match text::visual_line_n(&line) {
-                0 if n == 1 => break row.saturating_sub(r + 1),
-                0 => n -= 1,
-                _ => (),
-            },
-            None => break 0,
-        }
+// Replace `n` characters from cursor with `ch`, leaving the cursor on the
+// last replaced character. Abort if there are not enough characters in
+// the line.
+fn replace_chars(buf: &mut Buffer, n: usize, ch: char) -> Result<Event> {
+    use crate::event::Edit;
+
+    let cursor = buf.to_char_cursor();
+    let end = {
+        let row = buf.char_to_line(cursor);
+        buf.to_line_home(None) + line_chars(buf, row)
     };
-    Ok(saturate_cursor(buf, buf.line_to_char(row)))
+    let to = cursor.saturating_add(n);
+    if n == 0 || to > end {
+        return Ok(Event::Noop);
+    }
+
+    let oldt = buf.slice(cursor..to);
+    let newt = ch.to_string().repeat(n);
+
+    let change = Change::fork(buf.as_mut_change());
+    *buf.as_mut_change() = change;
+    buf.cud_delete(cursor..to)?;
+    buf.cud_str(Some(cursor), &newt)?;
+    buf.set_cursor(to - 1).clear_sticky_col();
+
+    Ok(Event::Edit(Edit::new_chg(cursor, oldt, newt)))
 }
 
-fn mto_paras_right(buf: &Buffer, mut n: usize) -> Result<usize> {
-    let row = buf.to_xy_cursor(None).row;
-    let mut iter = buf.lines_at(row, DP::Right)?.enumerate();
-    iter.next();
-    let row = loop {
-        match iter.next() {
-            Some((r, line)) => match text::visual_line_n(&line) {
-                0 if n == 1 => break row.saturating_add(r),
-                0 => n -= 1,
-                _ => (),
-            },
-            None => break 0,
-        }
+// Delete text between [from, to), save it in unnamed register and switch
+// to insert mode. Linewise change preserves the line.
+fn change_text(buf: &mut Buffer, from: usize, to: usize, linewise: bool) -> Result<Event> {
+    use crate::event::Edit;
+
+    let rtxt = buf.slice(from..to);
+    let (from, to) = if linewise {
+        // range might include the newline previous to the first line.
+        let from = match buf.to_line_home(Some(from)) {
+            home if home == from => from,
+            _ => buf.line_to_char(buf.char_to_line(from) + 1),
+        };
+        let txt = buf.slice(from..to);
+        let n = txt.trim_end_matches(&['\n', '\r'][..]).chars().count();
+        (from, from + n)
+    } else {
+        (from, to)
     };
-    Ok(saturate_cursor(buf, buf.line_to_char(row)))
-}
+    let rtxt = if_else!(linewise, rtxt, buf.slice(from..to));
+    save_register(buf, rtxt, linewise);
 
-fn mto_match_pair(buf: &Buffer) -> Result<usize> {
-    use crate::match_pair;
+    mod_insert(buf, 1, DP::None)?;
 
-    let cursor = buf.to_char_cursor();
-    Ok(match_pair::match_under_cursor(buf).unwrap_or(cursor))
+    let txt = buf.slice(from..to);
+    buf.cud_delete(from..to)?;
+    buf.set_cursor(from).clear_sticky_col();
+
+    Ok(Event::Edit(Edit::new_del(from, txt)))
 }
 
-fn mto_unmatch_pair(buf: &Buffer, ch: char, n: usize, dir: DP) -> Result<usize> {
-    use crate::match_pair;
+// Save text between [from, to) in unnamed register and move the cursor
+// to the start of the range.
+fn yank_text(buf: &mut Buffer, from: usize, to: usize, linewise: bool) -> Result<Event> {
+    let txt = buf.slice(from..to);
+    save_register(buf, txt, linewise);
 
     let cursor = buf.to_char_cursor();
-    let cursor = match dir {
-        DP::Left => match_pair::unmatch_before(buf, ch, n).unwrap_or(cursor),
-        DP::Right => match_pair::unmatch_after(buf, ch, n).unwrap_or(cursor),
-        _ => cursor,
+    let cursor = if linewise {
+        let home = match buf.to_line_home(Some(from)) {
+            home if home == from => from,
+            _ => buf.line_to_char(buf.char_to_line(from) + 1),
+        };
+        let row = buf.char_to_line(cursor);
+        if_else!(buf.char_to_line(home) == row, cursor, home)
+    } else {
+        from
     };
-    Ok(cursor)
-}
-
-fn mto_bracket(buf: &mut Buffer, e: Mto) -> Result<Event> {
-    let mut m = 0;
-    let mut cursor = buf.to_char_cursor();
-    match e {
-        Mto::Bracket(mut n, yin, yan, DP::Left) => {
-            let mut iter = buf.chars_at(cursor, DP::Left)?.enumerate();
-            cursor -= loop {
-                match iter.next() {
-                    Some((_, ch)) if ch == yin && m > 0 => m -= 1,
-                    Some((i, ch)) if ch == yin && n == 0 => break i + 1,
-                    Some((_, ch)) if ch == yin => n -= 1,
-                    Some((_, ch)) if ch == yan => m += 1,
-                    Some(_) => (),
-                    None => break 0,
-                }
-            };
-        }
-        Mto::Bracket(mut n, yin, yan, DP::Right) => {
-            let mut iter = buf.chars_at(cursor, DP::Right)?.enumerate();
-            cursor += {
-                loop {
-                    match iter.next() {
-                        Some((_, ch)) if ch == yin && m > 0 => m -= 1,
-                        Some((i, ch)) if ch == yin && n == 0 => break i,
-                        Some((_, ch)) if ch == yin => n -= 1,
-                        Some((_, ch)) if ch == yan => m += 1,
-                        Some(_) => (),
-                        None => break 0,
-                    }
-                }
-            };
-        }
-        _ => err_at!(Fatal, msg: format!("unreachable"))?,
-    }
+    buf.set_cursor(cursor).clear_sticky_col();
 
-    buf.set_cursor(cursor);
     Ok(Event::Noop)
 }
 
-fn mto_pattern(buf: &mut Buffer, evnt: Mto) -> Result<usize> {
-    use regex::Regex;
+// Return char ranges, as [from, to), of visual-block `blk` on each of its
+// lines, top line first. Lines shorter than the block give empty ranges.
+fn block_ranges(buf: &Buffer, blk: (usize, usize, usize, usize)) -> Vec<(usize, usize)> {
+    let (rf, rt, cf, ct) = blk;
+    (rf..=rt)
+        .map(|row| {
+            let (home, n) = (buf.line_to_char(row), line_chars(buf, row));
+            (home + cmp::min(cf, n), home + cmp::min(ct + 1, n))
+        })
+        .collect()
+}
 
-    let (n, patt, dp) = match evnt.clone() {
-        Mto::Pattern(n, Some(patt), dp) => {
-            let msg = format!("{}", patt);
-            let patt = err_at!(BadPattern, Regex::new(&patt), msg)?;
-            (n, patt, dp)
-        }
-        _ => err_at!(Fatal, msg: format!("unreachable"))?,
-    };
+// Save text from `ranges` in unnamed register, one line for each range.
+fn save_block(buf: &mut Buffer, ranges: &[(usize, usize)]) {
+    let lines: Vec<String> = ranges.iter().map(|(f, t)| buf.slice(*f..*t)).collect();
+    save_register(buf, lines.join("\n"), false);
+}
 
-    let get_matches = |txt: String| -> Vec<(usize, usize)> {
-        let iter = patt.find_iter(&txt).map(|m| (m.start(), m.end()));
-        iter.collect()
-    };
+// Delete text in `ranges`, bottom line first, so that char-index of
+// ranges above remain valid.
+fn delete_ranges(buf: &mut Buffer, ranges: &[(usize, usize)]) -> Result<Event> {
+    use crate::event::Edit;
 
-    let cursor = buf.to_char_cursor();
-    match dp {
-        DP::Left => {
-            let txt = buf.slice(..cursor);
-            let matches: Vec<(usize, usize)> = get_matches(txt);
-            match matches.into_iter().rev().skip(n.saturating_sub(1)).next() {
-                Some((start, _)) => Ok(start),
-                None => Ok(cursor),
-            }
-        }
-        DP::Right => {
-            let from = cursor.saturating_add(1);
-            let txt = buf.slice(from..);
-            let matches: Vec<(usize, usize)> = get_matches(txt);
-            match matches.into_iter().skip(n.saturating_sub(1)).next() {
-                Some((start, _)) => Ok(from + start),
-                None => Ok(cursor),
-            }
-        }
-        _ => err_at!(Fatal, msg: format!("unreachable"))?,
+    let mut evnt = Event::Noop;
+    for (from, to) in ranges.iter().rev().filter(|(f, t)| f < t) {
+        let txt = buf.slice(*from..*to);
+        buf.cud_delete(*from..*to)?;
+        evnt.push(Event::Edit(Edit::new_del(*from, txt)));
     }
+    Ok(evnt)
 }
 
-fn mod_insert(buf: &mut Buffer, repeat: usize, pos: DP) -> Result<Event> {
-    if pos == DP::TextCol {
-        let cursor = mto_line_home(buf, pos)?;
-        buf.set_cursor(cursor).set_sticky_col(pos, "home");
-    }
-    buf.inner = {
-        let ib = InsertBuffer::new(repeat, Change::fork(buf.as_mut_change()));
-        ib.into()
-    };
-    Ok(Event::Noop)
+// Delete visual-block `blk`, save it in unnamed register and move the
+// cursor to the block's top-left.
+fn delete_block(buf: &mut Buffer, blk: (usize, usize, usize, usize)) -> Result<Event> {
+    let ranges = block_ranges(buf, blk);
+    save_block(buf, &ranges);
+
+    let change = Change::fork(buf.as_mut_change());
+    *buf.as_mut_change() = change;
+    let evnt = delete_ranges(buf, &ranges)?;
+
+    buf.set_cursor(saturate_cursor(buf, ranges[0].0));
+    let cursor = mto_right(buf, 0, DP::LineBound)?;
+    buf.set_cursor(cursor).clear_sticky_col();
+
+    Ok(evnt)
 }
 
-fn mod_append(buf: &mut Buffer, repeat: usize, pos: DP) -> Result<Event> {
-    let cursor = match pos {
-        DP::End => {
-            let cursor = buf.to_char_cursor();
-            let n = text::visual_line_n(&buf.line(buf.char_to_line(cursor)));
-            buf.to_line_home(None) + n
-        }
-        DP::Right => buf.to_char_cursor().saturating_add(1),
-        _ => err_at!(Fatal, msg: format!("unreachable"))?,
-    };
+// Delete visual-block `blk`, save it in unnamed register and switch to
+// insert mode, text typed is inserted on all lines of the block.
+fn change_block(buf: &mut Buffer, blk: (usize, usize, usize, usize)) -> Result<Event> {
+    let ranges = block_ranges(buf, blk);
+    save_block(buf, &ranges);
+
+    let (rf, rt, cf, _) = blk;
+    let mut evnt = mod_insert_block(buf, rf, rt, cf, false)?;
+    evnt.push(delete_ranges(buf, &ranges)?);
+    buf.set_cursor(ranges[0].0).clear_sticky_col();
+
+    Ok(evnt)
+}
+
+// Save visual-block `blk` in unnamed register and move the cursor to the
+// block's top-left.
+fn yank_block(buf: &mut Buffer, blk: (usize, usize, usize, usize)) -> Result<Event> {
+    let ranges = block_ranges(buf, blk);
+    save_block(buf, &ranges);
+
+    buf.set_cursor(saturate_cursor(buf, ranges[0].0));
+    let cursor = mto_right(buf, 0, DP::LineBound)?;
     buf.set_cursor(cursor).clear_sticky_col();
 
-    buf.inner = {
-        let ib = InsertBuffer::new(repeat, Change::fork(buf.as_mut_change()));
-        ib.into()
-    };
     Ok(Event::Noop)
 }
 
-fn mod_open(buf: &mut Buffer, repeat: usize, pos: DP) -> Result<Event> {
-    match pos {
-        DP::Left => {
-            let cursor = {
-                let cursor = buf.line_to_char(buf.to_xy_cursor(None).row);
-                buf.cud_newlines(Some(cursor), buf.format.newline(), 1)?;
-                cursor
-            };
-            buf.set_cursor(cursor).clear_sticky_col();
-        }
-        DP::Right => {
-            let cursor = {
-                let cursor = buf.to_char_cursor();
-                let n = text::visual_line_n(&buf.line(buf.char_to_line(cursor)));
-                buf.to_line_home(None) + n
-            };
-            let cursor = buf.cud_newlines(Some(cursor), buf.format.newline(), 1)?;
-            buf.set_cursor(cursor).clear_sticky_col();
-        }
-        _ => err_at!(Fatal, msg: format!("unreachable"))?,
+// Save `txt` in unnamed register, and in the register selected via `"x`.
+fn save_register(buf: &mut Buffer, txt: String, linewise: bool) {
+    if let Some(index) = buf.register.take() {
+        let r = register::Register::new(index, txt.clone(), linewise);
+        register::set_register(&mut buf.registers, r);
     }
-    buf.inner = {
-        let ib = InsertBuffer::new(repeat, Change::fork(buf.as_mut_change()));
-        ib.into()
+    let r = register::Register::new(register::UNNAMED, txt, linewise);
+    register::set_register(&mut buf.registers, r);
+}
+
+// Put text from register `index`, or from the register selected via `"x`.
+fn put_register(buf: &mut Buffer, n: usize, index: char, dp: DP) -> Result<Event> {
+    use crate::event::Edit;
+
+    let index = buf.register.take().unwrap_or(index);
+    let r = match register::get_register(&buf.registers, index) {
+        Some(r) => r,
+        None => return Ok(Event::Noop),
     };
-    Ok(Event::Noop)
+    let txt = r.to_text().repeat(n);
+
+    let change = Change::fork(buf.as_mut_change());
+    *buf.as_mut_change() = change;
+
+    let row = buf.char_to_line(buf.to_char_cursor());
+    let (at, txt) = match dp {
+        DP::Left if r.is_linewise() => (buf.line_to_char(row), txt),
+        DP::Right if r.is_linewise() => match buf.line_to_char(row + 1) {
+            // last line without a newline.
+            at if at == buf.n_chars() && !buf.slice(..at).ends_with('\n') => {
+                let txt = {
+                    let nl = buf.format.newline().to_string();
+                    nl + text::Format::trim_newline(&txt).0
+                };
+                (at, txt)
+            }
+            at => (at, txt),
+        },
+        DP::Left => (buf.to_char_cursor(), txt),
+        DP::Right if line_chars(buf, row) == 0 => (buf.to_char_cursor(), txt),
+        DP::Right => (buf.to_char_cursor() + 1, txt),
+        dp => err_at!(Fatal, msg: format!("invalid direction: {}", dp))?,
+    };
+    let end = buf.cud_str(Some(at), &txt)?;
+
+    let cursor = if r.is_linewise() {
+        let row = if_else!(dp == DP::Left, row, row + 1);
+        buf.set_cursor(buf.line_to_char(row));
+        mto_line_home(buf, DP::TextCol)?
+    } else {
+        end.saturating_sub(1)
+    };
+    buf.set_cursor(cursor).clear_sticky_col();
+
+    Ok(Event::Edit(Edit::new_ins(at, txt)))
 }
 
 /// Function to skip whitespace in a line.
@@ -1998,6 +5070,27 @@ pub fn skip_whitespace(line: &str, off: usize, dp: DP) -> Result<usize> {
     Ok(n)
 }
 
+/// Function to skip alphanumeric characters, including `_`, in a line.
+///
+/// Cursor position within the line is specified by `off` and direction
+/// in which to skip is specified by `dp`. Skipping to the right includes
+/// the character at `off`, skipping to the left excludes it. Return the
+/// number of positions skipped.
+pub fn skip_alphanumeric(line: &str, off: usize, dp: DP) -> Result<usize> {
+    let line = text::visual_line(&line);
+    let is_an = |ch: &char| ch.is_alphanumeric() || *ch == '_';
+
+    let n = match dp {
+        DP::Right => line.chars().skip(off).take_while(is_an).count(),
+        DP::Left => {
+            let chars: Vec<char> = line.chars().take(off).collect();
+            chars.into_iter().rev().take_while(is_an).count()
+        }
+        dp => err_at!(Fatal, msg: format!("invalid direction: {}", dp))?,
+    };
+    Ok(n)
+}
+
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd)]
 enum MtoWord {
     St(usize), // start - (n,) number of words to move.
@@ -2476,12 +5569,18 @@ pub struct IterLine<'a> {
     _change: cell::Ref<'a, Change>, // holding a reference.
     iter: ropey::iter::Lines<'a>,
     reverse: bool,
+    n: usize, // remaining lines to iterate.
 }
 
 impl<'a> Iterator for IterLine<'a> {
     type Item = String;
 
     fn next(&mut self) -> Option<Self::Item> {
+        if self.n == 0 {
+            return None;
+        }
+        self.n -= 1;
+
         if self.reverse {
             self.iter.prev().map(|l| l.to_string())
         } else {
@@ -2522,6 +5621,54 @@ impl Default for StickyCol {
     }
 }
 
+// Last change made in normal mode, replayed by dot command. If the change
+// had switched to insert mode, events inserted upto `Esc` are remembered
+// along with the command.
+#[derive(Clone, Default)]
+struct LastChange {
+    evnt: Event,
+    inserts: Option<Event>,
+}
+
+impl LastChange {
+    fn new(evnt: Event) -> LastChange {
+        LastChange {
+            evnt,
+            inserts: None,
+        }
+    }
+
+    fn with_inserts(evnt: Event, inserts: Event) -> LastChange {
+        LastChange {
+            evnt,
+            inserts: Some(inserts),
+        }
+    }
+
+    // Return whether `evnt` makes a change that can be repeated.
+    fn is_change(evnt: &Event) -> bool {
+        match evnt {
+            Event::Md(Mod::Insert(_, _)) => true,
+            Event::Md(Mod::Append(_, _)) => true,
+            Event::Md(Mod::Open(_, _)) => true,
+            Event::Op(Opr::Yank(_, _)) | Event::Op(Opr::Fold(_, _)) => false,
+            Event::Op(_) => true,
+            Event::Wr(Cud::Delete(_)) | Event::Wr(Cud::Backspace(_)) => true,
+            Event::Put(_, _, _) | Event::ReplaceChar(_, _) | Event::Join(_) => true,
+            _ => false,
+        }
+    }
+
+    fn to_events(&self) -> Event {
+        let mut evnts = self.evnt.clone();
+        if let Some(inserts) = &self.inserts {
+            inserts.clone().for_each(|evnt| evnts.push(evnt));
+            evnts.push(Event::Md(Mod::Esc));
+        }
+        evnts
+    }
+}
+
 #[derive(Clone)]
 enum TabState {
     Active(String),
@@ -2535,6 +5682,29 @@ impl Default for TabState {
 }
 
 #[inline]
+// Convert `range` into (from, to) char-index, bound by `n_chars`, where `to`
+// is exclusive.
+fn to_char_range<R>(range: R, n_chars: usize) -> (usize, usize)
+where
+    R: RangeBounds<usize>,
+{
+    use std::ops::Bound::{Excluded, Included, Unbounded};
+
+    let from = match range.start_bound() {
+        Included(from) => cmp::min(*from, n_chars.saturating_sub(1)),
+        Excluded(from) => cmp::min(from.saturating_add(1), n_chars),
+        Unbounded => 0,
+    };
+
+    let to = match range.end_bound() {
+        Included(to) => cmp::min((*to).saturating_add(1), n_chars),
+        Excluded(to) => cmp::min(*to, n_chars),
+        Unbounded => n_chars,
+    };
+
+    (from, to)
+}
+
 fn saturate_cursor(buf: &Buffer, cursor: usize) -> usize {
     if_else!(cursor >= buf.n_chars(), buf.last_char_idx(), cursor)
 }
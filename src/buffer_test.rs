@@ -25,3 +25,1743 @@ fn test_newline() {
     assert_eq!(ss[2].as_str(), "how\r\n", "{}", ss[2]);
     assert_eq!(ss[3].as_str(), "are you", "{}", ss[3]);
 }
+
+#[test]
+fn test_buffer_id() {
+    let (one, two) = (Buffer::empty(), Buffer::empty());
+    assert_ne!(one.to_id(), two.to_id());
+    assert_eq!(one.to_id(), format!("[no-name]#{}", one.to_num()));
+
+    let new_ted = || {
+        let loc = Location::new_ted("test-id", "hello\n".as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+    let (one, two) = (new_ted(), new_ted());
+    assert_ne!(one.to_id(), two.to_id());
+    assert_eq!(one.to_id(), format!("[test-id]#{}", one.to_num()));
+}
+
+#[test]
+fn test_undo_redo() {
+    use crate::event::Cud;
+
+    let mut buf = {
+        let loc = Location::new_ted("test-undo", "hello".as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+    assert!(buf.undo().unwrap() == Event::Noop);
+    assert!(buf.redo().unwrap() == Event::Noop);
+
+    let insert = |buf: &mut Buffer, ch: char| {
+        let mut evnts = Event::Md(Mod::Append(1, DP::End));
+        evnts.push(Event::Wr(Cud::Char(ch)));
+        evnts.push(Event::Md(Mod::Esc));
+        buf.on_event(evnts).unwrap();
+    };
+    insert(&mut buf, '1');
+    insert(&mut buf, '2');
+    assert_eq!(buf.to_string(), "hello12");
+
+    buf.on_event(Event::Undo(1)).unwrap();
+    assert_eq!(buf.to_string(), "hello1");
+    buf.on_event(Event::Undo(5)).unwrap();
+    assert_eq!(buf.to_string(), "hello");
+    assert!(buf.undo().unwrap() == Event::Noop);
+
+    buf.on_event(Event::Redo(1)).unwrap();
+    assert_eq!(buf.to_string(), "hello1");
+    buf.on_event(Event::Redo(1)).unwrap();
+    assert_eq!(buf.to_string(), "hello12");
+    assert!(buf.redo().unwrap() == Event::Noop);
+
+    // redo follows the branch that was last undone.
+    buf.on_event(Event::Undo(1)).unwrap();
+    insert(&mut buf, '3');
+    assert_eq!(buf.to_string(), "hello13");
+    buf.on_event(Event::Undo(1)).unwrap();
+    buf.on_event(Event::Redo(1)).unwrap();
+    assert_eq!(buf.to_string(), "hello13");
+}
+
+#[test]
+fn test_opr_delete() {
+    let mut buf = {
+        let txt = "hello world\nfoo bar\nbaz\n";
+        let loc = Location::new_ted("test-delete", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+
+    keys(&mut buf, "dw");
+    assert_eq!(buf.to_string(), "world\nfoo bar\nbaz\n");
+    keys(&mut buf, "P");
+    assert_eq!(buf.to_string(), "hello world\nfoo bar\nbaz\n");
+    assert_eq!(buf.to_char_cursor(), 5);
+
+    keys(&mut buf, "wdw");
+    assert_eq!(buf.to_string(), "hello \nfoo bar\nbaz\n");
+    keys(&mut buf, "u");
+    assert_eq!(buf.to_string(), "hello world\nfoo bar\nbaz\n");
+
+    keys(&mut buf, "jdd");
+    assert_eq!(buf.to_string(), "hello world\nbaz\n");
+    assert_eq!(buf.to_xy_cursor(None), (0, 1).into());
+    keys(&mut buf, "P");
+    assert_eq!(buf.to_string(), "hello world\nfoo bar\nbaz\n");
+
+    keys(&mut buf, "ggd$");
+    assert_eq!(buf.to_string(), "\nfoo bar\nbaz\n");
+    keys(&mut buf, "ujdG");
+    assert_eq!(buf.to_string(), "hello world\n");
+    keys(&mut buf, "p");
+    assert_eq!(buf.to_string(), "hello world\nfoo bar\nbaz\n");
+
+    keys(&mut buf, "gg2dd");
+    assert_eq!(buf.to_string(), "baz\n");
+    keys(&mut buf, "dd");
+    assert_eq!(buf.to_string(), "");
+}
+
+fn keys(buf: &mut Buffer, keys: &str) {
+    use crate::keymap_edit::KeyEdit;
+    use crossterm::event::KeyModifiers;
+
+    let mut km = KeyEdit::default();
+    for ch in keys.chars() {
+        let evnt = match ch {
+            '\x1b' => Event::Esc,
+            '\n' => Event::Enter(KeyModifiers::empty()),
+            '\t' => Event::Tab(KeyModifiers::empty()),
+            '\x08' => Event::Backspace(KeyModifiers::empty()),
+            '\x0f' => Event::Char('o', KeyModifiers::CONTROL),
+            '\x16' => Event::Char('v', KeyModifiers::CONTROL),
+            '←' => Event::Left(KeyModifiers::empty()),
+            // terminal reports upper-case letters along with SHIFT.
+            ch if ch.is_ascii_uppercase() => Event::Char(ch, KeyModifiers::SHIFT),
+            ch => Event::Char(ch, KeyModifiers::empty()),
+        };
+        let evnt = km.fold(buf, evnt).unwrap();
+        buf.on_event(evnt).unwrap();
+    }
+}
+
+#[test]
+fn test_opr_change() {
+    let mut buf = {
+        let txt = "hello world\nfoo bar\nbaz\n";
+        let loc = Location::new_ted("test-change", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+
+    keys(&mut buf, "cwhi\x1b");
+    assert_eq!(buf.to_string(), "hi world\nfoo bar\nbaz\n");
+    assert_eq!(buf.to_char_cursor(), 1);
+    keys(&mut buf, "w.");
+    assert_eq!(buf.to_string(), "hi hi\nfoo bar\nbaz\n");
+    keys(&mut buf, "u");
+    assert_eq!(buf.to_string(), "hi world\nfoo bar\nbaz\n");
+
+    keys(&mut buf, "jccnew line\x1b");
+    assert_eq!(buf.to_string(), "hi world\nnew line\nbaz\n");
+    keys(&mut buf, "j.");
+    assert_eq!(buf.to_string(), "hi world\nnew line\nnew line\n");
+
+    keys(&mut buf, "ggwc$there\x1b");
+    assert_eq!(buf.to_string(), "hi there\nnew line\nnew line\n");
+}
+
+#[test]
+fn test_visual() {
+    let mut buf = {
+        let txt = "hello world\nfoo bar\nbaz\n";
+        let loc = Location::new_ted("test-visual", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+
+    keys(&mut buf, "wve");
+    assert_eq!(buf.to_mode(), "visual");
+    assert_eq!(buf.to_selection(), Some((6, 11)));
+    keys(&mut buf, "\x1b");
+    assert_eq!(buf.to_mode(), "normal");
+    assert_eq!(buf.to_selection(), None);
+
+    keys(&mut buf, "vbd");
+    assert_eq!(buf.to_mode(), "normal");
+    assert_eq!(buf.to_string(), "hello \nfoo bar\nbaz\n");
+    keys(&mut buf, "u");
+
+    keys(&mut buf, "Vj");
+    assert_eq!(buf.to_selection(), Some((0, 20)));
+    keys(&mut buf, "y");
+    assert_eq!(buf.to_mode(), "normal");
+    keys(&mut buf, "Gp");
+    assert_eq!(
+        buf.to_string(),
+        "hello world\nfoo bar\nbaz\nhello world\nfoo bar\n"
+    );
+
+    keys(&mut buf, "ggjVjd");
+    assert_eq!(buf.to_string(), "hello world\nhello world\nfoo bar\n");
+
+    keys(&mut buf, "ggvecbye\x1b");
+    assert_eq!(buf.to_string(), "bye world\nhello world\nfoo bar\n");
+}
+
+#[test]
+fn test_visual_block() {
+    let new_buf = || {
+        let txt = "abcd\nef\nghij\n";
+        let loc = Location::new_ted("test-visual-block", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+
+    let mut buf = new_buf();
+    keys(&mut buf, "l\x16jjl");
+    assert_eq!(buf.to_mode(), "visual");
+    assert_eq!(buf.to_visual_block(), Some((0, 2, 1, 2)));
+    keys(&mut buf, "v");
+    assert_eq!(buf.to_visual_block(), None);
+    keys(&mut buf, "\x16");
+    assert_eq!(buf.to_visual_block(), Some((0, 2, 1, 2)));
+
+    // insert on every line that extends into the block.
+    keys(&mut buf, "IXY\x1b");
+    assert_eq!(buf.to_mode(), "normal");
+    assert_eq!(buf.to_string(), "aXYbcd\neXYf\ngXYhij\n");
+    keys(&mut buf, "u");
+    assert_eq!(buf.to_string(), "abcd\nef\nghij\n");
+
+    // append after the block, padding short lines.
+    let mut buf = new_buf();
+    keys(&mut buf, "lll\x16jjAZ\x1b");
+    assert_eq!(buf.to_string(), "abcdZ\nef  Z\nghijZ\n");
+
+    // delete the rectangle.
+    let mut buf = new_buf();
+    keys(&mut buf, "l\x16jjld");
+    assert_eq!(buf.to_string(), "ad\ne\ngj\n");
+    assert_eq!(buf.to_char_cursor(), 1);
+    keys(&mut buf, "u");
+    assert_eq!(buf.to_string(), "abcd\nef\nghij\n");
+
+    // yank and change the rectangle, block is put back characterwise.
+    let mut buf = new_buf();
+    keys(&mut buf, "\x16jlyP");
+    assert_eq!(buf.to_string(), "ab\nefabcd\nef\nghij\n");
+    keys(&mut buf, "u\x16jjc-\x1b");
+    assert_eq!(buf.to_string(), "-bcd\n-f\n-hij\n");
+}
+
+#[test]
+fn test_reselect() {
+    let mut buf = {
+        let txt = "hello world\nfoo bar\n";
+        let loc = Location::new_ted("test-reselect", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+
+    keys(&mut buf, "gv");
+    assert_eq!(buf.to_mode(), "normal");
+
+    keys(&mut buf, "wvey");
+    assert_eq!(buf.to_last_visual(), Some((6, 10, false)));
+    keys(&mut buf, "jgv");
+    assert_eq!(buf.to_mode(), "visual");
+    assert_eq!(buf.to_selection(), Some((6, 11)));
+    assert_eq!(buf.to_char_cursor(), 10);
+
+    // selection follows edits made before it.
+    keys(&mut buf, "\x1bggiXY\x1bjgv");
+    assert_eq!(buf.to_selection(), Some((8, 13)));
+    keys(&mut buf, "d");
+    assert_eq!(buf.to_string(), "XYhello \nfoo bar\n");
+
+    keys(&mut buf, "jVy");
+    keys(&mut buf, "ggddgv");
+    assert_eq!(buf.to_selection(), Some((0, 8)));
+}
+
+#[test]
+fn test_marks() {
+    let mut buf = {
+        let txt = "hello world\n  foo bar\n";
+        let loc = Location::new_ted("test-marks", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+
+    keys(&mut buf, "jwwma");
+    assert_eq!(buf.to_char_cursor(), 18);
+    keys(&mut buf, "ggiXX\x1b`a");
+    assert_eq!(buf.to_char_cursor(), 20);
+    keys(&mut buf, "gg'a");
+    assert_eq!(buf.to_char_cursor(), 16);
+
+    keys(&mut buf, "ggdd`a");
+    assert_eq!(buf.to_char_cursor(), 6);
+    keys(&mut buf, "0dw`a");
+    assert_eq!(buf.to_char_cursor(), 4);
+    keys(&mut buf, "0dw`a");
+    assert_eq!(buf.to_char_cursor(), 0);
+
+    buf.set_mark('b', 2);
+    keys(&mut buf, "0dl`b");
+    assert_eq!(buf.to_char_cursor(), 1);
+}
+
+#[test]
+fn test_jump_list() {
+    let mut buf = {
+        let txt = "one\ntwo\nthree\n";
+        let loc = Location::new_ted("test-jump-list", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+
+    keys(&mut buf, "/two\nG");
+    assert_eq!(buf.to_char_cursor(), 8);
+    // walk back with Ctrl-o and forward with Tab.
+    keys(&mut buf, "\x0f");
+    assert_eq!(buf.to_char_cursor(), 4);
+    keys(&mut buf, "\x0f\x0f");
+    assert_eq!(buf.to_char_cursor(), 0);
+    keys(&mut buf, "\t");
+    assert_eq!(buf.to_char_cursor(), 4);
+    keys(&mut buf, "\t\t");
+    assert_eq!(buf.to_char_cursor(), 8);
+
+    // '' and `` swap with the position before the last jump.
+    keys(&mut buf, "''");
+    assert_eq!(buf.to_char_cursor(), 4);
+    keys(&mut buf, "''");
+    assert_eq!(buf.to_char_cursor(), 8);
+    keys(&mut buf, "l``");
+    assert_eq!(buf.to_char_cursor(), 4);
+    keys(&mut buf, "``");
+    assert_eq!(buf.to_char_cursor(), 9);
+
+    // jump-list shifts along with edits.
+    keys(&mut buf, "ggix\x1b\x0f");
+    assert_eq!(buf.to_char_cursor(), 10);
+}
+
+#[test]
+fn test_change_list() {
+    let mut buf = {
+        let txt = "one\ntwo\nthree\n";
+        let loc = Location::new_ted("test-change-list", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+
+    keys(&mut buf, "xjxGx");
+    assert_eq!(buf.changelist(), vec![0, 3, 6]);
+    keys(&mut buf, "ggg;");
+    assert_eq!(buf.to_char_cursor(), 6);
+    keys(&mut buf, "2g;");
+    assert_eq!(buf.to_char_cursor(), 0);
+    keys(&mut buf, "g;");
+    assert_eq!(buf.to_char_cursor(), 0);
+    keys(&mut buf, "g,");
+    assert_eq!(buf.to_char_cursor(), 3);
+    keys(&mut buf, "g,g,");
+    assert_eq!(buf.to_char_cursor(), 6);
+
+    // one entry for an insert session, older entries shift along.
+    keys(&mut buf, "ggiab\x1b");
+    assert_eq!(buf.changelist(), vec![2, 5, 8, 1]);
+}
+
+#[test]
+fn test_text_objects() {
+    let new_buffer = |txt: &str| {
+        let loc = Location::new_ted("test-text-objects", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+    let cases = vec![
+        // words
+        ("foo bar baz\n", "wdiw", "foo  baz\n"),
+        ("foo bar baz\n", "wdaw", "foo baz\n"),
+        ("foo bar baz\n", "$daw", "foo bar\n"),
+        ("foo bar baz\n", "wd3iw", "foo \n"),
+        ("foo bar.baz qux\n", "wciWx\x1b", "foo x qux\n"),
+        // quotes
+        ("s = \"a \\\"b\\\" c\";\n", "ci\"x\x1b", "s = \"x\";\n"),
+        ("s = \"a\";\n", "da\"", "s =;\n"),
+        ("'a' 'b'\n", "$di'", "'a' ''\n"),
+        // blocks
+        ("f(a, (b), c)\n", "6ldi(", "f(a, (), c)\n"),
+        ("f(a, (b), c)\n", "6ld2i(", "f()\n"),
+        ("f(a, (b), c)\n", "6lda)", "f(a, , c)\n"),
+        ("f(a, (b), c)\n", "fcdib", "f()\n"),
+        ("fn f() {\n    a;\n}\n", "jdi{", "fn f() {\n}\n"),
+        (
+            "fn f() {\n    a;\n}\n",
+            "jyi{Gp",
+            "fn f() {\n    a;\n}\n    a;\n",
+        ),
+        ("no block\n", "di(", "no block\n"),
+    ];
+    for (txt, ks, out) in cases.into_iter() {
+        let mut buf = new_buffer(txt);
+        keys(&mut buf, ks);
+        assert_eq!(buf.to_string(), out, "{:?} {:?}", txt, ks);
+    }
+}
+
+#[test]
+fn test_sentence_objects() {
+    let txt = "One two. See e.g. this. End\n\nNew para.\n";
+    let cases = vec![
+        ("dis", " See e.g. this. End\n\nNew para.\n"),
+        ("das", "See e.g. this. End\n\nNew para.\n"),
+        ("fSdis", "One two.  End\n\nNew para.\n"),
+        ("fSdas", "One two. End\n\nNew para.\n"),
+        // abbreviations do not end a sentence.
+        ("fgdas", "One two. End\n\nNew para.\n"),
+        // last sentence, without trailing white-space.
+        ("fEdas", "One two. See e.g. this.\n\nNew para.\n"),
+        ("d2as", "End\n\nNew para.\n"),
+        ("fScisX\x1b", "One two. X End\n\nNew para.\n"),
+        (
+            "fSyas$p",
+            "One two. See e.g. this. EndSee e.g. this. \n\nNew para.\n",
+        ),
+        ("jdas", "One two. See e.g. this. End\n\nNew para.\n"),
+    ];
+    for (ks, out) in cases.into_iter() {
+        let mut buf = {
+            let loc = Location::new_ted("test-sentence-objects", txt.as_bytes(), false).unwrap();
+            Buffer::from_reader(loc).unwrap()
+        };
+        keys(&mut buf, ks);
+        assert_eq!(buf.to_string(), out, "{:?}", ks);
+    }
+}
+
+#[test]
+fn test_para_objects() {
+    let txt = "a\nb\n\nc\n\n\nd\ne\n";
+    let cases = vec![
+        ("dap", "c\n\n\nd\ne\n"),
+        ("dip", "\nc\n\n\nd\ne\n"),
+        ("3jdap", "a\nb\n\nd\ne\n"),
+        ("3jdip", "a\nb\n\n\n\nd\ne\n"),
+        ("2jdip", "a\nb\nc\n\n\nd\ne\n"),
+        ("2jdap", "a\nb\n\n\nd\ne\n"),
+        // last paragraph, without trailing blank lines.
+        ("Gdap", "a\nb\n\nc\n"),
+        ("d2ap", "d\ne\n"),
+        ("yipGp", "a\nb\n\nc\n\n\nd\ne\na\nb\n"),
+        ("yapGp", "a\nb\n\nc\n\n\nd\ne\na\nb\n\n"),
+        ("3jcipx\x1b", "a\nb\n\nx\n\n\nd\ne\n"),
+    ];
+    for (ks, out) in cases.into_iter() {
+        let mut buf = {
+            let loc = Location::new_ted("test-para-objects", txt.as_bytes(), false).unwrap();
+            Buffer::from_reader(loc).unwrap()
+        };
+        keys(&mut buf, ks);
+        assert_eq!(buf.to_string(), out, "{:?}", ks);
+    }
+}
+
+#[test]
+fn test_match_pair() {
+    let mut buf = {
+        let txt = "let x = f(a[0], {b});\nno brackets\n(\n)\n";
+        let loc = Location::new_ted("test-match-pair", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+
+    keys(&mut buf, "%");
+    assert_eq!(buf.to_char_cursor(), 19);
+    keys(&mut buf, "%");
+    assert_eq!(buf.to_char_cursor(), 9);
+    keys(&mut buf, "fa%");
+    assert_eq!(buf.to_char_cursor(), 13);
+    keys(&mut buf, "%");
+    assert_eq!(buf.to_char_cursor(), 11);
+    keys(&mut buf, "f{%");
+    assert_eq!(buf.to_char_cursor(), 18);
+
+    keys(&mut buf, "j0%");
+    assert_eq!(buf.to_char_cursor(), 22);
+    keys(&mut buf, "j%");
+    assert_eq!(buf.to_char_cursor(), 36);
+
+    keys(&mut buf, "ggd%");
+    assert_eq!(buf.to_string(), ";\nno brackets\n(\n)\n");
+}
+
+#[test]
+fn test_search() {
+    let mut buf = {
+        let txt = "héllo wörld\nfoo wörld\nbar\n";
+        let loc = Location::new_ted("test-search", txt.as_bytes(), false).unwrap();
+        let mut buf = Buffer::from_reader(loc).unwrap();
+        buf.set_wrapscan(false);
+        buf
+    };
+
+    assert_eq!(buf.search_preview("wör", DP::Right), Some(6));
+    assert_eq!(buf.search_preview("wör", DP::Left), None);
+    assert_eq!(buf.search_preview("xyz", DP::Right), None);
+    assert_eq!(buf.search_preview("(", DP::Right), None);
+    assert_eq!(buf.to_char_cursor(), 0);
+
+    keys(&mut buf, "/wör\n");
+    assert_eq!(buf.to_char_cursor(), 6);
+    keys(&mut buf, "n");
+    assert_eq!(buf.to_char_cursor(), 16);
+    keys(&mut buf, "?h\n");
+    assert_eq!(buf.to_char_cursor(), 0);
+    keys(&mut buf, "/bar\x1b");
+    assert_eq!(buf.to_char_cursor(), 0);
+    keys(&mut buf, "d/foo\n");
+    assert_eq!(buf.to_string(), "foo wörld\nbar\n");
+}
+
+#[test]
+fn test_search_word() {
+    let mut buf = {
+        let txt = "foo foobar bar\nbar foo_x foo\n";
+        let loc = Location::new_ted("test-search-word", txt.as_bytes(), false).unwrap();
+        let mut buf = Buffer::from_reader(loc).unwrap();
+        buf.set_wrapscan(false);
+        buf
+    };
+
+    keys(&mut buf, "*");
+    assert_eq!(buf.to_char_cursor(), 25);
+    keys(&mut buf, "n");
+    assert_eq!(buf.to_char_cursor(), 25);
+    keys(&mut buf, "N");
+    assert_eq!(buf.to_char_cursor(), 0);
+    keys(&mut buf, "#");
+    assert_eq!(buf.to_char_cursor(), 0);
+
+    // word after the cursor, and from the middle of a word.
+    keys(&mut buf, "3l*");
+    assert_eq!(buf.to_char_cursor(), 4);
+    keys(&mut buf, "6l*");
+    assert_eq!(buf.to_char_cursor(), 15);
+    keys(&mut buf, "l#");
+    assert_eq!(buf.to_char_cursor(), 11);
+    keys(&mut buf, "d*");
+    assert_eq!(buf.to_string(), "foo foobar bar foo_x foo\n");
+}
+
+#[test]
+fn test_search_smartcase() {
+    use crate::search::SearchOpts;
+
+    let mut buf = {
+        let txt = "Foo foo FOO\n";
+        let loc = Location::new_ted("test-smartcase", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+    buf.set_search_opts(SearchOpts {
+        ignorecase: false,
+        smartcase: true,
+    });
+
+    keys(&mut buf, "/foo\n");
+    assert_eq!(buf.to_char_cursor(), 4);
+    keys(&mut buf, "n");
+    assert_eq!(buf.to_char_cursor(), 8);
+    keys(&mut buf, "?Foo\n");
+    assert_eq!(buf.to_char_cursor(), 0);
+    keys(&mut buf, "/Foo\n");
+    assert_eq!(buf.to_char_cursor(), 0);
+
+    buf.set_search_opts(SearchOpts::default());
+    keys(&mut buf, "/FOO\n");
+    assert_eq!(buf.to_char_cursor(), 8);
+}
+
+#[test]
+fn test_substitute() {
+    use regex::Regex;
+
+    let mut buf = {
+        let txt = "foo foo\nbar foo\nfoo\nbaz\n";
+        let loc = Location::new_ted("test-substitute", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+
+    let patt = Regex::new("foo").unwrap();
+    buf.substitute(0, 2, &patt, "qux", false).unwrap();
+    assert_eq!(buf.to_string(), "qux foo\nbar qux\nfoo\nbaz\n");
+    assert_eq!(buf.to_char_cursor(), 8);
+
+    buf.substitute(0, 100, &patt, "x", true).unwrap();
+    assert_eq!(buf.to_string(), "qux x\nbar qux\nx\nbaz\n");
+    assert_eq!(buf.to_char_cursor(), 14);
+
+    let evnt = buf.substitute(3, 4, &patt, "x", true).unwrap();
+    assert!(evnt == Event::Noop);
+    assert_eq!(buf.to_string(), "qux x\nbar qux\nx\nbaz\n");
+
+    let patt = Regex::new("(b)(a)").unwrap();
+    buf.substitute(0, 4, &patt, "${2}${1}", true).unwrap();
+    assert_eq!(buf.to_string(), "qux x\nabr qux\nx\nabz\n");
+
+    keys(&mut buf, "u");
+    assert_eq!(buf.to_string(), "qux x\nbar qux\nx\nbaz\n");
+}
+
+#[test]
+fn test_join_lines() {
+    let mut buf = {
+        let txt = "foo\n    bar\n)baz \nqux\n\nend\n";
+        let loc = Location::new_ted("test-join-lines", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+
+    keys(&mut buf, "J");
+    assert_eq!(buf.to_string(), "foo bar\n)baz \nqux\n\nend\n");
+    assert_eq!(buf.to_char_cursor(), 3);
+    keys(&mut buf, "u");
+    assert_eq!(buf.to_string(), "foo\n    bar\n)baz \nqux\n\nend\n");
+
+    keys(&mut buf, "4J");
+    assert_eq!(buf.to_string(), "foo bar)baz qux\n\nend\n");
+    assert_eq!(buf.to_char_cursor(), 12);
+
+    keys(&mut buf, "jJ");
+    assert_eq!(buf.to_string(), "foo bar)baz qux\nend\n");
+    keys(&mut buf, "jJ");
+    assert_eq!(buf.to_string(), "foo bar)baz qux\nend\n");
+}
+
+#[test]
+fn test_delete_chars() {
+    let mut buf = {
+        let txt = "hello world\nfoo\n";
+        let loc = Location::new_ted("test-delete-chars", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+
+    keys(&mut buf, "x");
+    assert_eq!(buf.to_string(), "ello world\nfoo\n");
+    keys(&mut buf, "3x");
+    assert_eq!(buf.to_string(), "o world\nfoo\n");
+    keys(&mut buf, "$20x");
+    assert_eq!(buf.to_string(), "o worl\nfoo\n");
+    assert_eq!(buf.to_char_cursor(), 5);
+    keys(&mut buf, "2X");
+    assert_eq!(buf.to_string(), "o wl\nfoo\n");
+    assert_eq!(buf.to_char_cursor(), 3);
+    keys(&mut buf, "10X");
+    assert_eq!(buf.to_string(), "l\nfoo\n");
+    keys(&mut buf, "X");
+    assert_eq!(buf.to_string(), "l\nfoo\n");
+    keys(&mut buf, "jxp");
+    assert_eq!(buf.to_string(), "l\nofo\n");
+}
+
+#[test]
+fn test_replace_chars() {
+    let mut buf = {
+        let txt = "hello\nworld\n";
+        let loc = Location::new_ted("test-replace-chars", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+
+    keys(&mut buf, "rj");
+    assert_eq!(buf.to_string(), "jello\nworld\n");
+    assert_eq!(buf.to_char_cursor(), 0);
+    keys(&mut buf, "l3ra");
+    assert_eq!(buf.to_string(), "jaaao\nworld\n");
+    assert_eq!(buf.to_char_cursor(), 3);
+    keys(&mut buf, "3rb");
+    assert_eq!(buf.to_string(), "jaaao\nworld\n");
+    keys(&mut buf, "r\x1b");
+    assert_eq!(buf.to_string(), "jaaao\nworld\n");
+    keys(&mut buf, "2rb");
+    assert_eq!(buf.to_string(), "jaabb\nworld\n");
+    keys(&mut buf, "u");
+    assert_eq!(buf.to_string(), "jaaao\nworld\n");
+}
+
+#[test]
+fn test_opr_case() {
+    let mut buf = {
+        let txt = "Hello World\nfoo bar\n";
+        let loc = Location::new_ted("test-opr-case", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+
+    keys(&mut buf, "~");
+    assert_eq!(buf.to_string(), "hello World\nfoo bar\n");
+    assert_eq!(buf.to_char_cursor(), 1);
+    keys(&mut buf, "3~");
+    assert_eq!(buf.to_string(), "hELLo World\nfoo bar\n");
+    assert_eq!(buf.to_char_cursor(), 4);
+    keys(&mut buf, "$5~");
+    assert_eq!(buf.to_string(), "hELLo WorlD\nfoo bar\n");
+    assert_eq!(buf.to_char_cursor(), 10);
+
+    keys(&mut buf, "0gUw");
+    assert_eq!(buf.to_string(), "HELLO WorlD\nfoo bar\n");
+    assert_eq!(buf.to_char_cursor(), 0);
+    keys(&mut buf, "wgu$");
+    assert_eq!(buf.to_string(), "HELLO world\nfoo bar\n");
+    assert_eq!(buf.to_char_cursor(), 6);
+    keys(&mut buf, "jg~~");
+    assert_eq!(buf.to_string(), "HELLO world\nFOO BAR\n");
+    keys(&mut buf, "u");
+    assert_eq!(buf.to_string(), "HELLO world\nfoo bar\n");
+    keys(&mut buf, "0vlU");
+    assert_eq!(buf.to_string(), "HELLO world\nFOo bar\n");
+    assert_eq!(buf.to_mode(), "normal");
+}
+
+#[test]
+fn test_shift_lines() {
+    let mut buf = {
+        let txt = "foo\n\n  bar\n\tbaz\n";
+        let loc = Location::new_ted("test-shift-lines", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+
+    keys(&mut buf, ">>");
+    assert_eq!(buf.to_string(), "    foo\n\n  bar\n\tbaz\n");
+    assert_eq!(buf.to_char_cursor(), 4);
+    keys(&mut buf, "3>>");
+    assert_eq!(buf.to_string(), "        foo\n\n      bar\n\tbaz\n");
+    keys(&mut buf, "<<<<<<");
+    assert_eq!(buf.to_string(), "foo\n\n      bar\n\tbaz\n");
+    assert_eq!(buf.to_char_cursor(), 0);
+    keys(&mut buf, "Gk<j");
+    assert_eq!(buf.to_string(), "foo\n\n  bar\n    baz\n");
+    assert_eq!(buf.to_char_cursor(), 7);
+    keys(&mut buf, "u");
+    assert_eq!(buf.to_string(), "foo\n\n      bar\n\tbaz\n");
+
+    buf.set_expand_tab(false);
+    keys(&mut buf, "ggVj>");
+    assert_eq!(buf.to_string(), "    foo\n\n      bar\n\tbaz\n");
+    assert_eq!(buf.to_mode(), "normal");
+    keys(&mut buf, "jj>>");
+    assert_eq!(buf.to_string(), "    foo\n\n\t  bar\n\tbaz\n");
+
+    keys(&mut buf, "A");
+    buf.on_event(Event::Wr(Cud::LShift(1))).unwrap();
+    assert_eq!(buf.to_string(), "    foo\n\n      bar\n\tbaz\n");
+    assert_eq!(buf.to_char_cursor(), 18);
+
+    // tabs expand to buffer's tab_stop, not the default.
+    let mut buf = {
+        let loc = Location::new_ted("test-shift-lines", "\tfoo\n".as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+    buf.set_expand_tab(false).set_tab_stop(4).set_shift_width(4);
+    keys(&mut buf, ">>");
+    assert_eq!(buf.to_string(), "\t\tfoo\n");
+    buf.set_auto_indent(true).set_brace_indent(true);
+    keys(&mut buf, "A {\x1bobar\x1b");
+    assert_eq!(buf.to_string(), "\t\tfoo {\n\t\t\tbar\n");
+}
+
+#[test]
+fn test_dot_repeat() {
+    let mut buf = {
+        let txt = "one\ntwo\nthree\nfour\nfive\nsix\n";
+        let loc = Location::new_ted("test-dot-repeat", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+
+    keys(&mut buf, "dd.");
+    assert_eq!(buf.to_string(), "three\nfour\nfive\nsix\n");
+    keys(&mut buf, "x.");
+    assert_eq!(buf.to_string(), "ree\nfour\nfive\nsix\n");
+    keys(&mut buf, "yyj.");
+    assert_eq!(buf.to_string(), "ree\nour\nfive\nsix\n");
+    keys(&mut buf, "A!\x1bj.");
+    assert_eq!(buf.to_string(), "ree\nour!\nfive!\nsix\n");
+    keys(&mut buf, "Oab\x1b.");
+    assert_eq!(buf.to_string(), "ree\nour!\nab\nab\nfive!\nsix\n");
+    keys(&mut buf, ">>j.");
+    assert_eq!(buf.to_string(), "ree\nour!\n    ab\n    ab\nfive!\nsix\n");
+    keys(&mut buf, "02rzj.");
+    assert_eq!(buf.to_string(), "ree\nour!\n    ab\nzz  ab\nfzze!\nsix\n");
+    keys(&mut buf, "u");
+    assert_eq!(buf.to_string(), "ree\nour!\n    ab\nzz  ab\nfive!\nsix\n");
+}
+
+#[test]
+fn test_macro() {
+    use crate::keymap_edit::KeyEdit;
+    use crossterm::event::KeyModifiers;
+
+    let mut buf = {
+        let txt = "one\ntwo\nthree\nfour\nfive\n";
+        let loc = Location::new_ted("test-macro", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+
+    let mut km = KeyEdit::default();
+    let mut macros = vec![];
+    for ch in "qaA;\x1bjqj@a2@@".chars() {
+        let evnt = match ch {
+            '\x1b' => Event::Esc,
+            ch => Event::Char(ch, KeyModifiers::empty()),
+        };
+        match km.fold(&buf, evnt).unwrap() {
+            Event::Macro(n, evnts) => macros.push((n, evnts)),
+            evnt => {
+                buf.on_event(evnt).unwrap();
+            }
+        }
+    }
+    assert_eq!(buf.to_string(), "one;\ntwo\nthree\nfour\nfive\n");
+    assert_eq!(macros.len(), 2);
+    assert_eq!(macros[0].0, 1);
+    assert_eq!(macros[1].0, 2);
+    assert!(macros[0].1 == macros[1].1);
+    assert_eq!(macros[0].1.len(), 4);
+
+    for (n, evnts) in macros.into_iter() {
+        for _ in 0..n {
+            for evnt in evnts.iter() {
+                let evnt = km.fold(&buf, evnt.clone()).unwrap();
+                buf.on_event(evnt).unwrap();
+            }
+        }
+    }
+    assert_eq!(buf.to_string(), "one;\ntwo\nthree;\nfour;\nfive;\n");
+}
+
+#[test]
+fn test_format_lines() {
+    let mut buf = {
+        let txt = concat!(
+            "  one two three\n",
+            "four five six seven\n",
+            "\n",
+            "    // alpha beta gamma delta\n",
+            "    // epsilon\n",
+            "last\n"
+        );
+        let loc = Location::new_ted("test-format-lines", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+    buf.set_text_width(16);
+
+    keys(&mut buf, "gq}");
+    assert_eq!(
+        buf.to_string(),
+        concat!(
+            "  one two three\n  four five six\n  seven\n\n",
+            "    // alpha beta gamma delta\n    // epsilon\nlast\n"
+        )
+    );
+    assert_eq!(buf.to_char_cursor(), buf.line_to_char(2) + 2);
+
+    keys(&mut buf, "u");
+    assert_eq!(buf.to_string().lines().count(), 6);
+    keys(&mut buf, "4Ggwj");
+    assert_eq!(
+        buf.to_string(),
+        concat!(
+            "  one two three\nfour five six seven\n\n",
+            "    // alpha\n    // beta\n    // gamma\n    // delta\n    // epsilon\n",
+            "last\n"
+        )
+    );
+}
+
+#[test]
+fn test_write() {
+    let path = std::env::temp_dir().join("ted-test-buffer-write.txt");
+    std::fs::write(&path, "hello\n").unwrap();
+
+    let mut buf = {
+        let loc = Location::new_disk(path.as_os_str(), "utf-8").unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+    assert!(!buf.is_modified());
+
+    keys(&mut buf, "Aworld\x1b");
+    assert!(buf.is_modified());
+    assert_eq!(buf.write().unwrap(), 11);
+    assert!(!buf.is_modified());
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "helloworld\n");
+
+    // undo history is collapsed into the written content.
+    assert!(buf.undo().unwrap() == Event::Noop);
+    assert_eq!(buf.to_string(), "helloworld\n");
+
+    buf.set_read_only(true);
+    assert!(buf.write().is_err());
+
+    let loc = Location::new_ted("test-write", "hello".as_bytes(), false).unwrap();
+    assert!(Buffer::from_reader(loc).unwrap().write().is_err());
+
+    std::fs::remove_file(&path).ok();
+}
+
+#[test]
+fn test_text_count() {
+    let text = "Hello world. How are\nyou? Fine.\n\n  Next para here.\nEnd\n";
+    let mut buf = {
+        let loc = Location::new_ted("test-count", text.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+
+    let tc = buf.to_text_count().unwrap();
+    assert_eq!(tc.words, (1, 10), "{}", tc);
+    assert_eq!(tc.lines, (1, 5), "{}", tc);
+    assert_eq!(tc.sentences, (1, 5), "{}", tc);
+    assert_eq!(tc.paras, (1, 2), "{}", tc);
+    assert_eq!(buf.to_char_cursor(), 0);
+
+    // cursor on "para"
+    let cursor = text.find("para").unwrap();
+    buf.set_cursor(cursor);
+    let tc = buf.to_text_count().unwrap();
+    assert_eq!(tc.words, (8, 10), "{}", tc);
+    assert_eq!(tc.lines, (4, 5), "{}", tc);
+    assert_eq!(tc.sentences, (4, 5), "{}", tc);
+    assert_eq!(tc.paras, (2, 2), "{}", tc);
+    assert_eq!(buf.to_char_cursor(), cursor);
+
+    let mut buf = Buffer::empty();
+    let tc = buf.to_text_count().unwrap();
+    assert_eq!(tc.words, (0, 0), "{}", tc);
+    assert_eq!(tc.sentences, (0, 0), "{}", tc);
+    assert_eq!(tc.paras, (0, 0), "{}", tc);
+}
+
+#[test]
+fn test_sentence_abbreviation() {
+    let txt = "Mr. Smith went home. He slept, e.g. at noon. Done.\n";
+    let mut buf = {
+        let loc = Location::new_ted("test-sentence", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+
+    keys(&mut buf, ")");
+    assert_eq!(buf.to_char_cursor(), txt.find("He").unwrap());
+    keys(&mut buf, ")");
+    assert_eq!(buf.to_char_cursor(), txt.find("Done").unwrap());
+    keys(&mut buf, "(");
+    assert_eq!(buf.to_char_cursor(), txt.find("He").unwrap());
+    keys(&mut buf, "2(");
+    assert_eq!(buf.to_char_cursor(), 0);
+    keys(&mut buf, "2)");
+    assert_eq!(buf.to_char_cursor(), txt.find("Done").unwrap());
+
+    buf.set_abbreviations(vec![]).set_cursor(0);
+    keys(&mut buf, ")");
+    assert_eq!(buf.to_char_cursor(), txt.find("Smith").unwrap());
+
+    // empty lines and paragraph starts are sentence boundaries.
+    let txt = "Title\n\nBody text. More.\n";
+    let mut buf = {
+        let loc = Location::new_ted("test-sentence", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+    keys(&mut buf, ")");
+    assert_eq!(buf.to_char_cursor(), 6);
+    keys(&mut buf, ")");
+    assert_eq!(buf.to_char_cursor(), txt.find("Body").unwrap());
+    keys(&mut buf, ")");
+    assert_eq!(buf.to_char_cursor(), txt.find("More").unwrap());
+    keys(&mut buf, "2(");
+    assert_eq!(buf.to_char_cursor(), 6);
+}
+
+#[test]
+fn test_insert_tab() {
+    let new_buf = |txt: &str| {
+        let loc = Location::new_ted("test-insert-tab", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+
+    let mut buf = new_buf("ab\n");
+    buf.set_expand_tab(true).set_tab_stop(8);
+    keys(&mut buf, "A\tc\x1b");
+    assert_eq!(buf.to_string(), "ab      c\n");
+    keys(&mut buf, "0i\t\x1b");
+    assert_eq!(buf.to_string(), "        ab      c\n");
+
+    let mut buf = new_buf("ab\n");
+    buf.set_expand_tab(false);
+    keys(&mut buf, "A\tc\x1b");
+    assert_eq!(buf.to_string(), "ab\tc\n");
+
+    // soft-tab-stop, tab and backspace work with shift-width.
+    let mut buf = new_buf("ab\n");
+    buf.set_expand_tab(true)
+        .set_shift_width(4)
+        .set_soft_tab_stop(true);
+    keys(&mut buf, "A\t\tc\x1b");
+    assert_eq!(buf.to_string(), "ab      c\n");
+    keys(&mut buf, "A\x08\x08\x1b");
+    assert_eq!(buf.to_string(), "ab  \n");
+    keys(&mut buf, "A\x08\x1b");
+    assert_eq!(buf.to_string(), "ab\n");
+
+    // without soft-tab-stop, backspace deletes a single space.
+    buf.set_soft_tab_stop(false);
+    keys(&mut buf, "A\t\x08\x1b");
+    assert_eq!(buf.to_string(), "ab     \n");
+}
+
+#[test]
+fn test_auto_indent() {
+    let new_buf = |txt: &str| {
+        let loc = Location::new_ted("test-auto-indent", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+
+    let mut buf = new_buf("  ab\n");
+    keys(&mut buf, "A\ncd\x1b");
+    assert_eq!(buf.to_string(), "  ab\ncd\n");
+
+    let mut buf = new_buf("  ab\n");
+    buf.set_auto_indent(true);
+    keys(&mut buf, "A\ncd\x1b");
+    assert_eq!(buf.to_string(), "  ab\n  cd\n");
+    keys(&mut buf, "oef\x1b");
+    assert_eq!(buf.to_string(), "  ab\n  cd\n  ef\n");
+    keys(&mut buf, "ggOgh\x1b");
+    assert_eq!(buf.to_string(), "  gh\n  ab\n  cd\n  ef\n");
+
+    // extra indent after `{`, only for brace_indent.
+    let mut buf = new_buf("\tab {\n");
+    buf.set_auto_indent(true).set_shift_width(4);
+    keys(&mut buf, "ocd\x1b");
+    assert_eq!(buf.to_string(), "\tab {\n\tcd\n");
+    buf.set_brace_indent(true);
+    keys(&mut buf, "ggA\ncd\x1b");
+    assert_eq!(buf.to_string(), "\tab {\n            cd\n\tcd\n");
+    keys(&mut buf, "ggOef\x1b");
+    assert_eq!(buf.to_string(), "\tef\n\tab {\n            cd\n\tcd\n");
+}
+
+#[test]
+fn test_iabbrevs() {
+    let mut buf = {
+        let loc = Location::new_ted("test-iabbrevs", "ab\n".as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+    buf.set_iabbrevs(vec![("teh".to_string(), "the".to_string())]);
+
+    // expanded before the delimiter, but not within a word.
+    keys(&mut buf, "A teh, tehx teh\nteh\x1b");
+    assert_eq!(buf.to_string(), "ab the, tehx the\nteh\n");
+    assert_eq!(buf.to_char_cursor(), 19);
+
+    // expansion is undone along with the insert session.
+    keys(&mut buf, "u");
+    assert_eq!(buf.to_string(), "ab\n");
+}
+
+#[test]
+fn test_grapheme_motion() {
+    // flag emoji is a pair of regional indicators, accent is a combining mark.
+    let txt = "a\u{1F1EE}\u{1F1F3}e\u{301}b\n";
+    let mut buf = {
+        let loc = Location::new_ted("test-grapheme", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+
+    keys(&mut buf, "ll");
+    assert_eq!(buf.to_char_cursor(), 2);
+
+    buf.set_grapheme_motion(true);
+    keys(&mut buf, "0l");
+    assert_eq!(buf.to_char_cursor(), 1);
+    keys(&mut buf, "l");
+    assert_eq!(buf.to_char_cursor(), 3);
+    keys(&mut buf, "l");
+    assert_eq!(buf.to_char_cursor(), 5);
+    keys(&mut buf, "l");
+    assert_eq!(buf.to_char_cursor(), 5);
+    keys(&mut buf, "h");
+    assert_eq!(buf.to_char_cursor(), 3);
+    keys(&mut buf, "2h");
+    assert_eq!(buf.to_char_cursor(), 0);
+    keys(&mut buf, "3l");
+    assert_eq!(buf.to_char_cursor(), 5);
+}
+
+#[test]
+fn test_insert_str() {
+    use crate::event::Edit;
+
+    let mut buf = {
+        let txt = "hello world\n";
+        let loc = Location::new_ted("test-insert-str", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+
+    keys(&mut buf, "w");
+    let edit = buf.insert_str(5, ",").unwrap();
+    assert!(edit == Edit::new_ins(5, ",".to_string()));
+    assert_eq!(buf.to_string(), "hello, world\n");
+    assert_eq!(buf.to_char_cursor(), 7);
+
+    buf.insert_str(12, "!").unwrap();
+    assert_eq!(buf.to_string(), "hello, world!\n");
+    assert_eq!(buf.to_char_cursor(), 7);
+
+    assert!(buf.insert_str(100, "x").is_err());
+
+    keys(&mut buf, "u");
+    assert_eq!(buf.to_string(), "hello, world\n");
+    keys(&mut buf, "u");
+    assert_eq!(buf.to_string(), "hello world\n");
+}
+
+#[test]
+fn test_delete_range() {
+    use crate::event::Edit;
+
+    let mut buf = {
+        let txt = "hello, world!\n";
+        let loc = Location::new_ted("test-delete-range", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+
+    keys(&mut buf, "w");
+    let (txt, edit) = buf.delete_range(12, 13).unwrap();
+    assert_eq!(txt, "!");
+    assert!(edit == Edit::new_del(12, "!".to_string()));
+    assert_eq!(buf.to_string(), "hello, world\n");
+    assert_eq!(buf.to_char_cursor(), 5);
+
+    buf.delete_range(0, 2).unwrap();
+    assert_eq!(buf.to_string(), "llo, world\n");
+    assert_eq!(buf.to_char_cursor(), 3);
+    buf.delete_range(2, 5).unwrap();
+    assert_eq!(buf.to_string(), "llworld\n");
+    assert_eq!(buf.to_char_cursor(), 2);
+
+    assert!(buf.delete_range(3, 2).is_err());
+    assert!(buf.delete_range(0, 100).is_err());
+
+    keys(&mut buf, "uuu");
+    assert_eq!(buf.to_string(), "hello, world!\n");
+}
+
+#[test]
+fn test_history() {
+    let mut buf = {
+        let loc = Location::new_ted("test-history", "abc\n".as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+
+    keys(&mut buf, "xxuiyz\x1b");
+    assert_eq!(buf.to_string(), "yzbc\n");
+
+    let infos: Vec<ChangeInfo> = buf.history().collect();
+    let tree: Vec<(usize, Option<usize>)> = infos.iter().map(|c| (c.seq, c.parent)).collect();
+    assert_eq!(
+        tree,
+        vec![(0, None), (1, Some(0)), (2, Some(1)), (3, Some(1))]
+    );
+    let counts: Vec<(usize, usize)> = infos.iter().map(|c| (c.added, c.removed)).collect();
+    assert_eq!(counts, vec![(0, 0), (0, 1), (0, 1), (2, 0)]);
+    let currs: Vec<bool> = infos.iter().map(|c| c.current).collect();
+    assert_eq!(currs, vec![false, false, false, true]);
+    assert_eq!(infos[3].to_string(), "3* +2 -0");
+}
+
+#[test]
+fn test_persistent_undo() {
+    let path = std::env::temp_dir().join("ted-test-buffer-undo.bin");
+    let new_buf = |txt: &str| {
+        let loc = Location::new_ted("test-undo", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+
+    let mut buf = new_buf("abc\n");
+    keys(&mut buf, "xxuiyz\x1b");
+    assert_eq!(buf.to_string(), "yzbc\n");
+    assert!(buf.save_undo(&path).unwrap() > 0);
+
+    let mut other = new_buf("yzbc\n");
+    other.load_undo(&path).unwrap();
+    let (a, b): (Vec<ChangeInfo>, Vec<ChangeInfo>) =
+        (buf.history().collect(), other.history().collect());
+    assert_eq!(a, b);
+    keys(&mut other, "u");
+    assert_eq!(other.to_string(), "bc\n");
+    keys(&mut other, "u");
+    assert_eq!(other.to_string(), "abc\n");
+    other.redo().unwrap();
+    assert_eq!(other.to_string(), "bc\n");
+
+    // undo file is stale for a different content.
+    let mut other = new_buf("abc\n");
+    assert!(other.load_undo(&path).is_err());
+    assert_eq!(other.history().count(), 1);
+
+    std::fs::remove_file(&path).ok();
+}
+
+#[test]
+fn test_insert_undo() {
+    let mut buf = {
+        let loc = Location::new_ted("test-insert-undo", "x\n".as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+
+    // typed characters are undone together.
+    keys(&mut buf, "Aab\x1b");
+    assert_eq!(buf.to_string(), "xab\n");
+    keys(&mut buf, "u");
+    assert_eq!(buf.to_string(), "x\n");
+
+    // cursor motion starts a new undo step.
+    keys(&mut buf, "Aab←c\x1b");
+    assert_eq!(buf.to_string(), "xacb\n");
+    keys(&mut buf, "u");
+    assert_eq!(buf.to_string(), "xab\n");
+    keys(&mut buf, "u");
+    assert_eq!(buf.to_string(), "x\n");
+}
+
+#[test]
+fn test_replace_all() {
+    let mut buf = {
+        let txt = "foo bar Foo\nfoo1 bar\n";
+        let loc = Location::new_ted("test-replace-all", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+
+    let n = buf
+        .replace_all("f(o+)", "g$1", SearchOpts::default())
+        .unwrap();
+    assert_eq!(n, 2);
+    assert_eq!(buf.to_string(), "goo bar Foo\ngoo1 bar\n");
+
+    let opts = SearchOpts {
+        ignorecase: true,
+        smartcase: false,
+    };
+    let n = buf
+        .replace_all("(?P<w>b)ar|f(o+)", "${w}az$2", opts)
+        .unwrap();
+    assert_eq!(n, 3);
+    assert_eq!(buf.to_string(), "goo baz azoo\ngoo1 baz\n");
+
+    assert_eq!(buf.replace_all("xyz", "", opts).unwrap(), 0);
+    assert!(buf.replace_all("(", "", opts).is_err());
+
+    keys(&mut buf, "u");
+    assert_eq!(buf.to_string(), "goo bar Foo\ngoo1 bar\n");
+}
+
+#[test]
+fn test_strip_whitespace() {
+    let txt = "foo \nbar\n\t\nbaz \t \r\nqux\n";
+    let mut buf = {
+        let loc = Location::new_ted("test-strip-whitespace", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+
+    // cursor on the trailing whitespace is pulled back to the last char.
+    buf.set_cursor(16);
+    let evnt = buf.strip_whitespace().unwrap();
+    assert!(matches!(evnt, Event::Edit(_)));
+    assert_eq!(buf.to_string(), "foo\nbar\n\nbaz\r\nqux\n");
+    assert_eq!(buf.to_char_cursor(), 11);
+
+    let evnt = buf.strip_whitespace().unwrap();
+    assert!(evnt == Event::Noop);
+
+    // all lines are restored with a single undo.
+    keys(&mut buf, "u");
+    assert_eq!(buf.to_string(), txt);
+}
+
+#[test]
+fn test_folds() {
+    let new_buf = |txt: &str| {
+        let loc = Location::new_ted("test-folds", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+
+    // closed fold is skipped over by line motions, and entered on its
+    // first line.
+    let mut buf = new_buf("a\nb\nc\nd\ne\n");
+    keys(&mut buf, "jzfj");
+    assert_eq!(buf.to_folds(), vec![(1, 2, true)]);
+    assert_eq!(buf.to_char_cursor(), 2);
+    keys(&mut buf, "jk");
+    assert_eq!(buf.to_char_cursor(), 2);
+    keys(&mut buf, "kj");
+    assert_eq!(buf.to_char_cursor(), 2);
+    keys(&mut buf, "j");
+    assert_eq!(buf.to_char_cursor(), 6);
+
+    // other motions do not land on hidden lines of a closed fold, moving
+    // forward from within the fold moves past it.
+    let mut buf = new_buf("a b\nc d\ne f\ng h\n");
+    buf.add_fold(1, 2);
+    keys(&mut buf, "ww");
+    assert_eq!(buf.to_char_cursor(), 4);
+    keys(&mut buf, "ww");
+    assert_eq!(buf.to_char_cursor(), 12);
+    keys(&mut buf, "b");
+    assert_eq!(buf.to_char_cursor(), 4);
+    keys(&mut buf, "gg/f\n");
+    assert_eq!(buf.to_char_cursor(), 4);
+    keys(&mut buf, "n");
+    assert_eq!(buf.to_char_cursor(), 12);
+    keys(&mut buf, "Gk");
+    assert_eq!(buf.to_char_cursor(), 4);
+    keys(&mut buf, "gg");
+    assert_eq!(buf.to_char_cursor(), 0);
+    buf.set_cursor(10);
+    keys(&mut buf, "magg`a");
+    assert_eq!(buf.to_char_cursor(), 4);
+    keys(&mut buf, "x");
+    assert_eq!(buf.to_string(), "a b\n d\ne f\ng h\n");
+
+    let mut buf = new_buf("(\nb\nc)\nd\n");
+    buf.add_fold(1, 2);
+    keys(&mut buf, "%");
+    assert_eq!(buf.to_char_cursor(), 2);
+
+    // open and close the fold.
+    let mut buf = new_buf("a\nb\nc\nd\ne\n");
+    keys(&mut buf, "jzfjj");
+    keys(&mut buf, "kzoj");
+    assert_eq!(buf.to_folds(), vec![(1, 2, false)]);
+    assert_eq!(buf.to_char_cursor(), 4);
+    keys(&mut buf, "zc");
+    assert_eq!(buf.to_folds(), vec![(1, 2, true)]);
+    assert_eq!(buf.to_char_cursor(), 2);
+
+    // folds shift along with edits, and are dropped with their lines.
+    keys(&mut buf, "ggdd");
+    assert_eq!(buf.to_folds(), vec![(0, 1, true)]);
+    keys(&mut buf, "Vjd");
+    assert_eq!(buf.to_string(), "e\n");
+    assert_eq!(buf.to_folds(), vec![]);
+
+    // partially overlapping folds are nested.
+    let mut buf = new_buf("a\nb\nc\nd\ne\nf\n");
+    buf.add_fold(1, 2);
+    buf.add_fold(2, 3);
+    assert_eq!(buf.to_folds(), vec![(1, 2, true), (1, 3, true)]);
+    assert_eq!(buf.to_closed_fold(2), Some((1, 3)));
+    assert!(buf.open_fold(2));
+    assert_eq!(buf.to_closed_fold(2), Some((1, 2)));
+    assert!(buf.close_fold(2));
+    assert_eq!(buf.to_closed_fold(2), Some((1, 3)));
+    assert!(!buf.close_fold(5));
+
+    // folds derived from syntax retain their closed state, and `zf` only
+    // notifies that folds cannot be created.
+    buf.set_fold_syntax(true);
+    buf.set_syntax_folds(vec![(1, 3), (4, 5), (5, 9)]);
+    assert_eq!(buf.to_folds(), vec![(1, 3, true), (4, 5, false)]);
+    keys(&mut buf, "ggzfj");
+    assert_eq!(buf.to_folds(), vec![(1, 3, true), (4, 5, false)]);
+    let evnt = buf
+        .on_event(Event::Op(Opr::Fold(1, Mto::Down(1, DP::None))))
+        .unwrap();
+    assert!(matches!(evnt, Event::Notify(_)));
+
+    // closed state is retained when lines shift, and the fold grows.
+    keys(&mut buf, "ggOx\x1b");
+    assert_eq!(buf.to_folds(), vec![(2, 4, true), (5, 6, false)]);
+    buf.set_syntax_folds(vec![(2, 5), (6, 7)]);
+    assert_eq!(buf.to_folds(), vec![(2, 5, true), (6, 7, false)]);
+    buf.set_fold_syntax(false);
+    assert_eq!(buf.to_folds(), vec![]);
+}
+
+#[test]
+fn test_search_wrapscan() {
+    let mut buf = {
+        let txt = "foo bar\nbar foo\n";
+        let loc = Location::new_ted("test-wrapscan", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+    // search wraps around by default.
+    assert!(buf.wrapscan);
+    buf.set_wrapscan(false);
+
+    keys(&mut buf, "/foo\n");
+    assert_eq!(buf.to_char_cursor(), 12);
+    let evnt = buf
+        .on_event(Event::Mt(Mto::PatternR(1, DP::Right)))
+        .unwrap();
+    assert_eq!(buf.to_char_cursor(), 12);
+    assert!(matches!(evnt, Event::Notify(_)));
+    assert_eq!(buf.search_preview("bar", DP::Left), Some(8));
+    assert_eq!(buf.search_preview("bar", DP::Right), None);
+
+    buf.set_wrapscan(true);
+    let evnt = buf
+        .on_event(Event::Mt(Mto::PatternR(1, DP::Right)))
+        .unwrap();
+    assert_eq!(buf.to_char_cursor(), 0);
+    assert!(matches!(evnt, Event::Notify(_)));
+    let evnt = buf
+        .on_event(Event::Mt(Mto::PatternR(1, DP::Right)))
+        .unwrap();
+    assert_eq!(buf.to_char_cursor(), 12);
+    assert!(evnt == Event::Noop);
+    assert_eq!(buf.search_preview("bar", DP::Right), Some(4));
+    keys(&mut buf, "3n");
+    assert_eq!(buf.to_char_cursor(), 0);
+    keys(&mut buf, "N");
+    assert_eq!(buf.to_char_cursor(), 12);
+}
+
+#[test]
+fn test_lines_between() {
+    let buf = {
+        let txt = "one\ntwo\nthree\nfour";
+        let loc = Location::new_ted("test-lines-between", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+    let lines = |a, z, dp| -> Vec<String> { buf.lines_between(a, z, dp).unwrap().collect() };
+
+    assert_eq!(lines(1, 3, DP::Right), vec!["two\n", "three\n"]);
+    assert_eq!(lines(1, 3, DP::Left), vec!["three\n", "two\n"]);
+    assert_eq!(lines(0, 100, DP::Right).len(), 4);
+    assert_eq!(lines(2, 100, DP::Left), vec!["four", "three\n"]);
+    assert_eq!(lines(3, 3, DP::Right).len(), 0);
+    assert_eq!(lines(5, 2, DP::Left).len(), 0);
+}
+
+#[test]
+fn test_goto_row() {
+    let mut buf = {
+        let txt = "one\n  two\n  three end\n";
+        let loc = Location::new_ted("test-goto-row", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+
+    keys(&mut buf, "G");
+    assert_eq!(buf.to_char_cursor(), 12);
+    keys(&mut buf, "gg");
+    assert_eq!(buf.to_char_cursor(), 0);
+    keys(&mut buf, "2G");
+    assert_eq!(buf.to_char_cursor(), 6);
+    keys(&mut buf, "3gg");
+    assert_eq!(buf.to_char_cursor(), 12);
+    keys(&mut buf, "1G");
+    assert_eq!(buf.to_char_cursor(), 0);
+    keys(&mut buf, "100G");
+    assert_eq!(buf.to_char_cursor(), 12);
+
+    // sticky end-of-line column is reset by line jump.
+    keys(&mut buf, "gg$Gk");
+    assert_eq!(buf.to_char_cursor(), 6);
+
+    keys(&mut buf, "ggjdG");
+    assert_eq!(buf.to_string(), "one\n");
+}
+
+#[test]
+fn test_goto_offset() {
+    let mut buf = {
+        let txt = "héllo\nwörld\n";
+        let loc = Location::new_ted("test-goto-offset", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+
+    assert_eq!(buf.goto_offset(3), 2);
+    assert_eq!(buf.to_char_cursor(), 2);
+    // within a multi-byte character.
+    assert_eq!(buf.goto_offset(2), 1);
+    assert_eq!(buf.goto_offset(7), 6);
+    assert_eq!(buf.goto_offset(100), 10);
+    assert_eq!(buf.goto_offset(0), 0);
+}
+
+#[test]
+fn test_goto_line_col() {
+    let mut buf = {
+        let txt = "héllo
+wörld
+";
+        let loc = Location::new_ted("test-goto-line-col", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+
+    assert_eq!(buf.goto_line_col(2, 3), 8);
+    assert_eq!(buf.to_char_cursor(), 8);
+    assert_eq!(buf.goto_line_col(1, 1), 0);
+    assert_eq!(buf.goto_line_col(0, 0), 0);
+    // beyond the line and beyond the buffer.
+    assert_eq!(buf.goto_line_col(1, 100), 4);
+    assert_eq!(buf.goto_line_col(100, 2), 7);
+}
+
+#[test]
+fn test_percent() {
+    let mut buf = {
+        let txt: String = (1..=10).map(|i| format!("line{}\n", i)).collect();
+        let loc = Location::new_ted("test-percent", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+
+    keys(&mut buf, "50%");
+    assert_eq!(buf.to_xy_cursor(None).row, 4);
+    keys(&mut buf, "1%");
+    assert_eq!(buf.to_xy_cursor(None).row, 0);
+    keys(&mut buf, "91%");
+    assert_eq!(buf.to_xy_cursor(None).row, 9);
+    keys(&mut buf, "100%");
+    assert_eq!(buf.to_xy_cursor(None).row, 9);
+}
+
+#[test]
+fn test_find_char_repeat() {
+    let mut buf = {
+        let txt = "a x b x c x\n";
+        let loc = Location::new_ted("test-find-char", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+
+    keys(&mut buf, "fx");
+    assert_eq!(buf.to_char_cursor(), 2);
+    keys(&mut buf, ";");
+    assert_eq!(buf.to_char_cursor(), 6);
+    keys(&mut buf, ",");
+    assert_eq!(buf.to_char_cursor(), 2);
+
+    keys(&mut buf, "0tx");
+    assert_eq!(buf.to_char_cursor(), 1);
+    keys(&mut buf, ";");
+    assert_eq!(buf.to_char_cursor(), 5);
+    keys(&mut buf, ";");
+    assert_eq!(buf.to_char_cursor(), 9);
+    keys(&mut buf, ";");
+    assert_eq!(buf.to_char_cursor(), 9);
+    keys(&mut buf, ",");
+    assert_eq!(buf.to_char_cursor(), 7);
+    keys(&mut buf, ",");
+    assert_eq!(buf.to_char_cursor(), 3);
+
+    keys(&mut buf, "$Tx");
+    assert_eq!(buf.to_char_cursor(), 7);
+    keys(&mut buf, ";");
+    assert_eq!(buf.to_char_cursor(), 3);
+    keys(&mut buf, "$2;");
+    assert_eq!(buf.to_char_cursor(), 3);
+}
+
+#[test]
+fn test_select_register() {
+    let mut buf = {
+        let txt = "one two\nfoo\n";
+        let loc = Location::new_ted("test-select-register", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+
+    keys(&mut buf, "\"ayw");
+    assert_eq!(buf.to_string(), "one two\nfoo\n");
+    keys(&mut buf, "x");
+    assert_eq!(buf.to_string(), "ne two\nfoo\n");
+    keys(&mut buf, "j0\"aP");
+    assert_eq!(buf.to_string(), "ne two\none foo\n");
+    keys(&mut buf, "p");
+    assert_eq!(buf.to_string(), "ne two\none ofoo\n");
+
+    // clipboard register falls back to local register.
+    keys(&mut buf, "k\"+yyG\"+p");
+    assert_eq!(buf.to_string(), "ne two\none ofoo\nne two\n");
+    keys(&mut buf, "gg0vl\"by\"bP");
+    assert_eq!(buf.to_string(), "nene two\none ofoo\nne two\n");
+}
+
+#[test]
+fn test_paste() {
+    let mut buf = {
+        let loc = Location::new_ted("test-paste", "  ab\n".as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+    buf.set_auto_indent(true).set_brace_indent(true);
+
+    keys(&mut buf, "A");
+    buf.on_event(Event::Paste("{\r\nx\r\n}".to_string()))
+        .unwrap();
+    keys(&mut buf, "\x1b");
+    assert_eq!(buf.to_string(), "  ab{\nx\n}\n");
+
+    keys(&mut buf, "u");
+    assert_eq!(buf.to_string(), "  ab\n");
+    keys(&mut buf, ".");
+    assert_eq!(buf.to_string(), "  ab{\nx\n}\n");
+}
+
+#[test]
+fn test_empty_motions() {
+    let texts = ["", "\n", "\n\n", "abc\n\n", "\nabc", "   \n  x\n", "abc"];
+    let motions = [
+        "k", "j", "5j", "5k", "$", "5$", "g_", "|", "9|", "-", "+", "G", "gg", "$j", "$k", "gM",
+        "100gM",
+    ];
+    for txt in texts.iter() {
+        for mto in motions.iter() {
+            let mut buf = {
+                let loc = Location::new_ted("test-empty-motions", txt.as_bytes(), false).unwrap();
+                Buffer::from_reader(loc).unwrap()
+            };
+            for start in ["", "G$", "j"].iter() {
+                keys(&mut buf, start);
+                keys(&mut buf, mto);
+                let cursor = buf.to_char_cursor();
+                let xy = buf.to_xy_cursor(None);
+                let n = text::visual_line_n(&buf.line(xy.row));
+                assert!(
+                    cursor <= buf.n_chars(),
+                    "{:?} {:?} {:?} {}",
+                    txt,
+                    start,
+                    mto,
+                    cursor
+                );
+                assert!(
+                    xy.col < cmp::max(n, 1),
+                    "{:?} {:?} {:?} {}",
+                    txt,
+                    start,
+                    mto,
+                    xy
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_text_col_motions() {
+    let mut buf = {
+        let txt = "  ab\n    cdef\n  gh\n";
+        let loc = Location::new_ted("test-text-col", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+
+    // `-` and `+` land on the first non-blank, whatever the column.
+    keys(&mut buf, "j$-");
+    assert_eq!(buf.to_char_cursor(), 2);
+    keys(&mut buf, "l+");
+    assert_eq!(buf.to_char_cursor(), 9);
+    keys(&mut buf, "l-");
+    assert_eq!(buf.to_char_cursor(), 2);
+    keys(&mut buf, "2+");
+    assert_eq!(buf.to_char_cursor(), 16);
+}
+
+#[test]
+fn test_cursor_ord() {
+    let c = |col: usize, row: usize| Cursor { col, row };
+
+    // cursors are ordered by row, then by column.
+    let mut cursors = vec![c(3, 1), c(0, 2), c(5, 0), c(1, 1), c(0, 0)];
+    cursors.sort();
+    assert_eq!(cursors, vec![c(0, 0), c(5, 0), c(1, 1), c(3, 1), c(0, 2)]);
+
+    for a in cursors.iter() {
+        for b in cursors.iter() {
+            assert_eq!(a.partial_cmp(b), Some(a.cmp(b)), "{} {}", a, b);
+        }
+    }
+    assert!(c(5, 0) < c(1, 1));
+    assert_eq!(cmp::max(c(2, 3), c(4, 3)), c(4, 3));
+}
+
+#[test]
+fn test_goto_char() {
+    let mut buf = {
+        let txt = "one\ntwo\n";
+        let loc = Location::new_ted("test-goto-char", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+
+    // `go` is absolute, from the first character, not relative to cursor.
+    keys(&mut buf, "5go");
+    assert_eq!(buf.to_char_cursor(), 4);
+    keys(&mut buf, "5go");
+    assert_eq!(buf.to_char_cursor(), 4);
+    keys(&mut buf, "go");
+    assert_eq!(buf.to_char_cursor(), 0);
+    // newline is not a cursor position.
+    keys(&mut buf, "4go");
+    assert_eq!(buf.to_char_cursor(), 2);
+    // last character and beyond.
+    keys(&mut buf, "7go");
+    assert_eq!(buf.to_char_cursor(), 6);
+    keys(&mut buf, "100go");
+    assert_eq!(buf.to_char_cursor(), 6);
+}
+
+#[test]
+fn test_insert_repeat() {
+    let new_buf = |txt: &str| {
+        let loc = Location::new_ted("test-insert-repeat", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+
+    let mut buf = new_buf("xy\n");
+    keys(&mut buf, "3ifoo\x1b");
+    assert_eq!(buf.to_string(), "foofoofooxy\n");
+    assert_eq!(buf.to_char_cursor(), 8);
+
+    // repeats with backspace and newline.
+    let mut buf = new_buf("xy\n");
+    keys(&mut buf, "3ifox\x08o\x1b");
+    assert_eq!(buf.to_string(), "foofoofooxy\n");
+    let mut buf = new_buf("xy\n");
+    keys(&mut buf, "2ia\nb\x1b");
+    assert_eq!(buf.to_string(), "a\nba\nbxy\n");
+    assert_eq!(buf.to_char_cursor(), 5);
+
+    // edits after the last cursor motion are repeated, trailing motion
+    // does not change where they are repeated.
+    let mut buf = new_buf("xy\n");
+    keys(&mut buf, "3ia←b\x1b");
+    assert_eq!(buf.to_string(), "bbbaxy\n");
+    let mut buf = new_buf("xy\n");
+    keys(&mut buf, "3Aab←\x1b");
+    assert_eq!(buf.to_string(), "xyababab\n");
+
+    // whole session is undone together.
+    keys(&mut buf, "u");
+    assert_eq!(buf.to_string(), "xy\n");
+}
+
+#[test]
+fn test_open_repeat() {
+    let new_buf = |txt: &str| {
+        let loc = Location::new_ted("test-open-repeat", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+
+    // each repeat opens a new line.
+    let mut buf = new_buf("xy\nz\n");
+    keys(&mut buf, "3oabc\x1b");
+    assert_eq!(buf.to_string(), "xy\nabc\nabc\nabc\nz\n");
+    assert_eq!(buf.to_char_cursor(), 13);
+    keys(&mut buf, "u");
+    assert_eq!(buf.to_string(), "xy\nz\n");
+
+    let mut buf = new_buf("xy\nz\n");
+    keys(&mut buf, "j2Oab\x1b");
+    assert_eq!(buf.to_string(), "xy\nab\nab\nz\n");
+    assert_eq!(buf.to_char_cursor(), 7);
+
+    // with auto-indent, and dot repeat.
+    let mut buf = new_buf("  xy\n");
+    buf.set_auto_indent(true);
+    keys(&mut buf, "2oab\x1b");
+    assert_eq!(buf.to_string(), "  xy\n  ab\n  ab\n");
+    keys(&mut buf, ".");
+    assert_eq!(buf.to_string(), "  xy\n  ab\n  ab\n  ab\n  ab\n");
+
+    // append and insert repeat within the line.
+    let mut buf = new_buf("xy\n");
+    keys(&mut buf, "3Aab\x1b2I-\x1b");
+    assert_eq!(buf.to_string(), "--xyababab\n");
+}
@@ -0,0 +1,105 @@
+//! Module implement access to system clipboard, text is piped through
+//! platform tools like `pbcopy`, `wl-copy`, `xclip` or `xsel`, whichever
+//! is available. Clipboard is available only when built with `clipboard`
+//! feature.
+
+#[allow(unused_imports)]
+use log::debug;
+
+#[cfg(feature = "clipboard")]
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+#[cfg(feature = "clipboard")]
+use crate::{Error, Result};
+
+// (copy-command, paste-command) for each backend, tried in order.
+#[cfg(feature = "clipboard")]
+const BACKENDS: [(&[&str], &[&str]); 4] = [
+    (&["pbcopy"], &["pbpaste"]),
+    (&["wl-copy"], &["wl-paste", "--no-newline"]),
+    (
+        &["xclip", "-selection", "clipboard"],
+        &["xclip", "-selection", "clipboard", "-o"],
+    ),
+    (
+        &["xsel", "--clipboard", "--input"],
+        &["xsel", "--clipboard", "--output"],
+    ),
+];
+
+/// Copy `txt` to system clipboard. Return false if no clipboard backend
+/// is available.
+#[cfg(feature = "clipboard")]
+pub fn set_text(txt: &str) -> bool {
+    for (cmd, _) in BACKENDS.iter() {
+        match copy_with(cmd, txt) {
+            Ok(_) => return true,
+            Err(err) => debug!("clipboard {}", err),
+        }
+    }
+    false
+}
+
+/// Return text from system clipboard. Return None if no clipboard backend
+/// is available.
+#[cfg(feature = "clipboard")]
+pub fn get_text() -> Option<String> {
+    for (_, cmd) in BACKENDS.iter() {
+        match paste_with(cmd) {
+            Ok(txt) => return Some(txt),
+            Err(err) => debug!("clipboard {}", err),
+        }
+    }
+    None
+}
+
+#[cfg(not(feature = "clipboard"))]
+pub fn set_text(_txt: &str) -> bool {
+    false
+}
+
+#[cfg(not(feature = "clipboard"))]
+pub fn get_text() -> Option<String> {
+    None
+}
+
+#[cfg(feature = "clipboard")]
+fn copy_with(cmd: &[&str], txt: &str) -> Result<()> {
+    let mut child = err_at!(
+        IOError,
+        Command::new(cmd[0])
+            .args(&cmd[1..])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+    )?;
+    // stdin is closed before waiting, so that the tool sees the end.
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(txt.as_bytes())?;
+    }
+    let status = child.wait()?;
+    match status.success() {
+        true => Ok(()),
+        false => err_at!(IOError, msg: format!("{} {}", cmd[0], status)),
+    }
+}
+
+#[cfg(feature = "clipboard")]
+fn paste_with(cmd: &[&str]) -> Result<String> {
+    let output = err_at!(
+        IOError,
+        Command::new(cmd[0])
+            .args(&cmd[1..])
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output()
+    )?;
+    match output.status.success() {
+        true => err_at!(FailConvert, String::from_utf8(output.stdout)),
+        false => err_at!(IOError, msg: format!("{} {}", cmd[0], output.status)),
+    }
+}
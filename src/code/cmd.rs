@@ -5,7 +5,19 @@ use log::{debug, trace};
 use std::convert::TryFrom;
 
 use crate::{
-    code::{cmd_set::Set, Code},
+    code::{
+        cmd_buffer::{Buffer, Buffers},
+        cmd_edit::Edit,
+        cmd_goto::Goto,
+        cmd_highlight::Highlight,
+        cmd_quickfix::{Cnext, Copen, Cprev},
+        cmd_quit::Quit,
+        cmd_set::Set,
+        cmd_substitute::Substitute,
+        cmd_whitespace::StripWhitespace,
+        cmd_write::{Write, WriteQuit},
+        Code,
+    },
     colors::ColorScheme,
     event::Event,
     syntax, Error, Result,
@@ -19,7 +31,7 @@ macro_rules! commands {
     ($(($var:ident, $t:ident, $name:expr)),*) => (
         lazy_static! {
             static ref CMD_NAMES: Vec<String> = vec![
-                $($name.to_string())*
+                $($name.to_string(),)*
             ];
         }
 
@@ -34,12 +46,12 @@ macro_rules! commands {
             fn try_from((content, scheme): (String, ColorScheme)) -> Result<Self> {
                 let syn = syntax::CodeCmd::new(&content, scheme)?;
                 let name = {
-                    let err = Error::Invalid("".to_string(), format!("no command"));
+                    let err = Error::BadCommand("".to_string(), format!("no command"));
                     err_at!(syn.to_command_name().ok_or(err))?
                 };
                 match name.as_str() {
                     $($name => Ok(Cmd::$var($t::new(syn)?)),)*
-                    name => err_at!(Invalid, msg: format!("command {}", name)),
+                    name => err_at!(BadCommand, msg: format!("command {}", name)),
                 }
             }
         }
@@ -54,7 +66,22 @@ macro_rules! commands {
     )
 }
 
-commands![(Set, Set, "set")];
+commands![
+    (Set, Set, "set"),
+    (Substitute, Substitute, "substitute"),
+    (Buffer, Buffer, "buffer"),
+    (Buffers, Buffers, "buffers"),
+    (Edit, Edit, "edit"),
+    (Goto, Goto, "goto"),
+    (Highlight, Highlight, "highlight"),
+    (Write, Write, "write"),
+    (Quit, Quit, "quit"),
+    (WriteQuit, WriteQuit, "write_quit"),
+    (StripWhitespace, StripWhitespace, "strip_whitespace"),
+    (Copen, Copen, "copen"),
+    (Cnext, Cnext, "cnext"),
+    (Cprev, Cprev, "cprev")
+];
 
 //pub fn on_tab(&mut self, s: &mut State) -> Result<()> {
 //    let span = Self::to_command_name(s);
@@ -97,3 +124,7 @@ commands![(Set, Set, "set")];
 
 //    Ok(())
 //}
+
+#[cfg(test)]
+#[path = "cmd_test.rs"]
+mod cmd_test;
@@ -0,0 +1,85 @@
+#[allow(unused_imports)]
+use log::{debug, error, trace};
+
+use crate::{
+    app::Application,
+    code::cmd::Command,
+    code::Code,
+    event::{self, Event},
+    pubsub::Notify,
+    syntax,
+    window::WindowLess,
+    Result,
+};
+
+/// Buffer command, `:b[uffer] {num}`, switch the focused window to buffer
+/// numbered `num`.
+pub struct Buffer {
+    num: Option<usize>,
+}
+
+impl Buffer {
+    pub fn new(syn: syntax::CodeCmd) -> Result<Self> {
+        let args = syn.to_command_args().unwrap_or_default();
+        let num = args.trim().parse().ok();
+        Ok(Buffer { num })
+    }
+}
+
+impl Command for Buffer {
+    fn on_command(&mut self, app: &mut Code) -> Result<Event> {
+        let id = {
+            let mut iter = app.as_buffers().iter();
+            iter.find(|b| Some(b.to_num()) == self.num)
+                .map(|b| b.to_id())
+        };
+        debug!("buffer {:?} {:?}", self.num, id);
+        match id {
+            Some(id) => Ok(Event::Appn(event::Appn::Buffer(id))),
+            None => {
+                let span = match self.num {
+                    Some(num) => format!("no buffer numbered {}", num),
+                    None => "invalid buffer number".to_string(),
+                };
+                app.notify("code", Notify::Status(vec![span.into()]))?;
+                Ok(Event::Noop)
+            }
+        }
+    }
+}
+
+/// Buffers command, `:ls` or `:buffers`, list buffers with their number,
+/// id, `%` for the buffer under edit and `+` for modified buffer.
+pub struct Buffers;
+
+impl Buffers {
+    pub fn new(_syn: syntax::CodeCmd) -> Result<Self> {
+        Ok(Buffers)
+    }
+}
+
+impl Command for Buffers {
+    fn on_command(&mut self, app: &mut Code) -> Result<Event> {
+        // buffers are maintained in the order of their use, most recent last.
+        let mut items: Vec<(usize, String)> = {
+            let n = app.as_buffers().len();
+            let iter = app.as_buffers().iter().enumerate();
+            iter.map(|(i, b)| {
+                let curr = if_else!(i + 1 == n, '%', ' ');
+                let modified = if_else!(b.is_modified(), '+', ' ');
+                let line = format!("{:>3} {}{} {:?}", b.to_num(), curr, modified, b.to_id());
+                (b.to_num(), line)
+            })
+            .collect()
+        };
+        items.sort_by_key(|(num, _)| *num);
+
+        let lines: Vec<String> = items.into_iter().map(|(_, line)| line).collect();
+        let wless = {
+            let coord = app.to_coord_wless(lines.len());
+            let content = lines.join("\n");
+            WindowLess::new("buffers", &content, coord, app.to_color_scheme(None))?
+        };
+        Ok(Event::Appn(event::Appn::Less(Box::new(wless))))
+    }
+}
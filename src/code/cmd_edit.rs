@@ -1,19 +1,77 @@
-#[derive(Clone)]
-pub enum Edit {
-    Initial {
-        name: String,
-    },
-    TabComp {
-        name: String,
-        choices: Vec<String>,
-        choice: usize,
-    },
+#[allow(unused_imports)]
+use log::{debug, error, trace};
+
+use std::{ffi, path};
+
+use crate::{
+    buffer::Buffer,
+    code::cmd::Command,
+    code::Code,
+    event::{self, Event},
+    location::Location,
+    syntax, Error, Result,
+};
+
+/// Edit command, `:e[dit][!] {path}`, open file `path` in the focused
+/// window. If `path` does not exist, an empty buffer is bound to it, so
+/// that a subsequent write creates the file.
+///
+/// Refuse to switch away from a modified buffer, unless `!` is used, in
+/// which case the modified buffer is retained in the background.
+pub struct Edit {
+    path: Option<String>,
+    force: bool,
+}
+
+impl Edit {
+    pub fn new(syn: syntax::CodeCmd) -> Result<Self> {
+        let args = syn.to_command_args().unwrap_or_default();
+        let (force, path) = match args.strip_prefix('!') {
+            Some(path) => (true, path.trim()),
+            None => (false, args.trim()),
+        };
+        let path = if_else!(path.is_empty(), None, Some(path.to_string()));
+        Ok(Edit { path, force })
+    }
 }
 
-impl Default for Edit {
-    fn default() -> Edit {
-        Edit::Initial {
-            name: "edit".to_string(),
+impl Command for Edit {
+    fn on_command(&mut self, app: &mut Code) -> Result<Event> {
+        let path: ffi::OsString = match self.path.as_ref() {
+            Some(path) => path.into(),
+            None => err_at!(BadCommand, msg: format!("edit missing file name"))?,
+        };
+
+        let modified = match app.as_mut_current_buffer() {
+            Some(buf) => buf.is_modified(),
+            None => false,
+        };
+        if modified && !self.force {
+            err_at!(Invalid, msg: format!("no write since last change (add ! to override)"))?
         }
+
+        let loc = Location::new_disk_file(&path, "utf-8")?;
+        let id = loc.to_string();
+        let is_open = app.as_buffer(&id).is_ok();
+        debug!("edit {:?} is_open:{}", id, is_open);
+
+        if !is_open {
+            let exists = match &loc {
+                Location::Disk { path_file, .. } => path::Path::new(path_file).exists(),
+                _ => false,
+            };
+            let read_only = loc.is_read_only();
+            let mut buf = if exists {
+                Buffer::from_reader(loc)?
+            } else {
+                let mut buf = Buffer::empty();
+                buf.location = loc;
+                buf
+            };
+            app.config_buffer(&mut buf, read_only || app.config.read_only);
+            app.add_buffer(buf);
+        }
+
+        Ok(Event::Appn(event::Appn::Buffer(id)))
     }
 }
@@ -0,0 +1,31 @@
+#[allow(unused_imports)]
+use log::{debug, error, trace};
+
+use crate::{code::cmd::Command, code::Code, event::Event, syntax, Error, Result};
+
+/// Goto command, `:go[to] [count]`, move the cursor to byte `count` in the
+/// buffer under edit, first byte being 1. Default is the first byte.
+pub struct Goto {
+    count: usize,
+}
+
+impl Goto {
+    pub fn new(syn: syntax::CodeCmd) -> Result<Self> {
+        let args = syn.to_command_args().unwrap_or_default();
+        let count = match args.trim() {
+            "" => 1,
+            arg => err_at!(BadCommand, arg.parse::<usize>(), format!("goto {:?}", arg))?,
+        };
+        Ok(Goto { count })
+    }
+}
+
+impl Command for Goto {
+    fn on_command(&mut self, app: &mut Code) -> Result<Event> {
+        if let Some(buf) = app.as_mut_current_buffer() {
+            let cursor = buf.goto_offset(self.count.saturating_sub(1));
+            debug!("goto {} cursor:{}", self.count, cursor);
+        }
+        Ok(Event::Noop)
+    }
+}
@@ -0,0 +1,71 @@
+#[allow(unused_imports)]
+use log::{debug, error, trace};
+
+use std::convert::TryFrom;
+
+use crate::{
+    code::cmd::Command,
+    code::Code,
+    colors::{self, ColorScheme},
+    event::{self, Event},
+    syntax,
+    term::Style,
+    Error, Result,
+};
+
+/// Highlight command, `:hi[ghlight] {name} [fg={color}] [bg={color}]
+/// [attr={attrs}]`, override the style for highlight `name` in the color
+/// scheme in use. Refer [Style::to_color] and [Style::to_attrs] for
+/// color and attribute values.
+pub struct Highlight {
+    args: String,
+}
+
+impl Highlight {
+    pub fn new(syn: syntax::CodeCmd) -> Result<Self> {
+        let args = syn.to_command_args().unwrap_or_default();
+        Ok(Highlight { args })
+    }
+
+    fn to_style(&self, scheme: &ColorScheme) -> Result<(colors::Highlight, Style)> {
+        let mut words = self.args.split_whitespace();
+        let hl: colors::Highlight = match words.next() {
+            Some(name) => TryFrom::try_from(name)?,
+            None => err_at!(BadCommand, msg: format!("highlight missing name"))?,
+        };
+
+        let mut style = scheme.to_style(hl);
+        for word in words {
+            let (key, val) = match word.find('=') {
+                Some(off) => (&word[..off], &word[off + 1..]),
+                None => err_at!(BadCommand, msg: format!("highlight argument {:?}", word))?,
+            };
+            let color = || Style::to_color(&toml::Value::String(val.to_string()));
+            match key {
+                "fg" => style.fg = Some(color()?),
+                "bg" => style.bg = Some(color()?),
+                "attr" | "attribute" => style.attrs = Style::to_attrs(val)?,
+                key => err_at!(BadCommand, msg: format!("highlight argument {:?}", key))?,
+            }
+        }
+
+        Ok((hl, style))
+    }
+}
+
+impl Command for Highlight {
+    fn on_command(&mut self, app: &mut Code) -> Result<Event> {
+        let (hl, style) = self.to_style(&app.to_color_scheme(None))?;
+        debug!("highlight {} {}", hl, style);
+
+        match app.as_mut_color_scheme() {
+            Some(scheme) => scheme.set_style(hl, style),
+            None => err_at!(Invalid, msg: format!("no color scheme"))?,
+        };
+        Ok(Event::Appn(event::Appn::ColorScheme))
+    }
+}
+
+#[cfg(test)]
+#[path = "cmd_highlight_test.rs"]
+mod cmd_highlight_test;
@@ -0,0 +1,40 @@
+use super::*;
+
+use crate::term::{Attribute, Color};
+
+#[test]
+fn test_to_style() {
+    let scheme = ColorScheme::default().unwrap();
+    let to_style = |args: &str| {
+        let val = Highlight {
+            args: args.to_string(),
+        };
+        val.to_style(&scheme)
+    };
+
+    let (hl, style) = to_style(" string fg=#ff8800 bg=244 attr=bold|underline").unwrap();
+    assert_eq!(hl.to_string(), "string");
+    assert!(
+        style.fg
+            == Some(Color::Rgb {
+                r: 255,
+                g: 136,
+                b: 0
+            })
+    );
+    assert!(style.bg == Some(Color::AnsiValue(244)));
+    assert!(style.attrs == vec![Attribute::Bold, Attribute::Underlined]);
+
+    // styles not mentioned are retained.
+    let old = scheme.to_style(colors::Highlight::Comment);
+    let (_, style) = to_style("comment fg=red").unwrap();
+    assert!(style.fg == Some(Color::Red));
+    assert!(style.bg == old.bg);
+    assert!(style.attrs == old.attrs);
+
+    assert!(to_style("").is_err());
+    assert!(to_style("no-such-highlight fg=red").is_err());
+    assert!(to_style("comment fg").is_err());
+    assert!(to_style("comment xy=red").is_err());
+    assert!(to_style("comment fg=no-such-color").is_err());
+}
@@ -0,0 +1,124 @@
+#[allow(unused_imports)]
+use log::{debug, error, trace};
+
+use crate::{
+    app::Application,
+    buffer::Buffer,
+    code::cmd::Command,
+    code::{quickfix, Code},
+    event::{self, Event},
+    pubsub::Notify,
+    syntax,
+    window::WindowLess,
+    Error, Result,
+};
+
+/// Copen command, `:cope[n]`, list quickfix entries, refer
+/// [quickfix::QuickfixList], entry visited last is marked with `>`.
+pub struct Copen;
+
+impl Copen {
+    pub fn new(syn: syntax::CodeCmd) -> Result<Self> {
+        match syn.to_command_args().unwrap_or_default().trim() {
+            "" => Ok(Copen),
+            args => err_at!(BadCommand, msg: format!("copen {:?}", args)),
+        }
+    }
+}
+
+impl Command for Copen {
+    fn on_command(&mut self, app: &mut Code) -> Result<Event> {
+        let lines = app.as_quickfix().to_lines();
+        if lines.is_empty() {
+            let span = "no quickfix list".to_string();
+            app.notify("code", Notify::Status(vec![span.into()]))?;
+            return Ok(Event::Noop);
+        }
+        let wless = {
+            let coord = app.to_coord_wless(lines.len());
+            let content = lines.join("\n");
+            WindowLess::new("quickfix", &content, coord, app.to_color_scheme(None))?
+        };
+        Ok(Event::Appn(event::Appn::Less(Box::new(wless))))
+    }
+}
+
+/// Cnext command, `:cn[ext]`, jump to the next quickfix entry, opening
+/// its file if not already open.
+pub struct Cnext;
+
+impl Cnext {
+    pub fn new(syn: syntax::CodeCmd) -> Result<Self> {
+        match syn.to_command_args().unwrap_or_default().trim() {
+            "" => Ok(Cnext),
+            args => err_at!(BadCommand, msg: format!("cnext {:?}", args)),
+        }
+    }
+}
+
+impl Command for Cnext {
+    fn on_command(&mut self, app: &mut Code) -> Result<Event> {
+        let entry = app.as_mut_quickfix().next_entry();
+        goto_entry(app, entry)
+    }
+}
+
+/// Cprev command, `:cp[rev]`, jump to the previous quickfix entry,
+/// opening its file if not already open.
+pub struct Cprev;
+
+impl Cprev {
+    pub fn new(syn: syntax::CodeCmd) -> Result<Self> {
+        match syn.to_command_args().unwrap_or_default().trim() {
+            "" => Ok(Cprev),
+            args => err_at!(BadCommand, msg: format!("cprev {:?}", args)),
+        }
+    }
+}
+
+impl Command for Cprev {
+    fn on_command(&mut self, app: &mut Code) -> Result<Event> {
+        let entry = app.as_mut_quickfix().prev_entry();
+        goto_entry(app, entry)
+    }
+}
+
+// set the cursor on entry's line and column, in its buffer, and switch the
+// focused window to that buffer. Entry's message is shown in status line.
+fn goto_entry(app: &mut Code, entry: Option<quickfix::Entry>) -> Result<Event> {
+    let (loc, line, col, msg) = match entry {
+        Some(entry) => entry,
+        None => {
+            let span = "no more items".to_string();
+            app.notify("code", Notify::Status(vec![span.into()]))?;
+            return Ok(Event::Noop);
+        }
+    };
+
+    let id = {
+        let name = loc.to_string();
+        let mut iter = app.as_buffers().iter();
+        iter.find(|b| b.to_location().to_string() == name)
+            .map(|b| b.to_id())
+    };
+    let id = match id {
+        Some(id) => id,
+        None => {
+            let read_only = loc.is_read_only();
+            let mut buf = Buffer::from_reader(loc)?;
+            app.config_buffer(&mut buf, read_only || app.config.read_only);
+            let id = buf.to_id();
+            app.add_buffer(buf);
+            id
+        }
+    };
+    let cursor = app.as_mut_buffer(&id)?.goto_line_col(line, col);
+    debug!("quickfix {:?} {}:{} cursor:{}", id, line, col, cursor);
+
+    let span = {
+        let (off, n) = (app.as_quickfix().to_current(), app.as_quickfix().len());
+        format!("({} of {}) {}", off.map(|off| off + 1).unwrap_or(0), n, msg)
+    };
+    app.notify("code", Notify::Status(vec![span.into()]))?;
+    Ok(Event::Appn(event::Appn::Buffer(id)))
+}
@@ -0,0 +1,31 @@
+#[allow(unused_imports)]
+use log::{debug, error, trace};
+
+use crate::{code::cmd::Command, code::Code, event::Event, syntax, Error, Result};
+
+/// Quit command, `:q[uit][!]`, quit the application. Refuse to quit if
+/// the buffer under edit is modified, unless `!` is used.
+pub struct Quit {
+    force: bool,
+}
+
+impl Quit {
+    pub fn new(syn: syntax::CodeCmd) -> Result<Self> {
+        let args = syn.to_command_args().unwrap_or_default();
+        let force = args.trim() == "!";
+        Ok(Quit { force })
+    }
+}
+
+impl Command for Quit {
+    fn on_command(&mut self, app: &mut Code) -> Result<Event> {
+        let modified = match app.as_mut_current_buffer() {
+            Some(buf) => buf.is_modified(),
+            None => false,
+        };
+        if modified && !self.force {
+            err_at!(Invalid, msg: format!("no write since last change (add ! to override)"))?
+        }
+        Ok(Event::Quit)
+    }
+}
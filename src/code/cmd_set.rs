@@ -1,72 +1,35 @@
 #[allow(unused_imports)]
 use log::{debug, error, trace};
 
-use crate::{code::cmd::Command, code::Code, event::Event, syntax, Result};
-
+use crate::{
+    code::{cmd::Command, config, Code},
+    event::{self, Event},
+    syntax, Result,
+};
+
+/// Set command, `:se[t] {option}`, `:set no{option}` and
+/// `:set {option}={value}`, change configuration of the running
+/// application. Several options can be set in one go, separated by
+/// whitespace, refer [config::parse_options] for option names.
 pub struct Set {
-    #[allow(dead_code)]
-    inner: Inner,
-}
-
-enum Inner {
-    #[allow(dead_code)]
-    Config {
-        param: String,
-    },
-    None,
+    args: String,
 }
 
 impl Set {
     pub fn new(syn: syntax::CodeCmd) -> Result<Self> {
-        match Self::convert(syn) {
-            Some(inner) => Ok(Set { inner }),
-            None => Ok(Set { inner: Inner::None }),
-        }
-    }
-
-    fn convert(syn: syntax::CodeCmd) -> Option<Inner> {
-        let tree = syn.into_parse_tree()?;
-
-        let node_cmd = {
-            let root = tree.root_node();
-            root.child(root.child_count().saturating_sub(1))?
-        };
-
-        let node_set = node_cmd.child(0)?;
-        assert_eq!(node_set.kind(), "set", "{}", node_set.kind());
-
-        let node_config_param = node_set.child(1)?;
-        let param = node_config_param.child(0)?.kind().to_string();
-        Some(Inner::Config { param })
+        let args = syn.to_command_args().unwrap_or_default();
+        Ok(Set { args })
     }
 }
 
 impl Command for Set {
-    fn on_command(&mut self, _app: &mut Code) -> Result<Event> {
-        // use crate::code::config::Config
-        //let config: &mut Config = app.as_mut();
-        //match self.args.as_str() {
-        //    "wrap" => {
-        //        config.wrap = true;
-        //        debug!("set all windows to wrap text");
-        //    }
-        //    "nowrap" => {
-        //        config.wrap = false;
-        //        debug!("set all windows to non-wrap text");
-        //    }
-        //    "ro" => {
-        //        config.read_only = true;
-        //        debug!("set default file open to read-only mode");
-        //    }
-        //    "noro" => {
-        //        config.read_only = false;
-        //        debug!("set default file open to read-write mode");
-        //    }
-        //    _ => {
-        //        error!("invalid configuration command {}", self.args);
-        //    }
-        //}
-
-        Ok(Event::Noop)
+    fn on_command(&mut self, app: &mut Code) -> Result<Event> {
+        // config is left untouched if any of the options is invalid.
+        let ctml = config::parse_options(&self.args)?;
+        debug!("set {}", self.args.trim());
+
+        let config = app.as_config().clone().mixin(ctml);
+        app.set_config(config);
+        Ok(Event::Appn(event::Appn::Config))
     }
 }
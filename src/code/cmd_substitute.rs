@@ -0,0 +1,178 @@
+#[allow(unused_imports)]
+use log::{debug, error, trace};
+use regex::{Regex, RegexBuilder};
+
+use crate::{
+    buffer::Buffer, code::cmd::Command, code::Code, event::Event, syntax, window::WinBuffer, Error,
+    Result,
+};
+
+/// Substitute command, `:[range]s[ubstitute]/{pattern}/{string}/[flags]`.
+///
+/// Supported flags are `g` to replace all matches in a line, `i` and `I`
+/// to ignore and respect case, respectively, while matching the pattern.
+pub struct Substitute {
+    range: Option<String>,
+    patt: Regex,
+    rep: String,
+    global: bool,
+}
+
+impl Substitute {
+    pub fn new(syn: syntax::CodeCmd) -> Result<Self> {
+        let range = syn.to_range();
+        let args = syn.to_command_args().unwrap_or_default();
+        let (patt, rep, flags) = parse_args(args.trim())?;
+
+        let mut global = false;
+        let mut builder = RegexBuilder::new(&patt);
+        for ch in flags.chars() {
+            match ch {
+                'g' => global = true,
+                'i' => {
+                    builder.case_insensitive(true);
+                }
+                'I' => {
+                    builder.case_insensitive(false);
+                }
+                ch => err_at!(BadCommand, msg: format!("substitute flag {:?}", ch))?,
+            }
+        }
+        let patt = err_at!(BadPattern, builder.build(), patt)?;
+
+        Ok(Substitute {
+            range,
+            patt,
+            rep: to_replacement(&rep),
+            global,
+        })
+    }
+}
+
+impl Command for Substitute {
+    fn on_command(&mut self, app: &mut Code) -> Result<Event> {
+        let buf = match app.as_mut_current_buffer() {
+            Some(buf) => buf,
+            None => return Ok(Event::Noop),
+        };
+        let (from, to) = to_line_range(buf, self.range.as_deref())?;
+        debug!("substitute {}..{} {:?} {:?}", from, to, self.patt, self.rep);
+        buf.substitute(from, to, &self.patt, &self.rep, self.global)
+    }
+}
+
+// Split `/pattern/string/flags` into its parts. The first character is
+// the delimiter and can be escaped within the pattern and string.
+fn parse_args(args: &str) -> Result<(String, String, String)> {
+    let mut chars = args.chars();
+    let delim = match chars.next() {
+        Some(ch) if !ch.is_alphanumeric() && ch != '\\' && ch != '"' => ch,
+        Some(ch) => err_at!(BadCommand, msg: format!("substitute delimiter {:?}", ch))?,
+        None => err_at!(BadCommand, msg: format!("substitute missing pattern"))?,
+    };
+
+    let mut parts: Vec<String> = vec![String::default()];
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' if parts.len() < 3 => match chars.next() {
+                Some(ch) if ch == delim => parts.last_mut().unwrap().push(ch),
+                Some(ch) => {
+                    let part = parts.last_mut().unwrap();
+                    part.push('\\');
+                    part.push(ch);
+                }
+                None => parts.last_mut().unwrap().push('\\'),
+            },
+            ch if ch == delim && parts.len() < 3 => parts.push(String::default()),
+            ch => parts.last_mut().unwrap().push(ch),
+        }
+    }
+
+    let mut iter = parts.into_iter();
+    let patt = iter.next().unwrap_or_default();
+    let rep = iter.next().unwrap_or_default();
+    let flags = iter.next().unwrap_or_default();
+    if patt.is_empty() {
+        err_at!(BadCommand, msg: format!("substitute empty pattern"))?
+    }
+    Ok((patt, rep, flags))
+}
+
+// Convert vim style replacement string to regex style replacement string.
+// `&` and `\0` refer to the whole match, `\1`..`\9` refer to captured
+// groups, and `\&` is a literal `&`.
+fn to_replacement(rep: &str) -> String {
+    let mut out = String::default();
+    let mut chars = rep.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '&' => out.push_str("${0}"),
+            '$' => out.push_str("$$"),
+            '\\' => match chars.next() {
+                Some(ch) if ch.is_ascii_digit() => out.push_str(&format!("${{{}}}", ch)),
+                Some('n') | Some('r') => out.push('\n'),
+                Some('t') => out.push('\t'),
+                Some(ch) => out.push(ch),
+                None => out.push('\\'),
+            },
+            ch => out.push(ch),
+        }
+    }
+    out
+}
+
+// Convert range, like `%`, `.`, `$`, `n`, `a,b`, into line-index range
+// [from, to). Line numbers in range start from 1, and the current line
+// is used when range is not specified.
+fn to_line_range(buf: &Buffer, range: Option<&str>) -> Result<(usize, usize)> {
+    let row = buf.to_xy_cursor(None).row;
+    let last = buf.to_last_line_idx();
+
+    let (from, to) = match range.map(|r| r.trim()) {
+        None | Some("") => (row, row),
+        Some("%") => (0, last),
+        Some(range) => match range.split_once(',') {
+            Some((a, b)) => (to_address(a, row, last)?, to_address(b, row, last)?),
+            None => {
+                let a = to_address(range, row, last)?;
+                (a, a)
+            }
+        },
+    };
+
+    match from <= to {
+        true => Ok((from, to + 1)),
+        false => Ok((to, from + 1)),
+    }
+}
+
+// Convert a single address with optional `+n` or `-n` offset into
+// line-index.
+fn to_address(addr: &str, row: usize, last: usize) -> Result<usize> {
+    let addr = addr.trim();
+    let n = addr.find(&['+', '-'][..]).unwrap_or(addr.len());
+    let (base, off) = addr.split_at(n);
+
+    let base = match base {
+        "" | "." => row,
+        "$" => last,
+        base => match base.parse::<usize>() {
+            Ok(n) => n.saturating_sub(1),
+            Err(_) => err_at!(BadCommand, msg: format!("range address {:?}", base))?,
+        },
+    };
+    let addr = match off.split_at(if_else!(off.is_empty(), 0, 1)) {
+        ("", _) => base,
+        (sign, "") => if_else!(sign == "+", base + 1, base.saturating_sub(1)),
+        (sign, n) => {
+            let n = err_at!(BadCommand, n.parse::<usize>(), format!("offset {:?}", n))?;
+            if_else!(sign == "+", base + n, base.saturating_sub(n))
+        }
+    };
+
+    Ok(std::cmp::min(addr, last))
+}
+
+#[cfg(test)]
+#[path = "cmd_substitute_test.rs"]
+mod cmd_substitute_test;
@@ -0,0 +1,47 @@
+use super::*;
+
+#[test]
+fn test_parse_args() {
+    let (p, r, f) = parse_args("/foo/bar/g").unwrap();
+    assert_eq!((p.as_str(), r.as_str(), f.as_str()), ("foo", "bar", "g"));
+
+    let (p, r, f) = parse_args("#a/b#c\\#d#").unwrap();
+    assert_eq!((p.as_str(), r.as_str(), f.as_str()), ("a/b", "c#d", ""));
+
+    let (p, r, f) = parse_args("/a\\(b\\)/").unwrap();
+    assert_eq!((p.as_str(), r.as_str(), f.as_str()), ("a\\(b\\)", "", ""));
+
+    let (p, r, f) = parse_args("/foo").unwrap();
+    assert_eq!((p.as_str(), r.as_str(), f.as_str()), ("foo", "", ""));
+
+    assert!(parse_args("").is_err());
+    assert!(parse_args("//bar/").is_err());
+    assert!(parse_args("afoo").is_err());
+    // malformed commands are reported as BadCommand.
+    assert!(matches!(parse_args("afoo"), Err(Error::BadCommand(_, _))));
+}
+
+#[test]
+fn test_to_replacement() {
+    assert_eq!(to_replacement("bar"), "bar");
+    assert_eq!(to_replacement("<&>"), "<${0}>");
+    assert_eq!(to_replacement("\\2-\\1"), "${2}-${1}");
+    assert_eq!(to_replacement("\\&$"), "&$$");
+}
+
+#[test]
+fn test_to_address() {
+    assert_eq!(to_address(".", 3, 10).unwrap(), 3);
+    assert_eq!(to_address("$", 3, 10).unwrap(), 10);
+    assert_eq!(to_address("1", 3, 10).unwrap(), 0);
+    assert_eq!(to_address("5", 3, 10).unwrap(), 4);
+    assert_eq!(to_address(".+2", 3, 10).unwrap(), 5);
+    assert_eq!(to_address(".-", 3, 10).unwrap(), 2);
+    assert_eq!(to_address("$-1", 3, 10).unwrap(), 9);
+    assert_eq!(to_address("100", 3, 10).unwrap(), 10);
+    assert!(to_address("'a", 3, 10).is_err());
+    assert!(matches!(
+        to_address("x", 3, 10),
+        Err(Error::BadCommand(_, _))
+    ));
+}
@@ -0,0 +1,266 @@
+use super::*;
+
+use std::{env, fs, sync::mpsc};
+
+use crate::{
+    app::Application,
+    code::code_test::{new_buffer, new_code},
+    event,
+};
+
+#[test]
+fn test_cmd_substitute() {
+    let scheme = ColorScheme::default().unwrap();
+    let mut app = new_code();
+    app.add_buffer(new_buffer("test-cmd-substitute"));
+
+    let mut cmd = Cmd::try_from((":s/o/0/".to_string(), scheme.clone())).unwrap();
+    assert!(matches!(cmd, Cmd::Substitute(_)));
+    cmd.on_command(&mut app).unwrap();
+    let buf = app.as_mut_current_buffer().unwrap();
+    assert_eq!(buf.to_string(), "hell0 world\n");
+
+    let mut cmd = Cmd::try_from((":%s/o/0/g".to_string(), scheme.clone())).unwrap();
+    cmd.on_command(&mut app).unwrap();
+    let buf = app.as_mut_current_buffer().unwrap();
+    assert_eq!(buf.to_string(), "hell0 w0rld\n");
+
+    // lines without a command are reported as BadCommand.
+    let res = Cmd::try_from((":xyz".to_string(), scheme));
+    assert!(matches!(res, Err(Error::BadCommand(_, _))));
+}
+
+#[test]
+fn test_cmd_buffers() {
+    let scheme = ColorScheme::default().unwrap();
+    let mut app = new_code();
+    let (one, two) = (new_buffer("test-cmd-one"), new_buffer("test-cmd-two"));
+    let (num, id) = (one.to_num(), one.to_id());
+    app.add_buffer(one);
+    app.add_buffer(two);
+
+    for line in &[":ls", ":buffers"] {
+        let cmd = Cmd::try_from((line.to_string(), scheme.clone())).unwrap();
+        assert!(matches!(cmd, Cmd::Buffers(_)), "{}", line);
+    }
+    for line in &[format!(":b {}", num), format!(":buffer {}", num)] {
+        let mut cmd = Cmd::try_from((line.to_string(), scheme.clone())).unwrap();
+        let evnt = cmd.on_command(&mut app).unwrap();
+        assert!(
+            evnt == Event::Appn(event::Appn::Buffer(id.clone())),
+            "{}",
+            line
+        );
+    }
+}
+
+#[test]
+fn test_cmd_write() {
+    let scheme = ColorScheme::default().unwrap();
+    let mut app = new_code();
+    let (tx, _rx) = mpsc::channel();
+    app.subscribe("code", tx);
+    app.add_buffer(new_buffer("test-cmd-write"));
+
+    let path = env::temp_dir().join("test-cmd-write.txt");
+    let line = format!(":write {}", path.to_str().unwrap());
+    let mut cmd = Cmd::try_from((line, scheme.clone())).unwrap();
+    assert!(matches!(cmd, Cmd::Write(_)));
+    cmd.on_command(&mut app).unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "hello world\n");
+
+    // buffer is now bound to path, write it back without arguments.
+    let mut cmd = Cmd::try_from((":w".to_string(), scheme)).unwrap();
+    assert!(matches!(cmd, Cmd::Write(_)));
+    cmd.on_command(&mut app).unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "hello world\n");
+
+    fs::remove_file(&path).ok();
+}
+
+#[test]
+fn test_cmd_edit() {
+    let scheme = ColorScheme::default().unwrap();
+    let mut app = new_code();
+    let mut buf = new_buffer("test-cmd-edit");
+    buf.on_event(Event::Wr(event::Cud::Delete(6))).unwrap();
+    app.add_buffer(buf);
+
+    let path = env::temp_dir().join("test-cmd-edit.txt");
+    let path = path.to_str().unwrap();
+
+    // modified buffer is not switched away from, unless forced.
+    let mut cmd = Cmd::try_from((format!(":e {}", path), scheme.clone())).unwrap();
+    assert!(matches!(cmd, Cmd::Edit(_)));
+    assert!(cmd.on_command(&mut app).is_err());
+
+    let mut cmd = Cmd::try_from((format!(":edit! {}", path), scheme)).unwrap();
+    assert!(matches!(cmd, Cmd::Edit(_)));
+    match cmd.on_command(&mut app).unwrap() {
+        Event::Appn(event::Appn::Buffer(id)) => assert!(app.as_buffer(&id).is_ok()),
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn test_cmd_quit() {
+    let scheme = ColorScheme::default().unwrap();
+    let mut app = new_code();
+    let mut buf = new_buffer("test-cmd-quit");
+    buf.on_event(Event::Wr(event::Cud::Delete(6))).unwrap();
+    app.add_buffer(buf);
+
+    // modified buffer refuses to quit, unless forced.
+    for line in &[":q", ":quit"] {
+        let mut cmd = Cmd::try_from((line.to_string(), scheme.clone())).unwrap();
+        assert!(matches!(cmd, Cmd::Quit(_)), "{}", line);
+        assert!(cmd.on_command(&mut app).is_err(), "{}", line);
+    }
+    for line in &[":q!", ":quit!"] {
+        let mut cmd = Cmd::try_from((line.to_string(), scheme.clone())).unwrap();
+        assert!(matches!(cmd, Cmd::Quit(_)), "{}", line);
+        assert!(cmd.on_command(&mut app).unwrap() == Event::Quit, "{}", line);
+    }
+
+    let cmd = Cmd::try_from((":wq".to_string(), scheme)).unwrap();
+    assert!(matches!(cmd, Cmd::WriteQuit(_)));
+}
+
+#[test]
+fn test_cmd_highlight() {
+    use crate::{colors::Highlight, term::Color};
+
+    let scheme = ColorScheme::default().unwrap();
+    let mut app = new_code();
+    app.schemes.push(scheme.clone());
+
+    let line = ":hi comment fg=red".to_string();
+    let mut cmd = Cmd::try_from((line, scheme.clone())).unwrap();
+    assert!(matches!(cmd, Cmd::Highlight(_)));
+    let evnt = cmd.on_command(&mut app).unwrap();
+    assert!(evnt == Event::Appn(event::Appn::ColorScheme));
+    let style = app.to_color_scheme(None).to_style(Highlight::Comment);
+    assert!(style.fg == Some(Color::Red));
+
+    let line = ":highlight string bg=blue".to_string();
+    let mut cmd = Cmd::try_from((line, scheme)).unwrap();
+    assert!(matches!(cmd, Cmd::Highlight(_)));
+    cmd.on_command(&mut app).unwrap();
+    let style = app.to_color_scheme(None).to_style(Highlight::Str);
+    assert!(style.bg == Some(Color::Blue));
+}
+
+#[test]
+fn test_cmd_goto() {
+    use crate::window::WinBuffer;
+
+    let scheme = ColorScheme::default().unwrap();
+    let mut app = new_code();
+    app.add_buffer(new_buffer("test-cmd-goto"));
+
+    let testcases = vec![(":goto 7", 6), (":go 5", 4), (":go", 0), (":goto 100", 10)];
+    for (line, cursor) in testcases.into_iter() {
+        let mut cmd = Cmd::try_from((line.to_string(), scheme.clone())).unwrap();
+        assert!(matches!(cmd, Cmd::Goto(_)), "{}", line);
+        cmd.on_command(&mut app).unwrap();
+        let buf = app.as_mut_current_buffer().unwrap();
+        assert_eq!(buf.to_char_cursor(), cursor, "{}", line);
+    }
+
+    let res = Cmd::try_from((":goto xyz".to_string(), scheme));
+    assert!(matches!(res, Err(Error::BadCommand(_, _))));
+}
+
+#[test]
+fn test_cmd_set() {
+    let scheme = ColorScheme::default().unwrap();
+    let mut app = new_code();
+    assert!(app.as_config().line_number);
+
+    let mut cmd = Cmd::try_from((":set sw=2 nonu".to_string(), scheme.clone())).unwrap();
+    assert!(matches!(cmd, Cmd::Set(_)));
+    let evnt = cmd.on_command(&mut app).unwrap();
+    assert!(evnt == Event::Appn(event::Appn::Config));
+    assert_eq!(app.as_config().shift_width, 2);
+    assert!(!app.as_config().line_number);
+
+    let mut cmd = Cmd::try_from((":se number".to_string(), scheme.clone())).unwrap();
+    assert!(matches!(cmd, Cmd::Set(_)));
+    cmd.on_command(&mut app).unwrap();
+    assert!(app.as_config().line_number);
+
+    // config is left untouched if any of the options is invalid.
+    let mut cmd = Cmd::try_from((":set sw=8 no-such-option".to_string(), scheme)).unwrap();
+    assert!(cmd.on_command(&mut app).is_err());
+    assert_eq!(app.as_config().shift_width, 2);
+}
+
+#[test]
+fn test_cmd_strip_whitespace() {
+    let scheme = ColorScheme::default().unwrap();
+    let mut app = new_code();
+    let mut buf = new_buffer("test-cmd-strip-whitespace");
+    buf.cud_str(Some(5), " \t").unwrap();
+    buf.cud_str(Some(13), "  ").unwrap();
+    assert_eq!(buf.to_string(), "hello \t world  \n");
+    app.add_buffer(buf);
+
+    let mut cmd = Cmd::try_from((":StripWhitespace".to_string(), scheme)).unwrap();
+    assert!(matches!(cmd, Cmd::StripWhitespace(_)));
+    cmd.on_command(&mut app).unwrap();
+    let buf = app.as_mut_current_buffer().unwrap();
+    assert_eq!(buf.to_string(), "hello \t world\n");
+}
+
+#[test]
+fn test_cmd_quickfix() {
+    let scheme = ColorScheme::default().unwrap();
+    let mut app = new_code();
+    let (tx, _rx) = mpsc::channel();
+    app.subscribe("code", tx);
+    app.schemes.push(scheme.clone());
+
+    let buf = new_buffer("test-cmd-quickfix");
+    let (loc, id) = (buf.to_location(), buf.to_id());
+    app.add_buffer(buf);
+    app.as_mut_quickfix()
+        .add_entry(loc.clone(), 1, 1, "first")
+        .add_entry(loc, 1, 7, "second");
+
+    for line in &[":copen", ":cope"] {
+        let mut cmd = Cmd::try_from((line.to_string(), scheme.clone())).unwrap();
+        assert!(matches!(cmd, Cmd::Copen(_)), "{}", line);
+        let evnt = cmd.on_command(&mut app).unwrap();
+        assert!(
+            matches!(evnt, Event::Appn(event::Appn::Less(_))),
+            "{}",
+            line
+        );
+    }
+
+    let testcases = vec![
+        (":cnext", 0),
+        (":cn", 1),
+        (":cprev", 0),
+        (":cnext", 1),
+        (":cN", 0),
+        (":cn", 1),
+        (":cNext", 0),
+        (":cn", 1),
+        (":cp", 0),
+    ];
+    for (line, current) in testcases.into_iter() {
+        let mut cmd = Cmd::try_from((line.to_string(), scheme.clone())).unwrap();
+        assert!(matches!(cmd, Cmd::Cnext(_) | Cmd::Cprev(_)), "{}", line);
+        let evnt = cmd.on_command(&mut app).unwrap();
+        assert!(
+            evnt == Event::Appn(event::Appn::Buffer(id.clone())),
+            "{}",
+            line
+        );
+        assert_eq!(app.as_quickfix().to_current(), Some(current), "{}", line);
+    }
+
+    let res = Cmd::try_from((":copen xyz".to_string(), scheme));
+    assert!(matches!(res, Err(Error::BadCommand(_, _))));
+}
@@ -0,0 +1,30 @@
+#[allow(unused_imports)]
+use log::{debug, error, trace};
+
+use crate::{code::cmd::Command, code::Code, event::Event, syntax, Error, Result};
+
+/// StripWhitespace command, `:StripWhitespace`, remove trailing spaces and
+/// tabs from all lines in the buffer under edit. Can be undone as a single
+/// change.
+pub struct StripWhitespace;
+
+impl StripWhitespace {
+    pub fn new(syn: syntax::CodeCmd) -> Result<Self> {
+        match syn.to_command_args().unwrap_or_default().trim() {
+            "" => Ok(StripWhitespace),
+            args => err_at!(BadCommand, msg: format!("StripWhitespace {:?}", args)),
+        }
+    }
+}
+
+impl Command for StripWhitespace {
+    fn on_command(&mut self, app: &mut Code) -> Result<Event> {
+        match app.as_mut_current_buffer() {
+            Some(buf) => {
+                debug!("strip whitespace {}", buf.to_id());
+                buf.strip_whitespace()
+            }
+            None => Ok(Event::Noop),
+        }
+    }
+}
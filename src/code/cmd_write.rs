@@ -1,19 +1,113 @@
-#[derive(Clone)]
-pub enum Write {
-    Initial {
-        name: String,
-    },
-    TabComp {
-        name: String,
-        choices: Vec<String>,
-        choice: usize,
-    },
+#[allow(unused_imports)]
+use log::{debug, error, trace};
+
+use std::{ffi, mem};
+
+use crate::{
+    app::Application, code::cmd::Command, code::Code, event, event::Event, location::Location,
+    pubsub::Notify, syntax, Error, Result,
+};
+
+/// Write command, `:w[rite] [path]`, write the buffer under edit to its
+/// file. If `path` is supplied, write buffer's content to `path` instead.
+/// Buffers that are not backed by a file, like content piped via stdin,
+/// prompt for a `path`, and are bound to that file once written.
+pub struct Write {
+    path: Option<String>,
 }
 
-impl Default for Write {
-    fn default() -> Write {
-        Write::Initial {
-            name: "write".to_string(),
+impl Write {
+    pub fn new(syn: syntax::CodeCmd) -> Result<Self> {
+        let path = match syn.to_command_args() {
+            Some(args) if !args.trim().is_empty() => Some(args.trim().to_string()),
+            _ => None,
+        };
+        Ok(Write { path })
+    }
+}
+
+impl Command for Write {
+    fn on_command(&mut self, app: &mut Code) -> Result<Event> {
+        // buffer-id prior to binding the buffer with the written file.
+        let mut old_id = None;
+        let (id, n) = {
+            let buf = match app.as_mut_current_buffer() {
+                Some(buf) => buf,
+                None => err_at!(Fatal, msg: format!("no buffer to write"))?,
+            };
+            if buf.is_read_only() {
+                err_at!(IOError, msg: format!("read-only buffer {}", buf.to_id()))?
+            }
+            match self.path.as_ref() {
+                None if !matches!(buf.to_location(), Location::Disk { .. }) => {
+                    let content = "w ".to_string();
+                    return Ok(Event::Appn(event::Appn::Command(content)));
+                }
+                Some(path) => {
+                    let enc = match buf.to_location() {
+                        Location::Disk { enc, .. } => enc,
+                        _ => "utf-8".to_string(),
+                    };
+                    let path: ffi::OsString = path.into();
+                    let loc = Location::new_disk_file(&path, &enc)?;
+                    match buf.to_location() {
+                        Location::Disk { .. } => (loc.to_string(), loc.write(&buf.to_string())?),
+                        _ => {
+                            let id = buf.to_id();
+                            let old_loc = mem::replace(&mut buf.location, loc);
+                            match buf.write() {
+                                Ok(n) => {
+                                    old_id = Some(id);
+                                    (buf.to_id(), n)
+                                }
+                                Err(err) => {
+                                    buf.location = old_loc;
+                                    return Err(err);
+                                }
+                            }
+                        }
+                    }
+                }
+                None => (buf.to_id(), buf.write()?),
+            }
+        };
+        debug!("write {:?} {} bytes", id, n);
+
+        let span = format!("{:?} {}B written", id, n);
+        app.notify("code", Notify::Status(vec![span.into()]))?;
+
+        // buffer is known by its new id, switch the window to the same.
+        match old_id.and_then(|old_id| app.take_buffer(&old_id)) {
+            Some(buf) => {
+                app.add_buffer(buf);
+                Ok(Event::Appn(event::Appn::Buffer(id)))
+            }
+            None => Ok(Event::Noop),
         }
     }
 }
+
+/// WriteQuit command, `:wq`, write the buffer under edit to its file and
+/// quit the application. Application is not quit if write fails, or if
+/// the buffer is not backed by a file.
+pub struct WriteQuit;
+
+impl WriteQuit {
+    pub fn new(_syn: syntax::CodeCmd) -> Result<Self> {
+        Ok(WriteQuit)
+    }
+}
+
+impl Command for WriteQuit {
+    fn on_command(&mut self, app: &mut Code) -> Result<Event> {
+        let mut w = Write { path: None };
+        match w.on_command(app)? {
+            Event::Noop => Ok(Event::Quit),
+            evnt => Ok(evnt),
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "cmd_write_test.rs"]
+mod cmd_write_test;
@@ -0,0 +1,35 @@
+use super::*;
+
+use std::{env, fs, sync::mpsc};
+
+use crate::code::code_test::{new_buffer, new_code};
+
+#[test]
+fn test_write_unbound() {
+    let mut app = new_code();
+    let (tx, _rx) = mpsc::channel();
+    app.subscribe("code", tx);
+
+    let mut buf = new_buffer("test-write-unbound");
+    buf.on_event(Event::Wr(event::Cud::Delete(6))).unwrap();
+    assert!(buf.is_modified());
+    let id = buf.to_id();
+    app.add_buffer(buf);
+
+    let path = env::temp_dir().join("test-write-unbound.txt");
+    let mut w = Write {
+        path: Some(path.to_str().unwrap().to_string()),
+    };
+    let evnt = w.on_command(&mut app).unwrap();
+
+    // buffer is bound to the written file, and known by its new id.
+    let buf = app.as_mut_current_buffer().unwrap();
+    assert!(matches!(buf.to_location(), Location::Disk { .. }));
+    assert!(!buf.is_modified());
+    assert!(buf.to_id() != id);
+    assert!(evnt == Event::Appn(event::Appn::Buffer(buf.to_id())));
+    assert!(app.as_buffer(&id).is_err());
+    assert_eq!(fs::read_to_string(&path).unwrap(), "world\n");
+
+    fs::remove_file(&path).ok();
+}
@@ -0,0 +1,153 @@
+use super::*;
+
+use crate::location::Location;
+
+pub(super) fn new_code() -> Code {
+    Code {
+        config_value: toml::Value::Table(Default::default()),
+        config: Config::default(),
+        coord: Coord::new(1, 1, 25, 80),
+        subscribers: PubSub::default(),
+        schemes: Vec::default(),
+        buffers: Vec::default(),
+        buffer_ids: HashMap::default(),
+        marks: mark::new_marks(),
+        quickfix: QuickfixList::default(),
+        cmd_history: VecDeque::default(),
+        remaps: Vec::default(),
+        inner: Inner::default(),
+    }
+}
+
+pub(super) fn new_buffer(name: &str) -> Buffer {
+    let loc = Location::new_ted(name, "hello world\n".as_bytes(), false).unwrap();
+    Buffer::from_reader(loc).unwrap()
+}
+
+#[test]
+fn test_buffer_list() {
+    let mut app = new_code();
+    let (one, two) = (new_buffer("test-code-one"), new_buffer("test-code-two"));
+    let (id_one, id_two) = (one.to_id(), two.to_id());
+
+    app.add_buffer(one);
+    app.add_buffer(two);
+    assert_eq!(app.as_mut_current_buffer().unwrap().to_id(), id_two);
+    assert_eq!(app.as_buffer(&id_one).unwrap().to_id(), id_one);
+
+    // switching to a buffer makes it the most recent one.
+    let buf = app.take_buffer(&id_one).unwrap();
+    app.add_buffer(buf);
+    let ids: Vec<String> = app.as_buffers().iter().map(|b| b.to_id()).collect();
+    assert_eq!(ids, vec![id_two.clone(), id_one.clone()]);
+    assert_eq!(app.as_mut_buffer(&id_two).unwrap().to_id(), id_two);
+
+    // unknown buffer is an error, not a panic.
+    assert!(app.take_buffer(&id_two).is_some());
+    match app.as_buffer(&id_two) {
+        Err(Error::FailBuffer(_, _)) => (),
+        Err(err) => panic!("unexpected {}", err),
+        Ok(buf) => panic!("unexpected {}", buf.to_id()),
+    }
+    assert!(app.as_mut_buffer(&id_two).is_err());
+    assert!(app.take_buffer(&id_two).is_none());
+    assert_eq!(app.as_buffer(&id_one).unwrap().to_id(), id_one);
+}
+
+#[test]
+fn test_quickfix() {
+    use crate::code::{
+        cmd::Command,
+        cmd_quickfix::{Cnext, Cprev},
+    };
+
+    let mut app = new_code();
+    let (tx, rx) = mpsc::channel();
+    app.subscribe("code", tx);
+
+    let one = {
+        let txt = "fn main() {\n    let x = 1;\n}\n";
+        let loc = Location::new_ted("test-quickfix", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+    let id = one.to_id();
+    let loc = one.to_location();
+    app.add_buffer(one);
+    app.add_buffer(new_buffer("test-quickfix-two"));
+
+    // no entries to visit.
+    assert!(Cnext.on_command(&mut app).unwrap() == Event::Noop);
+    assert!(rx.try_recv().is_ok());
+
+    app.set_quickfix(vec![
+        (loc.clone(), 2, 9, "unused variable `x`".to_string()),
+        (loc, 3, 1, "expected `;`".to_string()),
+    ]);
+    let evnt = Cnext.on_command(&mut app).unwrap();
+    assert!(evnt == Event::Appn(event::Appn::Buffer(id.clone())));
+    assert_eq!(app.as_buffer(&id).unwrap().to_char_cursor(), 20);
+    match rx.try_recv().unwrap() {
+        pubsub::Notify::Status(spans) => {
+            assert_eq!(spans[0].content, "(1 of 2) unused variable `x`")
+        }
+        _ => panic!("expected status"),
+    }
+
+    Cnext.on_command(&mut app).unwrap();
+    assert_eq!(app.as_buffer(&id).unwrap().to_char_cursor(), 27);
+    assert!(Cnext.on_command(&mut app).unwrap() == Event::Noop);
+    Cprev.on_command(&mut app).unwrap();
+    assert_eq!(app.as_buffer(&id).unwrap().to_char_cursor(), 20);
+    assert_eq!(app.as_quickfix().to_current(), Some(0));
+}
+
+#[test]
+fn test_focus_next() {
+    let mut app = new_code();
+    app.schemes = vec![ColorScheme::default().unwrap()];
+    let (one, two) = (new_buffer("test-focus-one"), new_buffer("test-focus-two"));
+    let (id_one, id_two) = (one.to_id(), two.to_id());
+
+    let mut edit = Edit {
+        wfile: (&app, &one, app.to_coord_wfile()).into(),
+        splits: Vec::default(),
+        wsugg: app.to_wsugg(),
+        wstat: app.to_wstat(),
+        wtabs: app.to_wtabs(),
+    };
+    app.add_buffer(one);
+    app.add_buffer(two);
+    edit.split(&app, false /*vertical*/).unwrap();
+    edit.switch_buffer(&mut app, &id_two).unwrap();
+    assert_eq!(app.as_mut_current_buffer().unwrap().to_id(), id_two);
+
+    // buffer of the focused window becomes the buffer under edit.
+    edit.focus_next(&mut app).unwrap();
+    assert_eq!(edit.wfile.to_buffer_id(), id_one);
+    assert_eq!(app.as_mut_current_buffer().unwrap().to_id(), id_one);
+    edit.focus_next(&mut app).unwrap();
+    assert_eq!(app.as_mut_current_buffer().unwrap().to_id(), id_two);
+}
+
+#[test]
+fn test_set_config_modeline() {
+    let mut app = new_code();
+    let buf = {
+        let txt = "hello world\n# ted: ts=4 sw=2\n";
+        let loc = Location::new_ted("test-config-modeline", txt.as_bytes(), false).unwrap();
+        let mut buf = Buffer::from_reader(loc).unwrap();
+        app.config_buffer(&mut buf, false);
+        buf
+    };
+    let id = buf.to_id();
+    app.add_buffer(buf);
+    assert_eq!(app.as_buffer(&id).unwrap().tab_stop, 4);
+
+    // unrelated options shall not override the modeline.
+    let ctml = config::parse_options("nu et").unwrap();
+    let config = app.config.clone().mixin(ctml);
+    app.set_config(config);
+    let buf = app.as_buffer(&id).unwrap();
+    assert_eq!((buf.tab_stop, buf.shift_width), (4, 2));
+    assert!(app.config.line_number);
+}
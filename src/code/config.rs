@@ -1,16 +1,256 @@
 use toml;
 
-use std::{convert::TryFrom, convert::TryInto, ffi, fs, str::FromStr};
+#[allow(unused_imports)]
+use log::{debug, info};
 
-use crate::{Error, Result};
+use std::{convert::TryFrom, convert::TryInto, ffi, fs, path, str::FromStr};
+
+use crate::{buffer::Buffer, window::WinBuffer, Error, Result};
 
 config![
     // read-only will force all files to be opened in read-only mode.
     (read_only, bool, false),
     (scroll_off, u16, 0),
     (line_number, bool, true),
+    // along with line_number, show line numbers relative to the cursor
+    // line, while cursor line shows its absolute number.
+    (relative_number, bool, false),
+    // highlight trailing spaces and tabs on each line.
+    (highlight_trailing, bool, false),
+    // make whitespace visible, tabs are shown as `▸`, trailing spaces as
+    // `·` and line ends as `¶`.
+    (list, bool, false),
+    // highlight the background of line under cursor.
+    (cursor_line, bool, false),
     (wrap, bool, true),
+    // number of columns to use for each step of indent, and whether to
+    // indent with spaces instead of tabs.
+    (shift_width, usize, 4),
+    (expand_tab, bool, true),
+    // number of columns that a tab character expands to, when rendered.
+    (tab_stop, usize, 8),
+    // along with expand_tab, tab and backspace in insert mode work with
+    // shift_width columns of spaces.
+    (soft_tab_stop, bool, false),
+    // copy indentation of the current line when opening a new line.
+    (auto_indent, bool, false),
+    // move left and right by grapheme clusters, like an emoji with its
+    // modifiers, instead of characters.
+    (grapheme_motion, bool, false),
+    // maximum width of a line, in columns, when formatting text.
+    (text_width, usize, 80),
+    // case-insensitive search, smartcase overrides it for patterns
+    // having uppercase characters.
+    (ignorecase, bool, false),
+    (smartcase, bool, false),
+    // pattern search wraps around the end, or the beginning, of buffer.
+    (wrapscan, bool, true),
+    // "manual" folds are created with `zf`, "syntax" folds are derived from
+    // the syntax tree, for file types parsed with tree-sitter.
+    (fold_method, String, "manual".to_string()),
+    // abbreviations, like `Mr.` and `e.g.`, that do not end a sentence.
+    (
+        abbreviations,
+        Vec<String>,
+        crate::buffer::ABBREVIATIONS.iter().map(|s| s.to_string()).collect()
+    ),
+    // insert-mode abbreviations, like "teh the", trigger word is expanded
+    // to the rest when followed by a non-word character.
+    (iabbrev, Vec<String>, vec![]),
+    // number of command-lines to remember in history.
+    (history, usize, 100),
+    // user key-maps for normal and insert mode, like "jk <Esc>", refer
+    // [crate::keymap::KeyRemap] for details.
+    (nnoremap, Vec<String>, vec![]),
+    (inoremap, Vec<String>, vec![]),
     (left_margin_char, char, '|'),
     (top_margin_char, char, '-'),
     (color_scheme, String, "default".to_string())
 ];
+
+/// Name of the per-project configuration file, looked up in the current
+/// directory and its ancestors, refer [read_tedrc].
+pub const TEDRC: &str = ".tedrc";
+
+// (config-name, vim-names) for options that can be switched on and off,
+// as in `:set number` and `:set nonumber`.
+const FLAG_OPTIONS: [(&str, &[&str]); 14] = [
+    ("read_only", &["readonly", "ro"]),
+    ("line_number", &["number", "nu"]),
+    ("relative_number", &["relativenumber", "rnu"]),
+    ("highlight_trailing", &["hltrailing"]),
+    ("list", &[]),
+    ("cursor_line", &["cursorline", "cul"]),
+    ("wrap", &[]),
+    ("expand_tab", &["expandtab", "et"]),
+    ("soft_tab_stop", &["softtabstop", "sts"]),
+    ("auto_indent", &["autoindent", "ai"]),
+    ("grapheme_motion", &["graphememotion"]),
+    ("ignorecase", &["ic"]),
+    ("smartcase", &["scs"]),
+    ("wrapscan", &["ws"]),
+];
+
+// (config-name, vim-names) for options that take a number, as in
+// `:set shiftwidth=2`.
+const NUMBER_OPTIONS: [(&str, &[&str]); 5] = [
+    ("scroll_off", &["scrolloff", "so"]),
+    ("shift_width", &["shiftwidth", "sw"]),
+    ("tab_stop", &["tabstop", "ts"]),
+    ("text_width", &["textwidth", "tw"]),
+    ("history", &["hi"]),
+];
+
+// (config-name, vim-names, values) for options that take one of the
+// listed values, as in `:set foldmethod=syntax`.
+const STRING_OPTIONS: [(&str, &[&str], &[&str]); 1] =
+    [("fold_method", &["foldmethod", "fdm"], &["manual", "syntax"])];
+
+/// Parse whitespace separated options, as `{option}`, `no{option}` and
+/// `{option}={value}`, into a shadow config that can be mixed into
+/// [Config]. Options are named as in vim, like `number` and `sw`, or by
+/// their configuration name, like `line_number` and `shift_width`.
+pub fn parse_options(args: &str) -> Result<ConfigToml> {
+    let mut table = toml::map::Map::new();
+    for arg in args.split_whitespace() {
+        let (key, value) = parse_option(arg)?;
+        table.insert(key.to_string(), value);
+    }
+    if table.is_empty() {
+        err_at!(BadCommand, msg: format!("set missing option"))?
+    }
+    let value = toml::Value::Table(table);
+    err_at!(BadCommand, value.try_into::<ConfigToml>())
+}
+
+fn parse_option(arg: &str) -> Result<(&'static str, toml::Value)> {
+    let find = |options: &[(&'static str, &[&str])], name: &str| {
+        let mut iter = options.iter();
+        let item = iter.find(|(key, names)| *key == name || names.contains(&name));
+        item.map(|(key, _)| *key)
+    };
+
+    let (name, value) = match arg.find('=') {
+        Some(off) => (&arg[..off], Some(&arg[off + 1..])),
+        None => (arg, None),
+    };
+    match value {
+        None => {
+            if let Some(key) = find(&FLAG_OPTIONS, name) {
+                return Ok((key, toml::Value::Boolean(true)));
+            }
+            let key = name.strip_prefix("no").and_then(|n| find(&FLAG_OPTIONS, n));
+            if let Some(key) = key {
+                return Ok((key, toml::Value::Boolean(false)));
+            }
+        }
+        Some(value) => {
+            if let Some(key) = find(&NUMBER_OPTIONS, name) {
+                let n = err_at!(BadCommand, value.parse::<u32>(), format!("set {:?}", arg))?;
+                return Ok((key, toml::Value::Integer(n.into())));
+            }
+            let mut iter = STRING_OPTIONS.iter();
+            let item = iter.find(|(key, names, _)| *key == name || names.contains(&name));
+            if let Some((key, _, values)) = item {
+                if values.contains(&value) {
+                    return Ok((*key, toml::Value::String(value.to_string())));
+                }
+            }
+        }
+    }
+    err_at!(BadCommand, msg: format!("set unknown option {:?}", arg))
+}
+
+// options allowed in a modeline, a file shall not change anything beyond
+// its own formatting.
+const MODELINE_OPTIONS: [&str; 4] = ["tab_stop", "shift_width", "expand_tab", "text_width"];
+
+// number of lines, from the start and from the end of the buffer, to look
+// for modelines.
+const MODELINE_LINES: usize = 5;
+
+/// Scan the first and last few lines of `buf` for modelines and return
+/// options found in them. Modelines are of the form `ted: ts=4 sw=4 et`
+/// or `vim: set ts=4 sw=4 et:`, preceded by whitespace or at the start of
+/// the line, typically within a comment. Only `tab_stop`, `shift_width`,
+/// `expand_tab` and `text_width` are recognized, all other options are
+/// ignored.
+pub fn read_modeline(buf: &Buffer) -> ConfigToml {
+    let n = buf.n_lines();
+    let rows =
+        (0..n.min(MODELINE_LINES)).chain(n.saturating_sub(MODELINE_LINES).max(MODELINE_LINES)..n);
+
+    let mut table = toml::map::Map::new();
+    for args in rows.filter_map(|row| to_modeline_args(&buf.line(row))) {
+        for arg in args.split_whitespace() {
+            match parse_option(arg) {
+                Ok((key, value)) if MODELINE_OPTIONS.contains(&key) => {
+                    table.insert(key.to_string(), value);
+                }
+                _ => debug!("modeline {} ignored {:?}", buf.to_id(), arg),
+            }
+        }
+    }
+    // options are already validated, conversion shall not fail.
+    toml::Value::Table(table).try_into().unwrap()
+}
+
+// return options from modeline in `line`, if any, separated by whitespace.
+fn to_modeline_args(line: &str) -> Option<String> {
+    let off = {
+        let iter = ["ted:", "vim:"].iter().flat_map(|m| line.match_indices(m));
+        let iter = iter.filter(|(i, _)| *i == 0 || line[..*i].ends_with(char::is_whitespace));
+        iter.map(|(i, _)| i).min()?
+    };
+
+    let text = line[off + 4..].trim_start();
+    match text
+        .strip_prefix("set ")
+        .or_else(|| text.strip_prefix("se "))
+    {
+        // `set` form ends with a ':', rest of the line is ignored.
+        Some(text) => text.split(':').next().map(|s| s.to_string()),
+        None => Some(text.replace(':', " ")),
+    }
+}
+
+/// Look for [TEDRC] file in `dir` and its ancestors, and parse the nearest
+/// one found. Each line of the file lists options as in `:set` command,
+/// optionally prefixed with `set`. Blank lines and lines starting with
+/// `"` or `#` are ignored.
+///
+/// Project configuration from [TEDRC] overrides the configuration from
+/// `~/.ted.toml`, which in turn overrides the defaults.
+pub fn read_tedrc(dir: &path::Path) -> Result<Option<ConfigToml>> {
+    let file = match dir.ancestors().map(|d| d.join(TEDRC)).find(|f| f.is_file()) {
+        Some(file) => file,
+        None => return Ok(None),
+    };
+    info!("load project configuration from {:?}", file);
+
+    let text = fs::read_to_string(&file)?;
+    let mut args = String::default();
+    for line in text.lines().map(|l| l.trim()) {
+        let line = line.strip_prefix(':').unwrap_or(line);
+        let line = match line.split_whitespace().next() {
+            None => continue,
+            Some(word) if word.starts_with('"') || word.starts_with('#') => continue,
+            Some("set") | Some("se") => match line.split_once(char::is_whitespace) {
+                Some((_, line)) => line,
+                None => continue,
+            },
+            Some(_) => line,
+        };
+        args.push(' ');
+        args.push_str(line);
+    }
+
+    match args.trim().is_empty() {
+        true => Ok(None),
+        false => Ok(Some(parse_options(&args)?)),
+    }
+}
+
+#[cfg(test)]
+#[path = "config_test.rs"]
+mod config_test;
@@ -0,0 +1,97 @@
+use super::*;
+
+use std::env;
+
+#[test]
+fn test_parse_options() {
+    let config = Config::default();
+
+    let config = config.mixin(parse_options(" nowrap nu rnu sw=2 so=5").unwrap());
+    assert!(!config.wrap && config.line_number && config.relative_number);
+    assert_eq!(config.shift_width, 2);
+    assert_eq!(config.scroll_off, 5);
+
+    let ctml = parse_options("wrap ignorecase norelative_number tab_stop=4").unwrap();
+    let config = config.mixin(ctml);
+    assert!(config.wrap && config.ignorecase && !config.relative_number);
+    assert_eq!(config.tab_stop, 4);
+    // options not mentioned are retained.
+    assert_eq!(config.shift_width, 2);
+
+    assert_eq!(config.fold_method, "manual");
+    let config = config.mixin(parse_options("fdm=syntax").unwrap());
+    assert_eq!(config.fold_method, "syntax");
+    assert!(parse_options("fdm=indent").is_err());
+
+    // invalid options are reported as BadCommand.
+    for args in &["", "nu nofoo", "sw=x", "so=100000", "wrap=1", "nosw"] {
+        let res = parse_options(args);
+        assert!(matches!(res, Err(Error::BadCommand(_, _))), "{:?}", args);
+    }
+}
+
+#[test]
+fn test_read_tedrc() {
+    let root = env::temp_dir().join(format!("test-tedrc-{}", std::process::id()));
+    let dir = root.join("src").join("code");
+    fs::create_dir_all(&dir).unwrap();
+
+    assert!(read_tedrc(&dir).unwrap().is_none());
+
+    // nearest ancestor's file is picked.
+    let text = "\" project settings\n\nset nonumber\n:se relativenumber sw=2\n";
+    fs::write(root.join(TEDRC), text).unwrap();
+    let config = Config::default().mixin(read_tedrc(&dir).unwrap().unwrap());
+    assert!(!config.line_number && config.relative_number);
+    assert_eq!(config.shift_width, 2);
+
+    // project file overrides home configuration, which overrides defaults.
+    let home: ConfigToml = toml::from_str("line_number = true\ntab_stop = 2").unwrap();
+    let config = Config::default()
+        .mixin(home)
+        .mixin(read_tedrc(&dir).unwrap().unwrap());
+    assert!(!config.line_number);
+    assert_eq!(config.tab_stop, 2);
+
+    fs::write(dir.join(TEDRC), "# broken\nnumber=1\n").unwrap();
+    let res = read_tedrc(&dir);
+    assert!(matches!(res, Err(Error::BadCommand(_, _))));
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_read_modeline() {
+    use crate::location::Location;
+
+    let new_buffer = |name: &str, txt: String| {
+        let loc = Location::new_ted(name, txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+    let body: String = (0..20).map(|i| format!("line {}\n", i)).collect();
+
+    // modeline in the last line.
+    let txt = format!("{}// vim: set ts=2 sw=2 noet: tw=10\n", body);
+    let buf = new_buffer("test-modeline-set", txt);
+    let config = Config::default().mixin(read_modeline(&buf));
+    assert_eq!(config.tab_stop, 2);
+    assert_eq!(config.shift_width, 2);
+    assert!(!config.expand_tab);
+    assert_eq!(config.text_width, Config::default().text_width);
+
+    // untrusted and unknown options are ignored.
+    let txt = format!("{}# ted: tw=60:ro nonu foo=1 ts=x", body);
+    let buf = new_buffer("test-modeline-plain", txt);
+    let config = Config::default().mixin(read_modeline(&buf));
+    assert_eq!(config.text_width, 60);
+    assert!(!config.read_only && config.line_number);
+    assert_eq!(config.tab_stop, Config::default().tab_stop);
+
+    // modelines beyond the first and last few lines are not scanned, and
+    // the marker shall begin a word.
+    let txt = format!("{}# vim: ts=2\n{}# xvim: sw=2\n", body, body);
+    let buf = new_buffer("test-modeline-middle", txt);
+    let config = Config::default().mixin(read_modeline(&buf));
+    assert_eq!(config.tab_stop, Config::default().tab_stop);
+    assert_eq!(config.shift_width, Config::default().shift_width);
+}
@@ -1,10 +1,18 @@
 mod cmd;
+mod cmd_buffer;
+mod cmd_edit;
+mod cmd_goto;
+mod cmd_highlight;
+mod cmd_quickfix;
+mod cmd_quit;
 mod cmd_set;
-//mod cmd_edit;
+mod cmd_substitute;
+mod cmd_whitespace;
+mod cmd_write;
 //mod cmd_file;
-//mod cmd_write;
 
 mod config;
+mod quickfix;
 mod window_cmd;
 mod window_edit;
 mod window_file;
@@ -13,21 +21,34 @@ mod window_file;
 use log::{debug, error, trace};
 use toml;
 
-use std::{convert::TryFrom, ffi, mem, sync::mpsc};
+use std::{
+    cmp,
+    collections::{HashMap, VecDeque},
+    convert::TryFrom,
+    env, ffi, mem,
+    sync::mpsc,
+};
 
 use crate::{
     app::Application,
     buffer::Buffer,
     code::config::Config,
+    code::quickfix::QuickfixList,
     code::window_cmd::WindowCmd,
     code::window_file::WindowFile,
     colors::ColorScheme,
     event::{self, Event},
+    keymap::KeyRemap,
     location::Location,
     mark,
     pubsub::{self, PubSub},
+    search::SearchOpts,
     state::{self, State},
-    window::{Coord, Cursor, Window, WindowLess, WindowPrompt, WindowStatus, WindowSuggest},
+    syntax,
+    window::{
+        Coord, Cursor, WinBuffer, Window, WindowLess, WindowPrompt, WindowStatus, WindowSuggest,
+        WindowTabs,
+    },
     Error, Result,
 };
 
@@ -44,10 +65,18 @@ pub struct Code {
     subscribers: PubSub,
     // a copy of all available color schemes.
     schemes: Vec<ColorScheme>,
-    // buffer-list
+    // buffer-list, in the order of their use, most recent at the back.
     buffers: Vec<Buffer>,
+    // index of buffer-id to its position in buffer-list.
+    buffer_ids: HashMap<String, usize>,
     // list of global marks,
     marks: mark::Marks,
+    // locations, like compiler errors, visited via `:cnext` and `:cprev`.
+    quickfix: QuickfixList,
+    // command-line history, most recent command at the back.
+    cmd_history: VecDeque<String>,
+    // user key-maps, parsed from configuration.
+    remaps: Vec<KeyRemap>,
 
     // application state machine
     inner: Inner,
@@ -71,14 +100,27 @@ impl Inner {
             Inner::None => unreachable!(),
         }
     }
+
+    fn as_mut_edit(&mut self) -> &mut Edit {
+        match self {
+            Inner::Edit(edit) => edit,
+            Inner::Prompt(val) => &mut val.edit,
+            Inner::Command(val) => &mut val.edit,
+            Inner::Less(val) => &mut val.edit,
+            Inner::None => unreachable!(),
+        }
+    }
 }
 
 struct Edit {
-    #[allow(dead_code)]
+    // window under focus.
     wfile: WindowFile,
+    // rest of the windows split from the focused window.
+    splits: Vec<WindowFile>,
     #[allow(dead_code)]
     wsugg: WindowSuggest,
     wstat: WindowStatus,
+    wtabs: WindowTabs,
 }
 
 struct Prompt {
@@ -124,10 +166,24 @@ impl<'a> TryFrom<(&'a State, Coord)> for Code {
                 crate::config::to_section(toml_value, "code")
             };
             let mut config = Config::default().mixin(value.try_into().unwrap());
+            // project configuration overrides ~/.ted.toml, refer
+            // [config::read_tedrc].
+            let cwd = err_at!(IOError, env::current_dir());
+            match cwd.and_then(|cwd| config::read_tedrc(&cwd)) {
+                Ok(Some(ctml)) => config = config.mixin(ctml),
+                Ok(None) => (),
+                Err(err) => error!("{} {}", config::TEDRC, err),
+            }
             config.read_only = state.opts.read_only;
             config
         };
 
+        let remaps = {
+            let nmaps = config.nnoremap.iter().map(|m| KeyRemap::new("normal", m));
+            let imaps = config.inoremap.iter().map(|m| KeyRemap::new("insert", m));
+            nmaps.chain(imaps).collect::<Result<Vec<KeyRemap>>>()?
+        };
+
         debug!(
             "starting app `code` coord:{} config...\n{}",
             coord,
@@ -141,16 +197,25 @@ impl<'a> TryFrom<(&'a State, Coord)> for Code {
             schemes: state.schemes.clone(),
             subscribers: state.subscribers.clone(),
             buffers: Vec::default(),
+            buffer_ids: HashMap::default(),
             marks: mark::new_marks(),
+            quickfix: QuickfixList::default(),
+            cmd_history: VecDeque::default(),
+            remaps,
             inner: Inner::default(),
         };
 
         let (buffers, prompts) = {
             let files = {
-                let iter = state.opts.files.iter();
+                // `-` is for content piped via stdin.
+                let iter = state.opts.files.iter().filter(|f| f.as_str() != "-");
                 iter.map(|f| (f.clone(), format!("utf-8"))).collect()
             };
-            match app.open_cmd_files(files) {
+            let (mut bufs, ps) = app.open_cmd_files(files);
+            if let Some(data) = state.stdin.as_ref() {
+                bufs.insert(0, app.open_stdin(data)?);
+            }
+            match (bufs, ps) {
                 (bufs, ps) if bufs.len() == 0 => (vec![Buffer::empty()], ps),
                 (bufs, ps) => (bufs, ps),
             }
@@ -161,12 +226,17 @@ impl<'a> TryFrom<(&'a State, Coord)> for Code {
             let scheme = app.to_color_scheme(None);
             Edit {
                 wfile: (&app, buffer, app.to_coord_wfile()).into(),
+                splits: Vec::default(),
                 wsugg: WindowSuggest::new(app.to_coord_wsugg(), scheme),
-                wstat: WindowStatus::new(app.to_coord_wstat()),
+                wstat: app.to_wstat(),
+                wtabs: app.to_wtabs(),
             }
         };
 
-        app.buffers = buffers;
+        buffers
+            .into_iter()
+            .rev()
+            .for_each(|buf| app.add_buffer(buf));
         app.inner = if prompts.len() > 0 {
             Inner::Prompt(Prompt { edit, prompts })
         } else {
@@ -177,29 +247,85 @@ impl<'a> TryFrom<(&'a State, Coord)> for Code {
 }
 
 impl Code {
+    /// Add `buffer` as the most recently used buffer, replacing the buffer
+    /// with the same id, if any.
     pub fn add_buffer(&mut self, buffer: Buffer) {
-        self.buffers.insert(0, buffer)
+        self.take_buffer(&buffer.to_id());
+        self.buffer_ids.insert(buffer.to_id(), self.buffers.len());
+        self.buffers.push(buffer)
+    }
+
+    /// Return the buffer under edit. Buffers are maintained in the order
+    /// of their use, refer [Code::add_buffer].
+    pub fn as_mut_current_buffer(&mut self) -> Option<&mut Buffer> {
+        self.buffers.last_mut()
+    }
+
+    /// Return all buffers, in the order of their use, most recent last.
+    pub fn as_buffers(&self) -> &[Buffer] {
+        &self.buffers
+    }
+
+    /// Return the command-line history, most recent command at the back.
+    pub fn as_cmd_history(&self) -> &VecDeque<String> {
+        &self.cmd_history
     }
 
+    /// Add an executed command-line to history. An older entry for the same
+    /// command is removed, and history is trimmed to `history` config.
+    pub fn add_cmd_history(&mut self, content: String) {
+        self.cmd_history.retain(|c| c != &content);
+        self.cmd_history.push_back(content);
+        while self.cmd_history.len() > self.config.history {
+            self.cmd_history.pop_front();
+        }
+    }
+
+    /// Return the quickfix list, refer [QuickfixList].
+    pub fn as_quickfix(&self) -> &QuickfixList {
+        &self.quickfix
+    }
+
+    /// Return the quickfix list for in-place modification, like adding
+    /// entries via [QuickfixList::add_entry].
+    pub fn as_mut_quickfix(&mut self) -> &mut QuickfixList {
+        &mut self.quickfix
+    }
+
+    /// Replace the quickfix list with `entries`, as (location, line, col,
+    /// message), subsequent `:cnext` shall jump to the first entry.
+    pub fn set_quickfix(&mut self, entries: Vec<quickfix::Entry>) {
+        self.quickfix = QuickfixList::new(entries);
+    }
+
+    /// Remove buffer `id` from the buffer-list and return the same.
     pub fn take_buffer(&mut self, id: &str) -> Option<Buffer> {
-        let mut iter = self
-            .buffers
-            .iter()
-            .enumerate()
-            .filter_map(|(i, buf)| if buf.to_id() == id { Some(i) } else { None })
-            .take(1);
-        match iter.next() {
-            Some(i) => Some(self.buffers.remove(i)),
-            None => None,
+        let i = self.buffer_ids.remove(id)?;
+        let buf = self.buffers.remove(i);
+        // most often it is the current buffer, at the back.
+        if i < self.buffers.len() {
+            for j in self.buffer_ids.values_mut().filter(|j| **j > i) {
+                *j -= 1
+            }
         }
+        Some(buf)
     }
 }
 
 impl Code {
+    // top row is for tab line and bottom row is for status line.
     #[inline]
     fn to_coord_wfile(&self) -> Coord {
         let mut coord = self.coord;
-        coord.hgt = coord.hgt.saturating_sub(1);
+        coord.row += 1;
+        coord.hgt = coord.hgt.saturating_sub(2);
+        coord
+    }
+
+    #[inline]
+    fn to_coord_wtabs(&self) -> Coord {
+        let mut coord = self.coord;
+        coord.hgt = 1;
         coord
     }
 
@@ -218,6 +344,16 @@ impl Code {
         coord
     }
 
+    // buffer-list and such are shown from the bottom of edit area.
+    #[inline]
+    fn to_coord_wless(&self, n: usize) -> Coord {
+        let mut coord = self.to_coord_wfile();
+        let n = cmp::min(n, coord.hgt as usize) as u16;
+        coord.row += coord.hgt - n;
+        coord.hgt = n;
+        coord
+    }
+
     #[inline]
     fn to_coord_wstat(&self) -> Coord {
         let mut coord = self.coord;
@@ -234,20 +370,21 @@ impl Code {
         coord
     }
 
-    pub fn as_buffer(&self, id: &str) -> Option<&Buffer> {
-        self.buffers
-            .iter()
-            .filter_map(|buf| if buf.to_id() == id { Some(buf) } else { None })
-            .take(1)
-            .next()
+    /// Return buffer `id`, error if `id` is not in the buffer-list.
+    pub fn as_buffer(&self, id: &str) -> Result<&Buffer> {
+        match self.buffer_ids.get(id) {
+            Some(i) => Ok(&self.buffers[*i]),
+            None => err_at!(FailBuffer, msg: format!("no buffer {}", id)),
+        }
     }
 
-    pub fn as_mut_buffer(&mut self, id: &str) -> Option<&mut Buffer> {
-        self.buffers
-            .iter_mut()
-            .filter_map(|buf| if buf.to_id() == id { Some(buf) } else { None })
-            .take(1)
-            .next()
+    /// Return mutable reference to buffer `id`, error if `id` is not in
+    /// the buffer-list.
+    pub fn as_mut_buffer(&mut self, id: &str) -> Result<&mut Buffer> {
+        match self.buffer_ids.get(id) {
+            Some(i) => Ok(&mut self.buffers[*i]),
+            None => err_at!(FailBuffer, msg: format!("no buffer {}", id)),
+        }
     }
 
     pub fn as_config(&self) -> &Config {
@@ -264,12 +401,201 @@ impl Code {
         self.to_color_scheme(Some("default"))
     }
 
+    /// Return the color scheme in use, for in-place modification. Refer
+    /// [Code::to_color_scheme].
+    pub fn as_mut_color_scheme(&mut self) -> Option<&mut ColorScheme> {
+        let name = self.config.color_scheme.as_str();
+        let off = match self.schemes.iter().position(|s| s.name == name) {
+            Some(off) => Some(off),
+            None => self.schemes.iter().position(|s| s.name == "default"),
+        }?;
+        self.schemes.get_mut(off)
+    }
+
     pub fn to_wsugg(&self) -> WindowSuggest {
         WindowSuggest::new(self.to_coord_wsugg(), self.to_color_scheme(None))
     }
+
+    pub fn to_wstat(&self) -> WindowStatus {
+        WindowStatus::new(self.to_coord_wstat(), self.to_color_scheme(None))
+    }
+
+    pub fn to_wtabs(&self) -> WindowTabs {
+        WindowTabs::new(self.to_coord_wtabs(), self.to_color_scheme(None))
+    }
+}
+
+impl Edit {
+    // split the focused window horizontally or vertically, new window is
+    // on the top or on the left, and takes the focus.
+    fn split(&mut self, app: &Code, vertical: bool) -> Result<()> {
+        let coord = self.wfile.to_coord();
+        // each half shall have room for a margin and some text.
+        let (a, b) = match vertical {
+            true if coord.wth >= 4 => coord.split_v(),
+            false if coord.hgt >= 4 => coord.split_h(),
+            _ => return Ok(()),
+        };
+
+        let wfile = self.wfile.to_split(app, a)?;
+        self.wfile.set_coord(b);
+        self.splits.push(mem::replace(&mut self.wfile, wfile));
+        Ok(())
+    }
+
+    // switch the focused window to buffer `id`, which becomes the buffer
+    // under edit.
+    fn switch_buffer(&mut self, app: &mut Code, id: &str) -> Result<()> {
+        match app.take_buffer(id) {
+            Some(buf) => {
+                self.wfile.set_buffer(&buf)?;
+                app.add_buffer(buf);
+                Ok(())
+            }
+            None => err_at!(Invalid, msg: format!("buffer {}", id)),
+        }
+    }
+
+    // color scheme is modified, render all windows afresh in new colors.
+    fn restyle(&mut self, app: &Code) -> Result<()> {
+        self.wfile.set_color_scheme(app)?;
+        for w in self.splits.iter_mut() {
+            w.set_color_scheme(app)?;
+        }
+        self.wsugg = app.to_wsugg();
+        self.wstat = app.to_wstat();
+        self.wtabs = app.to_wtabs();
+        self.redraw();
+        Ok(())
+    }
+
+    // configuration is modified, render all windows afresh as per the
+    // new configuration.
+    fn reconfigure(&mut self, app: &Code) {
+        self.wfile.set_config(app);
+        for w in self.splits.iter_mut() {
+            w.set_config(app);
+        }
+        self.redraw();
+    }
+
+    // render all windows afresh, say after an overlay is closed.
+    fn redraw(&mut self) {
+        self.wfile.set_force_redraw();
+        for w in self.splits.iter_mut() {
+            w.set_force_redraw();
+        }
+    }
+
+    // re-tile all windows from `old` coordinate to `new` coordinate.
+    fn rescale(&mut self, old: Coord, new: Coord) {
+        let coord = self.wfile.to_coord().rescale(old, new);
+        self.wfile.set_coord(coord);
+        for w in self.splits.iter_mut() {
+            let coord = w.to_coord().rescale(old, new);
+            w.set_coord(coord);
+        }
+    }
+
+    // move focus to the next window, in the order of their position on
+    // the screen, and restore that window's cursor on its buffer, which
+    // becomes the buffer under edit.
+    fn focus_next(&mut self, app: &mut Code) -> Result<()> {
+        if self.splits.is_empty() {
+            return Ok(());
+        }
+
+        let key = |w: &WindowFile| {
+            let (col, row) = w.to_coord().to_origin();
+            (row, col)
+        };
+        let curr = key(&self.wfile);
+        let off = {
+            let iter = self.splits.iter().enumerate();
+            let next = iter
+                .clone()
+                .filter(|(_, w)| key(w) > curr)
+                .min_by_key(|(_, w)| key(w));
+            let first = iter.min_by_key(|(_, w)| key(w));
+            next.or(first).map(|(i, _)| i).unwrap()
+        };
+        mem::swap(&mut self.wfile, &mut self.splits[off]);
+
+        // focused window's buffer becomes the buffer under edit, commands
+        // like `:w` and `:s` apply on the buffer under edit.
+        if let Some(mut buf) = app.take_buffer(&self.wfile.to_buffer_id()) {
+            let cursor = self.wfile.to_buffer_cursor(&buf);
+            buf.set_cursor(cursor).clear_sticky_col();
+            app.add_buffer(buf);
+        }
+        Ok(())
+    }
+
+    // edits made via the focused window are applied on other windows
+    // sharing the same buffer.
+    fn sync_splits(&mut self, app: &Code) -> Result<()> {
+        let edits = self.wfile.take_edits();
+        if edits.is_empty() {
+            return Ok(());
+        }
+        let id = self.wfile.to_buffer_id();
+        for w in self.splits.iter_mut().filter(|w| w.to_buffer_id() == id) {
+            w.on_shared_edits(app, edits.clone())?;
+        }
+        Ok(())
+    }
+
+    // refresh windows out of focus, each with its own cursor, and finally
+    // refresh the focused window.
+    fn on_refresh(&mut self, app: &mut Code) -> Result<()> {
+        for w in self.splits.iter_mut() {
+            let id = w.to_buffer_id();
+            let cursor = app.as_mut_buffer(&id).ok().map(|buf| {
+                let cursor = buf.to_char_cursor();
+                buf.set_cursor(w.to_buffer_cursor(buf));
+                cursor
+            });
+            w.on_refresh(app)?;
+            if let (Some(cursor), Ok(buf)) = (cursor, app.as_mut_buffer(&id)) {
+                buf.set_cursor(cursor);
+            }
+        }
+        self.wfile.on_refresh(app)
+    }
 }
 
 impl Code {
+    // terminal is resized, re-tile the windows proportionally and reflow
+    // the overlays to the new width.
+    fn on_resize(&mut self, inner: &mut Inner, cols: u16, rows: u16) {
+        let old = self.to_coord_wfile();
+        self.coord = self.coord.resize_to(rows, cols);
+        let new = self.to_coord_wfile();
+
+        let edit = inner.as_mut_edit();
+        edit.rescale(old, new);
+        edit.wsugg = self.to_wsugg();
+        edit.wstat = self.to_wstat();
+        edit.wtabs = self.to_wtabs();
+
+        match inner {
+            Inner::Prompt(val) => {
+                let coord = self.to_coord_wprompt();
+                val.prompts.iter_mut().for_each(|w| {
+                    w.set_coord(coord);
+                });
+            }
+            Inner::Command(val) => {
+                val.wcmd.set_coord(self.to_coord_wcmd());
+            }
+            Inner::Less(val) => {
+                let n = val.wless.to_coord().hgt as usize;
+                val.wless.set_coord(self.to_coord_wless(n));
+            }
+            Inner::Edit(_) | Inner::None => (),
+        }
+    }
+
     fn open_cmd_files(&self, files: Vec<(String, String)>) -> (Vec<Buffer>, Vec<WindowPrompt>) {
         let coord = self.to_coord_wprompt();
         let (mut buffers, mut prompts) = (vec![], vec![]);
@@ -297,12 +623,12 @@ impl Code {
             match Buffer::from_reader(loc) {
                 Ok(mut buf) if read_only => {
                     debug!("opening {} in read-mode", loc_msg);
-                    buf.set_read_only(true);
+                    self.config_buffer(&mut buf, true);
                     buffers.push(buf);
                 }
                 Ok(mut buf) => {
                     debug!("opening {} in write-mode", loc_msg);
-                    buf.set_read_only(self.config.read_only);
+                    self.config_buffer(&mut buf, self.config.read_only);
                     buffers.push(buf);
                 }
                 Err(err) => {
@@ -318,6 +644,73 @@ impl Code {
 
         (buffers, prompts)
     }
+
+    // content piped via stdin is opened as buffer without a file, writing
+    // it shall prompt for a file name.
+    fn open_stdin(&self, data: &[u8]) -> Result<Buffer> {
+        let loc = Location::new_ted("stdin", data, false)?;
+        let mut buf = Buffer::from_reader(loc)?;
+        self.config_buffer(&mut buf, self.config.read_only);
+        Ok(buf)
+    }
+
+    /// Replace application configuration with `config`, options that
+    /// apply to buffers, like `shift_width`, are applied on all open
+    /// buffers, options from buffer's modeline continue to override them.
+    pub fn set_config(&mut self, config: Config) {
+        self.config = config;
+
+        let mut buffers = mem::take(&mut self.buffers);
+        for buf in buffers.iter_mut() {
+            let read_only = buf.is_read_only();
+            self.config_buffer(buf, read_only);
+        }
+        self.buffers = buffers;
+    }
+
+    // configure a newly opened buffer as per application config, options
+    // from buffer's modeline override the application config.
+    fn config_buffer(&self, buf: &mut Buffer, read_only: bool) {
+        let config = self.config.clone().mixin(config::read_modeline(buf));
+        self.apply_config(&config, buf, read_only);
+    }
+
+    fn apply_config(&self, config: &Config, buf: &mut Buffer, read_only: bool) {
+        let search_opts = SearchOpts {
+            ignorecase: config.ignorecase,
+            smartcase: config.smartcase,
+        };
+        let file_type = syntax::to_file_type(buf);
+        buf.set_read_only(read_only)
+            .set_shift_width(config.shift_width)
+            .set_expand_tab(config.expand_tab)
+            .set_tab_stop(config.tab_stop)
+            .set_soft_tab_stop(config.soft_tab_stop)
+            .set_auto_indent(config.auto_indent)
+            .set_brace_indent(syntax::is_brace_indent(&file_type))
+            .set_grapheme_motion(config.grapheme_motion)
+            .set_text_width(config.text_width)
+            .set_search_opts(search_opts)
+            .set_wrapscan(config.wrapscan)
+            .set_fold_syntax(config.fold_method == "syntax")
+            .set_abbreviations(config.abbreviations.clone())
+            .set_iabbrevs(self.to_iabbrevs())
+            .set_file_type(&file_type);
+    }
+
+    // parse insert-mode abbreviations, entries without a replacement are
+    // ignored.
+    fn to_iabbrevs(&self) -> Vec<(String, String)> {
+        let iter = self.config.iabbrev.iter().filter_map(|abbr| {
+            let abbr = abbr.trim();
+            let off = abbr.find(char::is_whitespace)?;
+            Some((
+                abbr[..off].to_string(),
+                abbr[off..].trim_start().to_string(),
+            ))
+        });
+        iter.collect()
+    }
 }
 
 impl Application for Code {
@@ -344,6 +737,11 @@ impl Application for Code {
 
         let inner = mem::replace(&mut self.inner, Inner::default());
         let (mut inner, evnt) = match (inner, evnt.clone()) {
+            (mut inner, Event::Resize(cols, rows)) => {
+                debug!("code event {}", evnt);
+                self.on_resize(&mut inner, cols, rows);
+                (inner, Event::Noop)
+            }
             (Inner::Edit(edit), Event::Mr(mrk)) => {
                 debug!("code event {}", evnt);
                 mark::set_mark(&mut self.marks, mrk);
@@ -366,6 +764,14 @@ impl Application for Code {
                 debug!("code event {}", evnt);
                 (Inner::Edit(edit), Event::Mt(Mto::Jump(typ, mindex)))
             }
+            (Inner::Edit(mut edit), Event::Click(col, row)) => {
+                debug!("code event {}", evnt);
+                let evnt = match edit.wtabs.to_buffer_at(col, row) {
+                    Some(id) => Event::Appn(event::Appn::Buffer(id)),
+                    None => edit.wfile.on_event(self, evnt)?,
+                };
+                (Inner::Edit(edit), evnt)
+            }
             (Inner::Edit(edit), Event::Char(':', m)) if m.is_empty() => {
                 debug!("code event {}", evnt);
                 let prefix = edit.wfile.to_event_prefix();
@@ -406,7 +812,58 @@ impl Application for Code {
                     edit: inner.into_edit(),
                     wless: *wless,
                 }),
-                Event::Esc => Inner::Edit(inner.into_edit()),
+                Event::Appn(event::Appn::SplitH) => {
+                    inner.as_mut_edit().split(self, false /*vertical*/)?;
+                    inner
+                }
+                Event::Appn(event::Appn::SplitV) => {
+                    inner.as_mut_edit().split(self, true /*vertical*/)?;
+                    inner
+                }
+                Event::Appn(event::Appn::FocusNext) => {
+                    inner.as_mut_edit().focus_next(self)?;
+                    inner
+                }
+                Event::Appn(event::Appn::Buffer(id)) => {
+                    inner.as_mut_edit().switch_buffer(self, &id)?;
+                    inner
+                }
+                Event::Appn(event::Appn::Command(content)) => {
+                    let mut wcmd = WindowCmd::new(self.to_coord_wcmd(), self)?;
+                    wcmd.set_content(&content)?;
+                    Inner::Command(Command {
+                        edit: inner.into_edit(),
+                        wcmd,
+                    })
+                }
+                Event::Appn(event::Appn::ColorScheme) => {
+                    inner.as_mut_edit().restyle(self)?;
+                    inner
+                }
+                Event::Appn(event::Appn::Config) => {
+                    inner.as_mut_edit().reconfigure(self);
+                    inner
+                }
+                // overlay is closed, render the windows afresh.
+                Event::Esc => match inner {
+                    Inner::Less(less) => {
+                        let mut edit = less.edit;
+                        edit.redraw();
+                        Inner::Edit(edit)
+                    }
+                    inner => Inner::Edit(inner.into_edit()),
+                },
+                // edits from commands, apply them on the window's syntax tree.
+                evnt @ Event::Edit(_) => match inner {
+                    Inner::Edit(mut edit) => {
+                        new_evnt.push(edit.wfile.on_event(self, evnt)?);
+                        Inner::Edit(edit)
+                    }
+                    inner => {
+                        new_evnt.push(evnt);
+                        inner
+                    }
+                },
                 evnt => {
                     new_evnt.push(evnt);
                     inner
@@ -414,6 +871,8 @@ impl Application for Code {
             }
         }
 
+        inner.as_mut_edit().sync_splits(self)?;
+
         self.inner = inner;
         Ok(new_evnt)
     }
@@ -422,23 +881,29 @@ impl Application for Code {
         let mut inner = mem::replace(&mut self.inner, Inner::default());
         match &mut inner {
             Inner::Edit(edit) => {
-                edit.wfile.on_refresh(self)?;
+                edit.on_refresh(self)?;
+                if let Ok(buf) = self.as_buffer(&edit.wfile.to_buffer_id()) {
+                    edit.wstat.set_buffer(buf, edit.wfile.to_viewport());
+                }
                 edit.wstat.on_refresh()?;
-                // TODO: edit.tbcline.on_refresh(self)?;
+                let id = edit.wfile.to_buffer_id();
+                edit.wtabs
+                    .set_buffers(self.as_buffers(), &id)
+                    .on_refresh()?;
             }
             Inner::Prompt(prompt) => {
-                prompt.edit.wfile.on_refresh(self)?;
+                prompt.edit.on_refresh(self)?;
                 match prompt.prompts.first_mut() {
                     Some(p) => p.on_refresh()?,
                     None => (),
                 };
             }
             Inner::Command(cmd) => {
-                cmd.edit.wfile.on_refresh(self)?;
+                cmd.edit.on_refresh(self)?;
                 cmd.wcmd.on_refresh(self)?;
             }
             Inner::Less(less) => {
-                less.edit.wfile.on_refresh(self)?;
+                less.edit.on_refresh(self)?;
                 less.wless.on_refresh()?;
             }
             Inner::None => unreachable!(),
@@ -461,7 +926,7 @@ impl Application for Code {
         };
         let active = false;
         match self.as_buffer(&edit.wfile.to_buffer_id()) {
-            Some(buf) => {
+            Ok(buf) => {
                 let text = match buf.to_location().to_title(wth) {
                     Ok(text) => text,
                     Err(_) => {
@@ -476,7 +941,7 @@ impl Application for Code {
                     active,
                 }
             }
-            None => {
+            Err(_) => {
                 let text = "∞".to_string();
                 let modified = false;
                 state::TabTitle {
@@ -488,3 +953,7 @@ impl Application for Code {
         }
     }
 }
+
+#[cfg(test)]
+#[path = "code_test.rs"]
+mod code_test;
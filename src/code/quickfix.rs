@@ -0,0 +1,91 @@
+//! Module implement quickfix list, a list of locations, like compiler
+//! errors and grep matches, that can be listed with `:copen` and visited
+//! one after the other with `:cnext` and `:cprev`.
+
+#[allow(unused_imports)]
+use log::{debug, trace};
+
+use crate::location::Location;
+
+/// Quickfix entry as (location, line, col, message), line and col start
+/// from 1.
+pub type Entry = (Location, usize, usize, String);
+
+/// List of quickfix entries, visited in the order they were added.
+#[derive(Clone, Default)]
+pub struct QuickfixList {
+    entries: Vec<Entry>,
+    // index of the entry visited last.
+    curr: Option<usize>,
+}
+
+impl QuickfixList {
+    pub fn new(entries: Vec<Entry>) -> QuickfixList {
+        QuickfixList {
+            entries,
+            curr: None,
+        }
+    }
+
+    /// Add an entry to the end of the list.
+    pub fn add_entry(&mut self, loc: Location, line: usize, col: usize, msg: &str) -> &mut Self {
+        self.entries.push((loc, line, col, msg.to_string()));
+        self
+    }
+
+    /// Remove all entries.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.curr = None;
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn as_entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Return the index of the entry visited last, if any.
+    pub fn to_current(&self) -> Option<usize> {
+        self.curr
+    }
+
+    /// Move to the entry after the one visited last, or to the first entry
+    /// if none is visited yet. Return None if there are no more entries.
+    pub fn next_entry(&mut self) -> Option<Entry> {
+        let off = self.curr.map(|off| off + 1).unwrap_or(0);
+        let entry = self.entries.get(off)?.clone();
+        self.curr = Some(off);
+        Some(entry)
+    }
+
+    /// Move to the entry before the one visited last. Return None if there
+    /// are no more entries.
+    pub fn prev_entry(&mut self) -> Option<Entry> {
+        let off = self.curr?.checked_sub(1)?;
+        let entry = self.entries.get(off)?.clone();
+        self.curr = Some(off);
+        Some(entry)
+    }
+
+    /// Return entries formatted as `{location}:{line}:{col}: {message}`, one
+    /// per line, entry visited last is marked with `>`.
+    pub fn to_lines(&self) -> Vec<String> {
+        let iter = self.entries.iter().enumerate();
+        iter.map(|(i, (loc, line, col, msg))| {
+            let curr = if_else!(Some(i) == self.curr, '>', ' ');
+            format!("{} {}:{}:{}: {}", curr, loc, line, col, msg)
+        })
+        .collect()
+    }
+}
+
+#[cfg(test)]
+#[path = "quickfix_test.rs"]
+mod quickfix_test;
@@ -0,0 +1,36 @@
+use super::*;
+
+#[test]
+fn test_quickfix_list() {
+    let loc = |name: &str| Location::new_ted(name, "".as_bytes(), false).unwrap();
+
+    let mut qfl = QuickfixList::default();
+    assert!(qfl.is_empty());
+    assert!(qfl.next_entry().is_none());
+    assert!(qfl.prev_entry().is_none());
+
+    qfl.add_entry(loc("a.rs"), 10, 5, "unused variable")
+        .add_entry(loc("b.rs"), 1, 1, "missing semicolon");
+    assert_eq!(qfl.len(), 2);
+    assert_eq!(qfl.to_current(), None);
+
+    let (_, line, col, msg) = qfl.next_entry().unwrap();
+    assert_eq!((line, col, msg.as_str()), (10, 5, "unused variable"));
+    assert!(qfl.prev_entry().is_none());
+    assert_eq!(qfl.next_entry().unwrap().3, "missing semicolon");
+    // no more entries, stay with the last visited entry.
+    assert!(qfl.next_entry().is_none());
+    assert_eq!(qfl.to_current(), Some(1));
+    assert_eq!(
+        qfl.to_lines(),
+        vec![
+            "  [a.rs]:10:5: unused variable".to_string(),
+            "> [b.rs]:1:1: missing semicolon".to_string(),
+        ]
+    );
+    assert_eq!(qfl.prev_entry().unwrap().3, "unused variable");
+
+    qfl.clear();
+    assert!(qfl.is_empty());
+    assert_eq!(qfl.to_current(), None);
+}
@@ -2,16 +2,19 @@ use crossterm::{cursor as term_cursor, queue};
 #[allow(unused_imports)]
 use log::trace;
 
-use std::{convert::TryInto, fmt, io, mem, result};
+use std::{cmp, collections::VecDeque, convert::TryInto, fmt, io, mem, ops::DerefMut, result};
 
 use crate::{
+    app::Application,
     buffer::{self, Buffer},
     code::{self, cmd},
-    colors::ColorScheme,
-    event::Event,
+    colors::{ColorScheme, Highlight},
+    event::{Event, DP},
     keymap::Keymap,
     location::Location,
-    term::Spanline,
+    pubsub::Notify,
+    tabc::{self, TabComplete},
+    term::{self, Span, Spanline},
     view,
     window::{Coord, Cursor, Render, WinBuffer, Window, WindowSuggest},
     Error, Result,
@@ -24,6 +27,10 @@ pub struct WindowCmd {
     buf: Buffer,
     scheme: ColorScheme,
     keymap: Keymap,
+    // (typed-prefix, history-index) while recalling from history.
+    recall: Option<(String, usize)>,
+    // (argument-offset, completions) while cycling through completions.
+    tabc: Option<(usize, TabComplete)>,
     #[allow(dead_code)]
     wsugg: WindowSuggest,
 }
@@ -40,13 +47,7 @@ impl fmt::Display for WindowCmd {
 
 impl WindowCmd {
     pub fn new(coord: Coord, app: &code::Code) -> Result<WindowCmd> {
-        let mut buf = {
-            let read_only = false;
-            let loc = Location::new_ted("code-cmd", io::empty(), read_only)?;
-            Buffer::from_reader(loc)?
-        };
-        buf.set_insert_mode();
-        buf.cud_char(None, ':').unwrap();
+        let buf = new_cmd_buffer(":")?;
 
         let cursor = view::NoWrap::initial_cursor(false /*line_number*/);
         let obc_xy = (0, 0).into();
@@ -57,11 +58,28 @@ impl WindowCmd {
             buf,
             scheme: app.to_color_scheme(None),
             keymap: Keymap::new_cmd(),
+            recall: None,
+            tabc: None,
             wsugg: app.to_wsugg(),
         })
     }
 }
 
+impl WindowCmd {
+    /// Pre-fill the command-line with `content`, following the `:`.
+    pub fn set_content(&mut self, content: &str) -> Result<&mut Self> {
+        self.buf = new_cmd_buffer(&format!(":{}", content))?;
+        Ok(self)
+    }
+
+    /// Move this window to `coord`, say when the terminal is resized.
+    pub fn set_coord(&mut self, coord: Coord) -> &mut Self {
+        self.coord = coord;
+        self.cursor.col = cmp::min(self.cursor.col, coord.wth.saturating_sub(1));
+        self
+    }
+}
+
 impl Window for WindowCmd {
     type App = code::Code;
 
@@ -90,28 +108,125 @@ impl Window for WindowCmd {
         false
     }
 
+    #[inline]
+    fn config_relative_number(&self) -> bool {
+        false
+    }
+
+    #[inline]
+    fn config_highlight_trailing(&self) -> bool {
+        false
+    }
+
+    #[inline]
+    fn config_list(&self) -> bool {
+        false
+    }
+
+    #[inline]
+    fn config_cursor_line(&self) -> bool {
+        false
+    }
+
     #[inline]
     fn config_scroll_offset(&self) -> u16 {
         0
     }
 
+    #[inline]
+    fn config_tab_stop(&self) -> usize {
+        buffer::TAB_STOP
+    }
+
     fn on_event(&mut self, app: &mut code::Code, mut evnt: Event) -> Result<Event> {
         use crate::code::cmd::Command;
 
         let mut buf = mem::replace(&mut self.buf, Buffer::empty());
+        let recall = self.recall.take();
+        let tabc = self.tabc.take();
         evnt = match self.keymap.fold(&mut buf, evnt)? {
+            // recall older command, matching the typed prefix, from history.
+            Event::Up(_) => {
+                let (prefix, idx) = match recall {
+                    Some((prefix, idx)) => (prefix, Some(idx)),
+                    None => (buf.to_string(), None),
+                };
+                let history = app.as_cmd_history();
+                match recall_history(history, &prefix, idx, DP::Left) {
+                    Some(idx) => {
+                        buf = new_cmd_buffer(&history[idx])?;
+                        self.recall = Some((prefix, idx));
+                    }
+                    None => self.recall = idx.map(|idx| (prefix, idx)),
+                }
+                Event::Noop
+            }
+            // recall newer command, back to typed prefix past the newest.
+            Event::Down(_) => {
+                if let Some((prefix, idx)) = recall {
+                    let history = app.as_cmd_history();
+                    match recall_history(history, &prefix, Some(idx), DP::Right) {
+                        Some(idx) => {
+                            buf = new_cmd_buffer(&history[idx])?;
+                            self.recall = Some((prefix, idx));
+                        }
+                        None => buf = new_cmd_buffer(&prefix)?,
+                    }
+                }
+                Event::Noop
+            }
+            // complete file path for commands like `:e` and `:w`, repeated
+            // tab cycle through the matches.
+            Event::Tab(_) => {
+                let content = buf.to_string();
+                match tabc {
+                    Some((off, mut tabc)) => {
+                        let arg = tabc.next().unwrap_or_default();
+                        buf = new_cmd_buffer(&format!("{}{}", &content[..off], arg))?;
+                        self.tabc = Some((off, tabc));
+                    }
+                    None => {
+                        if let Some(off) = to_path_arg(&content) {
+                            let paths = tabc::complete_path(&content[off..]);
+                            let arg = tabc::common_prefix(&paths);
+                            if !paths.is_empty() {
+                                buf = new_cmd_buffer(&format!("{}{}", &content[..off], arg))?;
+                            }
+                            if paths.len() > 1 {
+                                self.tabc = Some((off, TabComplete::new(arg, paths)));
+                            }
+                        }
+                    }
+                }
+                Event::Noop
+            }
             Event::N(n) => {
                 let s = format!(".,.+{}", n.saturating_sub(1));
                 buf.cud_str(Some(0), &s)?;
                 Event::Noop
             }
             Event::Enter(_) => {
-                let mut val: cmd::Cmd = {
-                    let content = buf.to_string();
-                    (content, self.scheme.clone()).try_into()?
-                };
-                let mut evnt = val.on_command(app)?;
-                evnt.push(Event::Esc);
+                let content = buf.to_string();
+                let res: Result<cmd::Cmd> = (content.clone(), self.scheme.clone()).try_into();
+                // exit the command window before applying command's events.
+                let mut evnt = Event::Esc;
+                match res.and_then(|mut val| val.on_command(app)) {
+                    Ok(cmd_evnt) => {
+                        app.add_cmd_history(content);
+                        evnt.push(cmd_evnt)
+                    }
+                    Err(err @ Error::Fatal(_, _)) => Err(err)?,
+                    // report command failures as status, instead of bailing out.
+                    Err(err @ Error::BadCommand(_, _)) => {
+                        let style = self.scheme.to_style(Highlight::Error);
+                        let span = Span::from(err.to_error()).using(style);
+                        app.notify("code", Notify::Status(vec![span]))?
+                    }
+                    Err(err) => {
+                        let span = err.to_error();
+                        app.notify("code", Notify::Status(vec![span.into()]))?
+                    }
+                }
                 evnt
             }
             evnt => buf.on_event(evnt)?,
@@ -126,13 +241,60 @@ impl Window for WindowCmd {
 
         let mut v: view::NoWrap = (&*self, self.obc_xy).try_into()?;
         v.shift_cursor(&self.buf)?;
-        self.cursor = v.render(&self.buf, self, None)?;
+        self.cursor = {
+            let mut tm = err_at!(Fatal, term::TERM.lock())?;
+            v.render(&self.buf, self, &mut vec![], tm.deref_mut())?
+        };
         self.obc_xy = self.buf.to_xy_cursor(None);
 
         Ok(())
     }
 }
 
+// Return a command-line buffer, in insert mode, with `content`.
+fn new_cmd_buffer(content: &str) -> Result<Buffer> {
+    let mut buf = {
+        let read_only = false;
+        let loc = Location::new_ted("code-cmd", io::empty(), read_only)?;
+        Buffer::from_reader(loc)?
+    };
+    buf.set_insert_mode();
+    buf.cud_str(None, content)?;
+    buf.set_cursor(buf.n_chars());
+    Ok(buf)
+}
+
+// Return the byte-offset of path argument in `content`, if it is a command
+// taking a file path, like `:e {path}` and `:w {path}`.
+fn to_path_arg(content: &str) -> Option<usize> {
+    let line = content.strip_prefix(':')?;
+    let n = line.find(char::is_whitespace)?;
+    match line[..n].trim_end_matches('!') {
+        "e" | "edit" | "w" | "write" => {
+            let arg = line[n..].trim_start();
+            Some(content.len() - arg.len())
+        }
+        _ => None,
+    }
+}
+
+// Return index of the entry in `history` starting with `prefix`. Search
+// older than `idx` for [DP::Left] and newer than `idx` for [DP::Right],
+// `idx` as None start the search from the newest entry.
+fn recall_history(
+    history: &VecDeque<String>,
+    prefix: &str,
+    idx: Option<usize>,
+    dp: DP,
+) -> Option<usize> {
+    let idx = idx.unwrap_or(history.len());
+    match dp {
+        DP::Left => (0..idx).rev().find(|i| history[*i].starts_with(prefix)),
+        DP::Right => (idx + 1..history.len()).find(|i| history[*i].starts_with(prefix)),
+        _ => None,
+    }
+}
+
 impl Render for WindowCmd {
     type Buf = Buffer;
 
@@ -146,3 +308,7 @@ impl Render for WindowCmd {
         buf.to_span_line(a, z)
     }
 }
+
+#[cfg(test)]
+#[path = "window_cmd_test.rs"]
+mod window_cmd_test;
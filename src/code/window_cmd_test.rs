@@ -0,0 +1,65 @@
+use super::*;
+
+use std::sync::mpsc;
+
+use crate::code::code_test::{new_buffer, new_code};
+
+#[test]
+fn test_recall_history() {
+    let history: VecDeque<String> = vec![":set wrap", ":w", ":s/a/b/", ":set nowrap"]
+        .into_iter()
+        .map(String::from)
+        .collect();
+
+    assert_eq!(recall_history(&history, ":", None, DP::Left), Some(3));
+    assert_eq!(recall_history(&history, ":", Some(3), DP::Left), Some(2));
+    assert_eq!(recall_history(&history, ":", Some(0), DP::Left), None);
+    assert_eq!(recall_history(&history, ":", None, DP::Right), None);
+    assert_eq!(recall_history(&history, ":", Some(2), DP::Right), Some(3));
+    assert_eq!(recall_history(&history, ":", Some(3), DP::Right), None);
+
+    // filtered by prefix.
+    assert_eq!(recall_history(&history, ":set", None, DP::Left), Some(3));
+    assert_eq!(recall_history(&history, ":set", Some(3), DP::Left), Some(0));
+    assert_eq!(
+        recall_history(&history, ":set", Some(0), DP::Right),
+        Some(3)
+    );
+    assert_eq!(recall_history(&history, ":q", None, DP::Left), None);
+
+    assert_eq!(recall_history(&VecDeque::new(), ":", None, DP::Left), None);
+}
+
+#[test]
+fn test_to_path_arg() {
+    assert_eq!(to_path_arg(":e src/bu"), Some(3));
+    assert_eq!(to_path_arg(":edit  src"), Some(7));
+    assert_eq!(to_path_arg(":e! "), Some(4));
+    assert_eq!(to_path_arg(":w /tmp/x"), Some(3));
+    assert_eq!(to_path_arg(":e"), None);
+    assert_eq!(to_path_arg(":set wrap"), None);
+    assert_eq!(to_path_arg("e src"), None);
+}
+
+#[test]
+fn test_cmd_history() {
+    let mut app = new_code();
+    let (tx, _rx) = mpsc::channel();
+    app.subscribe("code", tx);
+    app.schemes.push(ColorScheme::default().unwrap());
+    app.add_buffer(new_buffer("test-cmd-history"));
+
+    let enter = Event::Enter(crossterm::event::KeyModifiers::empty());
+    let mut wcmd = WindowCmd::new(Coord::new(25, 1, 1, 80), &app).unwrap();
+
+    // failed commands are not recorded in history.
+    wcmd.set_content("xyz").unwrap();
+    wcmd.on_event(&mut app, enter.clone()).unwrap();
+    wcmd.set_content("goto xyz").unwrap();
+    wcmd.on_event(&mut app, enter.clone()).unwrap();
+    assert!(app.as_cmd_history().is_empty());
+
+    wcmd.set_content("goto 1").unwrap();
+    wcmd.on_event(&mut app, enter).unwrap();
+    assert_eq!(app.as_cmd_history(), &vec![":goto 1".to_string()]);
+}
@@ -1,7 +1,7 @@
 #[allow(unused_imports)]
 use log::{debug, trace};
 
-use std::{cmp, convert::TryInto, fmt, result};
+use std::{cmp, convert::TryInto, fmt, mem, ops::DerefMut, result};
 
 use crate::{
     app::Application,
@@ -12,12 +12,17 @@ use crate::{
     keymap::Keymap,
     scroll,
     syntax::{self, Syntax},
-    term::Spanline,
+    term::{self, Spanline},
     view,
     window::{Coord, Cursor, Render, WinBuffer, Window},
     Error, Result,
 };
 
+// minimum window width to render line numbers.
+const MIN_LINE_NUMBER_WIDTH: u16 = 20;
+// number of lines to scroll for every mouse wheel event.
+const WHEEL_SCROLL_LINES: usize = 3;
+
 pub struct WindowEdit {
     coord: Coord,
     cursor: Cursor,
@@ -29,11 +34,31 @@ pub struct WindowEdit {
     scheme: ColorScheme,
     keymap: Keymap,
     old_screen: Option<Vec<view::ScrLine>>,
+    // lines rendered by the last refresh, only lines that change are
+    // rendered again.
+    old_lines: Vec<Spanline>,
+    // render all lines afresh on the next refresh.
+    force_redraw: bool,
+    // folds are yet to be derived from the syntax tree, applicable only
+    // when buffer's fold_syntax is set.
+    syntax_folds: bool,
     // configuration.
     wrap: bool,
     scroll_off: u16,
     line_number: bool,
+    relative_number: bool,
+    highlight_trailing: bool,
+    list: bool,
+    cursor_line: bool,
+    tab_stop: usize,
     scroll: Option<usize>,
+    // cursor before incremental search, restored once search is done.
+    search_origin: Option<usize>,
+    // whether the last relative motion failed to move the cursor.
+    mto_failed: bool,
+    // edits applied on the buffer via this window, yet to be applied on
+    // other windows sharing the same buffer.
+    edits: Vec<Event>,
 }
 
 impl fmt::Display for WindowEdit {
@@ -63,13 +88,29 @@ impl<'a, 'b> From<(&'a code::Code, &'b Buffer, Coord)> for WindowEdit {
             altn_buf_id: None,
             syn: syntax::detect(buf, &scheme).unwrap(),
             scheme,
-            keymap: Keymap::new_edit(),
+            keymap: {
+                let mut keymap = Keymap::new_edit();
+                keymap.set_remaps(app.remaps.clone());
+                keymap
+            },
             old_screen: None,
+            old_lines: Vec::default(),
+            force_redraw: false,
+            syntax_folds: true,
             // configuration
             wrap: app.as_ref().wrap,
             scroll_off: app.as_ref().scroll_off,
             line_number: app.as_ref().line_number,
+            relative_number: app.as_ref().relative_number,
+            highlight_trailing: app.as_ref().highlight_trailing,
+            list: app.as_ref().list,
+            cursor_line: app.as_ref().cursor_line,
+            // buffer's tab_stop might be set by its modeline.
+            tab_stop: buf.tab_stop,
             scroll: None,
+            search_origin: None,
+            mto_failed: false,
+            edits: Vec::default(),
         };
         debug!("{} {} {}", w, w.scroll_off, w.line_number);
         w
@@ -77,11 +118,19 @@ impl<'a, 'b> From<(&'a code::Code, &'b Buffer, Coord)> for WindowEdit {
 }
 
 impl WindowEdit {
-    #[allow(dead_code)]
-    pub fn set_buffer(&mut self, buf: &Buffer) -> &mut Self {
+    /// Switch this window to `buf`, buffer's own cursor is retained and
+    /// the view is aligned with it.
+    pub fn set_buffer(&mut self, buf: &Buffer) -> Result<&mut Self> {
         self.altn_buf_id = Some(self.curr_buf_id.clone());
         self.curr_buf_id = buf.to_id();
-        self
+        self.syn = syntax::detect(buf, &self.scheme)?;
+        self.obc_xy = buf.to_xy_cursor(None);
+        self.cursor.row = cmp::min(self.cursor.row as usize, self.obc_xy.row) as u16;
+        self.tab_stop = buf.tab_stop;
+        self.old_screen = None;
+        self.syntax_folds = true;
+        self.edits.clear();
+        Ok(self)
     }
 
     #[allow(dead_code)]
@@ -100,9 +149,182 @@ impl WindowEdit {
     pub fn to_event_prefix(&self) -> Event {
         self.keymap.to_event_prefix()
     }
+
+    /// Move this window to `coord`, cursor is clamped within the new
+    /// coordinate and screen is rendered afresh.
+    pub fn set_coord(&mut self, coord: Coord) -> &mut Self {
+        self.coord = coord;
+        self.cursor.row = cmp::min(self.cursor.row, coord.hgt.saturating_sub(1));
+        self.cursor.col = cmp::min(self.cursor.col, coord.wth.saturating_sub(1));
+        self.old_screen = None;
+        self.force_redraw = true;
+        self
+    }
+
+    /// Render all lines afresh on the next refresh, say after the screen
+    /// is overwritten by another window.
+    pub fn set_force_redraw(&mut self) -> &mut Self {
+        self.force_redraw = true;
+        self
+    }
+
+    /// Return a new window on the same buffer, at `coord`, starting with
+    /// the same view as this window.
+    pub fn to_split(&self, app: &code::Code, coord: Coord) -> Result<WindowEdit> {
+        let buf = app.as_buffer(&self.curr_buf_id)?;
+        let mut we: WindowEdit = (app, buf, coord).into();
+        we.cursor = self.cursor;
+        we.obc_xy = self.obc_xy;
+        we.scroll = self.scroll;
+        we.set_coord(coord);
+        Ok(we)
+    }
+
+    /// Return this window's cursor as character index into `buf`. Buffer
+    /// might have changed via other windows, hence the cursor is clamped.
+    pub fn to_buffer_cursor(&self, buf: &Buffer) -> usize {
+        use crate::text;
+
+        let row = cmp::min(self.obc_xy.row, buf.to_last_line_idx());
+        let n = text::visual_line_n(&buf.line(row));
+        buf.line_to_char(row) + cmp::min(self.obc_xy.col, n.saturating_sub(1))
+    }
+
+    /// Return the first and last line-index displayed by this window, as
+    /// of its last refresh.
+    pub fn to_viewport(&self) -> Option<(usize, usize)> {
+        let lines = self.old_screen.as_ref()?.iter();
+        let mut iter = lines.filter(|sl| !sl.colk.is_empty()).map(|sl| sl.line_idx);
+        let top = iter.next()?;
+        Some((top, iter.next_back().unwrap_or(top)))
+    }
+
+    /// Pick the latest color scheme from `app`, syntax highlighting is
+    /// re-computed and screen is rendered afresh.
+    pub fn set_color_scheme(&mut self, app: &code::Code) -> Result<&mut Self> {
+        self.scheme = app.to_color_scheme(None);
+        if let Ok(buf) = app.as_buffer(&self.curr_buf_id) {
+            self.syn = syntax::detect(buf, &self.scheme)?;
+        }
+        self.old_screen = None;
+        self.syntax_folds = true;
+        Ok(self)
+    }
+
+    /// Pick the latest configuration from `app`, like wrap and line
+    /// numbers, screen is rendered afresh.
+    pub fn set_config(&mut self, app: &code::Code) -> &mut Self {
+        let config = app.as_config();
+        self.wrap = config.wrap;
+        self.scroll_off = config.scroll_off;
+        self.line_number = config.line_number;
+        self.relative_number = config.relative_number;
+        self.highlight_trailing = config.highlight_trailing;
+        self.list = config.list;
+        self.cursor_line = config.cursor_line;
+        if let Ok(buf) = app.as_buffer(&self.curr_buf_id) {
+            self.tab_stop = buf.tab_stop;
+        }
+        self.old_screen = None;
+        self.force_redraw = true;
+        self.syntax_folds = true;
+        self
+    }
+
+    /// Return edits applied on the buffer via this window, since the
+    /// last call.
+    pub fn take_edits(&mut self) -> Vec<Event> {
+        self.edits.drain(..).collect()
+    }
+
+    /// Apply edits, made via other windows sharing the same buffer, on
+    /// this window's syntax tree.
+    pub fn on_shared_edits(&mut self, app: &code::Code, edits: Vec<Event>) -> Result<()> {
+        if let Ok(buf) = app.as_buffer(&self.curr_buf_id) {
+            self.syn.on_edit(buf, Event::from(edits))?;
+        }
+        self.syntax_folds = true;
+        Ok(())
+    }
 }
 
 impl WindowEdit {
+    // fold events via keymap. For incremental search, move the cursor to
+    // the match as the pattern is typed, and restore the original cursor
+    // once the search is committed or cancelled.
+    fn fold(&mut self, app: &mut code::Code, buf: &mut Buffer, evnt: Event) -> Result<Event> {
+        use crate::pubsub::Notify;
+
+        let evnt = match evnt {
+            // mouse events are not folded, nor recorded into macros.
+            evnt @ Event::Click(_, _) => evnt,
+            Event::Wheel(dp) => {
+                let mto = event::Mto::WinScroll(WHEEL_SCROLL_LINES, Scroll::Ones, dp);
+                Event::Mt(mto)
+            }
+            evnt => self.keymap.fold(buf, evnt)?,
+        };
+        match self.keymap.to_event_prefix() {
+            Event::S(patt, dp) => {
+                let origin = *self.search_origin.get_or_insert(buf.to_char_cursor());
+                buf.set_cursor(origin);
+                if !patt.is_empty() {
+                    if let Some(cursor) = buf.search_preview(&patt, dp) {
+                        buf.set_cursor(cursor);
+                    }
+                }
+                let span = format!("{}{}", if_else!(dp == DP::Left, '?', '/'), patt);
+                app.notify("code", Notify::Status(vec![span.into()]))?;
+            }
+            _ => {
+                if let Some(origin) = self.search_origin.take() {
+                    buf.set_cursor(origin);
+                }
+            }
+        }
+
+        Ok(evnt)
+    }
+
+    // replay raw events from macro `n` times, replay is stopped early when
+    // a relative motion fails to move the cursor. Events replayed for a
+    // key-map are folded with noremap, which is restored once the replay
+    // is done, even on error.
+    fn replay_macro(&mut self, app: &mut code::Code, n: usize, es: Vec<Event>) -> Result<Event> {
+        let noremap = self.keymap.take_noremap();
+        let saved = self.keymap.is_noremap();
+        let res = self.replay_events(app, n, es, noremap);
+        self.keymap.set_noremap(saved);
+        res
+    }
+
+    fn replay_events(
+        &mut self,
+        app: &mut code::Code,
+        n: usize,
+        es: Vec<Event>,
+        noremap: usize,
+    ) -> Result<Event> {
+        let mut evnts = vec![];
+        for _ in 0..n {
+            for (i, evnt) in es.iter().enumerate() {
+                self.keymap.set_noremap(i < noremap);
+                self.mto_failed = false;
+                match self.on_event(app, evnt.clone())? {
+                    Event::Noop => (),
+                    evnt => evnts.push(evnt),
+                }
+                if self.mto_failed {
+                    break;
+                }
+            }
+            if self.mto_failed {
+                break;
+            }
+        }
+        Ok(if_else!(evnts.is_empty(), Event::Noop, Event::from(evnts)))
+    }
+
     #[inline]
     pub fn to_buffer_id(&self) -> String {
         self.curr_buf_id.clone()
@@ -126,14 +348,52 @@ impl WindowEdit {
         }
     }
 
+    // line-number column width for screen `lines`, computed just like the view.
+    fn to_nu_width(&self, buf: &Buffer, lines: &[view::ScrLine]) -> u16 {
+        let (ln, rn) = (self.config_line_number(), self.config_relative_number());
+        view::to_nu_width(lines, buf.to_xy_cursor(None).row, ln, rn).1
+    }
+
     fn to_slide_width(&self, buf: &Buffer) -> Result<usize> {
-        let (_, nu_wth) = {
+        let nu_wth = {
             let lines = self.to_edit_lines(buf)?;
-            view::to_nu_width(&lines, self.line_number)
+            self.to_nu_width(buf, &lines)
         };
         Ok((self.coord.wth.saturating_sub(nu_wth) / 2) as usize)
     }
 
+    // translate a mouse click at screen position (`col`, `row`), starting
+    // from (0, 0), into buffer char-index. Return None if the click falls
+    // outside the text area, like the number column or beyond the buffer.
+    fn to_click_cursor(&self, buf: &Buffer, col: u16, row: u16) -> Result<Option<usize>> {
+        let (x, y) = self.coord.to_origin_cursor();
+        let (col, row) = match (col.checked_sub(x), row.checked_sub(y)) {
+            (Some(col), Some(row)) if col < self.coord.wth && row < self.coord.hgt => (col, row),
+            _ => return Ok(None),
+        };
+
+        // visual column at the left edge, for wrapped lines it is None.
+        let (lines, vcol) = if self.wrap {
+            let mut v: view::Wrap = (&*self, self.obc_xy).try_into()?;
+            v.shift_cursor(buf)?;
+            (v.to_edit_lines(buf), None)
+        } else {
+            let mut v: view::NoWrap = (&*self, self.obc_xy).try_into()?;
+            v.shift_cursor(buf)?;
+            (v.to_edit_lines(buf), Some(v.to_vcol()))
+        };
+        let sl = match lines.get(row as usize) {
+            Some(sl) if !sl.colk.is_empty() && col >= sl.nu_wth => sl,
+            _ => return Ok(None),
+        };
+
+        let off = (col - sl.nu_wth) as usize;
+        let line = buf.line(sl.line_idx);
+        let home = buf.line_to_char(sl.line_idx);
+        let col = click_col(&line, sl.bc - home, sl.n as usize, off, vcol, self.tab_stop);
+        Ok(Some(home + col))
+    }
+
     fn mto_screen_home(&self, buf: &Buffer, dp: DP) -> Result<usize> {
         let lines = self.to_edit_lines(buf)?;
         let nbc = match view::cursor_line(&lines, buf.to_char_cursor()) {
@@ -155,7 +415,7 @@ impl WindowEdit {
 
     fn mto_screen_middle(&self, buf: &Buffer) -> Result<usize> {
         let lines = self.to_edit_lines(buf)?;
-        let (_, nu_wth) = view::to_nu_width(&lines, self.line_number);
+        let nu_wth = self.to_nu_width(buf, &lines);
         let middle = self.coord.wth.saturating_sub(nu_wth) / 2;
 
         let nbc = match view::cursor_line(&lines, buf.to_char_cursor()) {
@@ -186,9 +446,9 @@ impl WindowEdit {
     }
 
     fn mto_screen_up(&self, buf: &Buffer, n: usize) -> Result<usize> {
-        let (_, nu_wth) = {
+        let nu_wth = {
             let lines = self.to_edit_lines(buf)?;
-            view::to_nu_width(&lines, self.line_number)
+            self.to_nu_width(buf, &lines)
         };
 
         let name = "mto_screen_up";
@@ -209,9 +469,9 @@ impl WindowEdit {
     }
 
     fn mto_screen_down(&self, buf: &Buffer, n: usize) -> Result<usize> {
-        let (_, nu_wth) = {
+        let nu_wth = {
             let lines = self.to_edit_lines(buf)?;
-            view::to_nu_width(&lines, self.line_number)
+            self.to_nu_width(buf, &lines)
         };
 
         let name = "mto_screen_down";
@@ -231,61 +491,20 @@ impl WindowEdit {
         Ok(nbc)
     }
 
-    fn mto_win_high(&self, buf: &Buffer, n: usize) -> Result<usize> {
-        let lines: Vec<view::ScrLine> = {
-            let lines = self.to_edit_lines(buf)?;
-            let iter = lines.into_iter().filter(|line| !line.colk.is_empty());
-            iter.collect()
-        };
-        let nbc = match lines.len() {
-            0 => buf.to_char_cursor(),
-            _ => {
-                let sl = match lines.iter().skip(n.saturating_sub(1)).next() {
-                    Some(sl) => sl,
-                    None => lines.last().unwrap(),
-                };
-                let xy = buf.to_xy_cursor(Some(sl.bc));
-                let line = buf.line(xy.row);
-                sl.bc + buffer::skip_whitespace(&line, xy.col, DP::Right)?
-            }
-        };
-        Ok(nbc)
-    }
-
-    fn mto_win_middle(&self, buf: &Buffer) -> Result<usize> {
-        let lines = self.to_edit_lines(buf)?;
-        let nbc = {
-            let bc = match lines.len() {
-                0 => buf.to_char_cursor(),
-                m => lines[m / 2].bc,
-            };
-            let xy = buf.to_xy_cursor(Some(bc));
-            let line = buf.line(xy.row);
-            bc + buffer::skip_whitespace(&line, xy.col, DP::Right)?
+    // translate screen-relative motions `H`, `M` and `L` into buffer
+    // line-index, based on the lines currently displayed.
+    fn to_win_row(&self, buf: &Buffer, mto: &event::Mto) -> Result<usize> {
+        let rows: Vec<usize> = {
+            let lines = self.to_edit_lines(buf)?.into_iter();
+            let iter = lines.filter(|sl| !sl.colk.is_empty());
+            let mut rows: Vec<usize> = iter.map(|sl| sl.line_idx).collect();
+            // wrapped lines span more than one screen line.
+            rows.dedup();
+            rows
         };
-        Ok(nbc)
-    }
-
-    fn mto_win_low(&self, buf: &Buffer, n: usize) -> Result<usize> {
-        let lines: Vec<view::ScrLine> = {
-            let lines = self.to_edit_lines(buf)?;
-            let iter = lines.into_iter().filter(|line| !line.colk.is_empty());
-            iter.collect()
-        };
-        let nbc = match lines.len() {
-            0 => buf.to_char_cursor(),
-            m => {
-                let off = if m < (self.scroll_off as usize) {
-                    m.saturating_sub(n + 1)
-                } else {
-                    cmp::max(m.saturating_sub(n + 1), self.scroll_off as usize)
-                };
-                let xy = buf.to_xy_cursor(Some(lines[off].bc));
-                let line = buf.line(xy.row);
-                lines[off].bc + buffer::skip_whitespace(&line, xy.col, DP::Right)?
-            }
-        };
-        Ok(nbc)
+        let last = buf.to_last_line_idx();
+        let row = win_row(&rows, last, self.scroll_off as usize, mto);
+        Ok(row.unwrap_or_else(|| buf.to_xy_cursor(None).row))
     }
 
     fn mto_win_scroll(
@@ -310,23 +529,32 @@ impl WindowEdit {
                 let (cursor, nbc) = scroll_down(&name, self, buf, n)?;
                 Ok((nbc, Some(cursor)))
             }
-            (Scroll::Lines, DP::Left) if n == 1 => {
-                let n = self.scroll.unwrap_or((self.coord.hgt / 2) as usize);
-                let (cursor, nbc) = scroll_up(&name, self, buf, n)?;
-                Ok((nbc, Some(cursor)))
-            }
-            (Scroll::Lines, DP::Left) => {
-                let (cursor, nbc) = scroll_up(&name, self, buf, n)?;
-                Ok((nbc, Some(cursor)))
-            }
-            (Scroll::Lines, DP::Right) if n == 1 => {
-                let n = self.scroll.unwrap_or((self.coord.hgt / 2) as usize);
-                let (cursor, nbc) = scroll_down(&name, self, buf, n)?;
-                Ok((nbc, Some(cursor)))
-            }
-            (Scroll::Lines, DP::Right) => {
-                let (cursor, nbc) = scroll_down(&name, self, buf, n)?;
-                Ok((nbc, Some(cursor)))
+            (Scroll::Lines, dp) => {
+                // count, if supplied, is remembered for subsequent scrolls.
+                let n = match n {
+                    1 => self.scroll.unwrap_or((self.coord.hgt / 2) as usize),
+                    n => {
+                        self.scroll = Some(n);
+                        n
+                    }
+                };
+                let xy = buf.to_xy_cursor(None);
+                let (cursor, nbc) = match dp {
+                    DP::Left => scroll_up(&name, self, buf, n)?,
+                    _ => scroll_down(&name, self, buf, n)?,
+                };
+                self.obc_xy = buf.to_xy_cursor(Some(nbc));
+                self.cursor = cursor;
+
+                // cursor follows the scroll by as many lines, window shall
+                // catch up with the cursor when scroll hits buffer's edge.
+                let row = match dp {
+                    DP::Left => xy.row.saturating_sub(n),
+                    _ => cmp::min(xy.row.saturating_add(n), buf.to_last_line_idx()),
+                };
+                let nbc = buf.line_to_char(row);
+                let nbc = nbc + buffer::skip_whitespace(&buf.line(row), 0, DP::Right)?;
+                Ok((nbc, None))
             }
             (Scroll::Pages, DP::Left) => {
                 let n_page = self.coord.hgt.saturating_sub(2) as usize;
@@ -338,100 +566,39 @@ impl WindowEdit {
                 let (cursor, nbc) = scroll_down(&name, self, buf, n_page * n)?;
                 Ok((nbc, Some(cursor)))
             }
-            (Scroll::TextUp, pos) => {
+            (scrll @ Scroll::TextUp, pos)
+            | (scrll @ Scroll::TextCenter, pos)
+            | (scrll @ Scroll::TextBottom, pos) => {
                 let lines = self.to_edit_lines(buf)?;
-                let (_, nu_wth) = view::to_nu_width(&lines, self.line_number);
-
-                let (cursor, nbc) = if n == 0 {
-                    let nbc = lines[self.cursor.row as usize].bc;
-                    let (row, col) = (self.scroll_off, nu_wth);
-                    (Cursor { row, col }, nbc)
-                } else {
-                    buf.set_cursor(0);
-                    let (row, col) = (0, nu_wth);
-                    self.cursor = Cursor { row, col };
-
-                    let n = n.saturating_sub((self.scroll_off as usize) + 1);
-                    let (mut cursor, nbc) = scroll_down(&name, self, buf, n)?;
-                    cursor.row = self.scroll_off;
-                    (cursor, nbc)
-                };
-
-                buf.set_cursor(nbc);
-                self.obc_xy = buf.to_xy_cursor(Some(nbc));
-                self.cursor = cursor;
-
-                let nbc = match pos {
-                    DP::TextCol => {
-                        let xy = buf.to_xy_cursor(Some(nbc));
-                        let line = buf.line(xy.row);
-                        nbc + buffer::skip_whitespace(&line, xy.col, DP::Right)?
+                let nu_wth = self.to_nu_width(buf, &lines);
+
+                // with count, line `n` is scrolled instead of cursor line.
+                let last = buf.to_last_line_idx();
+                let xy = match n {
+                    0 => buf.to_xy_cursor(None),
+                    n => {
+                        let home = buf.line_to_char(cmp::min(n - 1, last));
+                        buf.to_xy_cursor(Some(home))
                     }
-                    _ => nbc,
                 };
-                Ok((nbc, None))
-            }
-            (Scroll::TextCenter, pos) => {
-                let lines = self.to_edit_lines(buf)?;
-                let (_, nu_wth) = view::to_nu_width(&lines, self.line_number);
-
-                let (cursor, nbc) = if n == 0 {
-                    let nbc = lines[self.cursor.row as usize].bc;
-                    let (row, col) = (self.coord.hgt / 2, nu_wth);
-                    (Cursor { row, col }, nbc)
-                } else {
-                    buf.set_cursor(0);
-                    let (row, col) = (0, nu_wth);
-                    self.cursor = Cursor { row, col };
-
-                    let n = n.saturating_sub((self.scroll_off as usize) + 1);
-                    let (mut cursor, nbc) = scroll_down(&name, self, buf, n)?;
-                    cursor.row = self.coord.hgt / 2;
-                    (cursor, nbc)
+                let row = {
+                    let (hgt, so) = (self.coord.hgt as usize, self.scroll_off as usize);
+                    to_scroll_row(scrll, hgt, so, xy.row, last - xy.row)
                 };
 
-                buf.set_cursor(nbc);
-                self.obc_xy = buf.to_xy_cursor(Some(nbc));
-                self.cursor = cursor;
-                let nbc = match pos {
-                    DP::TextCol => {
-                        let xy = buf.to_xy_cursor(Some(nbc));
-                        let line = buf.line(xy.row);
-                        nbc + buffer::skip_whitespace(&line, xy.col, DP::Right)?
-                    }
-                    _ => nbc,
-                };
-                Ok((nbc, None))
-            }
-            (Scroll::TextBottom, pos) => {
-                let lines = self.to_edit_lines(buf)?;
-                let (_, nu_wth) = view::to_nu_width(&lines, self.line_number);
-
-                let (cursor, nbc) = if n == 0 {
-                    let nbc = lines[self.cursor.row as usize].bc;
-                    let (row, col) = (self.coord.hgt.saturating_sub(1), nu_wth);
-                    (Cursor { row, col }, nbc)
-                } else {
-                    buf.set_cursor(0);
-                    let (row, col) = (0, nu_wth);
-                    self.cursor = Cursor { row, col };
-
-                    let n = n.saturating_sub((self.scroll_off as usize) + 1);
-                    let (mut cursor, nbc) = scroll_down(&name, self, buf, n)?;
-                    cursor.row = self.coord.hgt.saturating_sub(1);
-                    (cursor, nbc)
+                let home = buf.line_to_char(xy.row);
+                self.obc_xy = buf.to_xy_cursor(Some(home));
+                self.cursor = Cursor {
+                    row: row as u16,
+                    col: nu_wth,
                 };
 
-                buf.set_cursor(nbc);
-                self.obc_xy = buf.to_xy_cursor(Some(nbc));
-                self.cursor = cursor;
                 let nbc = match pos {
                     DP::TextCol => {
-                        let xy = buf.to_xy_cursor(Some(nbc));
                         let line = buf.line(xy.row);
-                        nbc + buffer::skip_whitespace(&line, xy.col, DP::Right)?
+                        home + buffer::skip_whitespace(&line, 0, DP::Right)?
                     }
-                    _ => nbc,
+                    _ => home + xy.col,
                 };
                 Ok((nbc, None))
             }
@@ -459,9 +626,9 @@ impl WindowEdit {
                 Ok((nbc, Some(cursor)))
             }
             (Scroll::Align, DP::Left) if !self.wrap => {
-                let (_, nu_wth) = {
+                let nu_wth = {
                     let lines = self.to_edit_lines(buf)?;
-                    view::to_nu_width(&lines, self.line_number)
+                    self.to_nu_width(buf, &lines)
                 };
                 let n = self.cursor.col.saturating_sub(nu_wth) as usize;
                 let (cursor, nbc) = scroll_right(&name, self, buf, n)?;
@@ -477,6 +644,45 @@ impl WindowEdit {
     }
 }
 
+// Return the line-index for screen motion `mto`, from `rows` displayed on
+// the window. `H` and `L` keep `scroll_off` lines from window's top and
+// bottom edges, unless the edge is buffer's first or `last` line.
+fn win_row(rows: &[usize], last: usize, scroll_off: usize, mto: &event::Mto) -> Option<usize> {
+    use crate::event::Mto::{WinH, WinL, WinM};
+
+    let (top, bottom) = (*rows.first()?, *rows.last()?);
+    let m = rows.len() - 1;
+    let off = match mto {
+        WinH(n) => {
+            let so = if_else!(top > 0, scroll_off, 0);
+            cmp::max(n.saturating_sub(1), so)
+        }
+        WinM => m / 2,
+        WinL(n) => {
+            let so = if_else!(bottom < last, scroll_off, 0);
+            cmp::min(m.saturating_sub(n.saturating_sub(1)), m.saturating_sub(so))
+        }
+        _ => return None,
+    };
+    Some(rows[cmp::min(off, m)])
+}
+
+// Return the screen row for a line, when scrolling it to the top, center
+// or bottom of the window, with `above` and `below` lines in the buffer.
+// Scroll is clamped so that window does not start before buffer's first
+// line, nor end after buffer's last line.
+fn to_scroll_row(scrll: Scroll, hgt: usize, so: usize, above: usize, below: usize) -> usize {
+    let max = hgt.saturating_sub(1);
+    let row = match scrll {
+        Scroll::TextUp => so,
+        Scroll::TextCenter => max / 2,
+        Scroll::TextBottom => max.saturating_sub(so),
+        _ => 0,
+    };
+    let row = cmp::max(row, max.saturating_sub(below));
+    cmp::min(cmp::min(row, above), max)
+}
+
 impl Window for WindowEdit {
     type App = code::Code;
 
@@ -502,7 +708,29 @@ impl Window for WindowEdit {
 
     #[inline]
     fn config_line_number(&self) -> bool {
-        self.line_number
+        // narrow windows, say after a vertical split, drop line numbers
+        // to leave room for the text.
+        self.line_number && self.coord.wth >= MIN_LINE_NUMBER_WIDTH
+    }
+
+    #[inline]
+    fn config_relative_number(&self) -> bool {
+        self.relative_number
+    }
+
+    #[inline]
+    fn config_highlight_trailing(&self) -> bool {
+        self.highlight_trailing
+    }
+
+    #[inline]
+    fn config_list(&self) -> bool {
+        self.list
+    }
+
+    #[inline]
+    fn config_cursor_line(&self) -> bool {
+        self.cursor_line
     }
 
     #[inline]
@@ -510,11 +738,16 @@ impl Window for WindowEdit {
         self.scroll_off
     }
 
+    #[inline]
+    fn config_tab_stop(&self) -> usize {
+        self.tab_stop
+    }
+
     fn on_event(&mut self, app: &mut code::Code, evnt: Event) -> Result<Event> {
         use crate::{event::Mto, pubsub::Notify};
 
         let (evnt, buf) = match app.take_buffer(&self.curr_buf_id) {
-            Some(mut buf) => match self.keymap.fold(&mut buf, evnt)? {
+            Some(mut buf) => match self.fold(app, &mut buf, evnt)? {
                 Event::Mt(Mto::ScreenHome(dp)) => {
                     let nbc = self.mto_screen_home(&buf, dp)?;
                     buf.set_cursor(nbc).clear_sticky_col();
@@ -540,20 +773,12 @@ impl Window for WindowEdit {
                     buf.set_cursor(nbc).clear_sticky_col();
                     (Event::Noop, Some(buf))
                 }
-                Event::Mt(Mto::WinH(n)) => {
-                    let nbc = self.mto_win_high(&buf, n.saturating_sub(1))?;
-                    buf.set_cursor(nbc).clear_sticky_col();
-                    (Event::Noop, Some(buf))
-                }
-                Event::Mt(Mto::WinM) => {
-                    let nbc = self.mto_win_middle(&buf)?;
-                    buf.set_cursor(nbc).clear_sticky_col();
-                    (Event::Noop, Some(buf))
-                }
-                Event::Mt(Mto::WinL(n)) => {
-                    let nbc = self.mto_win_low(&buf, n.saturating_sub(1))?;
-                    buf.set_cursor(nbc).clear_sticky_col();
-                    (Event::Noop, Some(buf))
+                Event::Mt(mto @ Mto::WinH(_))
+                | Event::Mt(mto @ Mto::WinM)
+                | Event::Mt(mto @ Mto::WinL(_)) => {
+                    let row = self.to_win_row(&buf, &mto)?;
+                    let evnt = buf.on_event(Event::Mt(Mto::Row(row + 1, DP::TextCol)))?;
+                    (evnt, Some(buf))
                 }
                 Event::Mt(mto @ Mto::WinScroll(_, _, _)) => {
                     let (nbc, cursor) = self.mto_win_scroll(&mut buf, mto)?;
@@ -567,13 +792,38 @@ impl Window for WindowEdit {
                     buf.set_cursor(nbc).clear_sticky_col();
                     (Event::Noop, Some(buf))
                 }
+                Event::Click(col, row) => {
+                    if let Some(nbc) = self.to_click_cursor(&buf, col, row)? {
+                        buf.set_cursor(nbc).clear_sticky_col();
+                    }
+                    (Event::Noop, Some(buf))
+                }
                 Event::Appn(event::Appn::StatusCursor) => {
-                    let msg = vec![self.syn.to_status_cursor()?];
+                    let msg = vec![self.syn.to_status_cursor(&mut buf)?];
                     app.notify("code", Notify::Status(msg))?;
                     (Event::Noop, Some(buf))
                 }
+                Event::Macro(n, evnts) => {
+                    app.add_buffer(buf);
+                    (self.replay_macro(app, n, evnts)?, None)
+                }
                 evnt => {
-                    let evnt = buf.on_event(evnt)?;
+                    let cursor = buf.to_char_cursor();
+                    let relative = matches!(&evnt, Event::Mt(mto) if mto.is_relative());
+                    let evnt = match buf.on_event(evnt)? {
+                        Event::Notify(notf) => {
+                            app.notify("code", notf)?;
+                            Event::Noop
+                        }
+                        evnt => evnt,
+                    };
+                    self.mto_failed = relative && cursor == buf.to_char_cursor();
+                    for e in evnt.clone() {
+                        if let Event::Edit(_) = e {
+                            self.edits.push(e);
+                            self.syntax_folds = true;
+                        }
+                    }
                     let evnt = self.syn.on_edit(&mut buf, evnt)?;
                     (evnt, Some(buf))
                 }
@@ -581,27 +831,37 @@ impl Window for WindowEdit {
             None => (evnt, None),
         };
 
-        buf.map(|buf| app.add_buffer(buf));
+        if let Some(mut buf) = buf {
+            if buf.fold_syntax && self.syntax_folds {
+                buf.set_syntax_folds(self.syn.to_folds());
+                self.syntax_folds = false;
+            }
+            app.add_buffer(buf);
+        }
         Ok(evnt)
     }
 
     fn on_refresh(&mut self, app: &mut code::Code) -> Result<()> {
-        let err = {
-            let s = format!("buffer {}", self.curr_buf_id);
-            Error::Invalid(String::new(), s)
-        };
-        let buf = err_at!(app.as_buffer(&self.curr_buf_id).ok_or(err))?;
+        let buf = app.as_buffer(&self.curr_buf_id)?;
+        if self.force_redraw {
+            self.old_lines.clear();
+            self.force_redraw = false;
+        }
+        let mut old_lines = mem::take(&mut self.old_lines);
+
+        let mut tm = err_at!(Fatal, term::TERM.lock())?;
         self.cursor = if self.wrap {
             let mut v: view::Wrap = (&*self, self.obc_xy).try_into()?;
             v.shift_cursor(buf)?;
-            let old_screen = self.old_screen.replace(v.to_edit_lines(buf));
-            v.render(buf, self, old_screen)?
+            self.old_screen = Some(v.to_edit_lines(buf));
+            v.render(buf, self, &mut old_lines, tm.deref_mut())?
         } else {
             let mut v: view::NoWrap = (&*self, self.obc_xy).try_into()?;
             v.shift_cursor(buf)?;
-            let old_screen = self.old_screen.replace(v.to_edit_lines(buf));
-            v.render(buf, self, old_screen)?
+            self.old_screen = Some(v.to_edit_lines(buf));
+            v.render(buf, self, &mut old_lines, tm.deref_mut())?
         };
+        self.old_lines = old_lines;
         self.obc_xy = buf.to_xy_cursor(None);
         debug!("obc_xy:{}", self.obc_xy);
 
@@ -620,3 +880,35 @@ impl Render for WindowEdit {
         self.syn.to_span_line(buf, a, z)
     }
 }
+
+// return the column within `line` under a click, `off` columns from the
+// left edge of the screen-line showing `n` characters from column `start`.
+// `vcol`, visual column at the left edge, is None for wrapped lines, which
+// do not expand tabs. Column is clamped to the last character.
+fn click_col(
+    line: &str,
+    start: usize,
+    n: usize,
+    off: usize,
+    vcol: Option<usize>,
+    ts: usize,
+) -> usize {
+    use crate::text;
+
+    let (col, end) = match vcol {
+        Some(vcol) => {
+            let col = text::from_vcol(line, vcol + off + 1, ts);
+            (col, text::visual_line_n(line))
+        }
+        None => {
+            let txt: String = line.chars().skip(start).take(n).collect();
+            let col = start + text::from_vcol(&txt, off + 1, 1);
+            (col, start + text::visual_line_n(&txt))
+        }
+    };
+    cmp::min(col.saturating_sub(1), end.saturating_sub(1))
+}
+
+#[cfg(test)]
+#[path = "window_edit_test.rs"]
+mod window_edit_test;
@@ -0,0 +1,70 @@
+use super::*;
+
+#[test]
+fn test_win_row() {
+    use crate::event::Mto::{WinH, WinL, WinM};
+
+    let rows: Vec<usize> = (10..20).collect();
+    assert_eq!(win_row(&rows, 100, 0, &WinH(1)), Some(10));
+    assert_eq!(win_row(&rows, 100, 0, &WinH(3)), Some(12));
+    assert_eq!(win_row(&rows, 100, 3, &WinH(1)), Some(13));
+    assert_eq!(win_row(&rows, 100, 0, &WinH(100)), Some(19));
+    assert_eq!(win_row(&rows, 100, 0, &WinM), Some(14));
+    assert_eq!(win_row(&rows, 100, 0, &WinL(1)), Some(19));
+    assert_eq!(win_row(&rows, 100, 0, &WinL(3)), Some(17));
+    assert_eq!(win_row(&rows, 100, 3, &WinL(1)), Some(16));
+    assert_eq!(win_row(&rows, 100, 0, &WinL(100)), Some(10));
+
+    // edges are buffer's first and last line.
+    let rows: Vec<usize> = (0..5).collect();
+    assert_eq!(win_row(&rows, 4, 3, &WinH(1)), Some(0));
+    assert_eq!(win_row(&rows, 4, 3, &WinL(1)), Some(4));
+    assert_eq!(win_row(&rows, 4, 3, &WinM), Some(2));
+
+    assert_eq!(win_row(&[], 4, 0, &WinM), None);
+}
+
+#[test]
+fn test_to_scroll_row() {
+    use crate::event::Scroll::{TextBottom, TextCenter, TextUp};
+
+    assert_eq!(to_scroll_row(TextUp, 20, 0, 50, 50), 0);
+    assert_eq!(to_scroll_row(TextUp, 20, 3, 50, 50), 3);
+    assert_eq!(to_scroll_row(TextCenter, 20, 0, 50, 50), 9);
+    assert_eq!(to_scroll_row(TextBottom, 20, 0, 50, 50), 19);
+    assert_eq!(to_scroll_row(TextBottom, 20, 3, 50, 50), 16);
+
+    // near the start of buffer, first line stays visible.
+    assert_eq!(to_scroll_row(TextCenter, 20, 0, 4, 50), 4);
+    assert_eq!(to_scroll_row(TextBottom, 20, 0, 4, 50), 4);
+    // near the end of buffer, last line stays at the bottom.
+    assert_eq!(to_scroll_row(TextUp, 20, 0, 50, 5), 14);
+    assert_eq!(to_scroll_row(TextCenter, 20, 0, 50, 5), 14);
+    // buffer shorter than window.
+    assert_eq!(to_scroll_row(TextUp, 20, 0, 3, 2), 3);
+}
+
+#[test]
+fn test_click_col() {
+    let line = "a\tbc\n";
+
+    // tabs are expanded, tab_stop is 4.
+    assert_eq!(click_col(line, 0, 4, 0, Some(0), 4), 0);
+    assert_eq!(click_col(line, 0, 4, 1, Some(0), 4), 1);
+    assert_eq!(click_col(line, 0, 4, 3, Some(0), 4), 1);
+    assert_eq!(click_col(line, 0, 4, 4, Some(0), 4), 2);
+    assert_eq!(click_col(line, 0, 4, 5, Some(0), 4), 3);
+    // beyond the end of line, clamped to the last character.
+    assert_eq!(click_col(line, 0, 4, 20, Some(0), 4), 3);
+    // scrolled to the right, from the middle of tab.
+    assert_eq!(click_col(line, 2, 2, 0, Some(2), 4), 1);
+    assert_eq!(click_col(line, 2, 2, 2, Some(2), 4), 2);
+
+    // wrapped lines, second screen-line starting from column 2.
+    assert_eq!(click_col(line, 2, 2, 0, None, 4), 2);
+    assert_eq!(click_col(line, 2, 2, 1, None, 4), 3);
+    assert_eq!(click_col(line, 2, 2, 5, None, 4), 3);
+
+    assert_eq!(click_col("\n", 0, 0, 5, Some(0), 4), 0);
+    assert_eq!(click_col("\n", 0, 0, 5, None, 4), 0);
+}
@@ -7,6 +7,7 @@ use crate::{
     buffer::Buffer,
     code,
     code::window_edit::WindowEdit,
+    colors::Highlight,
     event::{self, Event},
     term::Span,
     window::{Coord, Cursor, Window},
@@ -37,7 +38,7 @@ impl<'a, 'b> From<(&'a code::Code, &'b Buffer, Coord)> for WindowFile {
     fn from((app, buf, coord): (&'a code::Code, &'b Buffer, Coord)) -> Self {
         WindowFile {
             coord,
-            we: (app, buf, coord).into(),
+            we: (app, buf, to_edit_coord(coord)).into(),
         }
     }
 }
@@ -51,6 +52,66 @@ impl WindowFile {
     pub fn to_buffer_id(&self) -> String {
         self.we.to_buffer_id()
     }
+
+    /// Switch this window to `buf`, refer [WindowEdit::set_buffer].
+    pub fn set_buffer(&mut self, buf: &Buffer) -> Result<&mut Self> {
+        self.we.set_buffer(buf)?;
+        Ok(self)
+    }
+
+    /// Move this window to `coord`, refer [WindowEdit::set_coord].
+    pub fn set_coord(&mut self, coord: Coord) -> &mut Self {
+        self.coord = coord;
+        self.we.set_coord(to_edit_coord(coord));
+        self
+    }
+
+    /// Render this window afresh on the next refresh, refer
+    /// [WindowEdit::set_force_redraw].
+    pub fn set_force_redraw(&mut self) -> &mut Self {
+        self.we.set_force_redraw();
+        self
+    }
+
+    /// Return a new window on the same buffer, at `coord`, refer
+    /// [WindowEdit::to_split].
+    pub fn to_split(&self, app: &code::Code, coord: Coord) -> Result<WindowFile> {
+        let we = self.we.to_split(app, to_edit_coord(coord))?;
+        Ok(WindowFile { coord, we })
+    }
+
+    #[inline]
+    pub fn to_buffer_cursor(&self, buf: &Buffer) -> usize {
+        self.we.to_buffer_cursor(buf)
+    }
+
+    /// Refer [WindowEdit::to_viewport].
+    #[inline]
+    pub fn to_viewport(&self) -> Option<(usize, usize)> {
+        self.we.to_viewport()
+    }
+
+    /// Refer [WindowEdit::set_color_scheme].
+    pub fn set_color_scheme(&mut self, app: &code::Code) -> Result<&mut Self> {
+        self.we.set_color_scheme(app)?;
+        Ok(self)
+    }
+
+    /// Refer [WindowEdit::set_config].
+    pub fn set_config(&mut self, app: &code::Code) -> &mut Self {
+        self.we.set_config(app);
+        self
+    }
+
+    #[inline]
+    pub fn take_edits(&mut self) -> Vec<Event> {
+        self.we.take_edits()
+    }
+
+    #[inline]
+    pub fn on_shared_edits(&mut self, app: &code::Code, edits: Vec<Event>) -> Result<()> {
+        self.we.on_shared_edits(app, edits)
+    }
 }
 
 impl WindowFile {
@@ -77,11 +138,7 @@ impl WindowFile {
 
     fn status_file(&self, app: &code::Code) -> Result<Span> {
         let alt = format!("--display-error--");
-        let b = {
-            let id = self.we.to_buffer_id();
-            let err = Error::Invalid(String::new(), format!("buffer {}", id));
-            app.as_buffer(&id).ok_or(err)
-        }?;
+        let b = app.as_buffer(&self.we.to_buffer_id())?;
 
         let l_name = {
             let loc = b.to_location();
@@ -116,22 +173,25 @@ impl WindowFile {
     fn do_refresh(&mut self, app: &code::Code) -> Result<()> {
         use std::iter::repeat;
 
+        let canvas = app.to_color_scheme(None).to_style(Highlight::Canvas);
+
         if self.is_top_margin() {
             let iter = repeat(app.as_ref().top_margin_char);
             let span = {
                 let st = String::from_iter(iter.take(self.coord.wth as usize));
                 let mut span: Span = st.into();
                 span.set_cursor(self.coord.to_top_left());
-                span
+                span.using(canvas.clone())
             };
             err_at!(Fatal, termqu!(span))?;
         }
         if self.is_left_margin() {
             let st = app.as_ref().left_margin_char.to_string();
-            for _i in 0..self.coord.hgt {
+            let top = if_else!(self.is_top_margin(), 1, 0);
+            for row in top..self.coord.hgt {
                 let mut span: Span = st.clone().into();
-                span.set_cursor(self.coord.to_top_left());
-                err_at!(Fatal, termqu!(span))?;
+                span.set_cursor(self.coord.to_top_left().move_by(0, row as i16));
+                err_at!(Fatal, termqu!(span.using(canvas.clone())))?;
             }
         }
 
@@ -139,6 +199,19 @@ impl WindowFile {
     }
 }
 
+// Return the coordinate for edit-window, leaving out the top margin and
+// left margin, if any.
+fn to_edit_coord(coord: Coord) -> Coord {
+    let (col, row) = coord.to_origin();
+    let top = if_else!(row > 1, 1, 0);
+    let left = if_else!(col > 1, 1, 0);
+    let (hgt, wth) = (
+        coord.hgt.saturating_sub(top),
+        coord.wth.saturating_sub(left),
+    );
+    Coord::new(col + left, row + top, hgt, wth)
+}
+
 impl Window for WindowFile {
     type App = code::Code;
 
@@ -167,11 +240,36 @@ impl Window for WindowFile {
         self.we.config_line_number()
     }
 
+    #[inline]
+    fn config_relative_number(&self) -> bool {
+        self.we.config_relative_number()
+    }
+
+    #[inline]
+    fn config_highlight_trailing(&self) -> bool {
+        self.we.config_highlight_trailing()
+    }
+
+    #[inline]
+    fn config_list(&self) -> bool {
+        self.we.config_list()
+    }
+
+    #[inline]
+    fn config_cursor_line(&self) -> bool {
+        self.we.config_cursor_line()
+    }
+
     #[inline]
     fn config_scroll_offset(&self) -> u16 {
         self.we.config_scroll_offset()
     }
 
+    #[inline]
+    fn config_tab_stop(&self) -> usize {
+        self.we.config_tab_stop()
+    }
+
     fn on_event(&mut self, app: &mut code::Code, evnt: Event) -> Result<Event> {
         use crate::pubsub::Notify;
 
@@ -1,7 +1,7 @@
 #[allow(unused_imports)]
 use log::debug;
 
-use std::{fmt, result};
+use std::{cmp, fmt, result};
 
 use crate::{
     colors::{ColorScheme, Highlight},
@@ -48,9 +48,14 @@ impl ColKind {
 #[derive(Clone)]
 /// Line number rendering. Starts from 1 till last line the buffer, width is
 /// padded with adequate spaces on the left, and one space to the right.
+///
+/// In relative mode, lines are numbered by their distance from the cursor
+/// line, while the cursor line is numbered as is. Width is computed from
+/// the larger of cursor's line number and the largest distance displayed.
 pub struct ColNu {
     width: u16,
     line_number: bool,
+    cursor_row: Option<usize>,
     style_line_nr: Style,
     style_empty: Style,
 }
@@ -63,20 +68,14 @@ impl fmt::Display for ColNu {
 
 impl ColNu {
     pub fn new(line_idx: usize, line_number: bool) -> Self {
-        let width = {
-            use crate::buffer::MAX_LINES;
-
-            assert!(line_idx < MAX_LINES, "assert {}", line_idx);
-            // line number rendering starts from 1..
-            match (line_idx + 1).to_string().len() {
-                0 | 1 | 2 => 3 + 1,
-                n => n + 1,
-            }
-        };
+        use crate::buffer::MAX_LINES;
 
+        assert!(line_idx < MAX_LINES, "assert {}", line_idx);
+        // line number rendering starts from 1..
         ColNu {
-            width: width as u16,
+            width: to_nu_width(line_idx + 1),
             line_number,
+            cursor_row: None,
             style_line_nr: Style::default(),
             style_empty: Style::default(),
         }
@@ -90,6 +89,14 @@ impl ColNu {
         self
     }
 
+    /// Number lines relative to the cursor line at `cursor_row`, `distance`
+    /// is the largest distance of a displayed line from the cursor line.
+    pub fn set_relative(&mut self, cursor_row: usize, distance: usize) -> &mut Self {
+        self.width = to_nu_width(cmp::max(cursor_row + 1, distance));
+        self.cursor_row = Some(cursor_row);
+        self
+    }
+
     #[inline]
     pub fn to_width(&self) -> u16 {
         if_else!(self.line_number, self.width, 0)
@@ -102,6 +109,10 @@ impl ColNu {
 
         match nu {
             Nu(nu) if self.line_number => {
+                let nu = match self.cursor_row {
+                    Some(row) if nu != row + 1 => nu.max(row + 1) - nu.min(row + 1),
+                    _ => nu,
+                };
                 let span: Span = format!("{:>w$} ", nu, w = width).into();
                 span.using(self.style_line_nr.clone())
             }
@@ -117,3 +128,16 @@ impl ColNu {
         }
     }
 }
+
+// width of the column to render line number `nu`, padded with one space.
+fn to_nu_width(nu: usize) -> u16 {
+    let width = match nu.to_string().len() {
+        0 | 1 | 2 => 3 + 1,
+        n => n + 1,
+    };
+    width as u16
+}
+
+#[cfg(test)]
+#[path = "col_nu_test.rs"]
+mod col_nu_test;
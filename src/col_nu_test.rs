@@ -0,0 +1,34 @@
+use super::*;
+
+#[test]
+fn test_col_nu_relative() {
+    let mut nu = ColNu::new(120, true);
+    assert_eq!(nu.to_width(), 4);
+    assert_eq!(nu.to_span(ColKind::Nu(7)).content, "  7 ");
+
+    nu.set_relative(9, 111);
+    assert_eq!(nu.to_width(), 4);
+    assert_eq!(nu.to_span(ColKind::Nu(10)).content, " 10 ");
+    assert_eq!(nu.to_span(ColKind::Nu(7)).content, "  3 ");
+    assert_eq!(nu.to_span(ColKind::Nu(121)).content, "111 ");
+    assert_eq!(nu.to_span(ColKind::Wrap).content, "    ");
+
+    // width is from cursor's line number or the largest distance.
+    let mut nu = ColNu::new(1200, true);
+    assert_eq!(nu.to_width(), 5);
+    nu.set_relative(98, 30);
+    assert_eq!(nu.to_width(), 4);
+    assert_eq!(nu.to_span(ColKind::Nu(99)).content, " 99 ");
+    assert_eq!(nu.to_span(ColKind::Nu(129)).content, " 30 ");
+    nu.set_relative(998, 30);
+    assert_eq!(nu.to_width(), 4);
+    nu.set_relative(999, 30);
+    assert_eq!(nu.to_width(), 5);
+    nu.set_relative(98, 1100);
+    assert_eq!(nu.to_width(), 5);
+
+    let mut nu = ColNu::new(120, false);
+    nu.set_relative(9, 111);
+    assert_eq!(nu.to_width(), 0);
+    assert_eq!(nu.to_span(ColKind::Nu(7)).content, "");
+}
@@ -100,6 +100,12 @@ impl ColorScheme {
     pub fn to_style(&self, hl: Highlight) -> Style {
         self.hs[hl as u32 as usize].clone()
     }
+
+    /// Override the style for highlight `hl`.
+    pub fn set_style(&mut self, hl: Highlight, style: Style) -> &mut Self {
+        self.hs[hl as u32 as usize] = style;
+        self
+    }
 }
 
 macro_rules! highlight {
@@ -192,12 +198,15 @@ highlight![
     (LineNr, "line-nr"),
     (Prompt, "prompt"),
     (StatusLine, "status-line"),
+    (Whitespace, "whitespace"),
+    (NonText, "non-text"),
+    (CursorLine, "cursor-line"),
+    (Folded, "folded"),
     //ColorColumn
     //Conceal
     //Cursor
     //CursorColumn
     //CursorIM
-    //CursorLine
     //CursorLineNr
     //DiffAdd
     //DiffChange
@@ -207,7 +216,6 @@ highlight![
     //EndOfBuffer
     //ErrorMsg
     //FoldColumn
-    //Folded
     //IncSearch
     //lCursor
     //LineNrAbove
@@ -216,7 +224,6 @@ highlight![
     //Menu
     //ModeMsg
     //MoreMsg
-    //NonText
     //Pmenu
     //PmenuSbar
     //PmenuSel
@@ -83,7 +83,7 @@ macro_rules! config {
             type Error = Error;
 
             fn try_from(fname: ffi::OsString) -> Result<Self> {
-                err_at!(IOError, fs::read(&fname))?.as_slice().try_into()
+                fs::read(&fname)?.as_slice().try_into()
             }
         }
 
@@ -124,7 +124,7 @@ impl TryFrom<ConfigFile> for toml::Value {
     fn try_from(cf: ConfigFile) -> Result<Self> {
         use std::str::from_utf8;
 
-        let bytes = err_at!(IOError, fs::read(cf.0))?;
+        let bytes = fs::read(cf.0)?;
         let s = err_at!(FailConvert, from_utf8(&bytes))?;
         err_at!(FailConvert, s.parse())
     }
@@ -184,7 +184,7 @@ pub fn read_config(toml_file: Option<String>, ftype: Option<String>) -> Result<t
 
     if let Some(toml_file) = toml_file {
         let toml_file: ffi::OsString = toml_file.into();
-        files.push(err_at!(IOError, fs::canonicalize(&toml_file))?);
+        files.push(fs::canonicalize(&toml_file)?);
     }
 
     let mut config: toml::map::Map<String, toml::Value> = Default::default();
@@ -230,7 +230,7 @@ pub fn read_color_schemes() -> Result<Vec<ColorScheme>> {
                 parts.iter().collect()
             };
             let value: Option<toml::Value> = {
-                let bytes = err_at!(IOError, fs::read(&fp))?;
+                let bytes = fs::read(&fp)?;
                 match from_utf8(&bytes) {
                     Ok(s) => s.parse().ok(),
                     Err(_) => {
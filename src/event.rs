@@ -3,11 +3,12 @@
 //! Other events are created by application's `keymap` or application
 //! components.
 
-use crossterm::event::{Event as TermEvent, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{
+    Event as TermEvent, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent,
+};
 #[allow(unused_imports)]
 use log::debug;
 use tree_sitter as ts;
-use unicode_width::UnicodeWidthChar;
 
 use std::{fmt, iter::FromIterator, mem, result};
 
@@ -41,25 +42,46 @@ pub enum Event {
     BackTab,
     Esc,
     // prefix events
-    N(usize),     // Num-prefix (n,)
-    G(usize),     // Global     (n,)
-    B(usize, DP), // Bracket    (n, Left/Right)
-    F(usize, DP), // Find-char  (n, Left/Right)
-    T(usize, DP), // Till-char  (n, Left/Right)
-    J(char),      // jump prefix (['`],)
-    Z(usize),     // scroll prefix (n,)
-    M,            // mark prefix
-    Op(Opr),      // Operation  (op-event)
+    N(usize),       // Num-prefix (n,)
+    G(usize),       // Global     (n,)
+    B(usize, DP),   // Bracket    (n, Left/Right)
+    F(usize, DP),   // Find-char  (n, Left/Right)
+    T(usize, DP),   // Till-char  (n, Left/Right)
+    J(char),        // jump prefix (['`],)
+    O(usize, bool), // text-object prefix (n, around)
+    Z(usize),       // scroll prefix (n,)
+    R(usize),       // replace-char prefix (n,)
+    Q,              // record-macro prefix
+    At(usize),      // replay-macro prefix (n,)
+    W,              // window prefix
+    M,              // mark prefix
+    Quote,          // register prefix
+    S(String, DP),  // search prefix (pattern, Left/Right)
+    Op(Opr),        // Operation  (op-event)
     // folded events for buffer management.
-    Mt(Mto),        // Motion     (n, motion-event)
-    Mr(mark::Mark), // (mark-value,)
-    Md(Mod),        // modal command.
-    Wr(Cud),        // insert command.
+    Mt(Mto),                  // Motion     (n, motion-event)
+    Mr(mark::Mark),           // (mark-value,)
+    Reg(char),                // (register,) for next delete/change/yank/put
+    Md(Mod),                  // modal command.
+    Wr(Cud),                  // insert command.
+    Undo(usize),              // (n,) undo changes
+    Redo(usize),              // (n,) redo changes
+    Put(usize, char, DP),     // (n, register, Left/Right) put text
+    Dot(usize),               // (n,) repeat last change
+    Join(usize),              // (n,) join lines
+    Fold(bool),               // (open,) open or close fold under cursor
+    ReplaceChar(usize, char), // (n, char) replace chars under cursor
+    Macro(usize, Vec<Event>), // (n, raw-events) replay macro
     TabInsert(String),
     TabClear,
+    Paste(String), // text pasted into terminal, inserted verbatim
     // other events
     Appn(Appn),
-    JumpFrom(usize), // (cursor,)
+    JumpFrom(usize),  // (cursor,)
+    Resize(u16, u16), // (cols, rows) terminal resized
+    Click(u16, u16),  // (col, row) left mouse button pressed
+    Wheel(DP),        // mouse wheel scrolled, Left is up and Right is down
+    Quit,             // quit the application
     // local events
     Edit(Edit),
     List(Vec<Event>),
@@ -82,16 +104,21 @@ impl Event {
             Home(m) | End(m) | PageUp(m) | PageDown(m) => m,
             BackTab | Esc => empty,
             // prefix events
-            N(_) | G(_) | B(_, _) | F(_, _) | T(_, _) | M | J(_) | Z(_) => empty,
+            N(_) | G(_) | B(_, _) | F(_, _) | T(_, _) | M | J(_) | Z(_) | R(_) => empty,
+            O(_, _) => empty,
+            Q | At(_) | W | S(_, _) | Quote => empty,
             Op(op) => op.to_modifiers(),
             // folded events for buffer management.
-            Mr(_) => empty,
+            Mr(_) | Reg(_) => empty,
             Md(mode) => mode.to_modifiers(),
             Mt(mto) => mto.to_modifiers(),
             Wr(cud) => cud.to_modifiers(),
-            TabInsert(_) | TabClear => empty,
+            Undo(_) | Redo(_) | Put(_, _, _) | Dot(_) | Join(_) => empty,
+            ReplaceChar(_, _) | Macro(_, _) | Fold(_) => empty,
+            TabInsert(_) | TabClear | Paste(_) => empty,
             // other events
-            Appn(_) | JumpFrom(_) => empty,
+            Appn(_) | JumpFrom(_) | Resize(_, _) | Quit => empty,
+            Click(_, _) | Wheel(_) => empty,
             // local events
             Edit(_) | List(_) | Notify(_) | Noop => empty,
         }
@@ -124,7 +151,7 @@ impl Event {
     /// FIFO. This is useful when more events are accumulated as it gets
     /// processed across the pipeline.
     pub fn push(&mut self, evnt: Event) {
-        *self = match (self.clone(), evnt) {
+        *self = match (mem::replace(self, Event::Noop), evnt) {
             (old_evnt, Event::Noop) => old_evnt,
             (Event::Noop, evnt) => evnt,
             (Event::List(mut evnts), evnt) => {
@@ -252,19 +279,40 @@ impl fmt::Display for Event {
             F(n, dp) => write!(f, "f({},{})", n, dp),
             T(n, dp) => write!(f, "t({},{})", n, dp),
             M => write!(f, "m"),
+            Quote => write!(f, "quote"),
+            S(patt, dp) => write!(f, "s({:?},{})", patt, dp),
             J(ch) => write!(f, "j({})", ch),
+            O(n, around) => write!(f, "o({},{})", n, around),
             Z(n) => write!(f, "z({})", n),
+            R(n) => write!(f, "r({})", n),
+            Q => write!(f, "q"),
+            At(n) => write!(f, "at({})", n),
+            W => write!(f, "w"),
             Op(opr) => write!(f, "op({})", opr),
             // folded events for buffer management.
             Mr(mark) => write!(f, "mark({})", mark),
+            Reg(index) => write!(f, "reg({:?})", index),
             Mt(mt) => write!(f, "mt({})", mt),
             Md(mode) => write!(f, "md({})", mode),
             Wr(cud) => write!(f, "wr({})", cud),
+            Undo(n) => write!(f, "undo({})", n),
+            Redo(n) => write!(f, "redo({})", n),
+            Dot(n) => write!(f, "dot({})", n),
+            Join(n) => write!(f, "join({})", n),
+            Fold(open) => write!(f, "fold({})", open),
+            ReplaceChar(n, ch) => write!(f, "replace-char({},{:?})", n, ch),
+            Macro(n, es) => write!(f, "macro({},{})", n, es.len()),
+            Put(n, reg, dp) => write!(f, "put({},{:?},{})", n, reg, dp),
             TabInsert(_) => write!(f, "tab-insert"),
             TabClear => write!(f, "tab-clear"),
+            Paste(txt) => write!(f, "paste({})", txt.chars().count()),
             // other events
             Appn(cd) => write!(f, "Appn({})", cd),
             JumpFrom(cursor) => write!(f, "jump-from({})", cursor),
+            Resize(cols, rows) => write!(f, "resize({},{})", cols, rows),
+            Click(col, row) => write!(f, "click({},{})", col, row),
+            Wheel(dp) => write!(f, "wheel({})", dp),
+            Quit => write!(f, "quit"),
             // local events
             Edit(val) => write!(f, "edit({})", val),
             List(es) => write!(f, "list({})", es.len()),
@@ -302,6 +350,12 @@ impl From<TermEvent> for Event {
                     _ => Event::Noop,
                 }
             }
+            TermEvent::Resize(cols, rows) => Event::Resize(cols, rows),
+            TermEvent::Mouse(MouseEvent::Down(MouseButton::Left, col, row, _)) => {
+                Event::Click(col, row)
+            }
+            TermEvent::Mouse(MouseEvent::ScrollUp(_, _, _)) => Event::Wheel(DP::Left),
+            TermEvent::Mouse(MouseEvent::ScrollDown(_, _, _)) => Event::Wheel(DP::Right),
             _ => Event::Noop,
         }
     }
@@ -391,37 +445,23 @@ impl Edit {
 
 impl Edit {
     pub fn into_ts_input(self, buf: &Buffer) -> Result<ts::InputEdit> {
-        let (st, oe, ne) = match self {
-            Edit::Ins { cursor, txt } => {
-                let n = txt.chars().count();
-                (cursor, cursor, cursor + n)
-            }
-            Edit::Del { cursor, txt } => {
-                let n = txt.chars().count();
-                (cursor, cursor + n, cursor)
-            }
-            Edit::Chg { cursor, oldt, newt } => {
-                let m = oldt.chars().count();
-                let n = newt.chars().count();
-                (cursor, cursor + m, cursor + n)
-            }
+        let (st, oldt, newt) = match self {
+            Edit::Ins { cursor, txt } => (cursor, String::default(), txt),
+            Edit::Del { cursor, txt } => (cursor, txt, String::default()),
+            Edit::Chg { cursor, oldt, newt } => (cursor, oldt, newt),
+        };
+        let start_byte = Self::to_byte(st, buf);
+        let start_position = {
+            let (row, column) = Self::to_xy(st, buf);
+            ts::Point { row, column }
         };
         Ok(ts::InputEdit {
-            start_byte: Self::to_byte(st, buf),
-            old_end_byte: Self::to_byte_end(oe, buf)?,
-            new_end_byte: Self::to_byte_end(ne, buf)?,
-            start_position: {
-                let (row, column) = Self::to_xy(st, buf);
-                ts::Point { row, column }
-            },
-            old_end_position: {
-                let (row, column) = Self::to_xy_end(st, buf)?;
-                ts::Point { row, column }
-            },
-            new_end_position: {
-                let (row, column) = Self::to_xy_end(st, buf)?;
-                ts::Point { row, column }
-            },
+            start_byte,
+            old_end_byte: start_byte + oldt.len(),
+            new_end_byte: start_byte + newt.len(),
+            start_position,
+            old_end_position: Self::to_xy_end(start_position, &oldt),
+            new_end_position: Self::to_xy_end(start_position, &newt),
         })
     }
 
@@ -429,30 +469,25 @@ impl Edit {
         buf.char_to_byte(cursor)
     }
 
-    fn to_byte_end(cursor: usize, buf: &Buffer) -> Result<usize> {
-        let m = match buf.chars_at(cursor, DP::Right)?.next() {
-            Some(ch) => ch.width().unwrap_or(0).saturating_sub(1),
-            None => 0,
-        };
-        Ok(buf.char_to_byte(cursor) + m)
-    }
-
     fn to_xy(cursor: usize, buf: &Buffer) -> (usize, usize) {
         let buffer::Cursor { row, .. } = buf.to_xy_cursor(Some(cursor));
-        let row = buf.char_to_byte(buf.line_to_char(row));
-        let col = buf.char_to_byte(cursor).saturating_sub(row);
+        let home = buf.char_to_byte(buf.line_to_char(row));
+        let col = buf.char_to_byte(cursor).saturating_sub(home);
         (row, col)
     }
 
-    fn to_xy_end(cursor: usize, buf: &Buffer) -> Result<(usize, usize)> {
-        let m = match buf.chars_at(cursor, DP::Right)?.next() {
-            Some(ch) => ch.width().unwrap_or(0).saturating_sub(1),
-            None => 0,
-        };
-        let buffer::Cursor { row, .. } = buf.to_xy_cursor(Some(cursor));
-        let row = buf.char_to_byte(buf.line_to_char(row));
-        let col = buf.char_to_byte(cursor).saturating_sub(row);
-        Ok((row, col + m))
+    // end position, after `txt` is laid out from `start` position.
+    fn to_xy_end(start: ts::Point, txt: &str) -> ts::Point {
+        match txt.rfind('\n') {
+            Some(off) => ts::Point {
+                row: start.row + txt.matches('\n').count(),
+                column: txt.len() - (off + 1),
+            },
+            None => ts::Point {
+                row: start.row,
+                column: start.column + txt.len(),
+            },
+        }
     }
 }
 
@@ -556,6 +591,81 @@ impl fmt::Display for Opr {
 }
 
 impl Opr {
+    /// Return the count for this operation.
+    pub fn to_count(&self) -> usize {
+        self.clone().split().0
+    }
+
+    /// Return the motion-command for this operator.
+    pub fn to_motion(&self) -> Mto {
+        self.clone().split().1
+    }
+
+    /// Return whether `ch`, when repeated after the operator key, shall
+    /// operate linewise, as in `dd`, `yy`, `guu`.
+    pub fn is_linewise_key(&self, ch: char) -> bool {
+        match (self, ch) {
+            (Opr::Change(_, _), 'c') => true,
+            (Opr::Delete(_, _), 'd') => true,
+            (Opr::Yank(_, _), 'y') => true,
+            (Opr::Swapcase(_, _), '~') => true,
+            (Opr::Lowercase(_, _), 'u') => true,
+            (Opr::Uppercase(_, _), 'U') => true,
+            (Opr::Filter(_, _), '!') => true,
+            (Opr::Equal(_, _), '=') => true,
+            (Opr::Format(_, _), 'w') | (Opr::Format(_, _), 'q') => true,
+            (Opr::Encode(_, _), '?') => true,
+            (Opr::RShift(_, _), '>') => true,
+            (Opr::LShift(_, _), '<') => true,
+            (Opr::Func(_, _), '@') => true,
+            _ => false,
+        }
+    }
+
+    /// Operate with motion-command `mto`.
+    pub fn into_motion(self, mto: Mto) -> Self {
+        self.map(|n, _| (n, mto))
+    }
+
+    /// Operate linewise on `n` lines from the cursor, multiplied by
+    /// operator's own count.
+    pub fn into_linewise(self, n: usize) -> Self {
+        self.map(|m, _| {
+            let n = n.saturating_mul(m).saturating_sub(1);
+            (1, Mto::Down(n, DP::None))
+        })
+    }
+
+    fn map<F>(self, f: F) -> Self
+    where
+        F: FnOnce(usize, Mto) -> (usize, Mto),
+    {
+        let (n, mto, opr) = self.split();
+        let (n, mto) = f(n, mto);
+        opr(n, mto)
+    }
+
+    fn split(self) -> (usize, Mto, fn(usize, Mto) -> Opr) {
+        use Opr::*;
+
+        match self {
+            Change(n, mto) => (n, mto, Change),
+            Delete(n, mto) => (n, mto, Delete),
+            Yank(n, mto) => (n, mto, Yank),
+            Swapcase(n, mto) => (n, mto, Swapcase),
+            Lowercase(n, mto) => (n, mto, Lowercase),
+            Uppercase(n, mto) => (n, mto, Uppercase),
+            Filter(n, mto) => (n, mto, Filter),
+            Equal(n, mto) => (n, mto, Equal),
+            Format(n, mto) => (n, mto, Format),
+            Encode(n, mto) => (n, mto, Encode),
+            RShift(n, mto) => (n, mto, RShift),
+            LShift(n, mto) => (n, mto, LShift),
+            Fold(n, mto) => (n, mto, Fold),
+            Func(n, mto) => (n, mto, Func),
+        }
+    }
+
     fn to_modifiers(&self) -> KeyModifiers {
         KeyModifiers::empty()
     }
@@ -569,6 +679,9 @@ pub enum Mod {
     Append(usize, DP),  // (n, Right/End)
     Replace(usize, DP), // (n, None/TextCol)
     Open(usize, DP),    // (n, Left/Right)
+    Visual(bool),       // (linewise,)
+    VisualBlock,
+    Reselect,
 }
 
 impl fmt::Display for Mod {
@@ -579,6 +692,9 @@ impl fmt::Display for Mod {
             Mod::Append(n, dp) => write!(f, "append({},{})", n, dp),
             Mod::Replace(n, dp) => write!(f, "replace({},{})", n, dp),
             Mod::Open(n, dp) => write!(f, "open({},{})", n, dp),
+            Mod::Visual(linewise) => write!(f, "visual({})", linewise),
+            Mod::VisualBlock => write!(f, "visual-block"),
+            Mod::Reselect => write!(f, "reselect"),
         }
     }
 }
@@ -664,6 +780,34 @@ impl fmt::Display for Scroll {
     }
 }
 
+/// Text object, selected around the cursor, for operators. Inner object
+/// excludes the surrounding delimiters, or white-space, while around
+/// object includes them.
+#[derive(Clone, Eq, PartialEq)]
+pub enum TextObject {
+    Word(bool),              // (around,) `iw`, `aw`
+    WWord(bool),             // (around,) `iW`, `aW`
+    Quote(char, bool),       // (quote, around) `i"`, `a'`, ...
+    Block(char, char, bool), // (yin, yan, around) `i(`, `a{`, ...
+    Sentence(bool),          // (around,) `is`, `as`
+    Para(bool),              // (around,) `ip`, `ap`
+}
+
+impl fmt::Display for TextObject {
+    fn fmt(&self, f: &mut fmt::Formatter) -> result::Result<(), fmt::Error> {
+        match self {
+            TextObject::Word(around) => write!(f, "word({})", around),
+            TextObject::WWord(around) => write!(f, "wword({})", around),
+            TextObject::Quote(ch, around) => write!(f, "quote({:?},{})", ch, around),
+            TextObject::Block(yin, yan, around) => {
+                write!(f, "block({:?},{:?},{})", yin, yan, around)
+            }
+            TextObject::Sentence(around) => write!(f, "sentence({})", around),
+            TextObject::Para(around) => write!(f, "para({})", around),
+        }
+    }
+}
+
 /// Event argument specify the cursor motion.
 #[derive(Clone, Eq, PartialEq)]
 pub enum Mto {
@@ -685,7 +829,7 @@ pub enum Mto {
     Down(usize, DP),       // (n, TextCol/StickyCol/None)
     Row(usize, DP),        // (n, TextCol/None)
     Percent(usize, DP),    // (n, TextCol/None)
-    Cursor(usize),         // (n,)
+    Cursor(usize),         // (n,) n-th character, starting from 1
     ScreenUp(usize, DP),   // (n, None)
     ScreenDown(usize, DP), // (n, None)
     // word/sentence/para wise motion
@@ -702,11 +846,15 @@ pub enum Mto {
     MatchPair,
     UnmatchPair(usize, char, DP), // (n, marker, Left/Right)
     // jumps and marks
-    Jump(char, char), // (['`], [a-zA-Z0-9])
+    Jump(char, char),      // (['`], [a-zA-Z0-9])
+    JumpList(usize, DP),   // (n, Left/Right) older/newer in jump-list
+    ChangeList(usize, DP), // (n, Left/Right) older/newer in change-list
 
     Bracket(usize, char, char, DP),     // (n, yin, yan, Left/Right)
     Pattern(usize, Option<String>, DP), // (n, pattern, Left/Right)
     PatternR(usize, DP),                // repeat pattern (n, Left/Right)
+    SearchWord(usize, DP),              // word under cursor (n, Left/Right)
+    Object(usize, TextObject),          // (n, text-object) for operators
     None,
 }
 
@@ -753,6 +901,8 @@ impl fmt::Display for Mto {
                 write!(f, "unmatch-pair({},{},{})", n, ch, dir /* for exprs */)
             }
             Mto::Jump(typ, ch) => write!(f, "mark-jump({},{})", typ, ch),
+            Mto::JumpList(n, dp) => write!(f, "jump-list({},{})", n, dp),
+            Mto::ChangeList(n, dp) => write!(f, "change-list({},{})", n, dp),
 
             Mto::Bracket(n, ch1, ch2, dp) => {
                 //
@@ -760,6 +910,8 @@ impl fmt::Display for Mto {
             }
             Mto::Pattern(n, _, dp) => write!(f, "pattern({},{})", n, dp),
             Mto::PatternR(n, dp) => write!(f, "patternr({},{})", n, dp),
+            Mto::SearchWord(n, dp) => write!(f, "search-word({},{})", n, dp),
+            Mto::Object(n, obj) => write!(f, "object({},{})", n, obj),
             Mto::None => write!(f, "none"),
         }
     }
@@ -780,6 +932,35 @@ impl Mto {
         Ok(evnt)
     }
 
+    /// Return whether motion is relative to the cursor. Such motions are
+    /// said to fail when they can't move the cursor, like at buffer
+    /// boundaries or when there is no match.
+    pub fn is_relative(&self) -> bool {
+        use Mto::*;
+
+        match self {
+            Left(_, _) | Right(_, _) | Up(_, _) | Down(_, _) => true,
+            CharF(_, _, _) | CharT(_, _, _) | CharR(_, _) => true,
+            Word(_, _, _) | WWord(_, _, _) | Sentence(_, _) | Para(_, _) => true,
+            UnmatchPair(_, _, _) | Bracket(_, _, _, _) => true,
+            Pattern(_, _, _) | PatternR(_, _) | SearchWord(_, _) => true,
+            _ => false,
+        }
+    }
+
+    /// Return whether motion is a large jump, cursor before such motions
+    /// is remembered in the jump-list.
+    pub fn is_jump(&self) -> bool {
+        use Mto::*;
+
+        match self {
+            Row(_, _) | Percent(_, _) | Cursor(_) | MatchPair => true,
+            Sentence(_, _) | Para(_, _) | Jump(_, _) => true,
+            Pattern(_, _, _) | PatternR(_, _) | SearchWord(_, _) => true,
+            _ => false,
+        }
+    }
+
     fn to_modifiers(&self) -> KeyModifiers {
         KeyModifiers::empty()
     }
@@ -792,17 +973,32 @@ pub enum Appn {
     Prompt(Box<WindowPrompt>),
     StatusFile,
     StatusCursor,
+    SplitH,
+    SplitV,
+    FocusNext,
+    Buffer(String),
+    ColorScheme,
+    Config,
+    Command(String),
 }
 
 impl fmt::Display for Appn {
     fn fmt(&self, f: &mut fmt::Formatter) -> result::Result<(), fmt::Error> {
-        use Appn::{Less, Prompt, StatusCursor, StatusFile};
+        use Appn::{Buffer, ColorScheme, Command, Config, FocusNext, Less, Prompt};
+        use Appn::{SplitH, SplitV, StatusCursor, StatusFile};
 
         match self {
             Less(_) => write!(f, "less"),
             Prompt(_) => write!(f, "prompt"),
             StatusFile => write!(f, "status_file"),
             StatusCursor => write!(f, "status_cursor"),
+            SplitH => write!(f, "split_h"),
+            SplitV => write!(f, "split_v"),
+            FocusNext => write!(f, "focus_next"),
+            Buffer(id) => write!(f, "buffer({:?})", id),
+            ColorScheme => write!(f, "color_scheme"),
+            Config => write!(f, "config"),
+            Command(content) => write!(f, "command({:?})", content),
         }
     }
 }
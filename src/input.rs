@@ -0,0 +1,337 @@
+//! Module implement reading events from terminal.
+//!
+//! crossterm's event reader discards the markers that bracket pasted text,
+//! hence terminal input is read and parsed here. Text pasted in bracketed
+//! paste mode is returned as a single [Event::Paste], while keystrokes and
+//! mouse events are parsed the same way as crossterm does.
+
+use crossterm::event::{
+    Event as TermEvent, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent,
+};
+#[allow(unused_imports)]
+use log::{debug, trace};
+
+use std::{
+    collections::VecDeque,
+    io, str,
+    sync::atomic::{AtomicI32, Ordering::SeqCst},
+};
+
+use crate::{event::Event, Error, Result};
+
+const PASTE_START: &[u8] = b"\x1b[200~";
+const PASTE_END: &[u8] = b"\x1b[201~";
+
+// write end of the pipe, notified on SIGWINCH.
+static WINCH_FD: AtomicI32 = AtomicI32::new(-1);
+
+extern "C" fn on_winch(_: libc::c_int) {
+    let fd = WINCH_FD.load(SeqCst);
+    unsafe { libc::write(fd, b"w".as_ptr() as *const libc::c_void, 1) };
+}
+
+/// Read events from terminal, one [Input] value is used by the event-loop.
+pub struct Input {
+    // read and write end of the pipe, notified on SIGWINCH.
+    winch: [libc::c_int; 2],
+    parser: Parser,
+}
+
+impl Drop for Input {
+    fn drop(&mut self) {
+        unsafe {
+            libc::signal(libc::SIGWINCH, libc::SIG_DFL);
+            libc::close(self.winch[0]);
+            libc::close(self.winch[1]);
+        }
+    }
+}
+
+impl Input {
+    /// Create a new reader, terminal resize is notified via SIGWINCH.
+    pub fn new() -> Result<Input> {
+        let mut fds: [libc::c_int; 2] = [-1, -1];
+        if unsafe { libc::pipe(fds.as_mut_ptr()) } < 0 {
+            err_at!(IOError, Err(io::Error::last_os_error()))?
+        }
+        for fd in fds.iter() {
+            unsafe { libc::fcntl(*fd, libc::F_SETFL, libc::O_NONBLOCK) };
+        }
+        WINCH_FD.store(fds[1], SeqCst);
+
+        let handler = on_winch as extern "C" fn(libc::c_int);
+        unsafe { libc::signal(libc::SIGWINCH, handler as libc::sighandler_t) };
+
+        Ok(Input {
+            winch: fds,
+            parser: Parser::default(),
+        })
+    }
+
+    /// Block until the next event is available from terminal and return
+    /// the same. Terminal resize is returned as [Event::Resize].
+    pub fn read(&mut self) -> Result<Event> {
+        let mut buf = [0_u8; 1024];
+        let ptr = buf.as_mut_ptr() as *mut libc::c_void;
+
+        loop {
+            if let Some(evnt) = self.parser.evnts.pop_front() {
+                break Ok(evnt);
+            }
+
+            let mut fds = [
+                libc::pollfd {
+                    fd: libc::STDIN_FILENO,
+                    events: libc::POLLIN,
+                    revents: 0,
+                },
+                libc::pollfd {
+                    fd: self.winch[0],
+                    events: libc::POLLIN,
+                    revents: 0,
+                },
+            ];
+            if unsafe { libc::poll(fds.as_mut_ptr(), 2, -1) } < 0 {
+                match io::Error::last_os_error() {
+                    err if err.kind() == io::ErrorKind::Interrupted => continue,
+                    err => err_at!(IOError, Err(err))?,
+                }
+            }
+
+            if fds[1].revents & libc::POLLIN != 0 {
+                while unsafe { libc::read(self.winch[0], ptr, buf.len()) } > 0 {}
+                let (cols, rows) = err_at!(Fatal, crossterm::terminal::size())?;
+                self.parser.evnts.push_back(Event::Resize(cols, rows));
+            }
+
+            if fds[0].revents & (libc::POLLIN | libc::POLLHUP) != 0 {
+                let n = match unsafe { libc::read(libc::STDIN_FILENO, ptr, buf.len()) } {
+                    n if n > 0 => n as usize,
+                    0 => err_at!(IOError, msg: format!("terminal closed"))?,
+                    _ => match io::Error::last_os_error() {
+                        err if err.kind() == io::ErrorKind::Interrupted => continue,
+                        err => err_at!(IOError, Err(err))?,
+                    },
+                };
+                self.parser.advance(&buf[..n], n == buf.len());
+            }
+        }
+    }
+}
+
+// Parse bytes read from terminal into events. Bytes of an incomplete
+// sequence are held until the rest of the sequence is read.
+#[derive(Default)]
+struct Parser {
+    buf: Vec<u8>,
+    // pasted text, while within the bracketed paste markers.
+    paste: Option<Vec<u8>>,
+    evnts: VecDeque<Event>,
+}
+
+impl Parser {
+    // `more` tells whether more bytes are available to read, to tell a
+    // lone ESC key from an escape sequence.
+    fn advance(&mut self, bytes: &[u8], more: bool) {
+        for (i, byte) in bytes.iter().enumerate() {
+            let more = (i + 1) < bytes.len() || more;
+
+            if let Some(paste) = self.paste.as_mut() {
+                paste.push(*byte);
+                if paste.ends_with(PASTE_END) {
+                    paste.truncate(paste.len() - PASTE_END.len());
+                    let txt = String::from_utf8_lossy(paste).to_string();
+                    self.evnts.push_back(Event::Paste(txt));
+                    self.paste = None;
+                }
+                continue;
+            }
+
+            self.buf.push(*byte);
+            if self.buf == PASTE_START {
+                self.buf.clear();
+                self.paste = Some(vec![]);
+                continue;
+            }
+            match parse_event(&self.buf, more) {
+                Ok(Some(evnt)) => {
+                    self.evnts.push_back(evnt.into());
+                    self.buf.clear();
+                }
+                Ok(None) => (),
+                Err(err) => {
+                    trace!("discard input {}", err);
+                    self.buf.clear();
+                }
+            }
+        }
+    }
+}
+
+// Return None if `buf` is an incomplete sequence.
+fn parse_event(buf: &[u8], more: bool) -> Result<Option<TermEvent>> {
+    let key = |code, m| Ok(Some(TermEvent::Key(KeyEvent::new(code, m))));
+    let (empty, ctrl) = (KeyModifiers::empty(), KeyModifiers::CONTROL);
+
+    match buf {
+        [] => Ok(None),
+        [b'\x1b'] if more => Ok(None),
+        [b'\x1b'] => key(KeyCode::Esc, empty),
+        [b'\x1b', b'O'] => Ok(None),
+        [b'\x1b', b'O', val @ b'P'..=b'S'] => key(KeyCode::F(1 + val - b'P'), empty),
+        [b'\x1b', b'[', ..] => parse_csi(buf),
+        [b'\x1b', b'\x1b'] => key(KeyCode::Esc, empty),
+        [b'\x1b', b'O', ..] => err_at!(FailParse, msg: format!("input {:?}", buf)),
+        [b'\x1b', rest @ ..] => match parse_utf8_char(rest)? {
+            Some(ch) => key(KeyCode::Char(ch), KeyModifiers::ALT),
+            None => Ok(None),
+        },
+        [b'\r'] => key(KeyCode::Enter, empty),
+        [b'\t'] => key(KeyCode::Tab, empty),
+        [b'\x7f'] => key(KeyCode::Backspace, empty),
+        [c @ b'\x01'..=b'\x1a'] => key(KeyCode::Char((c - 0x1 + b'a') as char), ctrl),
+        [c @ b'\x1c'..=b'\x1f'] => key(KeyCode::Char((c - 0x1c + b'4') as char), ctrl),
+        [b'\0'] => key(KeyCode::Char(' '), ctrl),
+        _ => match parse_utf8_char(buf)? {
+            // terminal reports upper-case letters along with SHIFT.
+            Some(ch) if ch.is_uppercase() => key(KeyCode::Char(ch), KeyModifiers::SHIFT),
+            Some(ch) => key(KeyCode::Char(ch), empty),
+            None => Ok(None),
+        },
+    }
+}
+
+// Parse control sequence, starting with `ESC [`. Return None if `buf` is
+// an incomplete sequence.
+fn parse_csi(buf: &[u8]) -> Result<Option<TermEvent>> {
+    let key = |code| Ok(Some(TermEvent::Key(KeyEvent::from(code))));
+
+    match &buf[2..] {
+        [] => Ok(None),
+        [b'D'] => key(KeyCode::Left),
+        [b'C'] => key(KeyCode::Right),
+        [b'A'] => key(KeyCode::Up),
+        [b'B'] => key(KeyCode::Down),
+        [b'H'] => key(KeyCode::Home),
+        [b'F'] => key(KeyCode::End),
+        [b'Z'] => key(KeyCode::BackTab),
+        // X10 mouse, ESC [ M Cb Cx Cy, each offset by 32.
+        [b'M', cb, cx, cy] => {
+            let to_u16 = |x: &u8| u16::from(x.saturating_sub(32));
+            let mouse = to_mouse_event(to_u16(cb), to_u16(cx), to_u16(cy), false);
+            Ok(Some(TermEvent::Mouse(mouse)))
+        }
+        [b'M', ..] => Ok(None),
+        // SGR mouse, ESC [ < Cb ; Cx ; Cy (M | m), `m` for button release.
+        [b'<', .., last @ b'M'] | [b'<', .., last @ b'm'] => {
+            match to_params(&buf[3..buf.len() - 1])?[..] {
+                [cb, cx, cy] => {
+                    let mouse = to_mouse_event(cb, cx, cy, *last == b'm');
+                    Ok(Some(TermEvent::Mouse(mouse)))
+                }
+                _ => err_at!(FailParse, msg: format!("input {:?}", buf)),
+            }
+        }
+        [b'<', ..] => Ok(None),
+        // numbered sequences, the final byte is in the range 64-126.
+        [b'0'..=b'9', .., last] if (64..=126).contains(last) => {
+            let params = to_params(&buf[2..buf.len() - 1])?;
+            parse_csi_numbered(&params, *last)
+        }
+        [b'0'..=b'9', ..] => Ok(None),
+        _ => err_at!(FailParse, msg: format!("input {:?}", buf)),
+    }
+}
+
+fn parse_csi_numbered(params: &[u16], last: u8) -> Result<Option<TermEvent>> {
+    let code = match (last, params) {
+        // rxvt mouse, ESC [ Cb ; Cx ; Cy M
+        (b'M', [cb, cx, cy]) => {
+            let mouse = to_mouse_event(cb.saturating_sub(32), *cx, *cy, false);
+            return Ok(Some(TermEvent::Mouse(mouse)));
+        }
+        (b'~', [1, ..]) | (b'~', [7, ..]) => KeyCode::Home,
+        (b'~', [2, ..]) => KeyCode::Insert,
+        (b'~', [3, ..]) => KeyCode::Delete,
+        (b'~', [4, ..]) | (b'~', [8, ..]) => KeyCode::End,
+        (b'~', [5, ..]) => KeyCode::PageUp,
+        (b'~', [6, ..]) => KeyCode::PageDown,
+        (b'~', [v @ 11..=15, ..]) => KeyCode::F((v - 10) as u8),
+        (b'~', [v @ 17..=21, ..]) => KeyCode::F((v - 11) as u8),
+        (b'~', [v @ 23..=24, ..]) => KeyCode::F((v - 12) as u8),
+        (b'A', [1, _]) => KeyCode::Up,
+        (b'B', [1, _]) => KeyCode::Down,
+        (b'C', [1, _]) => KeyCode::Right,
+        (b'D', [1, _]) => KeyCode::Left,
+        (b'F', [1, _]) => KeyCode::End,
+        (b'H', [1, _]) => KeyCode::Home,
+        (b'P', [1, _]) => KeyCode::F(1),
+        (b'Q', [1, _]) => KeyCode::F(2),
+        (b'R', [1, _]) => KeyCode::F(3),
+        (b'S', [1, _]) => KeyCode::F(4),
+        _ => err_at!(FailParse, msg: format!("input {:?} {}", params, last))?,
+    };
+
+    let m = {
+        let mask = params.get(1).copied().unwrap_or(1).saturating_sub(1);
+        let mut m = KeyModifiers::empty();
+        m.set(KeyModifiers::SHIFT, mask & 1 != 0);
+        m.set(KeyModifiers::ALT, mask & 2 != 0);
+        m.set(KeyModifiers::CONTROL, mask & 4 != 0);
+        m
+    };
+    Ok(Some(TermEvent::Key(KeyEvent::new(code, m))))
+}
+
+// `cx` and `cy` start from 1, while the event's column and row start
+// from 0.
+fn to_mouse_event(cb: u16, cx: u16, cy: u16, up: bool) -> MouseEvent {
+    let (col, row) = (cx.saturating_sub(1), cy.saturating_sub(1));
+    let mut m = KeyModifiers::empty();
+    m.set(KeyModifiers::SHIFT, cb & 0b0000_0100 != 0);
+    m.set(KeyModifiers::ALT, cb & 0b0000_1000 != 0);
+    m.set(KeyModifiers::CONTROL, cb & 0b0001_0000 != 0);
+
+    let button = match cb & 0b0000_0011 {
+        1 => MouseButton::Middle,
+        2 => MouseButton::Right,
+        _ => MouseButton::Left,
+    };
+    let (wheel, drag) = (cb & 0b0100_0000 != 0, cb & 0b0010_0000 != 0);
+    match cb & 0b0000_0011 {
+        0 if wheel => MouseEvent::ScrollUp(col, row, m),
+        _ if wheel => MouseEvent::ScrollDown(col, row, m),
+        // X10 and rxvt report button release as button 3.
+        3 => MouseEvent::Up(button, col, row, m),
+        _ if up => MouseEvent::Up(button, col, row, m),
+        _ if drag => MouseEvent::Drag(button, col, row, m),
+        _ => MouseEvent::Down(button, col, row, m),
+    }
+}
+
+// parse semicolon separated numbers.
+fn to_params(buf: &[u8]) -> Result<Vec<u16>> {
+    let s = err_at!(FailParse, str::from_utf8(buf))?;
+    let mut params = vec![];
+    for param in s.split(';') {
+        params.push(err_at!(
+            FailParse,
+            param.parse::<u16>(),
+            format!("input {:?}", s)
+        )?);
+    }
+    Ok(params)
+}
+
+// Return None if `buf` is an incomplete utf8 encoding.
+fn parse_utf8_char(buf: &[u8]) -> Result<Option<char>> {
+    match str::from_utf8(buf) {
+        Ok(s) => Ok(s.chars().next()),
+        Err(err) if err.error_len().is_none() => Ok(None),
+        Err(err) => err_at!(FailParse, Err(err)),
+    }
+}
+
+#[cfg(test)]
+#[path = "input_test.rs"]
+mod input_test;
@@ -0,0 +1,71 @@
+use super::*;
+
+use crate::event::DP;
+
+fn parse(chunks: &[&[u8]]) -> Vec<Event> {
+    let mut parser = Parser::default();
+    for chunk in chunks.iter() {
+        parser.advance(chunk, false);
+    }
+    parser.evnts.into_iter().collect()
+}
+
+#[test]
+fn test_parse_keys() {
+    let empty = KeyModifiers::empty();
+
+    let evnts = parse(&[b"a\x1b[A\x1b[3~\x1b[1;5C\r\x7f\x17B\xc3\xa9", b"\x1b"]);
+    let refs = vec![
+        Event::Char('a', empty),
+        Event::Up(empty),
+        Event::Delete(empty),
+        Event::Noop, // ctrl+right is not a motion
+        Event::Enter(empty),
+        Event::Backspace(empty),
+        Event::Char('w', KeyModifiers::CONTROL),
+        Event::Char('B', KeyModifiers::SHIFT),
+        Event::Char('é', empty),
+        Event::Esc,
+    ];
+    assert!(evnts == refs);
+
+    // sequences split across reads are held until complete.
+    let evnts = parse(&[b"\x1b[", b"6~", b"\xc3", b"\xa9"]);
+    assert!(evnts == vec![Event::PageDown(empty), Event::Char('é', empty)]);
+
+    // malformed sequences are discarded.
+    let evnts = parse(&[b"\x1b[99~x"]);
+    assert!(evnts == vec![Event::Char('x', empty)]);
+}
+
+#[test]
+fn test_parse_mouse() {
+    let evnts = parse(&[b"\x1b[<0;10;5M\x1b[<0;10;5m\x1b[<64;1;1M\x1b[<65;1;1M"]);
+    let refs = vec![
+        Event::Click(9, 4),
+        Event::Noop,
+        Event::Wheel(DP::Left),
+        Event::Wheel(DP::Right),
+    ];
+    assert!(evnts == refs);
+
+    let evnts = parse(&[b"\x1b[M #%", b"\x1b[32;3;4M"]);
+    assert!(evnts == vec![Event::Click(2, 4), Event::Click(2, 3)]);
+}
+
+#[test]
+fn test_parse_paste() {
+    let empty = KeyModifiers::empty();
+
+    let evnts = parse(&[b"i\x1b[200~fn main() {\r\x1b[A}\x1b[201~x"]);
+    let refs = vec![
+        Event::Char('i', empty),
+        Event::Paste("fn main() {\r\x1b[A}".to_string()),
+        Event::Char('x', empty),
+    ];
+    assert!(evnts == refs);
+
+    // markers and pasted text split across reads.
+    let evnts = parse(&[b"\x1b[20", b"0~ab", b"c\x1b[2", b"01~"]);
+    assert!(evnts == vec![Event::Paste("abc".to_string())]);
+}
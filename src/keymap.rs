@@ -1,4 +1,10 @@
-use crate::{buffer::Buffer, event::Event, Error, Result};
+use crossterm::event::KeyModifiers;
+
+use crate::{
+    buffer::Buffer,
+    event::{Appn, Event},
+    Error, Result,
+};
 
 pub use crate::keymap_cmd::KeyCmd;
 pub use crate::keymap_edit::KeyEdit;
@@ -10,7 +16,7 @@ trait Keymapper {
 
 #[derive(Clone)]
 pub enum Keymap {
-    Edit(KeyEdit),
+    Edit(Box<KeyEdit>),
     Cmd(KeyCmd),
     Less(KeyLess),
     None,
@@ -24,7 +30,7 @@ impl Default for Keymap {
 
 impl Keymap {
     pub fn new_edit() -> Keymap {
-        Keymap::Edit(KeyEdit::default())
+        Keymap::Edit(Box::default())
     }
 
     pub fn new_cmd() -> Keymap {
@@ -37,6 +43,38 @@ impl Keymap {
 }
 
 impl Keymap {
+    /// Consult user key-maps, `remaps`, before the built-in keymap. Only
+    /// applicable for edit keymap.
+    pub fn set_remaps(&mut self, remaps: Vec<KeyRemap>) -> &mut Self {
+        if let Keymap::Edit(km) = self {
+            km.set_remaps(remaps);
+        }
+        self
+    }
+
+    /// Refer [KeyEdit::set_noremap], only applicable for edit keymap.
+    pub fn set_noremap(&mut self, noremap: bool) -> &mut Self {
+        if let Keymap::Edit(km) = self {
+            km.set_noremap(noremap);
+        }
+        self
+    }
+
+    pub fn is_noremap(&self) -> bool {
+        match self {
+            Keymap::Edit(km) => km.is_noremap(),
+            _ => false,
+        }
+    }
+
+    /// Refer [KeyEdit::take_noremap], only applicable for edit keymap.
+    pub fn take_noremap(&mut self) -> usize {
+        match self {
+            Keymap::Edit(km) => km.take_noremap(),
+            _ => 0,
+        }
+    }
+
     pub fn fold(&mut self, buf: &Buffer, evnt: Event) -> Result<Event> {
         match self {
             Keymap::Edit(km) => km.fold(buf, evnt),
@@ -55,3 +93,87 @@ impl Keymap {
         }
     }
 }
+
+/// User defined key-map, in the style of `nnoremap` and `inoremap`, maps a
+/// sequence of keys to another sequence of keys, or to a named command.
+/// Mapped keys are not re-mapped.
+#[derive(Clone)]
+pub struct KeyRemap {
+    pub mode: &'static str,
+    pub lhs: Vec<Event>,
+    pub rhs: Event,
+}
+
+impl KeyRemap {
+    /// Parse `map`, like `jk <Esc>`, as key-map for `mode`, either
+    /// "normal" or "insert". Left and right hand side are separated by
+    /// white-space and keys are in vim notation, refer [parse_keys].
+    /// Right hand side starting with `:`, like `:write`, opens the
+    /// command-line with that command.
+    pub fn new(mode: &'static str, map: &str) -> Result<KeyRemap> {
+        let map = map.trim();
+        let (lhs, rhs) = match map.find(char::is_whitespace) {
+            Some(off) => (&map[..off], map[off..].trim_start()),
+            None => err_at!(Invalid, msg: format!("key-map {:?}", map))?,
+        };
+        let lhs = parse_keys(lhs)?;
+        let rhs = match rhs.strip_prefix(':') {
+            Some(cmd) => Event::Appn(Appn::Command(cmd.to_string())),
+            None => Event::Macro(1, parse_keys(rhs)?),
+        };
+        Ok(KeyRemap { mode, lhs, rhs })
+    }
+}
+
+/// Parse keys in vim notation, like `jk`, `<Esc>`, `<C-s>`, into key
+/// events. Use `<lt>` for a literal `<`.
+pub fn parse_keys(keys: &str) -> Result<Vec<Event>> {
+    let empty = KeyModifiers::empty();
+
+    let mut evnts = vec![];
+    let mut chars = keys.chars();
+    while let Some(ch) = chars.next() {
+        let rest = chars.as_str();
+        let evnt = match rest.find('>') {
+            Some(off) if ch == '<' && off > 0 => {
+                chars = rest[off + 1..].chars();
+                to_named_key(&rest[..off])?
+            }
+            _ => Event::Char(ch, empty),
+        };
+        evnts.push(evnt);
+    }
+    Ok(evnts)
+}
+
+fn to_named_key(name: &str) -> Result<Event> {
+    let empty = KeyModifiers::empty();
+
+    let name = name.to_lowercase();
+    let evnt = match name.as_str() {
+        "esc" => Event::Esc,
+        "cr" | "enter" | "return" => Event::Enter(empty),
+        "tab" => Event::Tab(empty),
+        "bs" | "backspace" => Event::Backspace(empty),
+        "del" | "delete" => Event::Delete(empty),
+        "space" => Event::Char(' ', empty),
+        "lt" => Event::Char('<', empty),
+        "bar" => Event::Char('|', empty),
+        "up" => Event::Up(empty),
+        "down" => Event::Down(empty),
+        "left" => Event::Left(empty),
+        "right" => Event::Right(empty),
+        "home" => Event::Home(empty),
+        "end" => Event::End(empty),
+        "pageup" => Event::PageUp(empty),
+        "pagedown" => Event::PageDown(empty),
+        _ => {
+            let mut chars = name.strip_prefix("c-").unwrap_or("").chars();
+            match (chars.next(), chars.next()) {
+                (Some(ch), None) => Event::Char(ch, KeyModifiers::CONTROL),
+                _ => err_at!(Invalid, msg: format!("key <{}>", name))?,
+            }
+        }
+    };
+    Ok(evnt)
+}
@@ -1,12 +1,13 @@
 #[allow(unused_imports)]
 use log::{debug, trace};
 
-use std::mem;
+use std::{collections::HashMap, mem};
 
 use crate::{
     buffer::Buffer,
-    event::{self, Event, Mod, Mto, Scroll, DP},
-    Error, Result,
+    event::{self, Cud, Event, Mod, Mto, Opr, Scroll, DP},
+    keymap::KeyRemap,
+    register, Error, Result,
 };
 
 macro_rules! parse_n {
@@ -19,25 +20,155 @@ macro_rules! parse_n {
 #[derive(Clone, Default)]
 pub struct KeyEdit {
     prefix: Event,
+    // operator pending on a motion.
+    opr: Option<Opr>,
+    // macro being recorded, (register, raw-events)
+    recording: Option<(char, Vec<Event>)>,
+    // recorded macros, raw-events indexed by register.
+    macros: HashMap<char, Vec<Event>>,
+    // register of the last replayed macro.
+    last_macro: Option<char>,
+    // user key-maps, consulted before the built-in keymap.
+    remaps: Vec<KeyRemap>,
+    // typed keys held back, while they match a key-map's prefix.
+    pending: Vec<Event>,
+    // events being replayed for a key-map, are neither re-mapped nor
+    // recorded. Set only for the duration of the replay.
+    noremap: bool,
+    // number of leading events, in the macro folded last, to be replayed
+    // with noremap.
+    macro_noremap: usize,
 }
 
 impl KeyEdit {
+    pub fn set_remaps(&mut self, remaps: Vec<KeyRemap>) -> &mut Self {
+        self.remaps = remaps;
+        self
+    }
+
+    /// Fold subsequent events without user key-maps and without recording
+    /// them, used while replaying a key-map's events.
+    pub fn set_noremap(&mut self, noremap: bool) -> &mut Self {
+        self.noremap = noremap;
+        self
+    }
+
+    pub fn is_noremap(&self) -> bool {
+        self.noremap
+    }
+
+    /// Return the number of leading events, in the macro folded last, that
+    /// shall be replayed with [KeyEdit::set_noremap]. Zero for macros
+    /// recorded via `q`.
+    pub fn take_noremap(&mut self) -> usize {
+        mem::take(&mut self.macro_noremap)
+    }
+
     pub fn fold(&mut self, buf: &Buffer, evnt: Event) -> Result<Event> {
+        if !self.noremap {
+            if let Some((_, evnts)) = self.recording.as_mut() {
+                evnts.push(evnt.clone());
+            }
+            if let Some(evnt) = self.remap_fold(buf.to_mode(), evnt.clone()) {
+                return Ok(evnt);
+            }
+        }
+
         match buf.to_mode() {
             "insert" => self.insert_fold(buf, evnt),
             "normal" => self.normal_fold(buf, evnt),
+            "visual" => self.visual_fold(buf, evnt),
             _ => err_at!(Fatal, msg: format!("unreachable")),
         }
     }
 
     pub fn to_event_prefix(&self) -> Event {
-        self.prefix.clone()
+        match (&self.prefix, &self.opr) {
+            (Event::Noop, Some(opr)) => Event::Op(opr.clone()),
+            (prefix, _) => prefix.clone(),
+        }
+    }
+}
+
+impl KeyEdit {
+    // start recording raw events into register `ch`, uppercase register
+    // shall append to the existing macro.
+    fn start_recording(&mut self, ch: char) {
+        let evnts = match ch {
+            'A'..='Z' => {
+                let ch = ch.to_ascii_lowercase();
+                self.macros.get(&ch).cloned().unwrap_or_else(Vec::default)
+            }
+            _ => Vec::default(),
+        };
+        self.recording = Some((ch.to_ascii_lowercase(), evnts));
+    }
+
+    // stop recording and save the macro, sans the terminating `q`.
+    fn stop_recording(&mut self) {
+        if let Some((ch, mut evnts)) = self.recording.take() {
+            evnts.pop();
+            self.macros.insert(ch, evnts);
+        }
+    }
+
+    // match typed keys with user key-maps for `mode`. Keys are held back
+    // while they match a key-map's prefix, and replayed once the match is
+    // complete or fails. Return None if `evnt` is for the built-in keymap.
+    fn remap_fold(&mut self, mode: &str, evnt: Event) -> Option<Event> {
+        use crate::event::Event::{At, Quote, F, J, M, O, Q, R, S, T};
+
+        match &self.prefix {
+            // keys following these prefixes are taken literally.
+            F(_, _) | T(_, _) | R(_) | J(_) | O(_, _) | S(_, _) => return None,
+            M | Quote | Q | At(_) => return None,
+            _ if self.remaps.is_empty() => return None,
+            _ => (),
+        }
+
+        self.pending.push(evnt);
+        let keys: Vec<Event> = self.pending.iter().map(to_key).collect();
+
+        let mut partial = false;
+        for remap in self.remaps.iter().filter(|r| r.mode == mode) {
+            if remap.lhs == keys {
+                // exact match is preferred over a longer key-map.
+                self.pending.clear();
+                if let Event::Macro(_, evnts) = &remap.rhs {
+                    self.macro_noremap = evnts.len();
+                }
+                return Some(remap.rhs.clone());
+            }
+            partial = partial || remap.lhs.starts_with(&keys);
+        }
+
+        match self.pending.len() {
+            _ if partial => Some(Event::Noop),
+            1 => {
+                self.pending.clear();
+                None
+            }
+            n => {
+                // last key is matched afresh on replay, and recorded then.
+                if let Some((_, evnts)) = self.recording.as_mut() {
+                    evnts.pop();
+                }
+                self.macro_noremap = n - 1;
+                Some(Event::Macro(1, self.pending.drain(..).collect()))
+            }
+        }
+    }
+
+    fn to_macro(&self, n: usize, ch: char) -> Event {
+        match self.macros.get(&ch.to_ascii_lowercase()) {
+            Some(evnts) if !evnts.is_empty() => Event::Macro(n, evnts.clone()),
+            _ => Event::Noop,
+        }
     }
 }
 
 impl KeyEdit {
     fn insert_fold(&mut self, _: &Buffer, evnt: Event) -> Result<Event> {
-        use crate::event::Cud;
         use crate::event::Event::*;
 
         let noop = Event::Noop;
@@ -59,12 +190,12 @@ impl KeyEdit {
                 Event::Delete(_) => (noop, Wr(Cud::Delete(1))),
                 Event::Tab(_) => (noop, Wr(Cud::Tab(1))),
                 Event::Enter(_) => (noop, Wr(Cud::Enter(1))),
-                evnt @ Event::Up(_) => (noop, evnt),
-                evnt @ Event::Down(_) => (noop, evnt),
-                evnt @ Event::Left(_) => (noop, evnt),
-                evnt @ Event::Right(_) => (noop, evnt),
-                evnt @ Event::Home(_) => (noop, evnt),
-                evnt @ Event::End(_) => (noop, evnt),
+                Event::Up(_) => (noop, Mt(Mto::Up(1, DP::None))),
+                Event::Down(_) => (noop, Mt(Mto::Down(1, DP::None))),
+                Event::Left(_) => (noop, Mt(Mto::Left(1, DP::LineBound))),
+                Event::Right(_) => (noop, Mt(Mto::Right(1, DP::LineBound))),
+                Event::Home(_) => (noop, Mt(Mto::LineHome(DP::None))),
+                Event::End(_) => (noop, Mt(Mto::LineEnd(1, DP::None))),
                 evnt @ Event::PageUp(_) => (noop, evnt),
                 evnt @ Event::PageDown(_) => (noop, evnt),
                 evnt => (noop, evnt),
@@ -101,7 +232,114 @@ impl KeyEdit {
         Ok(evnt)
     }
 
-    fn normal_fold(&mut self, _: &Buffer, evnt: Event) -> Result<Event> {
+    fn normal_fold(&mut self, buf: &Buffer, evnt: Event) -> Result<Event> {
+        match self.opr.take() {
+            Some(opr) => self.opr_fold(buf, opr, evnt),
+            None => self.motion_fold(buf, evnt),
+        }
+    }
+
+    // fold events in visual mode, operators act on the selection, while
+    // motions extend the selection.
+    fn visual_fold(&mut self, buf: &Buffer, evnt: Event) -> Result<Event> {
+        use crate::event::Event::*;
+
+        let noop = Event::Noop;
+        let (empty, ctrl, shift) = {
+            use crossterm::event::KeyModifiers as KM;
+            let m = evnt.to_modifiers();
+            (m.is_empty(), m == KM::CONTROL, m == KM::SHIFT)
+        };
+
+        match (&self.prefix, evnt) {
+            (Event::Noop, Esc) => Ok(Md(Mod::Esc)),
+            (Event::Noop, Char('v', _)) if empty => Ok(Md(Mod::Visual(false))),
+            (Event::Noop, Char('V', _)) if empty | shift => Ok(Md(Mod::Visual(true))),
+            (Event::Noop, Char('v', _)) if ctrl => Ok(Md(Mod::VisualBlock)),
+            (Event::Noop, Char('I', _)) if empty | shift => Ok(Md(Mod::Insert(1, DP::TextCol))),
+            (Event::Noop, Char('A', _)) if empty | shift => Ok(Md(Mod::Append(1, DP::End))),
+            (Event::Noop, Char('d', _)) | (Event::Noop, Char('x', _)) if empty => {
+                Ok(Op(Opr::Delete(1, Mto::None)))
+            }
+            (Event::Noop, Char('c', _)) | (Event::Noop, Char('s', _)) if empty => {
+                Ok(Op(Opr::Change(1, Mto::None)))
+            }
+            (Event::Noop, Char('y', _)) if empty => Ok(Op(Opr::Yank(1, Mto::None))),
+            (Event::Noop, Char('~', _)) if empty => Ok(Op(Opr::Swapcase(1, Mto::None))),
+            (Event::Noop, Char('u', _)) if empty => Ok(Op(Opr::Lowercase(1, Mto::None))),
+            (Event::Noop, Char('U', _)) if empty | shift => Ok(Op(Opr::Uppercase(1, Mto::None))),
+            (Event::Noop, Char('>', _)) if empty => Ok(Op(Opr::RShift(1, Mto::None))),
+            (Event::Noop, Char('<', _)) if empty => Ok(Op(Opr::LShift(1, Mto::None))),
+            (G(_), Char('q', _)) | (G(_), Char('w', _)) if empty => {
+                self.prefix = Event::Noop;
+                Ok(Op(Opr::Format(1, Mto::None)))
+            }
+            (Z(_), Char('f', _)) if empty => {
+                self.prefix = Event::Noop;
+                Ok(Op(Opr::Fold(1, Mto::None)))
+            }
+            (_, evnt) => match self.motion_fold(buf, evnt)? {
+                evnt @ Mt(_) | evnt @ Reg(_) => Ok(evnt),
+                _ => {
+                    // operators and mode commands are not allowed here.
+                    self.opr = None;
+                    Ok(noop)
+                }
+            },
+        }
+    }
+
+    // fold events for pending operator, until its motion is complete. If
+    // the motion is invalid, operator is cancelled.
+    fn opr_fold(&mut self, buf: &Buffer, opr: Opr, evnt: Event) -> Result<Event> {
+        use std::mem::discriminant;
+
+        let n = match &self.prefix {
+            Event::Noop => Some(1),
+            Event::N(n) => Some(*n),
+            _ => None,
+        };
+        let evnt = match (n, evnt) {
+            (_, Event::Esc) => {
+                self.prefix = Event::Noop;
+                Event::Noop
+            }
+            (Some(n), Event::Char(ch, _)) if opr.is_linewise_key(ch) => {
+                self.prefix = Event::Noop;
+                Event::Op(opr.into_linewise(n))
+            }
+            // text-object, like `diw`, `ca(`.
+            (Some(n), Event::Char(ch @ 'i', m)) | (Some(n), Event::Char(ch @ 'a', m))
+                if m.is_empty() =>
+            {
+                self.prefix = Event::O(n, ch == 'a');
+                self.opr = Some(opr);
+                Event::Noop
+            }
+            (_, evnt) => match self.motion_fold(buf, evnt)? {
+                // operator doubled, like `dd`, `d3d`, `gUgU`.
+                Event::Noop => match self.opr.take() {
+                    Some(o) if discriminant(&o) == discriminant(&opr) => {
+                        Event::Op(opr.into_linewise(o.to_count()))
+                    }
+                    Some(_) => Event::Noop,
+                    None if self.prefix == Event::Noop => Event::Noop,
+                    None => {
+                        self.opr = Some(opr);
+                        Event::Noop
+                    }
+                },
+                Event::Mt(mto) => Event::Op(opr.into_motion(mto)),
+                _ => Event::Noop,
+            },
+        };
+
+        debug!("operator prefix:{} event:{}", self.to_event_prefix(), evnt);
+
+        Ok(evnt)
+    }
+
+    fn motion_fold(&mut self, _: &Buffer, evnt: Event) -> Result<Event> {
         use crate::event::Event::*;
 
         let noop = Event::Noop;
@@ -131,6 +369,7 @@ impl KeyEdit {
                 Char('f', _) => (F(1, DP::Right), noop),
                 Char('F', _) => (F(1, DP::Left), noop),
                 Char('t', _) => (T(1, DP::Right), noop),
+                Char('r', _) => (R(1), noop),
                 Char('T', _) => (T(1, DP::Left), noop),
                 Char(';', _) => (noop, Mt(Mto::CharR(1, DP::Right))),
                 Char(',', _) => (noop, Mt(Mto::CharR(1, DP::Left))),
@@ -163,8 +402,13 @@ impl KeyEdit {
                 Char('m', _) => (M, noop),
                 Char('\'', _) => (J('\''), noop),
                 Char('`', _) => (J('`'), noop),
+                Tab(_) => (noop, Mt(Mto::JumpList(1, DP::Right))),
                 Char('n', _) => (noop, Mt(Mto::PatternR(1, DP::Right))),
                 Char('N', _) => (noop, Mt(Mto::PatternR(1, DP::Left))),
+                Char('*', _) => (noop, Mt(Mto::SearchWord(1, DP::Right))),
+                Char('#', _) => (noop, Mt(Mto::SearchWord(1, DP::Left))),
+                Char('/', _) => (S(String::default(), DP::Right), noop),
+                Char('?', _) => (S(String::default(), DP::Left), noop),
                 // prefix commands
                 Char(ch @ '0'..='9', _) => (N(parse_n!(0, ch)), noop),
                 Char('[', _) => (B(1, DP::Left), noop),
@@ -175,11 +419,11 @@ impl KeyEdit {
                 Char('c', _) => (Op(event::Opr::Change(1, Mto::None)), noop),
                 Char('d', _) => (Op(event::Opr::Delete(1, Mto::None)), noop),
                 Char('y', _) => (Op(event::Opr::Yank(1, Mto::None)), noop),
-                Char('~', _) => (Op(event::Opr::Swapcase(1, Mto::None)), noop),
+                Char('~', _) => (noop, Op(event::Opr::Swapcase(1, Mto::None))),
                 Char('!', _) => (Op(event::Opr::Filter(1, Mto::None)), noop),
                 Char('=', _) => (Op(event::Opr::Equal(1, Mto::None)), noop),
-                Char('<', _) => (Op(event::Opr::RShift(1, Mto::None)), noop),
-                Char('>', _) => (Op(event::Opr::LShift(1, Mto::None)), noop),
+                Char('>', _) => (Op(event::Opr::RShift(1, Mto::None)), noop),
+                Char('<', _) => (Op(event::Opr::LShift(1, Mto::None)), noop),
                 //
                 Char('I', _) => (noop, Md(Mod::Insert(1, DP::TextCol))),
                 Char('i', _) => (noop, Md(Mod::Insert(1, DP::None))),
@@ -188,6 +432,24 @@ impl KeyEdit {
                 Char('O', _) => (noop, Md(Mod::Open(1, DP::Left))),
                 Char('o', _) => (noop, Md(Mod::Open(1, DP::Right))),
                 Char('R', _) => (noop, Md(Mod::Replace(1, DP::None))),
+                Char('v', _) => (noop, Md(Mod::Visual(false))),
+                Char('V', _) => (noop, Md(Mod::Visual(true))),
+                Char('"', _) => (Quote, noop),
+                Char('p', _) => (noop, Put(1, register::UNNAMED, DP::Right)),
+                Char('P', _) => (noop, Put(1, register::UNNAMED, DP::Left)),
+                Char('J', _) => (noop, Join(2)),
+                Char('x', _) => (noop, Wr(Cud::Delete(1))),
+                Char('X', _) => (noop, Wr(Cud::Backspace(1))),
+                // undo
+                Char('u', _) => (noop, Undo(1)),
+                Char('.', _) => (noop, Dot(1)),
+                // macros
+                Char('q', _) if self.recording.is_some() => {
+                    self.stop_recording();
+                    (noop.clone(), noop)
+                }
+                Char('q', _) => (Q, noop),
+                Char('@', _) => (At(1), noop),
                 evnt => (noop, evnt),
             },
             Event::Noop if ctrl => match evnt {
@@ -220,6 +482,14 @@ impl KeyEdit {
                     let evnt = Mt(Mto::WinScroll(1, Scroll::Pages, DP::Left));
                     (noop, evnt)
                 }
+                // motion command, jump-list
+                Char('o', _) => (noop, Mt(Mto::JumpList(1, DP::Left))),
+                Char('i', _) | Tab(_) => (noop, Mt(Mto::JumpList(1, DP::Right))),
+                // redo
+                Char('r', _) => (noop, Redo(1)),
+                Char('v', _) => (noop, Md(Mod::VisualBlock)),
+                // window commands
+                Char('w', _) => (W, noop),
                 evnt => (noop, evnt),
             },
             N(n) if empty | shift => match evnt {
@@ -238,6 +508,7 @@ impl KeyEdit {
                 Char('f', _) => (F(n, DP::Right), noop),
                 Char('F', _) => (F(n, DP::Left), noop),
                 Char('t', _) => (T(n, DP::Right), noop),
+                Char('r', _) => (R(n), noop),
                 Char('T', _) => (T(n, DP::Left), noop),
                 Char(';', _) => (noop, Mt(Mto::CharR(n, DP::Right))),
                 Char(',', _) => (noop, Mt(Mto::CharR(n, DP::Left))),
@@ -270,8 +541,11 @@ impl KeyEdit {
                 Char('m', _) => (M, noop),
                 Char('\'', _) => (J('\''), noop),
                 Char('`', _) => (J('`'), noop),
+                Tab(_) => (noop, Mt(Mto::JumpList(n, DP::Right))),
                 Char('n', _) => (noop, Mt(Mto::PatternR(n, DP::Right))),
                 Char('N', _) => (noop, Mt(Mto::PatternR(n, DP::Left))),
+                Char('*', _) => (noop, Mt(Mto::SearchWord(n, DP::Right))),
+                Char('#', _) => (noop, Mt(Mto::SearchWord(n, DP::Left))),
                 // prefix commands
                 Char(ch @ '0'..='9', _) => (N(parse_n!(n, ch)), noop),
                 Char('[', _) => (B(n, DP::Left), noop),
@@ -282,11 +556,11 @@ impl KeyEdit {
                 Char('c', _) => (Op(event::Opr::Change(n, Mto::None)), noop),
                 Char('d', _) => (Op(event::Opr::Delete(n, Mto::None)), noop),
                 Char('y', _) => (Op(event::Opr::Yank(n, Mto::None)), noop),
-                Char('~', _) => (Op(event::Opr::Swapcase(n, Mto::None)), noop),
+                Char('~', _) => (noop, Op(event::Opr::Swapcase(n, Mto::None))),
                 Char('!', _) => (Op(event::Opr::Filter(n, Mto::None)), noop),
                 Char('=', _) => (Op(event::Opr::Equal(n, Mto::None)), noop),
-                Char('<', _) => (Op(event::Opr::RShift(n, Mto::None)), noop),
-                Char('>', _) => (Op(event::Opr::LShift(n, Mto::None)), noop),
+                Char('>', _) => (Op(event::Opr::RShift(n, Mto::None)), noop),
+                Char('<', _) => (Op(event::Opr::LShift(n, Mto::None)), noop),
                 //
                 Char('I', _) => (noop, Md(Mod::Insert(n, DP::TextCol))),
                 Char('i', _) => (noop, Md(Mod::Insert(n, DP::None))),
@@ -294,6 +568,16 @@ impl KeyEdit {
                 Char('A', _) => (noop, Md(Mod::Append(n, DP::End))),
                 Char('O', _) => (noop, Md(Mod::Open(n, DP::Left))),
                 Char('o', _) => (noop, Md(Mod::Open(n, DP::Right))),
+                Char('p', _) => (noop, Put(n, register::UNNAMED, DP::Right)),
+                Char('P', _) => (noop, Put(n, register::UNNAMED, DP::Left)),
+                Char('J', _) => (noop, Join(n)),
+                Char('x', _) => (noop, Wr(Cud::Delete(n))),
+                Char('X', _) => (noop, Wr(Cud::Backspace(n))),
+                // undo
+                Char('u', _) => (noop, Undo(n)),
+                Char('.', _) => (noop, Dot(n)),
+                // macros
+                Char('@', _) => (At(n), noop),
                 evnt => (noop, evnt),
             },
             N(n) if ctrl => match evnt {
@@ -326,6 +610,11 @@ impl KeyEdit {
                     let evnt = Mt(Mto::WinScroll(n, Scroll::Pages, DP::Left));
                     (noop, evnt)
                 }
+                // motion command, jump-list
+                Char('o', _) => (noop, Mt(Mto::JumpList(n, DP::Left))),
+                Char('i', _) | Tab(_) => (noop, Mt(Mto::JumpList(n, DP::Right))),
+                // redo
+                Char('r', _) => (noop, Redo(n)),
                 evnt => (noop, evnt),
             },
             G(n) if empty | shift => match evnt {
@@ -349,11 +638,15 @@ impl KeyEdit {
                 Char('E', _) => (noop, Mt(Mto::WWord(n, DP::Left, DP::Start))),
 
                 Char('o', _) => (noop, Mt(Mto::Cursor(n))),
+                Char(';', _) => (noop, Mt(Mto::ChangeList(n, DP::Left))),
+                Char(',', _) => (noop, Mt(Mto::ChangeList(n, DP::Right))),
                 Char('I', _) => (noop, Md(Mod::Insert(n, DP::TextCol))),
+                Char('v', _) => (noop, Md(Mod::Reselect)),
                 // operation prefix
                 Char('~', _) => (Op(event::Opr::Swapcase(n, Mto::None)), noop),
                 Char('u', _) => (Op(event::Opr::Lowercase(n, Mto::None)), noop),
                 Char('U', _) => (Op(event::Opr::Uppercase(n, Mto::None)), noop),
+                Char('q', _) => (Op(event::Opr::Format(n, Mto::None)), noop),
                 Char('w', _) => (Op(event::Opr::Format(n, Mto::None)), noop),
                 Char('?', _) => (Op(event::Opr::Encode(n, Mto::None)), noop),
                 Char('@', _) => (Op(event::Opr::Func(n, Mto::None)), noop),
@@ -373,14 +666,43 @@ impl KeyEdit {
                 Char('}', _) => (noop, Mt(Mto::UnmatchPair(n, '}', d))),
                 evnt => (noop, evnt),
             },
-            F(n, d) if empty => match evnt {
+            F(n, d) if empty | shift => match evnt {
                 Char(ch, _) => (noop, Mt(Mto::CharF(n, Some(ch), d))),
                 evnt => (noop, evnt),
             },
-            T(n, d) if empty => match evnt {
+            T(n, d) if empty | shift => match evnt {
                 Char(ch, _) => (noop, Mt(Mto::CharT(n, Some(ch), d))),
                 evnt => (noop, evnt),
             },
+            R(n) => match evnt {
+                Char(ch, _) if !ctrl => (noop, ReplaceChar(n, ch)),
+                Tab(_) => (noop, ReplaceChar(n, '\t')),
+                _ => (noop, Event::Noop),
+            },
+            Q if empty | shift => match evnt {
+                Char(ch @ 'a'..='z', _) | Char(ch @ 'A'..='Z', _) => {
+                    self.start_recording(ch);
+                    (noop.clone(), noop)
+                }
+                _ => (noop, Event::Noop),
+            },
+            At(n) if empty | shift => match evnt {
+                Char('@', _) => match self.last_macro {
+                    Some(ch) => (noop, self.to_macro(n, ch)),
+                    None => (noop, Event::Noop),
+                },
+                Char(ch @ 'a'..='z', _) | Char(ch @ 'A'..='Z', _) => {
+                    self.last_macro = Some(ch.to_ascii_lowercase());
+                    (noop, self.to_macro(n, ch))
+                }
+                _ => (noop, Event::Noop),
+            },
+            W if empty | ctrl => match evnt {
+                Char('s', _) => (noop, Event::Appn(event::Appn::SplitH)),
+                Char('v', _) => (noop, Event::Appn(event::Appn::SplitV)),
+                Char('w', _) => (noop, Event::Appn(event::Appn::FocusNext)),
+                _ => (noop, Event::Noop),
+            },
             Z(n) if empty => match evnt {
                 // motion commands, window scroll - vertical
                 Enter(_) => {
@@ -440,6 +762,13 @@ impl KeyEdit {
                     let evnt = Mt(Mto::WinScroll(n, Scroll::Align, DP::Left));
                     (noop, evnt)
                 }
+                // fold commands, count defaults to ZERO for `z` prefix.
+                Char('f', _) => {
+                    let n = if_else!(n == 0, 1, n);
+                    (Op(event::Opr::Fold(n, Mto::None)), noop)
+                }
+                Char('o', _) => (noop, Event::Fold(true)),
+                Char('c', _) => (noop, Event::Fold(false)),
                 evnt => (noop, evnt),
             },
             M if empty => match evnt {
@@ -449,6 +778,32 @@ impl KeyEdit {
                 },
                 evnt => (noop, evnt),
             },
+            Quote if empty => match evnt {
+                Char(ch, _) if register::is_register(ch) => (noop, Reg(ch)),
+                evnt => (noop, evnt),
+            },
+            O(n, around) if empty | shift => {
+                use crate::event::TextObject::{Block, Para, Quote, Sentence, WWord, Word};
+
+                let obj = match evnt {
+                    Char('w', _) => Some(Word(around)),
+                    Char('W', _) => Some(WWord(around)),
+                    Char('s', _) => Some(Sentence(around)),
+                    Char('p', _) => Some(Para(around)),
+                    Char(ch @ '"', _) | Char(ch @ '\'', _) | Char(ch @ '`', _) => {
+                        Some(Quote(ch, around))
+                    }
+                    Char('(', _) | Char(')', _) | Char('b', _) => Some(Block('(', ')', around)),
+                    Char('{', _) | Char('}', _) | Char('B', _) => Some(Block('{', '}', around)),
+                    Char('[', _) | Char(']', _) => Some(Block('[', ']', around)),
+                    Char('<', _) | Char('>', _) => Some(Block('<', '>', around)),
+                    _ => None,
+                };
+                match obj {
+                    Some(obj) => (noop, Mt(Mto::Object(n, obj))),
+                    None => (noop, evnt),
+                }
+            }
             J(typ) if empty => match evnt {
                 Char(ch, _) => match ch {
                     '\'' | '`' => (noop, Mt(Mto::Jump(typ, ch))),
@@ -458,12 +813,51 @@ impl KeyEdit {
                 },
                 evnt => (noop, evnt),
             },
+            S(mut patt, dp) => match evnt {
+                Enter(_) if patt.is_empty() => (noop, Mt(Mto::PatternR(1, dp))),
+                Enter(_) => (noop, Mt(Mto::Pattern(1, Some(patt), dp))),
+                Esc => (noop.clone(), noop),
+                Backspace(_) if patt.is_empty() => (noop.clone(), noop),
+                Backspace(_) => {
+                    patt.pop();
+                    (S(patt, dp), noop)
+                }
+                Char(ch, _) if !ctrl => {
+                    patt.push(ch);
+                    (S(patt, dp), noop)
+                }
+                _ => (S(patt, dp), noop),
+            },
             prefix => (prefix, evnt),
         };
 
         debug!("normal prefix:{} event:{}", prefix, evnt);
 
-        self.prefix = prefix;
+        self.prefix = match prefix {
+            Op(opr) => {
+                self.opr = Some(opr);
+                Event::Noop
+            }
+            prefix => prefix,
+        };
         Ok(evnt)
     }
 }
+
+// key-maps are matched irrespective of shift modifier for characters.
+fn to_key(evnt: &Event) -> Event {
+    use crossterm::event::KeyModifiers as KM;
+
+    match evnt {
+        Event::Char(ch, m) => {
+            let mut m = *m;
+            m.remove(KM::SHIFT);
+            Event::Char(*ch, m)
+        }
+        evnt => evnt.clone(),
+    }
+}
+
+#[cfg(test)]
+#[path = "keymap_edit_test.rs"]
+mod keymap_edit_test;
@@ -0,0 +1,80 @@
+use crossterm::event::KeyModifiers;
+
+use super::*;
+use crate::{event::Appn, keymap::parse_keys, location::Location};
+
+// fold typed `keys` into `buf`, replaying macros like the edit window.
+fn fold_keys(km: &mut KeyEdit, buf: &mut Buffer, keys: Vec<Event>) {
+    for evnt in keys.into_iter() {
+        match km.fold(buf, evnt).unwrap() {
+            Event::Macro(_, evnts) => {
+                let (noremap, saved) = (km.take_noremap(), km.is_noremap());
+                for (i, evnt) in evnts.into_iter().enumerate() {
+                    km.set_noremap(i < noremap);
+                    fold_keys(km, buf, vec![evnt]);
+                }
+                km.set_noremap(saved);
+            }
+            evnt => {
+                buf.on_event(evnt).unwrap();
+            }
+        }
+    }
+}
+
+#[test]
+fn test_parse_keys() {
+    let empty = KeyModifiers::empty();
+
+    let keys = parse_keys("a<Esc><lt><C-s><Space>b<").unwrap();
+    let refs = vec![
+        Event::Char('a', empty),
+        Event::Esc,
+        Event::Char('<', empty),
+        Event::Char('s', KeyModifiers::CONTROL),
+        Event::Char(' ', empty),
+        Event::Char('b', empty),
+        Event::Char('<', empty),
+    ];
+    assert!(keys == refs);
+
+    assert!(parse_keys("<no-such-key>").is_err());
+    assert!(KeyRemap::new("normal", "jk").is_err());
+}
+
+#[test]
+fn test_remap() {
+    let mut buf = {
+        let loc = Location::new_ted("test-remap", "hello\n".as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+    let mut km = KeyEdit::default();
+    km.set_remaps(vec![
+        KeyRemap::new("insert", "jk <Esc>").unwrap(),
+        KeyRemap::new("normal", "Y y$").unwrap(),
+        KeyRemap::new("normal", "<C-s> :write").unwrap(),
+    ]);
+
+    // `jk` leaves insert mode, while `j` by itself is inserted.
+    fold_keys(&mut km, &mut buf, parse_keys("ijajjk").unwrap());
+    assert_eq!(buf.to_string(), "jajhello\n");
+    assert_eq!(buf.to_mode(), "normal");
+
+    // `Y` yanks till the end of line, instead of the whole line.
+    fold_keys(&mut km, &mut buf, parse_keys("0lYP").unwrap());
+    assert_eq!(buf.to_string(), "jajhelloajhello\n");
+
+    // a replay stopped early shall not leak noremap into typed keys.
+    let evnt = km.fold(&buf, parse_keys("Y").unwrap().remove(0)).unwrap();
+    assert!(matches!(evnt, Event::Macro(_, _)));
+    assert_eq!(km.take_noremap(), 2);
+    assert_eq!(km.take_noremap(), 0);
+    assert!(!km.is_noremap());
+    fold_keys(&mut km, &mut buf, parse_keys("Ajk").unwrap());
+    assert_eq!(buf.to_mode(), "normal");
+
+    let evnt = km
+        .fold(&buf, parse_keys("<C-s>").unwrap().remove(0))
+        .unwrap();
+    assert!(evnt == Event::Appn(Appn::Command("write".to_string())));
+}
@@ -4,7 +4,7 @@
 
 use lazy_static::lazy_static;
 
-use std::{fmt, result};
+use std::{error, fmt, io, panic, result};
 
 #[macro_use]
 pub mod util;
@@ -19,18 +19,23 @@ mod window_less;
 mod window_prompt;
 mod window_status;
 mod window_suggt;
+mod window_tabs;
 
 pub mod state;
 
 pub mod buffer;
+mod clipboard;
 mod col_nu;
 pub mod colors;
 pub mod event;
+mod input;
 pub mod location;
 mod mark;
 mod match_pair;
 pub mod pubsub;
+mod register;
 mod scroll;
+pub mod search;
 pub mod tabc;
 pub mod tss;
 mod view;
@@ -54,7 +59,9 @@ lazy_static! {
 /// Result returned by all `Ted` API.
 pub type Result<T> = result::Result<T, Error>;
 
-/// Collection of all `Ted` errors.
+/// Collection of all `Ted` errors. First field of each variant is the
+/// source location, `file:line`, where the error was raised, captured by
+/// the `err_at!` macro and included in its `Display` output.
 #[derive(Clone)]
 pub enum Error {
     Fatal(String, String),
@@ -66,12 +73,13 @@ pub enum Error {
     FailConvert(String, String),
     FailParse(String, String),
     FailBuffer(String, String),
+    BadCommand(String, String),
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> result::Result<(), fmt::Error> {
+        use Error::{BadCommand, FailBuffer, FailConvert, FailParse};
         use Error::{BadPattern, Fatal, IOError, Invalid, NoTopic, IPC};
-        use Error::{FailBuffer, FailConvert, FailParse};
 
         match self {
             Fatal(p, msg) => write!(f, "{} Fatal: {}", p, msg),
@@ -83,6 +91,7 @@ impl fmt::Display for Error {
             FailConvert(p, msg) => write!(f, "{} FailConvert: {}", p, msg),
             FailParse(p, msg) => write!(f, "{} FailParse: {}", p, msg),
             FailBuffer(p, msg) => write!(f, "{} FailBuffer: {}", p, msg),
+            BadCommand(p, msg) => write!(f, "{} BadCommand: {}", p, msg),
         }
     }
 }
@@ -95,8 +104,8 @@ impl fmt::Debug for Error {
 
 impl Error {
     pub fn to_loc(&self) -> String {
+        use Error::{BadCommand, FailBuffer, FailConvert, FailParse};
         use Error::{BadPattern, Fatal, IOError, Invalid, NoTopic, IPC};
-        use Error::{FailBuffer, FailConvert, FailParse};
 
         match self {
             Fatal(p, _) => p.clone(),
@@ -108,12 +117,13 @@ impl Error {
             FailConvert(p, _) => p.clone(),
             FailParse(p, _) => p.clone(),
             FailBuffer(p, _) => p.clone(),
+            BadCommand(p, _) => p.clone(),
         }
     }
 
     pub fn to_error(&self) -> String {
+        use Error::{BadCommand, FailBuffer, FailConvert, FailParse};
         use Error::{BadPattern, Fatal, IOError, Invalid, NoTopic, IPC};
-        use Error::{FailBuffer, FailConvert, FailParse};
 
         match self {
             Fatal(_, msg) => msg.clone(),
@@ -125,6 +135,27 @@ impl Error {
             FailConvert(_, msg) => msg.clone(),
             FailParse(_, msg) => msg.clone(),
             FailBuffer(_, msg) => msg.clone(),
+            BadCommand(_, msg) => msg.clone(),
         }
     }
 }
+
+impl error::Error for Error {}
+
+impl From<io::Error> for Error {
+    #[track_caller]
+    fn from(err: io::Error) -> Error {
+        let loc = panic::Location::caller();
+        let prefix = format!("{}:{}", loc.file(), loc.line());
+        Error::IOError(prefix, err.to_string())
+    }
+}
+
+impl From<regex::Error> for Error {
+    #[track_caller]
+    fn from(err: regex::Error) -> Error {
+        let loc = panic::Location::caller();
+        let prefix = format!("{}:{}", loc.file(), loc.line());
+        Error::BadPattern(prefix, err.to_string())
+    }
+}
@@ -1,16 +1,11 @@
 //! Module `location` implement the backing source for a buffer.
 
 use dirs;
-use lazy_static::lazy_static;
 
-use std::{ffi, fmt, io, path, result, sync::Mutex};
+use std::{ffi, fmt, io, path, result};
 
 use crate::{text, Error, Result};
 
-lazy_static! {
-    static ref MEM_BUFFER_N: Mutex<usize> = Mutex::new(0);
-}
-
 /// Location of buffer's content, typically persistent medium. Each
 /// variant denotes the location type.
 #[derive(Clone)]
@@ -53,7 +48,7 @@ impl Location {
             err_at!(IOError, res.map_err(|e| format!("{:?}", e)))?
         };
         let path_file = Self::canonicalize(fp).into_os_string();
-        let m = err_at!(IOError, fs::metadata(&path_file))?;
+        let m = fs::metadata(&path_file)?;
         Ok(Location::Disk {
             loc: loc.to_os_string(),
             path_file,
@@ -62,16 +57,36 @@ impl Location {
         })
     }
 
-    /// Create a memory-only buffer.
+    /// Create a new Disk location for buffer, similar to [Location::new_disk]
+    /// except that the file need not exist, typically used as the target
+    /// for writing buffer's content.
+    pub fn new_disk_file(loc: &ffi::OsStr, enc: &str) -> Result<Location> {
+        use std::fs;
+
+        let fp = {
+            let res = loc.to_os_string().into_string();
+            err_at!(IOError, res.map_err(|e| format!("{:?}", e)))?
+        };
+        let path_file = Self::canonicalize(fp).into_os_string();
+        let read_only = match fs::metadata(&path_file) {
+            Ok(m) => m.permissions().readonly(),
+            Err(_) => false,
+        };
+        Ok(Location::Disk {
+            loc: loc.to_os_string(),
+            path_file,
+            enc: enc.to_string(),
+            read_only,
+        })
+    }
+
+    /// Create a memory-only buffer. Memory locations are not named,
+    /// refer [crate::buffer::Buffer::to_id] for identifying them.
     pub fn new_memory<R>(r: R, enc: &str, read_only: bool) -> Result<Location>
     where
         R: io::Read,
     {
-        let name = {
-            let mut count = err_at!(Fatal, MEM_BUFFER_N.lock())?;
-            *count = *count + 1;
-            format!("[no-name-{}]", count)
-        };
+        let name = "[no-name]".to_string();
         let text: String = text::Encoding::from_reader(r, enc)?.into();
         Ok(Location::Memory {
             name,
@@ -113,7 +128,7 @@ impl Location {
             Location::Disk { path_file, enc, .. } => {
                 let fd = {
                     let mut oo = fs::OpenOptions::new();
-                    err_at!(IOError, oo.read(true).open(path_file))?
+                    oo.read(true).open(path_file)?
                 };
                 Ok(text::Encoding::from_reader(fd, enc)?.into())
             }
@@ -122,6 +137,33 @@ impl Location {
         }
     }
 
+    /// Write `text` to location, only Disk locations can be written to.
+    /// Return the number of bytes written.
+    pub fn write(&self, text: &str) -> Result<usize> {
+        use std::{convert::TryInto, fs};
+
+        match self {
+            Location::Disk {
+                path_file,
+                read_only: true,
+                ..
+            } => err_at!(IOError, msg: format!("read-only file {:?}", path_file)),
+            Location::Disk { path_file, enc, .. } => {
+                let fd = {
+                    let mut oo = fs::OpenOptions::new();
+                    let oo = oo.write(true).create(true).truncate(true);
+                    oo.open(path_file)?
+                };
+                let enc: text::Encoding = (text.to_string(), enc.clone()).try_into()?;
+                enc.save(fd)?;
+                Ok(text.len())
+            }
+            Location::Memory { name, .. } | Location::Ted { name, .. } => {
+                err_at!(IOError, msg: format!("no file name for {}", name))
+            }
+        }
+    }
+
     /// Read the content as String, and convert it to bytes.
     pub fn to_bytes(&self) -> Result<Vec<u8>> {
         Ok(self.read()?.into())
@@ -222,7 +264,7 @@ impl fmt::Display for Location {
 fn disk_cwd_loc(loc: path::PathBuf) -> Result<ffi::OsString> {
     use std::env;
 
-    let cwd = err_at!(IOError, env::current_dir())?;
+    let cwd = env::current_dir()?;
     let loc = if loc.starts_with(&cwd) {
         err_at!(Invalid, loc.strip_prefix(&cwd))?.into()
     } else if let Some(home) = dirs::home_dir() {
@@ -16,11 +16,19 @@ fn main() {
     }
 
     std::panic::set_hook(box |panic_info| {
-        let s = match panic_info.payload().downcast_ref::<String>() {
+        let payload = panic_info.payload();
+        let s = match payload.downcast_ref::<String>() {
             Some(s) => s.to_string(),
+            None => match payload.downcast_ref::<&str>() {
+                Some(s) => s.to_string(),
+                None => "???".to_string(),
+            },
+        };
+        let loc = match panic_info.location() {
+            Some(loc) => format!("{}:{}", loc.file(), loc.line()),
             None => "???".to_string(),
         };
-        let mut strng = format!("panic occured: {}", s);
+        let mut strng = format!("panic occured at {}: {}\n", loc, s);
         strng.push_str(&format!("{}", std::backtrace::Backtrace::capture()));
         fs::write("ted-panic.out", strng.as_bytes()).unwrap();
     });
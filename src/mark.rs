@@ -15,8 +15,6 @@ pub struct Mark {
     index: char,
     buf_id: String,
     cursor: usize,
-    col: usize,
-    row: usize,
 }
 
 impl fmt::Display for Mark {
@@ -31,21 +29,22 @@ impl From<char> for Mark {
             index: index,
             buf_id: String::default(),
             cursor: usize::default(),
-            col: usize::default(),
-            row: usize::default(),
         }
     }
 }
 
 impl Mark {
+    pub fn new(index: char, buf_id: String, cursor: usize) -> Mark {
+        Mark {
+            index,
+            buf_id,
+            cursor,
+        }
+    }
+
     pub fn into_mark(mut self, buf: &Buffer) -> Self {
         self.buf_id = buf.to_id();
         self.cursor = buf.to_char_cursor();
-
-        let bc_xy = buf.to_xy_cursor(Some(self.cursor));
-
-        self.col = bc_xy.col;
-        self.row = bc_xy.row;
         self
     }
 
@@ -63,11 +62,6 @@ impl Mark {
     pub fn to_index(&self) -> char {
         self.index
     }
-
-    #[inline]
-    pub fn to_row(&self) -> usize {
-        self.row
-    }
 }
 
 #[inline]
@@ -83,6 +77,184 @@ pub fn set_mark(marks: &mut Marks, mark: Mark) -> Option<Mark> {
     old
 }
 
+/// Shift marks after `n` chars are removed and `m` chars are inserted at
+/// char-index `at`. Marks within the removed text collapse to `at`.
+pub fn shift_marks(marks: &mut Marks, at: usize, n: usize, m: usize) {
+    for mrk in marks.iter_mut().filter_map(|mrk| mrk.as_mut()) {
+        mrk.cursor = shift_cursor(mrk.cursor, at, n, m);
+    }
+}
+
+fn shift_cursor(cursor: usize, at: usize, n: usize, m: usize) -> usize {
+    match cursor {
+        cursor if cursor < at => cursor,
+        cursor if cursor < (at + n) => at,
+        cursor => cursor - n + m,
+    }
+}
+
+/// Maximum number of positions remembered in the jump list.
+pub const JUMP_LIST_SIZE: usize = 100;
+
+/// Jump list, positions, as char-index, from where large motions like
+/// search, `gg`, `G`, `%` were made. Walked backward and forward via
+/// `Ctrl-o` and `Ctrl-i`.
+#[derive(Clone, Default)]
+pub struct Jumps {
+    list: Vec<usize>,
+    // position in the list, same as list.len() when not walking the list.
+    off: usize,
+}
+
+impl Jumps {
+    /// Remember `cursor` as the latest jump, older entry for the same
+    /// position is removed from the list.
+    pub fn push(&mut self, cursor: usize) {
+        self.list.retain(|c| *c != cursor);
+        self.list.push(cursor);
+        if self.list.len() > JUMP_LIST_SIZE {
+            self.list.remove(0);
+        }
+        self.off = self.list.len();
+    }
+
+    /// Walk `n` entries back from `cursor`, return the older position.
+    /// If not already walking the list, `cursor` is remembered so that
+    /// a forward walk can come back to it.
+    pub fn backward(&mut self, cursor: usize, n: usize) -> Option<usize> {
+        if self.off >= self.list.len() {
+            self.push(cursor);
+            self.off = self.list.len() - 1;
+        }
+        match self.off.checked_sub(n) {
+            Some(off) => {
+                self.off = off;
+                Some(self.list[off])
+            }
+            None => None,
+        }
+    }
+
+    /// Walk `n` entries forward, return the newer position.
+    pub fn forward(&mut self, n: usize) -> Option<usize> {
+        match self.off + n {
+            off if off < self.list.len() => {
+                self.off = off;
+                Some(self.list[off])
+            }
+            _ => None,
+        }
+    }
+
+    /// Shift positions, refer [shift_marks].
+    pub fn shift(&mut self, at: usize, n: usize, m: usize) {
+        for cursor in self.list.iter_mut() {
+            *cursor = shift_cursor(*cursor, at, n, m);
+        }
+    }
+}
+
+/// Maximum number of positions remembered in the change list.
+pub const CHANGE_LIST_SIZE: usize = 100;
+
+/// Change list, positions, as char-index, of recent edits, one entry for
+/// each change. Walked backward and forward via `g;` and `g,`.
+#[derive(Clone, Default)]
+pub struct Changes {
+    list: Vec<usize>,
+    // position in the list, same as list.len() when not walking the list.
+    off: usize,
+}
+
+impl Changes {
+    /// Remember `cursor` as the position of a new change.
+    pub fn push(&mut self, cursor: usize) {
+        self.list.push(cursor);
+        if self.list.len() > CHANGE_LIST_SIZE {
+            self.list.remove(0);
+        }
+        self.off = self.list.len();
+    }
+
+    /// Update the position of the latest change to `cursor`.
+    pub fn set_latest(&mut self, cursor: usize) {
+        match self.list.last_mut() {
+            Some(latest) => *latest = cursor,
+            None => self.list.push(cursor),
+        }
+        self.off = self.list.len();
+    }
+
+    /// Walk `n` entries back, return the older position.
+    pub fn backward(&mut self, n: usize) -> Option<usize> {
+        match self.off.checked_sub(n) {
+            Some(off) => {
+                self.off = off;
+                Some(self.list[off])
+            }
+            None => None,
+        }
+    }
+
+    /// Walk `n` entries forward, return the newer position.
+    pub fn forward(&mut self, n: usize) -> Option<usize> {
+        match self.off + n {
+            off if off < self.list.len() => {
+                self.off = off;
+                Some(self.list[off])
+            }
+            _ => None,
+        }
+    }
+
+    /// Shift positions, refer [shift_marks].
+    pub fn shift(&mut self, at: usize, n: usize, m: usize) {
+        for cursor in self.list.iter_mut() {
+            *cursor = shift_cursor(*cursor, at, n, m);
+        }
+    }
+
+    /// Return the list of positions, oldest first.
+    pub fn to_list(&self) -> Vec<usize> {
+        self.list.clone()
+    }
+}
+
+/// Last visual selection, as char-index of its anchor and cursor, restored
+/// via `gv`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Selection {
+    pub anchor: usize,
+    pub cursor: usize,
+    pub linewise: bool,
+    pub block: bool,
+}
+
+impl Selection {
+    /// Shift selection, refer [shift_marks].
+    pub fn shift(&mut self, at: usize, n: usize, m: usize) {
+        self.anchor = shift_cursor(self.anchor, at, n, m);
+        self.cursor = shift_cursor(self.cursor, at, n, m);
+    }
+}
+
+/// Fold over a range of lines, as char-index of its first line's home and
+/// its last line's home. Closed folds are rendered as a single line.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Fold {
+    pub start: usize,
+    pub end: usize,
+    pub closed: bool,
+}
+
+impl Fold {
+    /// Shift fold, refer [shift_marks].
+    pub fn shift(&mut self, at: usize, n: usize, m: usize) {
+        self.start = shift_cursor(self.start, at, n, m);
+        self.end = shift_cursor(self.end, at, n, m);
+    }
+}
+
 #[allow(dead_code)]
 #[inline]
 pub fn del_mark(marks: &mut Marks, index: u8) -> Option<Mark> {
@@ -18,14 +18,29 @@ lazy_static! {
     };
 }
 
-pub fn match_under_cursor(buf: &Buffer) -> Option<usize> {
+/// Scan forward on the current line, from cursor, for the first bracket
+/// among `()[]{}` and return the char-index of its balanced partner.
+pub fn match_on_line(buf: &Buffer) -> Option<usize> {
     use crate::event::DP;
 
     let cursor = buf.to_char_cursor();
+    let mut iter = buf.chars_at(cursor, DP::Right).ok()?.enumerate();
+    loop {
+        match iter.next()? {
+            (_, '\n') => break None,
+            (i, '{' | '}' | '(' | ')' | '[' | ']') => break match_at(buf, cursor + i),
+            _ => (),
+        }
+    }
+}
+
+fn match_at(buf: &Buffer, cursor: usize) -> Option<usize> {
+    use crate::event::DP;
+
     match buf.chars_at(cursor, DP::Right).ok()?.next()? {
-        '{' | '}' => CurlyBracket::match_under_cursor(buf),
-        '(' | ')' => Paranthesis::match_under_cursor(buf),
-        '[' | ']' => SquarBracket::match_under_cursor(buf),
+        '{' | '}' => CurlyBracket::match_at(buf, cursor),
+        '(' | ')' => Paranthesis::match_at(buf, cursor),
+        '[' | ']' => SquarBracket::match_at(buf, cursor),
         _ => None,
     }
 }
@@ -59,13 +74,13 @@ impl CurlyBracket {
         vec!['{', '}']
     }
 
-    fn match_under_cursor(buf: &Buffer) -> Option<usize> {
+    fn match_at(buf: &Buffer, cursor: usize) -> Option<usize> {
         use crate::event::DP;
 
-        let mut iter = buf.chars_at(buf.to_char_cursor(), DP::Right).ok()?;
+        let mut iter = buf.chars_at(cursor, DP::Right).ok()?;
         match iter.next() {
-            Some('{') => Self::match_fwd(buf),
-            Some('}') => Self::match_rev(buf),
+            Some('{') => Self::match_fwd(buf, cursor),
+            Some('}') => Self::match_rev(buf, cursor),
             _ => None,
         }
     }
@@ -106,10 +121,9 @@ impl CurlyBracket {
         }
     }
 
-    fn match_rev(buf: &Buffer) -> Option<usize> {
+    fn match_rev(buf: &Buffer, cursor: usize) -> Option<usize> {
         use crate::event::DP;
 
-        let cursor = buf.to_char_cursor();
         let mut iter = buf.chars_at(cursor, DP::Left).ok()?.enumerate();
         let mut stack = 0_usize;
         loop {
@@ -123,10 +137,9 @@ impl CurlyBracket {
         }
     }
 
-    fn match_fwd(buf: &Buffer) -> Option<usize> {
+    fn match_fwd(buf: &Buffer, cursor: usize) -> Option<usize> {
         use crate::event::DP;
 
-        let cursor = buf.to_char_cursor();
         let mut iter = buf.chars_at(cursor, DP::Right).ok()?.enumerate();
         let mut stack = 0_usize;
         loop {
@@ -148,13 +161,13 @@ impl Paranthesis {
         vec!['(', ')']
     }
 
-    fn match_under_cursor(buf: &Buffer) -> Option<usize> {
+    fn match_at(buf: &Buffer, cursor: usize) -> Option<usize> {
         use crate::event::DP;
 
-        let mut iter = buf.chars_at(buf.to_char_cursor(), DP::Right).ok()?;
+        let mut iter = buf.chars_at(cursor, DP::Right).ok()?;
         match iter.next() {
-            Some('(') => Self::match_fwd(buf),
-            Some(')') => Self::match_rev(buf),
+            Some('(') => Self::match_fwd(buf, cursor),
+            Some(')') => Self::match_rev(buf, cursor),
             _ => None,
         }
     }
@@ -195,10 +208,9 @@ impl Paranthesis {
         }
     }
 
-    fn match_rev(buf: &Buffer) -> Option<usize> {
+    fn match_rev(buf: &Buffer, cursor: usize) -> Option<usize> {
         use crate::event::DP;
 
-        let cursor = buf.to_char_cursor();
         let mut iter = buf.chars_at(cursor, DP::Left).ok()?.enumerate();
         let mut stack = 0_usize;
         loop {
@@ -212,15 +224,14 @@ impl Paranthesis {
         }
     }
 
-    fn match_fwd(buf: &Buffer) -> Option<usize> {
+    fn match_fwd(buf: &Buffer, cursor: usize) -> Option<usize> {
         use crate::event::DP;
 
-        let cursor = buf.to_char_cursor();
         let mut iter = buf.chars_at(cursor, DP::Right).ok()?.enumerate();
         let mut stack = 0_usize;
         loop {
             match iter.next() {
-                Some((i, ')')) if stack == 0 => break Some(cursor.saturating_add(i)),
+                Some((i, ')')) if stack == 1 => break Some(cursor.saturating_add(i)),
                 Some((_, ')')) => stack = stack.saturating_sub(1),
                 Some((_, '(')) => stack = stack.saturating_add(1),
                 Some((_, _)) => (),
@@ -237,21 +248,20 @@ impl SquarBracket {
         vec!['[', ']']
     }
 
-    fn match_under_cursor(buf: &Buffer) -> Option<usize> {
+    fn match_at(buf: &Buffer, cursor: usize) -> Option<usize> {
         use crate::event::DP;
 
-        let mut iter = buf.chars_at(buf.to_char_cursor(), DP::Right).ok()?;
+        let mut iter = buf.chars_at(cursor, DP::Right).ok()?;
         match iter.next() {
-            Some('[') => Self::match_fwd(buf),
-            Some(']') => Self::match_rev(buf),
+            Some('[') => Self::match_fwd(buf, cursor),
+            Some(']') => Self::match_rev(buf, cursor),
             _ => None,
         }
     }
 
-    fn match_rev(buf: &Buffer) -> Option<usize> {
+    fn match_rev(buf: &Buffer, cursor: usize) -> Option<usize> {
         use crate::event::DP;
 
-        let cursor = buf.to_char_cursor();
         let mut iter = buf.chars_at(cursor, DP::Left).ok()?.enumerate();
         let mut stack = 0_usize;
         loop {
@@ -265,15 +275,14 @@ impl SquarBracket {
         }
     }
 
-    fn match_fwd(buf: &Buffer) -> Option<usize> {
+    fn match_fwd(buf: &Buffer, cursor: usize) -> Option<usize> {
         use crate::event::DP;
 
-        let cursor = buf.to_char_cursor();
         let mut iter = buf.chars_at(cursor, DP::Right).ok()?.enumerate();
         let mut stack = 0_usize;
         loop {
             match iter.next() {
-                Some((i, ']')) if stack == 0 => break Some(cursor.saturating_add(i)),
+                Some((i, ']')) if stack == 1 => break Some(cursor.saturating_add(i)),
                 Some((_, ']')) => stack = stack.saturating_sub(1),
                 Some((_, '[')) => stack = stack.saturating_add(1),
                 Some((_, _)) => (),
@@ -71,7 +71,7 @@ impl PubSub {
                 }
                 Ok(())
             }
-            None => Err(Error::NoTopic(String::new())),
+            None => err_at!(Err(Error::NoTopic(String::new()))),
         }
     }
 
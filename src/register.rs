@@ -0,0 +1,94 @@
+#[allow(unused_imports)]
+use log::debug;
+
+use std::{fmt, result};
+
+use crate::clipboard;
+
+pub type Registers = [Option<Register>; 256];
+
+pub fn new_registers() -> Registers {
+    let mut registers: Registers = unsafe { std::mem::zeroed() };
+
+    (0..registers.len()).for_each(|i| registers[i] = None);
+    registers
+}
+
+/// Index for unnamed register, default register for delete, change, yank
+/// and put commands.
+pub const UNNAMED: char = '"';
+
+/// Index for clipboard register, backed by the system clipboard when
+/// available, refer [clipboard] module.
+pub const CLIPBOARD: char = '+';
+
+/// Return whether `index` is a valid register that can be selected for
+/// delete, change, yank and put commands.
+pub fn is_register(index: char) -> bool {
+    matches!(index, 'a'..='z' | UNNAMED | CLIPBOARD)
+}
+
+#[derive(Clone, Eq, PartialEq)]
+pub struct Register {
+    index: char,
+    txt: String,
+    linewise: bool,
+}
+
+impl fmt::Display for Register {
+    fn fmt(&self, f: &mut fmt::Formatter) -> result::Result<(), fmt::Error> {
+        let n = self.txt.chars().count();
+        write!(f, "Register<{:?},{},{}>", self.index, n, self.linewise)
+    }
+}
+
+impl Register {
+    pub fn new(index: char, txt: String, linewise: bool) -> Register {
+        Register {
+            index,
+            txt,
+            linewise,
+        }
+    }
+
+    #[inline]
+    pub fn to_text(&self) -> String {
+        self.txt.clone()
+    }
+
+    #[inline]
+    pub fn is_linewise(&self) -> bool {
+        self.linewise
+    }
+}
+
+/// Return register for `index`. Clipboard register is read from the
+/// system clipboard, falling back to its last saved value.
+pub fn get_register(registers: &Registers, index: char) -> Option<Register> {
+    let r = registers[index as usize].clone();
+    match index {
+        CLIPBOARD => match clipboard::get_text() {
+            // text is from this register, retain whether it is linewise.
+            Some(txt) if r.as_ref().map(|r| r.txt == txt) == Some(true) => r,
+            Some(txt) => {
+                let linewise = txt.ends_with('\n');
+                Some(Register::new(index, txt, linewise))
+            }
+            None => r,
+        },
+        _ => r,
+    }
+}
+
+/// Save `register`, and return its old value. Clipboard register is
+/// also copied to the system clipboard.
+pub fn set_register(registers: &mut Registers, register: Register) -> Option<Register> {
+    if register.index == CLIPBOARD && !clipboard::set_text(&register.txt) {
+        debug!("no clipboard, {} saved locally", register);
+    }
+
+    let index = register.index as usize;
+    let old = registers[index].take();
+    registers[index] = Some(register);
+    old
+}
@@ -32,9 +32,12 @@ where
             v.to_edit_lines(buf)
         }
     };
-    let (_, nu_wth) = view::to_nu_width(&lines, w.config_line_number());
+    let (_, nu_wth) = {
+        let (ln, rn) = (w.config_line_number(), w.config_relative_number());
+        view::to_nu_width(&lines, obc_xy.row, ln, rn)
+    };
 
-    let mut cursor = w.to_cursor().unwrap_or(Cursor::default());
+    let mut cursor = w.to_view_cursor().unwrap_or(Cursor::default());
     let col = cursor.col.saturating_sub(nu_wth) as usize;
     let (col, nbc) = if n < col {
         (((col - n) + nu_wth as usize) as u16, buf.to_char_cursor())
@@ -56,7 +59,7 @@ where
     let obc_xy = buf.to_xy_cursor(None);
 
     let wth = w.to_coord().wth.saturating_sub(1);
-    let mut cursor = w.to_cursor().unwrap_or(Cursor::default());
+    let mut cursor = w.to_view_cursor().unwrap_or(Cursor::default());
     let left_col = wth.saturating_sub(cursor.col);
     let (col, nbc) = if n < (left_col as usize) {
         (cursor.col + (n as u16), buf.to_char_cursor())
@@ -75,13 +78,14 @@ where
     B: WinBuffer,
 {
     let coord = w.to_coord();
-    let mut cursor = w.to_cursor().unwrap_or(Cursor::default());
+    let mut cursor = w.to_view_cursor().unwrap_or(Cursor::default());
 
     let nbc_xy = buf.to_xy_cursor(None);
     let mut nbc = buf.to_char_cursor();
 
     let scroll_off = w.config_scroll_offset();
     let line_number = w.config_line_number();
+    let relative_number = w.config_relative_number();
 
     let lines = match w.config_wrap() {
         true => {
@@ -116,7 +120,7 @@ where
             Box::new(iter)
         }
     };
-    let (_, nu_wth) = view::to_nu_width(&lines, line_number);
+    let (_, nu_wth) = view::to_nu_width(&lines, nbc_xy.row, line_number, relative_number);
 
     let ocol = cursor.col.saturating_sub(nu_wth);
     let max_wth = coord.wth.saturating_sub(nu_wth);
@@ -132,7 +136,8 @@ where
     }
     // adjust for new nu_wth
     cursor.col = {
-        let (_, nu_wth) = view::to_nu_width(&lines, line_number);
+        let row = buf.to_xy_cursor(Some(nbc)).row;
+        let (_, nu_wth) = view::to_nu_width(&lines, row, line_number, relative_number);
         cmp::min(cursor.col.saturating_add(nu_wth), coord.wth)
     };
 
@@ -145,13 +150,14 @@ where
     B: WinBuffer,
 {
     let coord = w.to_coord();
-    let mut cursor = w.to_cursor().unwrap_or(Cursor::default());
+    let mut cursor = w.to_view_cursor().unwrap_or(Cursor::default());
 
     let nbc_xy = buf.to_xy_cursor(None);
     let mut nbc = buf.to_char_cursor();
 
     let scroll_off = w.config_scroll_offset();
     let line_number = w.config_line_number();
+    let relative_number = w.config_relative_number();
 
     let lines = match w.config_wrap() {
         true => {
@@ -190,7 +196,7 @@ where
             Box::new(iter)
         }
     };
-    let (_, nu_wth) = view::to_nu_width(&lines, line_number);
+    let (_, nu_wth) = view::to_nu_width(&lines, nbc_xy.row, line_number, relative_number);
 
     let ocol = cursor.col.saturating_sub(nu_wth);
     let max_wth = coord.wth.saturating_sub(nu_wth);
@@ -206,7 +212,10 @@ where
         nbc = lines[cursor.row as usize].bc + (cursor.col as usize);
     }
     // adjust for new nu_wth
-    let (_, nu_wth) = view::to_nu_width(&lines, line_number);
+    let (_, nu_wth) = {
+        let row = buf.to_xy_cursor(Some(nbc)).row;
+        view::to_nu_width(&lines, row, line_number, relative_number)
+    };
     cursor.col = cmp::min(cursor.col.saturating_add(nu_wth), coord.wth);
 
     Ok((cursor, nbc))
@@ -417,7 +426,7 @@ where
         Ok(NowrapIter {
             name: name.to_string(),
             coord: w.to_coord(),
-            cursor: w.to_cursor().unwrap_or(Cursor::default()),
+            cursor: w.to_view_cursor().unwrap_or(Cursor::default()),
             buf,
             dir: DP::Right,
             line_number: w.config_line_number(),
@@ -434,7 +443,7 @@ where
         Ok(NowrapIter {
             name: name.to_string(),
             coord: w.to_coord(),
-            cursor: w.to_cursor().unwrap_or(Cursor::default()),
+            cursor: w.to_view_cursor().unwrap_or(Cursor::default()),
             buf,
             dir: DP::Left,
             line_number: w.config_line_number(),
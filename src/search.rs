@@ -0,0 +1,105 @@
+use regex::{Regex, RegexBuilder};
+
+use std::{convert::TryFrom, fmt, result};
+
+use crate::{Error, Result};
+
+/// Options to control pattern search within the buffer.
+#[derive(Clone, Copy, Default, Debug, Eq, PartialEq)]
+pub struct SearchOpts {
+    /// Ignore case while matching the pattern.
+    pub ignorecase: bool,
+    /// Override `ignorecase` if pattern contains uppercase characters.
+    pub smartcase: bool,
+}
+
+impl SearchOpts {
+    /// Return whether `patt` shall be matched ignoring the case.
+    pub fn is_ignorecase(&self, patt: &str) -> bool {
+        match self {
+            SearchOpts {
+                smartcase: true, ..
+            } => !patt.chars().any(char::is_uppercase),
+            SearchOpts { ignorecase, .. } => *ignorecase,
+        }
+    }
+}
+
+/// Compiled search pattern.
+#[derive(Clone)]
+pub struct Search {
+    patt: String,
+    re: Regex,
+}
+
+impl fmt::Display for Search {
+    fn fmt(&self, f: &mut fmt::Formatter) -> result::Result<(), fmt::Error> {
+        write!(f, "Search<{:?}>", self.patt)
+    }
+}
+
+impl<'a> TryFrom<&'a str> for Search {
+    type Error = Error;
+
+    fn try_from(patt: &'a str) -> Result<Search> {
+        Search::new(patt, SearchOpts::default())
+    }
+}
+
+impl Search {
+    /// Compile `patt` as regular expression, case sensitivity is
+    /// determined by `opts`.
+    pub fn new(patt: &str, opts: SearchOpts) -> Result<Search> {
+        let re = {
+            let mut builder = RegexBuilder::new(patt);
+            builder.case_insensitive(opts.is_ignorecase(patt));
+            err_at!(BadPattern, builder.build(), patt.to_string())?
+        };
+        Ok(Search {
+            patt: patt.to_string(),
+            re,
+        })
+    }
+
+    #[inline]
+    pub fn to_pattern(&self) -> String {
+        self.patt.clone()
+    }
+
+    #[inline]
+    pub fn as_regex(&self) -> &Regex {
+        &self.re
+    }
+
+    /// Return all matches in `text` as `(start, end)` char-index, in
+    /// ascending order.
+    pub fn to_matches(&self, text: &str) -> Vec<(usize, usize)> {
+        let mut matches = vec![];
+        let (mut off, mut n_chars) = (0, 0);
+        for m in self.re.find_iter(text) {
+            n_chars += text[off..m.start()].chars().count();
+            let n = m.as_str().chars().count();
+            matches.push((n_chars, n_chars + n));
+            n_chars += n;
+            off = m.end();
+        }
+        matches
+    }
+
+    /// Return the index of the first match in `matches` whose start is at
+    /// or after `off`, wrapping around to the first match if there is
+    /// none. `matches` shall be sorted by their start.
+    pub fn find(matches: &[(usize, usize)], off: usize) -> Option<usize> {
+        match matches.len() {
+            0 => None,
+            n => match matches.partition_point(|(s, _)| *s < off) {
+                i if i < n => Some(i),
+                _ => Some(0),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "search_test.rs"]
+mod search_test;
@@ -0,0 +1,58 @@
+use super::*;
+
+#[test]
+fn test_search_opts() {
+    let opts = SearchOpts::default();
+    let s = Search::new("foo", opts).unwrap();
+    assert!(s.as_regex().is_match("foo"));
+    assert!(!s.as_regex().is_match("FOO"));
+
+    let opts = SearchOpts {
+        ignorecase: true,
+        smartcase: false,
+    };
+    let s = Search::new("Foo", opts).unwrap();
+    assert!(s.as_regex().is_match("fOO"));
+
+    let opts = SearchOpts {
+        ignorecase: false,
+        smartcase: true,
+    };
+    let s = Search::new("foo", opts).unwrap();
+    assert!(s.as_regex().is_match("FOO"));
+    let s = Search::new("Foo", opts).unwrap();
+    assert!(s.as_regex().is_match("Foo"));
+    assert!(!s.as_regex().is_match("foo"));
+
+    assert!(Search::try_from("(").is_err());
+}
+
+#[test]
+fn test_search_find() {
+    let s = Search::try_from("ab").unwrap();
+    let matches = s.to_matches("ab xyz ab ab\u{1f600}ab");
+    assert_eq!(matches, vec![(0, 2), (7, 9), (10, 12), (13, 15)]);
+
+    // exact boundary
+    assert_eq!(Search::find(&matches, 0), Some(0));
+    assert_eq!(Search::find(&matches, 7), Some(1));
+    assert_eq!(Search::find(&matches, 13), Some(3));
+    // in between and within a match
+    assert_eq!(Search::find(&matches, 1), Some(1));
+    assert_eq!(Search::find(&matches, 8), Some(2));
+    // after last, wrap around
+    assert_eq!(Search::find(&matches, 14), Some(0));
+    assert_eq!(Search::find(&matches, 100), Some(0));
+
+    // before first
+    let matches = vec![(5, 6), (8, 10)];
+    assert_eq!(Search::find(&matches, 0), Some(0));
+
+    // single match
+    let matches = vec![(5, 6)];
+    assert_eq!(Search::find(&matches, 0), Some(0));
+    assert_eq!(Search::find(&matches, 5), Some(0));
+    assert_eq!(Search::find(&matches, 6), Some(0));
+
+    assert_eq!(Search::find(&[], 0), None);
+}
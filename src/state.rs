@@ -22,7 +22,7 @@ use structopt::StructOpt;
 use std::{
     cmp,
     convert::{TryFrom, TryInto},
-    ffi, fs,
+    ffi, fs, io,
     iter::FromIterator,
     mem, path,
     str::FromStr,
@@ -35,6 +35,7 @@ use crate::{
     colors::{self, ColorScheme},
     config,
     event::Event,
+    input::Input,
     pubsub::{Notify, PubSub},
     term::{self, Terminal},
     util,
@@ -118,6 +119,8 @@ pub struct State {
     pub schemes: Vec<ColorScheme>,
     /// Global subscribe-publish instance.
     pub subscribers: PubSub,
+    /// Content piped into ted via stdin, like `cat file | ted -`.
+    pub stdin: Option<Vec<u8>>,
 
     // state machine for tabed-windows.
     inner: Inner,
@@ -227,7 +230,9 @@ impl TryFrom<Opt> for State {
     type Error = Error;
 
     fn try_from(opts: Opt) -> Result<State> {
-        // first the terminal
+        // first the piped content, before taking over the terminal
+        let stdin = read_stdin(&opts)?;
+        // then the terminal
         let tm = Terminal::init()?;
         // then the logger
         init_logger(&opts)?;
@@ -247,6 +252,7 @@ impl TryFrom<Opt> for State {
             tm,
             schemes,
             subscribers: PubSub::default(),
+            stdin,
             inner: Inner::default(),
         })
     }
@@ -331,25 +337,18 @@ impl State {
 impl State {
     /// main event-loop.
     pub fn event_loop(mut self) -> Result<String> {
-        use crossterm::event::read;
-
         let mut stats = util::Latency::new("EVENT");
         let mut r_stats = util::Latency::new("READT");
 
         let mut inner = mem::replace(&mut self.inner, Inner::default());
+        let mut input = Input::new()?;
 
         // initial screen refresh
         inner.on_refresh(&self)?;
-        if let Some(cursor) = inner.to_cursor() {
-            err_at!(Fatal, termex!(cursor))?;
-        }
+        term::flush(inner.to_cursor())?;
 
         let mut evnts: Event = self.opts.clone().into();
         loop {
-            if evnts.clone().any(|evnt| Self::is_quit(&evnt)) {
-                break;
-            }
-
             // handle event(s)
             {
                 let start = time::Instant::now();
@@ -359,11 +358,14 @@ impl State {
                 evnts = self.bubble_down(&mut inner, evnts)?;
                 evnts = self.bubble_up(&mut inner, evnts)?;
 
-                inner.on_refresh(&self)?;
-                if let Some(cursor) = inner.to_cursor() {
-                    err_at!(Fatal, termex!(cursor))?;
+                if evnts.clone().any(|evnt| Self::is_quit(&evnt)) {
+                    break;
                 }
 
+                // entire frame is written to the terminal in one go.
+                inner.on_refresh(&self)?;
+                term::flush(inner.to_cursor())?;
+
                 stats.sample(start.elapsed());
             }
 
@@ -371,7 +373,7 @@ impl State {
 
             // new event
             let start = time::Instant::now();
-            evnts = err_at!(Fatal, read())?.into();
+            evnts = input.read()?;
             r_stats.sample(start.elapsed());
         }
 
@@ -382,7 +384,7 @@ impl State {
 
     fn is_quit(evnt: &Event) -> bool {
         match evnt {
-            Event::Char('q', _) => true,
+            Event::Quit => true,
             _ => false,
         }
     }
@@ -390,11 +392,33 @@ impl State {
     fn bubble_down(&mut self, inner: &mut Inner, evnts: Event) -> Result<Event> {
         let mut res_evnts: Event = Event::default();
         for evnt in evnts {
+            if let Event::Resize(cols, rows) = &evnt {
+                self.on_resize(inner, *cols, *rows)?;
+            }
             res_evnts.push(inner.on_event(evnt)?);
         }
         Ok(res_evnts)
     }
 
+    // terminal is resized, what is on the screen is stale, clear it so
+    // that windows are rendered afresh.
+    fn on_resize(&mut self, inner: &mut Inner, cols: u16, rows: u16) -> Result<()> {
+        use crossterm::terminal::{Clear, ClearType};
+
+        self.tm.cols = cols;
+        self.tm.rows = rows;
+        {
+            let mut tm = err_at!(Fatal, term::TERM.lock())?;
+            tm.cols = cols;
+            tm.rows = rows;
+        }
+        if let Inner::Multi { coord, .. } = inner {
+            coord.wth = cols;
+        }
+
+        err_at!(Fatal, termqu!(Clear(ClearType::All)))
+    }
+
     fn bubble_up(&mut self, _inner: &mut Inner, evnts: Event) -> Result<Event> {
         Ok(evnts)
     }
@@ -451,7 +475,7 @@ pub struct TabTitle {
 }
 
 impl TabTitle {
-    fn into_span(self, scheme: &ColorScheme) -> term::Span {
+    pub fn into_span(self, scheme: &ColorScheme) -> term::Span {
         use crate::colors::Highlight;
 
         let span: term::Span = self.text.clone().into();
@@ -477,6 +501,47 @@ impl TabTitle {
     }
 }
 
+// Read content piped into ted, when stdin is not a terminal and `-` is
+// supplied as file or no file is supplied. Either way, if stdin is not a
+// terminal, it is rebound to the controlling terminal, raw-mode and key
+// events are on stdin. To check manually, `echo hello | ted` shall open
+// the piped content for edit.
+fn read_stdin(opts: &Opt) -> Result<Option<Vec<u8>>> {
+    use crossterm::tty::IsTty;
+    use std::io::Read;
+
+    let stdin = io::stdin();
+    if stdin.is_tty() {
+        return Ok(None);
+    }
+
+    let dash = opts.files.iter().any(|f| f == "-");
+    let data = if dash || opts.files.is_empty() {
+        let mut data = vec![];
+        stdin.lock().read_to_end(&mut data)?;
+        Some(data)
+    } else {
+        None
+    };
+
+    rebind_stdin()?;
+    Ok(data)
+}
+
+// Replace stdin with the controlling terminal, `/dev/tty`.
+fn rebind_stdin() -> Result<()> {
+    use std::{fs, os::unix::io::AsRawFd};
+
+    let tty = {
+        let mut opts = fs::OpenOptions::new();
+        err_at!(IOError, opts.read(true).write(true).open("/dev/tty"))?
+    };
+    match unsafe { libc::dup2(tty.as_raw_fd(), libc::STDIN_FILENO) } {
+        n if n < 0 => err_at!(IOError, Err(io::Error::last_os_error())),
+        _ => Ok(()),
+    }
+}
+
 fn init_logger(opts: &Opt) -> Result<()> {
     let home_dir = err_at!(
         Fatal,
@@ -6,7 +6,7 @@ use crate::{
     buffer::Buffer,
     colors::ColorScheme,
     event::Event,
-    syntax::Syntax,
+    syntax::{self, Syntax},
     term::{Span, Spanline},
     Error, Result,
 };
@@ -18,6 +18,7 @@ extern "C" {
 pub struct CodeCmd {
     parser: ts::Parser,
     tree: Option<ts::Tree>,
+    content: String,
 }
 
 impl Clone for CodeCmd {
@@ -31,6 +32,7 @@ impl Clone for CodeCmd {
         CodeCmd {
             parser,
             tree: self.tree.clone(),
+            content: self.content.clone(),
         }
     }
 }
@@ -53,7 +55,11 @@ impl CodeCmd {
                 None
             }
         };
-        Ok(CodeCmd { parser, tree })
+        Ok(CodeCmd {
+            parser,
+            tree,
+            content: s.to_string(),
+        })
     }
 
     pub fn to_command_name(&self) -> Option<String> {
@@ -67,8 +73,26 @@ impl CodeCmd {
         }
     }
 
-    pub fn into_parse_tree(self) -> Option<ts::Tree> {
-        self.tree
+    /// Return the range prefixing the command, as text.
+    pub fn to_range(&self) -> Option<String> {
+        let root = self.tree.as_ref()?.root_node();
+        let mut cursor = root.walk();
+        let node = root.children(&mut cursor).find(|n| n.kind() == "range")?;
+        self.content
+            .get(node.start_byte()..node.end_byte())
+            .map(String::from)
+    }
+
+    /// Return the command's arguments, as text, following the command name.
+    pub fn to_command_args(&self) -> Option<String> {
+        let root = self.tree.as_ref()?.root_node();
+        let node = root.child(root.child_count().saturating_sub(1))?;
+        let node = match node.kind() {
+            "cmd" => node.child(0)?,
+            _ => return None,
+        };
+        let start = node.child(0)?.end_byte();
+        self.content.get(start..node.end_byte()).map(String::from)
     }
 }
 
@@ -102,7 +126,14 @@ impl Syntax for CodeCmd {
         buf.to_span_line(a, z)
     }
 
-    fn to_status_cursor(&self) -> Result<Span> {
+    fn to_status_cursor(&self, _buf: &mut Buffer) -> Result<Span> {
         Ok(format!("").into())
     }
+
+    fn to_folds(&self) -> Vec<(usize, usize)> {
+        match self.tree.as_ref() {
+            Some(tree) => syntax::to_fold_ranges(tree),
+            None => vec![],
+        }
+    }
 }
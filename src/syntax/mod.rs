@@ -39,7 +39,11 @@ pub trait Syntax {
 
     fn to_span_line(&self, buf: &Buffer, a: usize, z: usize) -> Result<term::Spanline>;
 
-    fn to_status_cursor(&self) -> Result<term::Span>;
+    fn to_status_cursor(&self, buf: &mut Buffer) -> Result<term::Span>;
+
+    /// Return fold ranges derived from the syntax tree, refer
+    /// [to_fold_ranges] for details.
+    fn to_folds(&self) -> Vec<(usize, usize)>;
 }
 
 macro_rules! syntax_for {
@@ -106,12 +110,19 @@ macro_rules! syntax_for {
                 }
             }
 
-            fn to_status_cursor(&self) -> Result<term::Span> {
+            fn to_status_cursor(&self, buf: &mut Buffer) -> Result<term::Span> {
                 match self {
-                    $(Syn::$variant(val) => val.to_status_cursor(),)*
+                    $(Syn::$variant(val) => val.to_status_cursor(buf),)*
                     Syn::None => Ok("".to_string().into())
                 }
             }
+
+            fn to_folds(&self) -> Vec<(usize, usize)> {
+                match self {
+                    $(Syn::$variant(val) => val.to_folds(),)*
+                    Syn::None => vec![],
+                }
+            }
         }
     };
 }
@@ -124,24 +135,101 @@ syntax_for![
 ];
 
 pub fn detect(buf: &Buffer, scheme: &ColorScheme) -> Result<Syn> {
-    let loc = buf.to_location();
+    let typ = match buf.file_type.as_str() {
+        "" => to_file_type(buf),
+        typ => typ.to_string(),
+    };
+    (typ.as_str(), buf.to_string().as_str(), scheme.clone()).try_into()
+}
+
+/// Detect file type for buffer's content, refer [detect_file_type].
+pub fn to_file_type(buf: &Buffer) -> String {
+    use crate::event::DP;
+
+    let first_line = match buf.lines_at(0, DP::Right) {
+        Ok(mut lines) => lines.next().unwrap_or_default(),
+        Err(_) => String::default(),
+    };
+    detect_file_type(&buf.to_location(), &first_line)
+}
 
-    let tt = match &loc {
+/// Detect file type using the file extension in `loc`, falling back to
+/// the interpreter named in `first_line`, if it is a `#!` shebang line.
+/// Default to `txt-plain`.
+pub fn detect_file_type(loc: &Location, first_line: &str) -> String {
+    let ext = match loc {
         Location::Disk { path_file, .. } => {
             let ext = path::Path::new(path_file).extension();
-            match ext.map(|ext| ext.to_str().unwrap_or("")) {
-                Some("toml") => "toml".to_string(),
-                Some("tss") => "tss".to_string(),
-                Some(_) | None => "".to_string(),
-            }
+            ext.and_then(|ext| ext.to_str())
         }
-        Location::Ted { .. } => "".to_string(),
-        Location::Memory { .. } => "".to_string(),
+        Location::Ted { .. } => None,
+        Location::Memory { .. } => None,
+    };
+    let typ = match ext {
+        Some("toml") => Some("toml".to_string()),
+        Some("tss") => Some("tss".to_string()),
+        Some("txt") => Some("txt-plain".to_string()),
+        Some(_) | None => None,
     };
 
-    // TODO: find other ways to detect the file's type.
+    match typ.or_else(|| shebang_to_file_type(first_line)) {
+        Some(typ) => typ,
+        None => "txt-plain".to_string(),
+    }
+}
+
+/// Return whether `file_type` has blocks enclosed in `{` and `}`, so that
+/// new lines following a `{` shall be indented further.
+pub fn is_brace_indent(file_type: &str) -> bool {
+    matches!(file_type, "tss")
+}
 
-    (tt.as_str(), buf.to_string().as_str(), scheme.clone()).try_into()
+// interpreter from shebang line, like `#!/usr/bin/tss` or
+// `#!/usr/bin/env tss`, is matched with supported file types.
+fn shebang_to_file_type(line: &str) -> Option<String> {
+    let mut words = line.strip_prefix("#!")?.split_whitespace();
+    let name = path::Path::new(words.next()?).file_name()?.to_str()?;
+    let name = match name {
+        "env" => words.find(|w| !w.starts_with('-'))?,
+        name => name,
+    };
+    FILE_TYPES.iter().find(|typ| typ.as_str() == name).cloned()
+}
+
+/// Return fold ranges, as (first-line, last-line) in line-index, for named
+/// nodes in `tree` spanning more than one line, like functions and blocks.
+/// Leaf nodes, like multi-line strings and comments, are not folded.
+/// A node ending at the start of a line does not include that line. When
+/// a node ends on the line where its sibling starts, like `} else {`, the
+/// node's range is cut short by a line, so that ranges are always nested.
+/// Ranges are sorted, outer ranges before inner ones.
+pub fn to_fold_ranges(tree: &ts::Tree) -> Vec<(usize, usize)> {
+    use std::collections::BTreeSet;
+
+    let mut ranges = vec![];
+    let mut nodes = vec![tree.root_node()];
+    while let Some(node) = nodes.pop() {
+        let mut tc = node.walk();
+        nodes.extend(node.children(&mut tc));
+        if node.parent().is_none() || !node.is_named() || node.child_count() == 0 {
+            continue;
+        }
+        let (a, z) = (node.start_position(), node.end_position());
+        let z = if_else!(z.column == 0, z.row.saturating_sub(1), z.row);
+        if a.row < z {
+            ranges.push((a.row, z))
+        }
+    }
+
+    let starts: BTreeSet<usize> = ranges.iter().map(|(a, _)| *a).collect();
+    let mut ranges: Vec<(usize, usize)> = {
+        let iter = ranges.into_iter();
+        let iter = iter.map(|(a, z)| (a, if_else!(starts.contains(&z), z - 1, z)));
+        iter.filter(|(a, z)| a < z).collect()
+    };
+    ranges.sort_by(|x, y| x.0.cmp(&y.0).then(y.1.cmp(&x.1)));
+    ranges.dedup();
+    ranges
 }
 
 /// Syntax highlighting using tree-sitter and ted-style-sheet automata.
@@ -154,10 +242,23 @@ pub fn highlight(
     till: usize,
 ) -> Result<term::Spanline> {
     let canvas = scheme.to_style(Highlight::Canvas);
+    highlight_canvas(buf, canvas, tree, atmt, from, till)
+}
+
+/// Same as [highlight], with `canvas` as the default style for segments
+/// not matched by any rule in `atmt`.
+pub fn highlight_canvas(
+    buf: &Buffer,
+    canvas: term::Style,
+    tree: &ts::Tree,
+    atmt: &mut Automata,
+    from: usize,
+    till: usize,
+) -> Result<term::Spanline> {
     let root = tree.root_node();
     let mut syns = {
         let (depth, sibling) = (0, 0);
-        let tok = Token::from_node(buf, &root, depth, sibling);
+        let tok = Token::from_node(buf, &root, None, depth, sibling);
         // trace!("{}", tok);
         match atmt.shift_in(&tok)? {
             Some(style) => vec![SyntSpan {
@@ -173,9 +274,7 @@ pub fn highlight(
     // trace!("highlight {}..{} syns:{:?}", from, till, syns);
 
     let depth = 1;
-    syns.extend(do_highlight(
-        buf, scheme, tree, atmt, root, depth, from, till,
-    )?);
+    syns.extend(do_highlight(buf, tree, atmt, root, depth, from, till)?);
     syns.sort_by(|a, b| b.cmp(a)); // reverse sorting
 
     // trace!("sorted syns:{:?}", syns);
@@ -192,7 +291,6 @@ pub fn highlight(
 
 fn do_highlight(
     buf: &Buffer,
-    scheme: &ColorScheme,
     tree: &ts::Tree,
     atmt: &mut Automata,
     node: ts::Node,
@@ -203,10 +301,19 @@ fn do_highlight(
     let mut syns = vec![];
     let mut tc = node.walk();
 
-    let children: Vec<ts::Node> = node.children(&mut tc).collect();
+    // children along with their field name.
+    let mut children: Vec<(ts::Node, Option<&str>)> = vec![];
+    if tc.goto_first_child() {
+        loop {
+            children.push((tc.node(), tc.field_name()));
+            if !tc.goto_next_sibling() {
+                break;
+            }
+        }
+    }
     let mut toks = Vec::with_capacity(16);
-    for (sibling, child) in children.iter().enumerate() {
-        let tok = Token::from_node(buf, child, depth, sibling);
+    for (sibling, (child, field)) in children.iter().enumerate() {
+        let tok = Token::from_node(buf, child, *field, depth, sibling);
         let overlap = tok.is_overlap(from, till);
         // trace!("{} overlap:{}", tok, overlap);
         if overlap {
@@ -225,11 +332,11 @@ fn do_highlight(
 
     // trace!("do-highlight {}..{} syns:{:?}", from, till, syns);
 
-    for (sibling, child) in children.into_iter().enumerate() {
+    for (sibling, (child, _)) in children.into_iter().enumerate() {
         if toks[sibling].1 {
             syns.extend({
                 let depth = depth + 1;
-                do_highlight(buf, scheme, tree, atmt, child, depth, from, till)?
+                do_highlight(buf, tree, atmt, child, depth, from, till)?
             });
         }
     }
@@ -501,3 +608,7 @@ impl SyntSpan {
         }
     }
 }
+
+#[cfg(test)]
+#[path = "syntax_test.rs"]
+mod syntax_test;
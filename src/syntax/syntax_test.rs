@@ -0,0 +1,47 @@
+use super::*;
+
+#[test]
+fn test_detect_file_type() {
+    let disk = |path: &str| Location::Disk {
+        loc: path.into(),
+        path_file: path.into(),
+        enc: "utf-8".to_string(),
+        read_only: false,
+    };
+
+    assert_eq!(detect_file_type(&disk("/a/Cargo.toml"), ""), "toml");
+    assert_eq!(detect_file_type(&disk("/a/ted.tss"), ""), "tss");
+    assert_eq!(
+        detect_file_type(&disk("/a/notes.txt"), "#!/bin/tss"),
+        "txt-plain"
+    );
+
+    // fall back to shebang line.
+    assert_eq!(detect_file_type(&disk("/a/style"), "#!/bin/tss"), "tss");
+    assert_eq!(
+        detect_file_type(&disk("/a/style"), "#!/usr/bin/env -S tss\n"),
+        "tss"
+    );
+    assert_eq!(detect_file_type(&disk("/a/run"), "#!/bin/sh"), "txt-plain");
+    assert_eq!(detect_file_type(&disk("/a/run"), "tss"), "txt-plain");
+
+    let loc = Location::new_memory("#!/usr/bin/env toml".as_bytes(), "utf-8", false).unwrap();
+    assert_eq!(detect_file_type(&loc, "#!/usr/bin/env toml"), "toml");
+    assert_eq!(detect_file_type(&Location::default(), ""), "txt-plain");
+}
+
+#[test]
+fn test_to_folds() {
+    let scheme = ColorScheme::default().unwrap();
+    let txt = concat!(
+        "keyword: { fg: red,\n",
+        "  bg: blue,\n",
+        "  attr: bold\n",
+        "};\n",
+        "string: { fg: green };\n",
+        "comment: { fg: red,\n",
+        "  attr: italic };\n",
+    );
+    let syn = Tss::new(txt, scheme).unwrap();
+    assert_eq!(syn.to_folds(), vec![(0, 3), (5, 6)]);
+}
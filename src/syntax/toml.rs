@@ -108,7 +108,18 @@ impl Syntax for Toml {
         }
     }
 
-    fn to_status_cursor(&self) -> Result<Span> {
+    fn to_status_cursor(&self, _buf: &mut Buffer) -> Result<Span> {
         Ok(format!("").into())
     }
+
+    fn to_folds(&self) -> Vec<(usize, usize)> {
+        match self.tree.as_ref() {
+            Some(tree) => syntax::to_fold_ranges(tree),
+            None => vec![],
+        }
+    }
 }
+
+#[cfg(test)]
+#[path = "toml_test.rs"]
+mod toml_test;
@@ -0,0 +1,48 @@
+use super::*;
+
+use crate::{event::Edit, location::Location};
+
+#[test]
+fn test_on_edit() {
+    // collect (kind, start_byte, end_byte) for every node in pre-order.
+    fn to_nodes(node: ts::Node, nodes: &mut Vec<(String, usize, usize)>) {
+        nodes.push((node.kind().to_string(), node.start_byte(), node.end_byte()));
+        let mut tc = node.walk();
+        for child in node.children(&mut tc) {
+            to_nodes(child, nodes)
+        }
+    }
+
+    let scheme = ColorScheme::default().unwrap();
+    let mut buf = {
+        let txt = "[package]\nname = \"ted\"\n";
+        let loc = Location::new_ted("test-toml", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+    let mut syn = Toml::new(&buf.to_string(), scheme.clone()).unwrap();
+
+    // multi-line insert, in the middle of the buffer.
+    let txt = "version = \"0.1.0\"\n\n[dependencies]\nlog = \"0.4\"\n";
+    let cursor = "[package]\nname = \"ted\"\n".find("name").unwrap();
+    buf.cud_str(Some(cursor), txt).unwrap();
+    let evnt = Event::Edit(Edit::new_ins(cursor, txt.to_string()));
+    assert!(syn.on_edit(&buf, evnt).unwrap() == Event::Noop);
+
+    let s = buf.to_string();
+    let tree = syn.tree.as_ref().unwrap();
+    let root = tree.root_node();
+    assert!(!root.has_error(), "{}", root.to_sexp());
+    assert_eq!(root.end_byte(), s.len());
+
+    let (mut nodes, mut ref_nodes) = (vec![], vec![]);
+    to_nodes(root, &mut nodes);
+    let ref_syn = Toml::new(&s, scheme).unwrap();
+    to_nodes(ref_syn.tree.as_ref().unwrap().root_node(), &mut ref_nodes);
+    assert_eq!(nodes, ref_nodes);
+
+    // nodes following the insert are shifted by the inserted text.
+    let a = s.find("[dependencies]").unwrap();
+    assert!(nodes.iter().any(|(_, x, _)| *x == a), "{:?}", nodes);
+    let a = s.rfind("name").unwrap();
+    assert!(nodes.iter().any(|(_, x, _)| *x == a), "{:?}", nodes);
+}
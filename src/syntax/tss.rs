@@ -108,7 +108,14 @@ impl Syntax for Tss {
         }
     }
 
-    fn to_status_cursor(&self) -> Result<Span> {
+    fn to_status_cursor(&self, _buf: &mut Buffer) -> Result<Span> {
         Ok(format!("").into())
     }
+
+    fn to_folds(&self) -> Vec<(usize, usize)> {
+        match self.tree.as_ref() {
+            Some(tree) => syntax::to_fold_ranges(tree),
+            None => vec![],
+        }
+    }
 }
@@ -94,30 +94,12 @@ impl Syntax for PlainText {
         Ok(spl.using(self.scheme.to_style(Highlight::Canvas)))
     }
 
-    fn to_status_cursor(&self) -> Result<Span> {
-        let nodes: Vec<ts::Node> = match self.tree.as_ref() {
-            Some(tree) => {
-                let mut tc = tree.walk();
-                tree.root_node().children(&mut tc).collect()
-            }
-            None => vec![],
-        };
+    fn to_status_cursor(&self, buf: &mut Buffer) -> Result<Span> {
+        Ok(buf.to_text_count()?.to_string().into())
+    }
 
-        let (mut ws, mut ss, mut ls, mut ps) = (0, 0, 0, 0);
-        let mut prev_kind: Option<&str> = None;
-        for node in nodes.into_iter() {
-            match (prev_kind, node.kind()) {
-                (_, "word") | (_, "wword") => ws += 1,
-                (_, "dot") => ss += 1,
-                (Some("nl"), "nl") => {
-                    ls += 1;
-                    ps += 1;
-                }
-                (_, "nl") => ls += 1,
-                _ => err_at!(Fatal, msg: format!("unreachable"))?,
-            }
-            prev_kind = Some(node.kind());
-        }
-        Ok(format!("{} {} {} {}", ws, ls, ss, ps).into())
+    // plain text has no blocks to fold.
+    fn to_folds(&self) -> Vec<(usize, usize)> {
+        vec![]
     }
 }
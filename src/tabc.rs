@@ -1,3 +1,5 @@
+use std::fs;
+
 #[derive(Clone)]
 pub struct TabComplete {
     span: String,
@@ -36,3 +38,55 @@ impl Iterator for TabComplete {
         }
     }
 }
+
+/// Return the longest prefix common to all `choices`.
+pub fn common_prefix(choices: &[String]) -> String {
+    let mut iter = choices.iter();
+    let mut prefix = match iter.next() {
+        Some(first) => first.clone(),
+        None => return String::default(),
+    };
+    for choice in iter {
+        let n = {
+            let iter = prefix.chars().zip(choice.chars());
+            iter.take_while(|(a, b)| a == b)
+                .map(|(a, _)| a.len_utf8())
+                .sum()
+        };
+        prefix.truncate(n);
+    }
+    prefix
+}
+
+/// Return file-system paths starting with `prefix`, in sorted order.
+/// Directories are suffixed with `/`, and hidden entries are included only
+/// when the file name in `prefix` starts with `.`.
+pub fn complete_path(prefix: &str) -> Vec<String> {
+    let (dir, name) = match prefix.rfind('/') {
+        Some(off) => (&prefix[..=off], &prefix[off + 1..]),
+        None => ("", prefix),
+    };
+    let entries = match fs::read_dir(if_else!(dir.is_empty(), ".", dir)) {
+        Ok(entries) => entries,
+        Err(_) => return vec![],
+    };
+
+    let mut paths: Vec<String> = entries
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let file_name = entry.file_name().into_string().ok()?;
+            let hidden = file_name.starts_with('.') && !name.starts_with('.');
+            if hidden || !file_name.starts_with(name) {
+                return None;
+            }
+            let suffix = if_else!(entry.path().is_dir(), "/", "");
+            Some(format!("{}{}{}", dir, file_name, suffix))
+        })
+        .collect();
+    paths.sort();
+    paths
+}
+
+#[cfg(test)]
+#[path = "tabc_test.rs"]
+mod tabc_test;
@@ -0,0 +1,44 @@
+use super::*;
+
+#[test]
+fn test_common_prefix() {
+    let choices = |cs: &[&str]| -> Vec<String> { cs.iter().map(|c| c.to_string()).collect() };
+
+    assert_eq!(common_prefix(&[]), "");
+    assert_eq!(common_prefix(&choices(&["src/"])), "src/");
+    assert_eq!(
+        common_prefix(&choices(&["buffer.rs", "buffer_test.rs"])),
+        "buffer"
+    );
+    assert_eq!(common_prefix(&choices(&["wörd", "wörld", "wö"])), "wö");
+    assert_eq!(common_prefix(&choices(&["abc", "xyz"])), "");
+}
+
+#[test]
+fn test_complete_path() {
+    let dir = std::env::temp_dir().join("ted-test-tabc");
+    fs::remove_dir_all(&dir).ok();
+    fs::create_dir_all(dir.join("sub")).unwrap();
+    for name in ["foo.rs", "foo_test.rs", "bar.rs", ".hidden"].iter() {
+        fs::write(dir.join(name), "").unwrap();
+    }
+    let prefix = format!("{}/", dir.to_str().unwrap());
+    let paths = |p: &str| -> Vec<String> {
+        let iter = complete_path(&format!("{}{}", prefix, p)).into_iter();
+        iter.map(|p| p[prefix.len()..].to_string()).collect()
+    };
+
+    assert_eq!(paths(""), vec!["bar.rs", "foo.rs", "foo_test.rs", "sub/"]);
+    assert_eq!(paths("foo"), vec!["foo.rs", "foo_test.rs"]);
+    assert_eq!(paths("s"), vec!["sub/"]);
+    assert_eq!(paths("."), vec![".hidden"]);
+    assert_eq!(paths("xyz"), Vec::<String>::new());
+    assert_eq!(paths("nodir/x"), Vec::<String>::new());
+
+    let mut tabc = TabComplete::new("foo".to_string(), paths("foo"));
+    assert_eq!(tabc.next().unwrap(), "foo.rs");
+    assert_eq!(tabc.next().unwrap(), "foo_test.rs");
+    assert_eq!(tabc.next().unwrap(), "foo");
+
+    fs::remove_dir_all(&dir).ok();
+}
@@ -75,28 +75,7 @@ macro_rules! termqu {
 #[macro_export]
 macro_rules! termex {
     ($cur:expr) => {{
-        use crossterm::{
-            cursor::{MoveTo, Show},
-            execute,
-        };
-        #[allow(unused_imports)]
-        use log::warn;
-        use std::io::{self, Write};
-        use std::ops::DerefMut;
-
-        let mut tm = err_at!(Fatal, crate::term::TERM.lock())?;
-        let buf = &mut tm.deref_mut().buf;
-
-        let move_to: MoveTo = $cur.into();
-        let res = err_at!(Fatal, execute!(buf, move_to, Show));
-
-        let mut stdout = io::stdout();
-        err_at!(IOError, stdout.write(buf))?;
-        err_at!(IOError, stdout.flush())?;
-
-        debug!("screen buffer {}", tm.buf.len());
-        tm.buf.truncate(0);
-        res
+        $crate::term::flush(Some($cur))
     }};
 }
 
@@ -131,7 +110,13 @@ impl Terminal {
         err_at!(Fatal, enable_raw_mode())?;
         err_at!(
             Fatal,
-            execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture, Hide)
+            execute!(
+                io::stdout(),
+                EnterAlternateScreen,
+                EnableMouseCapture,
+                EnableBracketedPaste,
+                Hide
+            )
         )?;
         debug!(
             "{} color_count:{}",
@@ -146,6 +131,36 @@ impl Terminal {
     pub fn to_screen_coord(&self) -> Coord {
         Coord::new(1, 1, self.rows, self.cols)
     }
+
+    // Write the queue-buffer to stdout with a single write and flush.
+    fn flush(&mut self, cursor: Option<Cursor>) -> Result<()> {
+        use crossterm::{
+            cursor::{MoveTo, Show},
+            queue,
+        };
+
+        // cursor is shown only after the frame is painted, to avoid flicker.
+        if let Some(cursor) = cursor {
+            let move_to: MoveTo = cursor.into();
+            err_at!(Fatal, queue!(&mut self.buf, move_to, Show))?;
+        }
+
+        let stdout = io::stdout();
+        let mut stdout = stdout.lock();
+        stdout.write_all(&self.buf)?;
+        stdout.flush()?;
+
+        debug!("screen buffer {}", self.buf.len());
+        self.buf.truncate(0);
+        Ok(())
+    }
+}
+
+/// Flush the frame, queued so far, onto the terminal. If `cursor` is
+/// supplied, move the cursor to it and show the cursor.
+pub fn flush(cursor: Option<Cursor>) -> Result<()> {
+    let mut tm = err_at!(Fatal, TERM.lock())?;
+    tm.flush(cursor)
 }
 
 impl fmt::Display for Terminal {
@@ -163,6 +178,7 @@ impl Drop for Terminal {
             io::stdout(),
             LeaveAlternateScreen,
             DisableMouseCapture,
+            DisableBracketedPaste,
             Show
         )
         .unwrap();
@@ -170,6 +186,141 @@ impl Drop for Terminal {
     }
 }
 
+/// Command to enable bracketed paste, pasted text is then bracketed
+/// between `ESC [ 200 ~` and `ESC [ 201 ~`, refer [crate::input].
+pub struct EnableBracketedPaste;
+
+impl Command for EnableBracketedPaste {
+    type AnsiType = &'static str;
+
+    fn ansi_code(&self) -> Self::AnsiType {
+        "\x1b[?2004h"
+    }
+}
+
+/// Command to disable bracketed paste.
+pub struct DisableBracketedPaste;
+
+impl Command for DisableBracketedPaste {
+    type AnsiType = &'static str;
+
+    fn ansi_code(&self) -> Self::AnsiType {
+        "\x1b[?2004l"
+    }
+}
+
+/// Sink for rendering spans on screen. [Terminal] queues them as ANSI
+/// commands, [Screen] paints them on an in-memory cell-grid.
+pub trait Sink {
+    /// Set the background color for subsequent output.
+    fn queue_bg(&mut self, bg: Color) -> Result<()>;
+
+    /// Render span at its cursor, if set, else after the previous output.
+    fn queue_span(&mut self, span: Span) -> Result<()>;
+
+    /// Render spanline at its cursor, if set, else after the previous
+    /// output.
+    fn queue_line(&mut self, line: Spanline) -> Result<()>;
+}
+
+impl Sink for Terminal {
+    fn queue_bg(&mut self, bg: Color) -> Result<()> {
+        use crossterm::{queue, style::SetBackgroundColor};
+
+        err_at!(Fatal, queue!(&mut self.buf, SetBackgroundColor(bg.into())))
+    }
+
+    fn queue_span(&mut self, span: Span) -> Result<()> {
+        use crossterm::queue;
+
+        err_at!(Fatal, queue!(&mut self.buf, span))
+    }
+
+    fn queue_line(&mut self, line: Spanline) -> Result<()> {
+        use crossterm::queue;
+
+        err_at!(Fatal, queue!(&mut self.buf, line))
+    }
+}
+
+/// Headless screen, a grid of `cols` x `rows` cells, to render windows
+/// without a terminal. Only the characters are captured, colors and
+/// attributes are ignored.
+pub struct Screen {
+    cols: u16,
+    rows: u16,
+    cursor: Cursor,
+    cells: Vec<Vec<char>>,
+}
+
+impl Screen {
+    /// Create a blank screen, filled with white-space.
+    pub fn new(cols: u16, rows: u16) -> Screen {
+        let cells = (0..rows).map(|_| vec![' '; cols as usize]).collect();
+        Screen {
+            cols,
+            rows,
+            cursor: Cursor::default(),
+            cells,
+        }
+    }
+
+    /// Return the screen content, one string for each row.
+    pub fn to_lines(&self) -> Vec<String> {
+        let iter = self.cells.iter();
+        // trailing cells of a wide character are marked with NULL.
+        iter.map(|row| row.iter().filter(|ch| **ch != '\0').collect())
+            .collect()
+    }
+
+    fn paint(&mut self, cursor: Option<Cursor>, content: &str) {
+        if let Some(cursor) = cursor {
+            self.cursor = cursor;
+        }
+        let Cursor { col, row } = self.cursor;
+        if row >= self.rows {
+            return;
+        }
+
+        let (row, mut col) = (row as usize, col as usize);
+        for ch in content.chars() {
+            let n = text::width(std::iter::once(ch));
+            if n == 0 {
+                continue;
+            } else if (col + n) > (self.cols as usize) {
+                break;
+            }
+            self.cells[row][col] = ch;
+            for i in 1..n {
+                self.cells[row][col + i] = '\0';
+            }
+            col += n;
+        }
+        self.cursor.col = col as u16;
+    }
+}
+
+impl Sink for Screen {
+    fn queue_bg(&mut self, _bg: Color) -> Result<()> {
+        Ok(())
+    }
+
+    fn queue_span(&mut self, span: Span) -> Result<()> {
+        self.paint(span.cursor, &span.content);
+        Ok(())
+    }
+
+    fn queue_line(&mut self, line: Spanline) -> Result<()> {
+        if let Some(cursor) = line.cursor {
+            self.cursor = cursor;
+        }
+        for span in line.spans.into_iter() {
+            self.queue_span(span)?
+        }
+        Ok(())
+    }
+}
+
 /// Attribute details for terminal text.
 #[derive(Clone, Eq, PartialEq)]
 pub enum Attribute {
@@ -281,7 +432,6 @@ impl<'a> TryFrom<&'a String> for Color {
     type Error = Error;
 
     fn try_from(color: &'a String) -> Result<Color> {
-        use std::iter::repeat;
         let from_str_radix = u8::from_str_radix;
 
         let n = color.len();
@@ -320,13 +470,12 @@ impl<'a> TryFrom<&'a String> for Color {
             _ if n == 0 => Color::Rgb { r: 0, g: 0, b: 0 },
             color => match color.chars().next() {
                 Some('#') if n == 1 => Color::Rgb { r: 0, g: 0, b: 0 },
+                Some('#') if n != 7 || !color.is_ascii() => {
+                    let msg = format!("invalid rgb color {:?}", color);
+                    err_at!(FailConvert, msg: msg)?
+                }
                 Some('#') => {
-                    let p = {
-                        let r = 6_usize.saturating_sub(n);
-                        let iter = repeat('0').take(r);
-                        String::from_iter(iter)
-                    };
-                    let s = p + &color[1..];
+                    let s = &color[1..];
                     let r = {
                         let rc = from_str_radix(&s[0..2], 16);
                         err_at!(FailConvert, rc, color)?
@@ -341,16 +490,22 @@ impl<'a> TryFrom<&'a String> for Color {
                     };
                     Color::Rgb { r, g, b }
                 }
-                Some(_) => {
-                    let rc = from_str_radix(color, 10);
-                    match err_at!(FailConvert, rc) {
-                        Ok(n) => Color::AnsiValue(n),
-                        _ => {
-                            let rc = from_str_radix(color, 16);
-                            Color::AnsiValue(err_at!(FailConvert, rc)?)
+                Some(_) => match color.strip_prefix("0x") {
+                    Some(hex) => {
+                        let rc = from_str_radix(hex, 16);
+                        Color::AnsiValue(err_at!(FailConvert, rc, color)?)
+                    }
+                    None => {
+                        let rc = from_str_radix(color, 10);
+                        match err_at!(FailConvert, rc) {
+                            Ok(n) => Color::AnsiValue(n),
+                            _ => {
+                                let rc = from_str_radix(color, 16);
+                                Color::AnsiValue(err_at!(FailConvert, rc, color)?)
+                            }
                         }
                     }
-                }
+                },
                 None => {
                     let msg = format!("invalid color");
                     err_at!(FailConvert, msg: msg)?
@@ -366,7 +521,7 @@ impl TryFrom<i64> for Color {
 
     fn try_from(color: i64) -> Result<Color> {
         let color: u8 = err_at!(
-            Invalid,
+            FailConvert,
             color.try_into(),
             format!("color value {} > 255", color)
         )?;
@@ -511,6 +666,10 @@ impl Style {
     /// * white
     /// * bg-canvas, use the canvas' background color,
     /// * fg-canvas, use the canvas' foreground color,
+    ///
+    /// Otherwise, color can be a `#rrggbb` hex triplet for true-color, or
+    /// an xterm 256-color index from 0 to 255, as integer or as string in
+    /// decimal or `0x` prefixed hexadecimal.
     pub fn to_color(color: &toml::Value) -> Result<Color> {
         let color: Color = match color {
             toml::Value::Integer(val) => (*val).try_into()?,
@@ -774,14 +933,35 @@ impl Spanline {
         self
     }
 
+    /// Truncate the span-line to `wth` columns, say when it does not fit
+    /// within the window's viewport.
+    pub fn truncate_width(&mut self, wth: usize) -> &mut Self {
+        let mut n = 0;
+        for (i, span) in self.spans.iter_mut().enumerate() {
+            let m = span.to_width();
+            if (n + m) > wth {
+                let iter = text::take_width(span.content.chars(), wth - n);
+                span.content = String::from_iter(iter);
+                self.spans.truncate(i + 1);
+                break;
+            }
+            n += m;
+        }
+        self
+    }
+
     pub fn optimize_spans(&mut self, canvas: Style) -> &mut Self {
         // carry forward previous background color to next span.
         let mut bg = canvas.bg.clone();
         for span in self.spans.iter_mut() {
-            if span.bg == bg {
+            // spans without background color, like padding, are rendered
+            // on canvas, even when following a highlighted span.
+            let span_bg = span.bg.clone().or_else(|| canvas.bg.clone());
+            if span_bg == bg {
                 span.bg = None
             } else {
-                bg = span.bg.clone()
+                span.bg = span_bg.clone();
+                bg = span_bg
             }
             // when rendering blank chars, don't bother with foreground
             if span.is_empty() {
@@ -807,6 +987,13 @@ impl Spanline {
         self
     }
 
+    /// Insert a new span value before all other spans.
+    #[inline]
+    pub fn prepend_span(&mut self, span: Span) -> &mut Self {
+        self.spans.insert(0, span);
+        self
+    }
+
     /// Return whether the span-line is empty.
     #[inline]
     pub fn is_empty(&self) -> bool {
@@ -828,6 +1015,99 @@ impl Spanline {
         self
     }
 
+    /// Apply `style` to the last `n` characters of the span-line, say to
+    /// highlight trailing whitespace. Spans are split as needed.
+    pub fn using_suffix(&mut self, mut n: usize, style: Style) -> &mut Self {
+        let mut i = self.spans.len();
+        while n > 0 && i > 0 {
+            i -= 1;
+            let m = self.spans[i].content.chars().count();
+            if m <= n {
+                self.spans[i] = self.spans[i].clone().using(style.clone());
+                n -= m;
+            } else {
+                let span = &mut self.spans[i];
+                let off = span.content.char_indices().nth(m - n).unwrap().0;
+                let suffix: Span = span.content.split_off(off).into();
+                self.spans.insert(i + 1, suffix.using(style.clone()));
+                n = 0;
+            }
+        }
+        self
+    }
+
+    /// Set background color to `bg` for spans rendered on `canvas`. Spans
+    /// having their own background color, and foreground colors of all
+    /// spans, are left as is.
+    pub fn set_canvas_bg(&mut self, canvas: &Style, bg: Option<Color>) -> &mut Self {
+        for span in self.spans.iter_mut() {
+            if span.bg.is_none() || span.bg == canvas.bg {
+                span.bg = bg.clone();
+            }
+        }
+        self
+    }
+
+    /// Replace every `from` character with `to`.
+    pub fn replace_chars(&mut self, from: char, to: char) -> &mut Self {
+        for span in self.spans.iter_mut() {
+            span.content = span.content.replace(from, &to.to_string());
+        }
+        self
+    }
+
+    /// Replace `from` character with `to`, only within the last `n`
+    /// characters of the span-line.
+    pub fn replace_suffix(&mut self, mut n: usize, from: char, to: char) -> &mut Self {
+        for span in self.spans.iter_mut().rev() {
+            if n == 0 {
+                break;
+            }
+            let m = span.content.chars().count();
+            let k = m.saturating_sub(n);
+            span.content = {
+                let iter = span.content.chars().enumerate();
+                iter.map(|(i, ch)| if_else!(i >= k && ch == from, to, ch))
+                    .collect()
+            };
+            n = n.saturating_sub(m);
+        }
+        self
+    }
+
+    /// Expand tabs into spaces, upto the next multiple of `tab_stop`,
+    /// where span-line starts at visual column `vcol`. Return the number
+    /// of columns added by the expansion.
+    pub fn expand_tabs(&mut self, vcol: usize, tab_stop: usize) -> usize {
+        self.expand_tabs_with(vcol, tab_stop, ' ')
+    }
+
+    /// Same as [Spanline::expand_tabs], except that the first column of
+    /// each expanded tab is filled with `mark`.
+    pub fn expand_tabs_with(&mut self, vcol: usize, tab_stop: usize, mark: char) -> usize {
+        let (mut n, mut added) = (vcol, 0);
+        for span in self.spans.iter_mut() {
+            let (content, m) = text::expand_tabs_with(&span.content, n, tab_stop, mark);
+            added += content.chars().count() - span.content.chars().count();
+            span.content = content;
+            n = m;
+        }
+        added
+    }
+
+    /// Spanline may need to be padded with `n_pad` bytes on the left, say
+    /// when a tab is partially scrolled out of the window's viewport.
+    pub fn left_padding(&mut self, n_pad: u16) -> &mut Self {
+        use std::iter::repeat;
+
+        if n_pad > 0 {
+            let n = n_pad as usize;
+            let span: Span = String::from_iter(repeat(' ').take(n)).into();
+            self.spans.insert(0, span)
+        }
+        self
+    }
+
     pub fn trim_newline(&mut self) -> usize {
         match self.spans.pop() {
             Some(span) => {
@@ -850,9 +1130,13 @@ impl Command for Spanline {
             Some(Cursor { col, row }) => MoveTo(*col, *row).to_string(),
             None => String::default(),
         };
-        for span in self.spans.clone().into_iter() {
+        for span in self.spans.iter() {
             s.push_str(&span.ansi_code());
         }
         s
     }
 }
+
+#[cfg(test)]
+#[path = "term_test.rs"]
+mod term_test;
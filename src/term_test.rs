@@ -0,0 +1,104 @@
+use super::*;
+
+#[test]
+fn test_to_color() {
+    let to_color = |s: &str| Style::to_color(&toml::Value::String(s.to_string()));
+
+    let rgb = Color::Rgb {
+        r: 0xff,
+        g: 0x88,
+        b: 0x00,
+    };
+    assert!(to_color("#ff8800").unwrap() == rgb);
+    assert!(to_color("#FF8800").unwrap() == rgb);
+    assert!(to_color("244").unwrap() == Color::AnsiValue(244));
+    assert!(to_color("0xf4").unwrap() == Color::AnsiValue(244));
+    assert!(Style::to_color(&toml::Value::Integer(244)).unwrap() == Color::AnsiValue(244));
+    assert!(to_color("dark-cyan").unwrap() == Color::DarkCyan);
+
+    let is_fail_convert = |res: Result<Color>| match res {
+        Err(Error::FailConvert(_, _)) => true,
+        _ => false,
+    };
+    assert!(is_fail_convert(to_color("#ff88zz")));
+    assert!(is_fail_convert(to_color("#ff880")));
+    assert!(is_fail_convert(to_color("#ff88001")));
+    assert!(is_fail_convert(to_color("#ffé880")));
+    assert!(is_fail_convert(to_color("256")));
+    assert!(is_fail_convert(to_color("0x100")));
+    assert!(is_fail_convert(to_color("nocolor")));
+    assert!(is_fail_convert(Style::to_color(&toml::Value::Integer(256))));
+}
+
+#[test]
+fn test_spanline_expand_tabs() {
+    let mut line: Spanline = vec![Span::from("a\t".to_string()), "\tb".to_string().into()]
+        .into_iter()
+        .collect();
+    assert_eq!(line.expand_tabs(1, 4), 4);
+    assert_eq!(format!("{:?}", line), r#"L("S(\"a  \"),S(\"    b\")")"#);
+}
+
+#[test]
+fn test_spanline_using_suffix() {
+    let style = {
+        let mut style = Style::default();
+        style.set_bg(Some(Color::AnsiValue(88)));
+        style
+    };
+
+    let mut line: Spanline = vec![Span::from("ab ".to_string()), "  ".to_string().into()]
+        .into_iter()
+        .collect();
+    line.using_suffix(3, style.clone());
+    assert_eq!(
+        format!("{:?}", line),
+        r#"L("S(\"ab\"),S(\" \"),S(\"  \")")"#
+    );
+    let bgs: Vec<Option<Color>> = line.spans.iter().map(|s| s.bg.clone()).collect();
+    assert!(bgs == vec![None, style.bg.clone(), style.bg.clone()]);
+
+    // nothing to highlight.
+    let mut line: Spanline = "ab".to_string().into();
+    line.using_suffix(0, style);
+    assert_eq!(format!("{:?}", line), r#"L("S(\"ab\")")"#);
+    assert!(line.spans[0].bg.is_none());
+}
+
+#[test]
+fn test_spanline_optimize_spans() {
+    let canvas = Style::default().set_bg(Some(Color::AnsiValue(234))).clone();
+    let style = Style::default().set_bg(Some(Color::AnsiValue(88))).clone();
+
+    let mut line: Spanline = "ab  ".to_string().into();
+    line.using_suffix(2, style).right_padding(2);
+    line.optimize_spans(canvas);
+    // padding switches back to canvas after the highlighted span.
+    let bgs: Vec<Option<Color>> = line.spans.iter().map(|s| s.bg.clone()).collect();
+    assert!(
+        bgs == vec![
+            None,
+            Some(Color::AnsiValue(88)),
+            Some(Color::AnsiValue(234))
+        ]
+    );
+}
+
+#[test]
+fn test_spanline_set_canvas_bg() {
+    let canvas = Style::default().set_bg(Some(Color::AnsiValue(234))).clone();
+    let bg = Some(Color::AnsiValue(236));
+
+    let mut line: Spanline = vec![
+        Span::from("fn".to_string()).with(Color::AnsiValue(172)),
+        Span::from(" ".to_string()).on(Color::AnsiValue(234)),
+        Span::from("  ".to_string()).on(Color::AnsiValue(88)),
+    ]
+    .into_iter()
+    .collect();
+    line.set_canvas_bg(&canvas, bg.clone());
+    // foreground and highlighted background are retained.
+    assert!(line.spans[0].fg == Some(Color::AnsiValue(172)));
+    let bgs: Vec<Option<Color>> = line.spans.iter().map(|s| s.bg.clone()).collect();
+    assert!(bgs == vec![bg.clone(), bg, Some(Color::AnsiValue(88))]);
+}
@@ -1,8 +1,9 @@
 //! Module implement types and functions to handle text.
 
+use unicode_segmentation::UnicodeSegmentation;
 use unicode_width::UnicodeWidthChar;
 
-use std::{convert::TryFrom, io};
+use std::{cmp, convert::TryFrom, io};
 
 use crate::{Error, Result};
 
@@ -42,7 +43,7 @@ impl Encoding {
         use std::str::from_utf8;
 
         let mut buf = vec![];
-        err_at!(IOError, r.read_to_end(&mut buf))?;
+        r.read_to_end(&mut buf)?;
 
         match fenc {
             "utf8" | "utf-8" => {
@@ -155,3 +156,120 @@ where
         }
     }
 }
+
+/// Return the visual column of character `col` in `line`, tabs expand
+/// upto the next multiple of `tab_stop`, wide characters take two columns
+/// and combining marks take none. Other control characters take a single
+/// column.
+pub fn to_vcol(line: &str, col: usize, tab_stop: usize) -> usize {
+    let iter = line.chars().take(col);
+    iter.fold(0, |vcol, ch| next_vcol(vcol, ch, tab_stop))
+}
+
+/// Return the character index of the first character in `line` starting
+/// at or after visual column `vcol`, refer [to_vcol] for details.
+pub fn from_vcol(line: &str, vcol: usize, tab_stop: usize) -> usize {
+    let mut n = 0;
+    for (col, ch) in visual_line(line).chars().enumerate() {
+        let m = next_vcol(n, ch, tab_stop);
+        // zero-width characters stay with the character they follow.
+        if n >= vcol && m > n {
+            return col;
+        }
+        n = m;
+    }
+    visual_line_n(line) + vcol.saturating_sub(n)
+}
+
+/// Expand tabs in `text` into spaces, where `text` starts at visual
+/// column `vcol`. Return the expanded text and the visual column at its
+/// end.
+pub fn expand_tabs(text: &str, vcol: usize, tab_stop: usize) -> (String, usize) {
+    expand_tabs_with(text, vcol, tab_stop, ' ')
+}
+
+/// Same as [expand_tabs], except that the first column of each expanded
+/// tab is filled with `mark`, say to make tabs visible.
+pub fn expand_tabs_with(text: &str, vcol: usize, tab_stop: usize, mark: char) -> (String, usize) {
+    let mut out = String::with_capacity(text.len());
+    let mut n = vcol;
+    for ch in text.chars() {
+        let m = next_vcol(n, ch, tab_stop);
+        match ch {
+            '\t' => {
+                out.push(mark);
+                out.push_str(&" ".repeat(m - n - 1))
+            }
+            ch => out.push(ch),
+        }
+        n = m;
+    }
+    (out, n)
+}
+
+/// Split `text` into segments whose visual-width does not exceed `wth`,
+/// return the number of characters in each segment. Tabs are not expanded
+/// and take a single column, refer [to_vcol] for details.
+pub fn split_width(text: &str, wth: usize) -> Vec<usize> {
+    let mut segments = vec![];
+    let (mut n, mut vcol) = (0, 0);
+    for ch in text.chars() {
+        let m = next_vcol(vcol, ch, 1);
+        if n > 0 && m > wth {
+            segments.push(n);
+            n = 0;
+            vcol = next_vcol(0, ch, 1);
+        } else {
+            vcol = m;
+        }
+        n += 1;
+    }
+    if n > 0 {
+        segments.push(n)
+    }
+    segments
+}
+
+/// Return the character index, in `line`, of the grapheme cluster that
+/// contains the character at `col`.
+pub fn grapheme_start(line: &str, col: usize) -> usize {
+    let mut start = 0;
+    for g in line.graphemes(true) {
+        let n = g.chars().count();
+        if col < start + n {
+            return start;
+        }
+        start += n;
+    }
+    col
+}
+
+/// Return the number of characters from `col` upto the end of its
+/// grapheme cluster in `line`, which is atleast 1.
+pub fn grapheme_len(line: &str, col: usize) -> usize {
+    let mut start = 0;
+    for g in line.graphemes(true) {
+        let n = g.chars().count();
+        if col < start + n {
+            return start + n - col;
+        }
+        start += n;
+    }
+    1
+}
+
+fn next_vcol(vcol: usize, ch: char, tab_stop: usize) -> usize {
+    let tab_stop = cmp::max(tab_stop, 1);
+    match ch {
+        '\t' => (vcol / tab_stop + 1) * tab_stop,
+        ch => vcol + ch.width().unwrap_or(1),
+    }
+}
+
+/// Return whether `word`, ending with `.`, `!` or `?`, ends a sentence.
+/// Abbreviations like `Mr.` and `e.g.` listed in `abbrs` do not end a
+/// sentence, comparison is case-insensitive.
+pub fn is_sentence_end(word: &str, abbrs: &[String]) -> bool {
+    let ok = matches!(word.chars().last(), Some('.') | Some('!') | Some('?'));
+    ok && !abbrs.iter().any(|abbr| abbr.eq_ignore_ascii_case(word))
+}
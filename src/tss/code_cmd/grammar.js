@@ -8,23 +8,42 @@ module.exports = grammar({
 
     newline: $ => /\r?\n/,
 
-    cmd: $ => choice($.set, $.buffer, $.buffers, $.edit),
+    cmd: $ => choice(
+        $.set, $.buffer, $.buffers, $.edit, $.goto, $.write, $.quit, $.write_quit,
+        $.highlight, $.substitute, $.strip_whitespace, $.copen, $.cnext, $.cprev,
+    ),
 
     range: $ => seq($.range_start, optional(seq(',', $.range_end))),
     range_start: $ => /([0-9.%]+|(\?[^?]+\?)|'[a-z])[+-]?[0-9]*/,
     range_end: $ => /([0-9.$]+|(\/.*\/)|'[a-z])[+-]?[0-9]*/,
 
-    set: $ => seq('set', $.config_param),
-    config_param: $ => choice(
-        'wrap',
-        'nowrap',
-    ),
+    set: $ => seq(choice('se', 'set'), /.*/),
+
+    edit: $ => seq(choice('e', 'edit'), /.*/),
+
+    goto: $ => seq(choice('go', 'goto'), optional(/[0-9]+/)),
+
+    write: $ => seq(choice('w', 'write'), /.*/),
+
+    quit: $ => seq(choice('q', 'quit'), optional('!')),
+
+    write_quit: $ => 'wq',
+
+    highlight: $ => seq(choice('hi', 'highlight'), /.*/),
+
+    substitute: $ => seq(choice('s', 'substitute'), /.*/),
+
+    buffer: $ => seq(choice('b', 'buffer'), /[0-9]+/),
+
+    buffers: $ => choice('ls', 'buffers'),
+
+    strip_whitespace: $ => seq('StripWhitespace', /.*/),
 
-    edit: $ => seq('edit', /.*/),
+    copen: $ => seq(choice('cope', 'copen'), /.*/),
 
-    buffer: $ => seq('buffer', /[0-9]+/),
+    cnext: $ => seq(choice('cn', 'cnext'), /.*/),
 
-    buffers: $ => seq('buffers'),
+    cprev: $ => seq(choice('cp', 'cprev', 'cN', 'cNext'), /.*/),
   }
 });
 
@@ -6,11 +6,11 @@
 #endif
 
 #define LANGUAGE_VERSION 11
-#define STATE_COUNT 19
-#define LARGE_STATE_COUNT 3
-#define SYMBOL_COUNT 22
+#define STATE_COUNT 36
+#define LARGE_STATE_COUNT 6
+#define SYMBOL_COUNT 53
 #define ALIAS_COUNT 0
-#define TOKEN_COUNT 14
+#define TOKEN_COUNT 37
 #define EXTERNAL_TOKEN_COUNT 0
 #define FIELD_COUNT 0
 #define MAX_ALIAS_SEQUENCE_LENGTH 3
@@ -21,22 +21,53 @@ enum {
   anon_sym_COMMA = 3,
   sym_range_start = 4,
   sym_range_end = 5,
-  anon_sym_set = 6,
-  anon_sym_wrap = 7,
-  anon_sym_nowrap = 8,
-  anon_sym_edit = 9,
-  aux_sym_edit_token1 = 10,
-  anon_sym_buffer = 11,
-  aux_sym_buffer_token1 = 12,
-  anon_sym_buffers = 13,
-  sym_s = 14,
-  sym_cmd = 15,
-  sym_range = 16,
-  sym_set = 17,
-  sym_config_param = 18,
-  sym_edit = 19,
-  sym_buffer = 20,
-  sym_buffers = 21,
+  anon_sym_se = 6,
+  anon_sym_set = 7,
+  aux_sym_set_token1 = 8,
+  anon_sym_e = 9,
+  anon_sym_edit = 10,
+  anon_sym_go = 11,
+  anon_sym_goto = 12,
+  aux_sym_goto_token1 = 13,
+  anon_sym_w = 14,
+  anon_sym_write = 15,
+  anon_sym_q = 16,
+  anon_sym_quit = 17,
+  anon_sym_BANG = 18,
+  sym_write_quit = 19,
+  anon_sym_hi = 20,
+  anon_sym_highlight = 21,
+  anon_sym_s = 22,
+  anon_sym_substitute = 23,
+  anon_sym_b = 24,
+  anon_sym_buffer = 25,
+  anon_sym_ls = 26,
+  anon_sym_buffers = 27,
+  anon_sym_StripWhitespace = 28,
+  anon_sym_cope = 29,
+  anon_sym_copen = 30,
+  anon_sym_cn = 31,
+  anon_sym_cnext = 32,
+  anon_sym_cp = 33,
+  anon_sym_cprev = 34,
+  anon_sym_cN = 35,
+  anon_sym_cNext = 36,
+  sym_s = 37,
+  sym_cmd = 38,
+  sym_range = 39,
+  sym_set = 40,
+  sym_edit = 41,
+  sym_goto = 42,
+  sym_write = 43,
+  sym_quit = 44,
+  sym_highlight = 45,
+  sym_substitute = 46,
+  sym_buffer = 47,
+  sym_buffers = 48,
+  sym_strip_whitespace = 49,
+  sym_copen = 50,
+  sym_cnext = 51,
+  sym_cprev = 52,
 };
 
 static const char *ts_symbol_names[] = {
@@ -46,22 +77,53 @@ static const char *ts_symbol_names[] = {
   [anon_sym_COMMA] = ",",
   [sym_range_start] = "range_start",
   [sym_range_end] = "range_end",
+  [anon_sym_se] = "se",
   [anon_sym_set] = "set",
-  [anon_sym_wrap] = "wrap",
-  [anon_sym_nowrap] = "nowrap",
+  [aux_sym_set_token1] = "set_token1",
+  [anon_sym_e] = "e",
   [anon_sym_edit] = "edit",
-  [aux_sym_edit_token1] = "edit_token1",
+  [anon_sym_go] = "go",
+  [anon_sym_goto] = "goto",
+  [aux_sym_goto_token1] = "goto_token1",
+  [anon_sym_w] = "w",
+  [anon_sym_write] = "write",
+  [anon_sym_q] = "q",
+  [anon_sym_quit] = "quit",
+  [anon_sym_BANG] = "!",
+  [sym_write_quit] = "write_quit",
+  [anon_sym_hi] = "hi",
+  [anon_sym_highlight] = "highlight",
+  [anon_sym_s] = "s",
+  [anon_sym_substitute] = "substitute",
+  [anon_sym_b] = "b",
   [anon_sym_buffer] = "buffer",
-  [aux_sym_buffer_token1] = "buffer_token1",
+  [anon_sym_ls] = "ls",
   [anon_sym_buffers] = "buffers",
+  [anon_sym_StripWhitespace] = "StripWhitespace",
+  [anon_sym_cope] = "cope",
+  [anon_sym_copen] = "copen",
+  [anon_sym_cn] = "cn",
+  [anon_sym_cnext] = "cnext",
+  [anon_sym_cp] = "cp",
+  [anon_sym_cprev] = "cprev",
+  [anon_sym_cN] = "cN",
+  [anon_sym_cNext] = "cNext",
   [sym_s] = "s",
   [sym_cmd] = "cmd",
   [sym_range] = "range",
   [sym_set] = "set",
-  [sym_config_param] = "config_param",
   [sym_edit] = "edit",
+  [sym_goto] = "goto",
+  [sym_write] = "write",
+  [sym_quit] = "quit",
+  [sym_highlight] = "highlight",
+  [sym_substitute] = "substitute",
   [sym_buffer] = "buffer",
   [sym_buffers] = "buffers",
+  [sym_strip_whitespace] = "strip_whitespace",
+  [sym_copen] = "copen",
+  [sym_cnext] = "cnext",
+  [sym_cprev] = "cprev",
 };
 
 static TSSymbol ts_symbol_map[] = {
@@ -71,22 +133,53 @@ static TSSymbol ts_symbol_map[] = {
   [anon_sym_COMMA] = anon_sym_COMMA,
   [sym_range_start] = sym_range_start,
   [sym_range_end] = sym_range_end,
+  [anon_sym_se] = anon_sym_se,
   [anon_sym_set] = anon_sym_set,
-  [anon_sym_wrap] = anon_sym_wrap,
-  [anon_sym_nowrap] = anon_sym_nowrap,
+  [aux_sym_set_token1] = aux_sym_set_token1,
+  [anon_sym_e] = anon_sym_e,
   [anon_sym_edit] = anon_sym_edit,
-  [aux_sym_edit_token1] = aux_sym_edit_token1,
+  [anon_sym_go] = anon_sym_go,
+  [anon_sym_goto] = anon_sym_goto,
+  [aux_sym_goto_token1] = aux_sym_goto_token1,
+  [anon_sym_w] = anon_sym_w,
+  [anon_sym_write] = anon_sym_write,
+  [anon_sym_q] = anon_sym_q,
+  [anon_sym_quit] = anon_sym_quit,
+  [anon_sym_BANG] = anon_sym_BANG,
+  [sym_write_quit] = sym_write_quit,
+  [anon_sym_hi] = anon_sym_hi,
+  [anon_sym_highlight] = anon_sym_highlight,
+  [anon_sym_s] = anon_sym_s,
+  [anon_sym_substitute] = anon_sym_substitute,
+  [anon_sym_b] = anon_sym_b,
   [anon_sym_buffer] = anon_sym_buffer,
-  [aux_sym_buffer_token1] = aux_sym_buffer_token1,
+  [anon_sym_ls] = anon_sym_ls,
   [anon_sym_buffers] = anon_sym_buffers,
+  [anon_sym_StripWhitespace] = anon_sym_StripWhitespace,
+  [anon_sym_cope] = anon_sym_cope,
+  [anon_sym_copen] = anon_sym_copen,
+  [anon_sym_cn] = anon_sym_cn,
+  [anon_sym_cnext] = anon_sym_cnext,
+  [anon_sym_cp] = anon_sym_cp,
+  [anon_sym_cprev] = anon_sym_cprev,
+  [anon_sym_cN] = anon_sym_cN,
+  [anon_sym_cNext] = anon_sym_cNext,
   [sym_s] = sym_s,
   [sym_cmd] = sym_cmd,
   [sym_range] = sym_range,
   [sym_set] = sym_set,
-  [sym_config_param] = sym_config_param,
   [sym_edit] = sym_edit,
+  [sym_goto] = sym_goto,
+  [sym_write] = sym_write,
+  [sym_quit] = sym_quit,
+  [sym_highlight] = sym_highlight,
+  [sym_substitute] = sym_substitute,
   [sym_buffer] = sym_buffer,
   [sym_buffers] = sym_buffers,
+  [sym_strip_whitespace] = sym_strip_whitespace,
+  [sym_copen] = sym_copen,
+  [sym_cnext] = sym_cnext,
+  [sym_cprev] = sym_cprev,
 };
 
 static const TSSymbolMetadata ts_symbol_metadata[] = {
@@ -114,15 +207,19 @@ static const TSSymbolMetadata ts_symbol_metadata[] = {
     .visible = true,
     .named = true,
   },
-  [anon_sym_set] = {
+  [anon_sym_se] = {
     .visible = true,
     .named = false,
   },
-  [anon_sym_wrap] = {
+  [anon_sym_set] = {
     .visible = true,
     .named = false,
   },
-  [anon_sym_nowrap] = {
+  [aux_sym_set_token1] = {
+    .visible = false,
+    .named = false,
+  },
+  [anon_sym_e] = {
     .visible = true,
     .named = false,
   },
@@ -130,22 +227,110 @@ static const TSSymbolMetadata ts_symbol_metadata[] = {
     .visible = true,
     .named = false,
   },
-  [aux_sym_edit_token1] = {
+  [anon_sym_go] = {
+    .visible = true,
+    .named = false,
+  },
+  [anon_sym_goto] = {
+    .visible = true,
+    .named = false,
+  },
+  [aux_sym_goto_token1] = {
     .visible = false,
     .named = false,
   },
+  [anon_sym_w] = {
+    .visible = true,
+    .named = false,
+  },
+  [anon_sym_write] = {
+    .visible = true,
+    .named = false,
+  },
+  [anon_sym_q] = {
+    .visible = true,
+    .named = false,
+  },
+  [anon_sym_quit] = {
+    .visible = true,
+    .named = false,
+  },
+  [anon_sym_BANG] = {
+    .visible = true,
+    .named = false,
+  },
+  [sym_write_quit] = {
+    .visible = true,
+    .named = true,
+  },
+  [anon_sym_hi] = {
+    .visible = true,
+    .named = false,
+  },
+  [anon_sym_highlight] = {
+    .visible = true,
+    .named = false,
+  },
+  [anon_sym_s] = {
+    .visible = true,
+    .named = false,
+  },
+  [anon_sym_substitute] = {
+    .visible = true,
+    .named = false,
+  },
+  [anon_sym_b] = {
+    .visible = true,
+    .named = false,
+  },
   [anon_sym_buffer] = {
     .visible = true,
     .named = false,
   },
-  [aux_sym_buffer_token1] = {
-    .visible = false,
+  [anon_sym_ls] = {
+    .visible = true,
     .named = false,
   },
   [anon_sym_buffers] = {
     .visible = true,
     .named = false,
   },
+  [anon_sym_StripWhitespace] = {
+    .visible = true,
+    .named = false,
+  },
+  [anon_sym_cope] = {
+    .visible = true,
+    .named = false,
+  },
+  [anon_sym_copen] = {
+    .visible = true,
+    .named = false,
+  },
+  [anon_sym_cn] = {
+    .visible = true,
+    .named = false,
+  },
+  [anon_sym_cnext] = {
+    .visible = true,
+    .named = false,
+  },
+  [anon_sym_cp] = {
+    .visible = true,
+    .named = false,
+  },
+  [anon_sym_cprev] = {
+    .visible = true,
+    .named = false,
+  },
+  [anon_sym_cN] = {
+    .visible = true,
+    .named = false,
+  },
+  [anon_sym_cNext] = {
+    .visible = true,
+    .named = false,
+  },
   [sym_s] = {
     .visible = true,
     .named = true,
@@ -162,11 +347,27 @@ static const TSSymbolMetadata ts_symbol_metadata[] = {
     .visible = true,
     .named = true,
   },
-  [sym_config_param] = {
+  [sym_edit] = {
     .visible = true,
     .named = true,
   },
-  [sym_edit] = {
+  [sym_goto] = {
+    .visible = true,
+    .named = true,
+  },
+  [sym_write] = {
+    .visible = true,
+    .named = true,
+  },
+  [sym_quit] = {
+    .visible = true,
+    .named = true,
+  },
+  [sym_highlight] = {
+    .visible = true,
+    .named = true,
+  },
+  [sym_substitute] = {
     .visible = true,
     .named = true,
   },
@@ -178,6 +379,22 @@ static const TSSymbolMetadata ts_symbol_metadata[] = {
     .visible = true,
     .named = true,
   },
+  [sym_strip_whitespace] = {
+    .visible = true,
+    .named = true,
+  },
+  [sym_copen] = {
+    .visible = true,
+    .named = true,
+  },
+  [sym_cnext] = {
+    .visible = true,
+    .named = true,
+  },
+  [sym_cprev] = {
+    .visible = true,
+    .named = true,
+  },
 };
 
 static TSSymbol ts_alias_sequences[1][MAX_ALIAS_SEQUENCE_LENGTH] = {
@@ -189,248 +406,522 @@ static bool ts_lex(TSLexer *lexer, TSStateId state) {
   eof = lexer->eof(lexer);
   switch (state) {
     case 0:
-      if (eof) ADVANCE(28);
-      if (lookahead == '\n') ADVANCE(30);
-      if (lookahead == '\r') ADVANCE(1);
-      if (lookahead == '$') ADVANCE(38);
-      if (lookahead == '%') ADVANCE(33);
-      if (lookahead == '\'') ADVANCE(24);
-      if (lookahead == ',') ADVANCE(31);
-      if (lookahead == '.') ADVANCE(32);
-      if (lookahead == '/') ADVANCE(4);
-      if (lookahead == ':') ADVANCE(29);
-      if (lookahead == '?') ADVANCE(26);
-      if (lookahead == 'b') ADVANCE(22);
-      if (lookahead == 'e') ADVANCE(8);
-      if (lookahead == 'n') ADVANCE(14);
-      if (lookahead == 's') ADVANCE(9);
-      if (lookahead == 'w') ADVANCE(17);
+      if (eof) ADVANCE(68);
+      if (lookahead == '\n') ADVANCE(70);
+      if (lookahead == '\r') ADVANCE(10);
+      if (lookahead == '!') ADVANCE(95);
+      if (lookahead == '$') ADVANCE(76);
+      if (lookahead == '%') ADVANCE(72);
+      if (lookahead == '\'') ADVANCE(11);
+      if (lookahead == ',') ADVANCE(71);
+      if (lookahead == '.') ADVANCE(73);
+      if (lookahead == '/') ADVANCE(12);
+      if (lookahead == ':') ADVANCE(69);
+      if (lookahead == '?') ADVANCE(13);
+      if (lookahead == 'S') ADVANCE(14);
+      if (lookahead == 'b') ADVANCE(101);
+      if (lookahead == 'c') ADVANCE(15);
+      if (lookahead == 'e') ADVANCE(86);
+      if (lookahead == 'g') ADVANCE(16);
+      if (lookahead == 'h') ADVANCE(17);
+      if (lookahead == 'l') ADVANCE(18);
+      if (lookahead == 'q') ADVANCE(93);
+      if (lookahead == 's') ADVANCE(99);
+      if (lookahead == 'w') ADVANCE(91);
       if (lookahead == '\t' ||
           lookahead == ' ') SKIP(0)
-      if (('0' <= lookahead && lookahead <= '9')) ADVANCE(32);
+      if (('0' <= lookahead && lookahead <= '9')) ADVANCE(73);
       END_STATE();
     case 1:
-      if (lookahead == '\n') ADVANCE(30);
+      if (lookahead == '\n') ADVANCE(70);
+      if (lookahead == '\r') ADVANCE(10);
+      if (lookahead == ':') ADVANCE(69);
+      if (lookahead == '\t' ||
+          lookahead == ' ') SKIP(1)
       END_STATE();
     case 2:
-      if (lookahead == '\n') ADVANCE(30);
-      if (lookahead == '\r') ADVANCE(1);
-      if (lookahead == '\'') ADVANCE(25);
-      if (lookahead == '/') ADVANCE(4);
+      if (eof) ADVANCE(68);
+      if (lookahead == '\n') ADVANCE(70);
+      if (lookahead == '\r') ADVANCE(10);
+      if (lookahead == '\'') ADVANCE(11);
+      if (lookahead == '?') ADVANCE(13);
+      if (lookahead == 'S') ADVANCE(14);
+      if (lookahead == 'b') ADVANCE(101);
+      if (lookahead == 'c') ADVANCE(15);
+      if (lookahead == 'e') ADVANCE(86);
+      if (lookahead == 'g') ADVANCE(16);
+      if (lookahead == 'h') ADVANCE(17);
+      if (lookahead == 'l') ADVANCE(18);
+      if (lookahead == 'q') ADVANCE(93);
+      if (lookahead == 's') ADVANCE(99);
+      if (lookahead == 'w') ADVANCE(91);
       if (lookahead == '\t' ||
           lookahead == ' ') SKIP(2)
-      if (lookahead == '$' ||
-          ('.' <= lookahead && lookahead <= '9')) ADVANCE(38);
+      if (lookahead == '%' ||
+          lookahead == '.' ||
+          ('0' <= lookahead && lookahead <= '9')) ADVANCE(72);
       END_STATE();
     case 3:
-      if (lookahead == '\n') ADVANCE(30);
-      if (lookahead == '\r') ADVANCE(1);
+      if (eof) ADVANCE(68);
+      if (lookahead == '\n') ADVANCE(70);
+      if (lookahead == '\r') ADVANCE(10);
+      if (lookahead == 'S') ADVANCE(14);
+      if (lookahead == 'b') ADVANCE(101);
+      if (lookahead == 'c') ADVANCE(15);
+      if (lookahead == 'e') ADVANCE(86);
+      if (lookahead == 'g') ADVANCE(16);
+      if (lookahead == 'h') ADVANCE(17);
+      if (lookahead == 'l') ADVANCE(18);
+      if (lookahead == 'q') ADVANCE(93);
+      if (lookahead == 's') ADVANCE(99);
+      if (lookahead == 'w') ADVANCE(91);
       if (lookahead == '\t' ||
           lookahead == ' ') SKIP(3)
-      if (('0' <= lookahead && lookahead <= '9')) ADVANCE(49);
       END_STATE();
     case 4:
-      if (lookahead == '/') ADVANCE(36);
-      if (lookahead != 0 &&
-          lookahead != '\n') ADVANCE(4);
+      if (eof) ADVANCE(68);
+      if (lookahead == '\n') ADVANCE(70);
+      if (lookahead == '\r') ADVANCE(10);
+      if (lookahead == ',') ADVANCE(71);
+      if (lookahead == 'S') ADVANCE(14);
+      if (lookahead == 'b') ADVANCE(101);
+      if (lookahead == 'c') ADVANCE(15);
+      if (lookahead == 'e') ADVANCE(86);
+      if (lookahead == 'g') ADVANCE(16);
+      if (lookahead == 'h') ADVANCE(17);
+      if (lookahead == 'l') ADVANCE(18);
+      if (lookahead == 'q') ADVANCE(93);
+      if (lookahead == 's') ADVANCE(99);
+      if (lookahead == 'w') ADVANCE(91);
+      if (lookahead == '\t' ||
+          lookahead == ' ') SKIP(4)
       END_STATE();
     case 5:
-      if (lookahead == '?') ADVANCE(34);
-      if (lookahead != 0) ADVANCE(5);
+      if (eof) ADVANCE(68);
+      if (lookahead == '\n') ADVANCE(70);
+      if (lookahead == '\r') ADVANCE(10);
+      if (lookahead == '\t' ||
+          lookahead == ' ') SKIP(5)
+      if (('0' <= lookahead && lookahead <= '9')) ADVANCE(90);
       END_STATE();
     case 6:
-      if (lookahead == 'a') ADVANCE(15);
+      if (eof) ADVANCE(68);
+      if (lookahead == '\n') ADVANCE(70);
+      if (lookahead == '\r') ADVANCE(10);
+      if (lookahead == '!') ADVANCE(95);
+      if (lookahead == '\t' ||
+          lookahead == ' ') SKIP(6)
       END_STATE();
     case 7:
-      if (lookahead == 'a') ADVANCE(16);
+      if (eof) ADVANCE(68);
+      if (lookahead == '\n') ADVANCE(70);
+      if (lookahead == '\r') ADVANCE(10);
+      if (lookahead == '\t' ||
+          lookahead == ' ') SKIP(7)
       END_STATE();
     case 8:
-      if (lookahead == 'd') ADVANCE(13);
+      if (lookahead == '\n') ADVANCE(70);
+      if (lookahead == '\r') ADVANCE(10);
+      if (lookahead == '\t' ||
+          lookahead == ' ') SKIP(8)
+      if (('0' <= lookahead && lookahead <= '9')) ADVANCE(90);
       END_STATE();
     case 9:
-      if (lookahead == 'e') ADVANCE(20);
+      if (lookahead == '\n') ADVANCE(70);
+      if (lookahead == '\r') ADVANCE(10);
+      if (lookahead == '\'') ADVANCE(19);
+      if (lookahead == '/') ADVANCE(12);
+      if (lookahead == '\t' ||
+          lookahead == ' ') SKIP(9)
+      if (lookahead == '$' ||
+          lookahead == '.' ||
+          ('0' <= lookahead && lookahead <= '9')) ADVANCE(76);
       END_STATE();
     case 10:
-      if (lookahead == 'e') ADVANCE(18);
+      if (lookahead == '\n') ADVANCE(70);
       END_STATE();
     case 11:
-      if (lookahead == 'f') ADVANCE(10);
+      if (('a' <= lookahead && lookahead <= 'z')) ADVANCE(75);
       END_STATE();
     case 12:
-      if (lookahead == 'f') ADVANCE(11);
+      if (lookahead == '/') ADVANCE(78);
+      if (lookahead != 0 &&
+          lookahead != '\n' &&
+          lookahead != '/') ADVANCE(12);
       END_STATE();
     case 13:
-      if (lookahead == 'i') ADVANCE(21);
+      if (lookahead != 0 &&
+          lookahead != '?') ADVANCE(20);
       END_STATE();
     case 14:
-      if (lookahead == 'o') ADVANCE(23);
+      if (lookahead == 't') ADVANCE(21);
       END_STATE();
     case 15:
-      if (lookahead == 'p') ADVANCE(42);
+      if (lookahead == 'N') ADVANCE(112);
+      if (lookahead == 'n') ADVANCE(108);
+      if (lookahead == 'o') ADVANCE(23);
+      if (lookahead == 'p') ADVANCE(110);
       END_STATE();
     case 16:
-      if (lookahead == 'p') ADVANCE(43);
+      if (lookahead == 'o') ADVANCE(88);
       END_STATE();
     case 17:
-      if (lookahead == 'r') ADVANCE(6);
+      if (lookahead == 'i') ADVANCE(97);
       END_STATE();
     case 18:
-      if (lookahead == 'r') ADVANCE(48);
+      if (lookahead == 's') ADVANCE(103);
       END_STATE();
     case 19:
-      if (lookahead == 'r') ADVANCE(7);
+      if (('a' <= lookahead && lookahead <= 'z')) ADVANCE(79);
       END_STATE();
     case 20:
-      if (lookahead == 't') ADVANCE(41);
+      if (lookahead == '?') ADVANCE(75);
+      if (lookahead != 0 &&
+          lookahead != '?') ADVANCE(20);
       END_STATE();
     case 21:
-      if (lookahead == 't') ADVANCE(44);
+      if (lookahead == 'r') ADVANCE(28);
       END_STATE();
     case 22:
-      if (lookahead == 'u') ADVANCE(12);
+      if (lookahead == 'f') ADVANCE(29);
       END_STATE();
     case 23:
-      if (lookahead == 'w') ADVANCE(19);
+      if (lookahead == 'p') ADVANCE(32);
       END_STATE();
     case 24:
-      if (('a' <= lookahead && lookahead <= 'z')) ADVANCE(34);
+      if (lookahead == 'i') ADVANCE(34);
       END_STATE();
     case 25:
-      if (('a' <= lookahead && lookahead <= 'z')) ADVANCE(39);
+      if (lookahead == 'i') ADVANCE(37);
       END_STATE();
     case 26:
-      if (lookahead != 0 &&
-          lookahead != '?') ADVANCE(5);
+      if (lookahead == 'b') ADVANCE(38);
       END_STATE();
     case 27:
-      if (eof) ADVANCE(28);
-      if (lookahead == '\n') ADVANCE(30);
-      if (lookahead == '\r') ADVANCE(1);
-      if (lookahead == '\'') ADVANCE(24);
-      if (lookahead == '?') ADVANCE(26);
-      if (lookahead == 'b') ADVANCE(22);
-      if (lookahead == 'e') ADVANCE(8);
-      if (lookahead == 's') ADVANCE(9);
-      if (lookahead == '\t' ||
-          lookahead == ' ') SKIP(27)
-      if (lookahead == '%' ||
-          lookahead == '.' ||
-          ('0' <= lookahead && lookahead <= '9')) ADVANCE(33);
+      if (lookahead == 'i') ADVANCE(39);
       END_STATE();
     case 28:
-      ACCEPT_TOKEN(ts_builtin_sym_end);
+      if (lookahead == 'i') ADVANCE(40);
       END_STATE();
     case 29:
-      ACCEPT_TOKEN(anon_sym_COLON);
+      if (lookahead == 'f') ADVANCE(41);
       END_STATE();
     case 30:
-      ACCEPT_TOKEN(sym_newline);
+      if (lookahead == 'x') ADVANCE(42);
       END_STATE();
     case 31:
-      ACCEPT_TOKEN(anon_sym_COMMA);
+      if (lookahead == 'x') ADVANCE(43);
       END_STATE();
     case 32:
-      ACCEPT_TOKEN(sym_range_start);
-      if (lookahead == '$') ADVANCE(38);
-      if (lookahead == '%') ADVANCE(33);
-      if (lookahead == '.') ADVANCE(32);
-      if (lookahead == '+' ||
-          lookahead == '-') ADVANCE(35);
-      if (('0' <= lookahead && lookahead <= '9')) ADVANCE(32);
+      if (lookahead == 'e') ADVANCE(106);
       END_STATE();
     case 33:
-      ACCEPT_TOKEN(sym_range_start);
-      if (lookahead == '%' ||
-          lookahead == '.') ADVANCE(33);
-      if (lookahead == '+' ||
-          lookahead == '-') ADVANCE(35);
-      if (('0' <= lookahead && lookahead <= '9')) ADVANCE(33);
+      if (lookahead == 'e') ADVANCE(44);
       END_STATE();
     case 34:
-      ACCEPT_TOKEN(sym_range_start);
-      if (lookahead == '+' ||
-          lookahead == '-') ADVANCE(35);
-      if (('0' <= lookahead && lookahead <= '9')) ADVANCE(35);
+      if (lookahead == 't') ADVANCE(87);
       END_STATE();
     case 35:
-      ACCEPT_TOKEN(sym_range_start);
-      if (('0' <= lookahead && lookahead <= '9')) ADVANCE(35);
+      if (lookahead == 'o') ADVANCE(89);
       END_STATE();
     case 36:
-      ACCEPT_TOKEN(sym_range_end);
-      if (lookahead == '/') ADVANCE(36);
-      if (lookahead == '+' ||
-          lookahead == '-') ADVANCE(37);
-      if (('0' <= lookahead && lookahead <= '9')) ADVANCE(37);
-      if (lookahead != 0 &&
-          lookahead != '\n') ADVANCE(4);
+      if (lookahead == 'h') ADVANCE(45);
       END_STATE();
     case 37:
-      ACCEPT_TOKEN(sym_range_end);
-      if (lookahead == '/') ADVANCE(36);
-      if (('0' <= lookahead && lookahead <= '9')) ADVANCE(37);
-      if (lookahead != 0 &&
-          lookahead != '\n') ADVANCE(4);
+      if (lookahead == 't') ADVANCE(94);
       END_STATE();
     case 38:
-      ACCEPT_TOKEN(sym_range_end);
-      if (lookahead == '$' ||
-          lookahead == '.') ADVANCE(38);
-      if (lookahead == '+' ||
-          lookahead == '-') ADVANCE(40);
-      if (('0' <= lookahead && lookahead <= '9')) ADVANCE(38);
+      if (lookahead == 's') ADVANCE(46);
       END_STATE();
     case 39:
-      ACCEPT_TOKEN(sym_range_end);
-      if (lookahead == '+' ||
-          lookahead == '-') ADVANCE(40);
-      if (('0' <= lookahead && lookahead <= '9')) ADVANCE(40);
+      if (lookahead == 't') ADVANCE(47);
       END_STATE();
     case 40:
-      ACCEPT_TOKEN(sym_range_end);
-      if (('0' <= lookahead && lookahead <= '9')) ADVANCE(40);
+      if (lookahead == 'p') ADVANCE(48);
       END_STATE();
     case 41:
-      ACCEPT_TOKEN(anon_sym_set);
+      if (lookahead == 'e') ADVANCE(49);
       END_STATE();
     case 42:
-      ACCEPT_TOKEN(anon_sym_wrap);
+      if (lookahead == 't') ADVANCE(113);
       END_STATE();
     case 43:
-      ACCEPT_TOKEN(anon_sym_nowrap);
+      if (lookahead == 't') ADVANCE(109);
       END_STATE();
     case 44:
-      ACCEPT_TOKEN(anon_sym_edit);
+      if (lookahead == 'v') ADVANCE(111);
       END_STATE();
     case 45:
-      ACCEPT_TOKEN(aux_sym_edit_token1);
-      if (lookahead == '\n') ADVANCE(30);
-      if (lookahead != 0) ADVANCE(47);
+      if (lookahead == 'l') ADVANCE(50);
       END_STATE();
     case 46:
-      ACCEPT_TOKEN(aux_sym_edit_token1);
-      if (lookahead == '\r') ADVANCE(45);
-      if (lookahead == '\t' ||
-          lookahead == ' ') ADVANCE(46);
-      if (lookahead != 0 &&
-          lookahead != '\n') ADVANCE(47);
+      if (lookahead == 't') ADVANCE(51);
       END_STATE();
     case 47:
-      ACCEPT_TOKEN(aux_sym_edit_token1);
-      if (lookahead != 0 &&
-          lookahead != '\n') ADVANCE(47);
+      if (lookahead == 'e') ADVANCE(92);
       END_STATE();
     case 48:
-      ACCEPT_TOKEN(anon_sym_buffer);
-      if (lookahead == 's') ADVANCE(50);
+      if (lookahead == 'W') ADVANCE(52);
       END_STATE();
     case 49:
-      ACCEPT_TOKEN(aux_sym_buffer_token1);
-      if (('0' <= lookahead && lookahead <= '9')) ADVANCE(49);
+      if (lookahead == 'r') ADVANCE(102);
       END_STATE();
     case 50:
+      if (lookahead == 'i') ADVANCE(53);
+      END_STATE();
+    case 51:
+      if (lookahead == 'i') ADVANCE(54);
+      END_STATE();
+    case 52:
+      if (lookahead == 'h') ADVANCE(55);
+      END_STATE();
+    case 53:
+      if (lookahead == 'g') ADVANCE(56);
+      END_STATE();
+    case 54:
+      if (lookahead == 't') ADVANCE(57);
+      END_STATE();
+    case 55:
+      if (lookahead == 'i') ADVANCE(58);
+      END_STATE();
+    case 56:
+      if (lookahead == 'h') ADVANCE(59);
+      END_STATE();
+    case 57:
+      if (lookahead == 'u') ADVANCE(60);
+      END_STATE();
+    case 58:
+      if (lookahead == 't') ADVANCE(61);
+      END_STATE();
+    case 59:
+      if (lookahead == 't') ADVANCE(98);
+      END_STATE();
+    case 60:
+      if (lookahead == 't') ADVANCE(62);
+      END_STATE();
+    case 61:
+      if (lookahead == 'e') ADVANCE(63);
+      END_STATE();
+    case 62:
+      if (lookahead == 'e') ADVANCE(100);
+      END_STATE();
+    case 63:
+      if (lookahead == 's') ADVANCE(64);
+      END_STATE();
+    case 64:
+      if (lookahead == 'p') ADVANCE(65);
+      END_STATE();
+    case 65:
+      if (lookahead == 'a') ADVANCE(66);
+      END_STATE();
+    case 66:
+      if (lookahead == 'c') ADVANCE(67);
+      END_STATE();
+    case 67:
+      if (lookahead == 'e') ADVANCE(105);
+      END_STATE();
+    case 68:
+      ACCEPT_TOKEN(ts_builtin_sym_end);
+      END_STATE();
+    case 69:
+      ACCEPT_TOKEN(anon_sym_COLON);
+      END_STATE();
+    case 70:
+      ACCEPT_TOKEN(sym_newline);
+      END_STATE();
+    case 71:
+      ACCEPT_TOKEN(anon_sym_COMMA);
+      END_STATE();
+    case 72:
+      ACCEPT_TOKEN(sym_range_start);
+      if (lookahead == '%' ||
+          lookahead == '.') ADVANCE(72);
+      if (lookahead == '+' ||
+          lookahead == '-') ADVANCE(74);
+      if (('0' <= lookahead && lookahead <= '9')) ADVANCE(72);
+      END_STATE();
+    case 73:
+      ACCEPT_TOKEN(sym_range_start);
+      if (lookahead == '$') ADVANCE(76);
+      if (lookahead == '%') ADVANCE(72);
+      if (lookahead == '.') ADVANCE(73);
+      if (lookahead == '+' ||
+          lookahead == '-') ADVANCE(74);
+      if (('0' <= lookahead && lookahead <= '9')) ADVANCE(73);
+      END_STATE();
+    case 74:
+      ACCEPT_TOKEN(sym_range_start);
+      if (('0' <= lookahead && lookahead <= '9')) ADVANCE(74);
+      END_STATE();
+    case 75:
+      ACCEPT_TOKEN(sym_range_start);
+      if (lookahead == '+' ||
+          lookahead == '-') ADVANCE(74);
+      if (('0' <= lookahead && lookahead <= '9')) ADVANCE(74);
+      END_STATE();
+    case 76:
+      ACCEPT_TOKEN(sym_range_end);
+      if (lookahead == '$' ||
+          lookahead == '.') ADVANCE(76);
+      if (lookahead == '+' ||
+          lookahead == '-') ADVANCE(77);
+      if (('0' <= lookahead && lookahead <= '9')) ADVANCE(76);
+      END_STATE();
+    case 77:
+      ACCEPT_TOKEN(sym_range_end);
+      if (('0' <= lookahead && lookahead <= '9')) ADVANCE(77);
+      END_STATE();
+    case 78:
+      ACCEPT_TOKEN(sym_range_end);
+      if (lookahead == '/') ADVANCE(78);
+      if (lookahead == '+' ||
+          lookahead == '-') ADVANCE(80);
+      if (('0' <= lookahead && lookahead <= '9')) ADVANCE(80);
+      if (lookahead != 0 &&
+          lookahead != '\n' &&
+          lookahead != '+' &&
+          lookahead != '-' &&
+          (lookahead < '/' || '9' < lookahead)) ADVANCE(12);
+      END_STATE();
+    case 79:
+      ACCEPT_TOKEN(sym_range_end);
+      if (lookahead == '+' ||
+          lookahead == '-') ADVANCE(77);
+      if (('0' <= lookahead && lookahead <= '9')) ADVANCE(77);
+      END_STATE();
+    case 80:
+      ACCEPT_TOKEN(sym_range_end);
+      if (lookahead == '/') ADVANCE(78);
+      if (('0' <= lookahead && lookahead <= '9')) ADVANCE(80);
+      if (lookahead != 0 &&
+          lookahead != '\n' &&
+          (lookahead < '/' || '9' < lookahead)) ADVANCE(12);
+      END_STATE();
+    case 81:
+      ACCEPT_TOKEN(anon_sym_se);
+      if (lookahead == 't') ADVANCE(82);
+      END_STATE();
+    case 82:
+      ACCEPT_TOKEN(anon_sym_set);
+      END_STATE();
+    case 83:
+      ACCEPT_TOKEN(aux_sym_set_token1);
+      if (lookahead == '\n') ADVANCE(70);
+      if (lookahead == '\r') ADVANCE(85);
+      if (lookahead != 0 &&
+          lookahead != '\n' &&
+          lookahead != '\r') ADVANCE(84);
+      END_STATE();
+    case 84:
+      ACCEPT_TOKEN(aux_sym_set_token1);
+      if (lookahead != 0 &&
+          lookahead != '\n') ADVANCE(84);
+      END_STATE();
+    case 85:
+      ACCEPT_TOKEN(aux_sym_set_token1);
+      if (lookahead == '\n') ADVANCE(70);
+      if (lookahead != 0 &&
+          lookahead != '\n') ADVANCE(84);
+      END_STATE();
+    case 86:
+      ACCEPT_TOKEN(anon_sym_e);
+      if (lookahead == 'd') ADVANCE(24);
+      END_STATE();
+    case 87:
+      ACCEPT_TOKEN(anon_sym_edit);
+      END_STATE();
+    case 88:
+      ACCEPT_TOKEN(anon_sym_go);
+      if (lookahead == 't') ADVANCE(35);
+      END_STATE();
+    case 89:
+      ACCEPT_TOKEN(anon_sym_goto);
+      END_STATE();
+    case 90:
+      ACCEPT_TOKEN(aux_sym_goto_token1);
+      if (('0' <= lookahead && lookahead <= '9')) ADVANCE(90);
+      END_STATE();
+    case 91:
+      ACCEPT_TOKEN(anon_sym_w);
+      if (lookahead == 'q') ADVANCE(96);
+      if (lookahead == 'r') ADVANCE(27);
+      END_STATE();
+    case 92:
+      ACCEPT_TOKEN(anon_sym_write);
+      END_STATE();
+    case 93:
+      ACCEPT_TOKEN(anon_sym_q);
+      if (lookahead == 'u') ADVANCE(25);
+      END_STATE();
+    case 94:
+      ACCEPT_TOKEN(anon_sym_quit);
+      END_STATE();
+    case 95:
+      ACCEPT_TOKEN(anon_sym_BANG);
+      END_STATE();
+    case 96:
+      ACCEPT_TOKEN(sym_write_quit);
+      END_STATE();
+    case 97:
+      ACCEPT_TOKEN(anon_sym_hi);
+      if (lookahead == 'g') ADVANCE(36);
+      END_STATE();
+    case 98:
+      ACCEPT_TOKEN(anon_sym_highlight);
+      END_STATE();
+    case 99:
+      ACCEPT_TOKEN(anon_sym_s);
+      if (lookahead == 'e') ADVANCE(81);
+      if (lookahead == 'u') ADVANCE(26);
+      END_STATE();
+    case 100:
+      ACCEPT_TOKEN(anon_sym_substitute);
+      END_STATE();
+    case 101:
+      ACCEPT_TOKEN(anon_sym_b);
+      if (lookahead == 'u') ADVANCE(22);
+      END_STATE();
+    case 102:
+      ACCEPT_TOKEN(anon_sym_buffer);
+      if (lookahead == 's') ADVANCE(104);
+      END_STATE();
+    case 103:
+      ACCEPT_TOKEN(anon_sym_ls);
+      END_STATE();
+    case 104:
       ACCEPT_TOKEN(anon_sym_buffers);
       END_STATE();
+    case 105:
+      ACCEPT_TOKEN(anon_sym_StripWhitespace);
+      END_STATE();
+    case 106:
+      ACCEPT_TOKEN(anon_sym_cope);
+      if (lookahead == 'n') ADVANCE(107);
+      END_STATE();
+    case 107:
+      ACCEPT_TOKEN(anon_sym_copen);
+      END_STATE();
+    case 108:
+      ACCEPT_TOKEN(anon_sym_cn);
+      if (lookahead == 'e') ADVANCE(31);
+      END_STATE();
+    case 109:
+      ACCEPT_TOKEN(anon_sym_cnext);
+      END_STATE();
+    case 110:
+      ACCEPT_TOKEN(anon_sym_cp);
+      if (lookahead == 'r') ADVANCE(33);
+      END_STATE();
+    case 111:
+      ACCEPT_TOKEN(anon_sym_cprev);
+      END_STATE();
+    case 112:
+      ACCEPT_TOKEN(anon_sym_cN);
+      if (lookahead == 'e') ADVANCE(30);
+      END_STATE();
+    case 113:
+      ACCEPT_TOKEN(anon_sym_cNext);
+      END_STATE();
     default:
       return false;
   }
@@ -438,24 +929,41 @@ static bool ts_lex(TSLexer *lexer, TSStateId state) {
 
 static TSLexMode ts_lex_modes[STATE_COUNT] = {
   [0] = {.lex_state = 0},
-  [1] = {.lex_state = 0},
-  [2] = {.lex_state = 27},
-  [3] = {.lex_state = 0},
-  [4] = {.lex_state = 0},
-  [5] = {.lex_state = 0},
-  [6] = {.lex_state = 0},
-  [7] = {.lex_state = 0},
-  [8] = {.lex_state = 46},
-  [9] = {.lex_state = 3},
-  [10] = {.lex_state = 0},
-  [11] = {.lex_state = 0},
-  [12] = {.lex_state = 0},
-  [13] = {.lex_state = 2},
-  [14] = {.lex_state = 0},
-  [15] = {.lex_state = 0},
-  [16] = {.lex_state = 0},
-  [17] = {.lex_state = 0},
-  [18] = {.lex_state = 0},
+  [1] = {.lex_state = 1},
+  [2] = {.lex_state = 2},
+  [3] = {.lex_state = 3},
+  [4] = {.lex_state = 4},
+  [5] = {.lex_state = 3},
+  [6] = {.lex_state = 5},
+  [7] = {.lex_state = 6},
+  [8] = {.lex_state = 7},
+  [9] = {.lex_state = 83},
+  [10] = {.lex_state = 83},
+  [11] = {.lex_state = 83},
+  [12] = {.lex_state = 7},
+  [13] = {.lex_state = 83},
+  [14] = {.lex_state = 83},
+  [15] = {.lex_state = 8},
+  [16] = {.lex_state = 7},
+  [17] = {.lex_state = 83},
+  [18] = {.lex_state = 83},
+  [19] = {.lex_state = 83},
+  [20] = {.lex_state = 83},
+  [21] = {.lex_state = 7},
+  [22] = {.lex_state = 9},
+  [23] = {.lex_state = 7},
+  [24] = {.lex_state = 7},
+  [25] = {.lex_state = 7},
+  [26] = {.lex_state = 7},
+  [27] = {.lex_state = 7},
+  [28] = {.lex_state = 7},
+  [29] = {.lex_state = 7},
+  [30] = {.lex_state = 7},
+  [31] = {.lex_state = 7},
+  [32] = {.lex_state = 7},
+  [33] = {.lex_state = 7},
+  [34] = {.lex_state = 7},
+  [35] = {.lex_state = 7},
 };
 
 static uint16_t ts_parse_table[LARGE_STATE_COUNT][SYMBOL_COUNT] = {
@@ -466,166 +974,391 @@ static uint16_t ts_parse_table[LARGE_STATE_COUNT][SYMBOL_COUNT] = {
     [anon_sym_COMMA] = ACTIONS(1),
     [sym_range_start] = ACTIONS(1),
     [sym_range_end] = ACTIONS(1),
+    [anon_sym_se] = ACTIONS(1),
     [anon_sym_set] = ACTIONS(1),
-    [anon_sym_wrap] = ACTIONS(1),
-    [anon_sym_nowrap] = ACTIONS(1),
+    [anon_sym_e] = ACTIONS(1),
     [anon_sym_edit] = ACTIONS(1),
+    [anon_sym_go] = ACTIONS(1),
+    [anon_sym_goto] = ACTIONS(1),
+    [aux_sym_goto_token1] = ACTIONS(1),
+    [anon_sym_w] = ACTIONS(1),
+    [anon_sym_write] = ACTIONS(1),
+    [anon_sym_q] = ACTIONS(1),
+    [anon_sym_quit] = ACTIONS(1),
+    [anon_sym_BANG] = ACTIONS(1),
+    [sym_write_quit] = ACTIONS(1),
+    [anon_sym_hi] = ACTIONS(1),
+    [anon_sym_highlight] = ACTIONS(1),
+    [anon_sym_s] = ACTIONS(1),
+    [anon_sym_substitute] = ACTIONS(1),
+    [anon_sym_b] = ACTIONS(1),
     [anon_sym_buffer] = ACTIONS(1),
-    [aux_sym_buffer_token1] = ACTIONS(1),
+    [anon_sym_ls] = ACTIONS(1),
     [anon_sym_buffers] = ACTIONS(1),
+    [anon_sym_StripWhitespace] = ACTIONS(1),
+    [anon_sym_cope] = ACTIONS(1),
+    [anon_sym_copen] = ACTIONS(1),
+    [anon_sym_cn] = ACTIONS(1),
+    [anon_sym_cnext] = ACTIONS(1),
+    [anon_sym_cp] = ACTIONS(1),
+    [anon_sym_cprev] = ACTIONS(1),
+    [anon_sym_cN] = ACTIONS(1),
+    [anon_sym_cNext] = ACTIONS(1),
   },
   [1] = {
-    [sym_s] = STATE(7),
+    [sym_s] = STATE(8),
     [anon_sym_COLON] = ACTIONS(5),
     [sym_newline] = ACTIONS(3),
   },
   [2] = {
-    [sym_cmd] = STATE(11),
+    [sym_cmd] = STATE(21),
     [sym_range] = STATE(3),
     [sym_set] = STATE(12),
     [sym_edit] = STATE(12),
+    [sym_goto] = STATE(12),
+    [sym_write] = STATE(12),
+    [sym_quit] = STATE(12),
+    [sym_highlight] = STATE(12),
+    [sym_substitute] = STATE(12),
     [sym_buffer] = STATE(12),
     [sym_buffers] = STATE(12),
+    [sym_strip_whitespace] = STATE(12),
+    [sym_copen] = STATE(12),
+    [sym_cnext] = STATE(12),
+    [sym_cprev] = STATE(12),
     [ts_builtin_sym_end] = ACTIONS(7),
     [sym_newline] = ACTIONS(3),
     [sym_range_start] = ACTIONS(9),
-    [anon_sym_set] = ACTIONS(11),
-    [anon_sym_edit] = ACTIONS(13),
-    [anon_sym_buffer] = ACTIONS(15),
-    [anon_sym_buffers] = ACTIONS(17),
+    [anon_sym_se] = ACTIONS(11),
+    [anon_sym_set] = ACTIONS(13),
+    [anon_sym_e] = ACTIONS(15),
+    [anon_sym_edit] = ACTIONS(17),
+    [anon_sym_go] = ACTIONS(19),
+    [anon_sym_goto] = ACTIONS(21),
+    [anon_sym_w] = ACTIONS(23),
+    [anon_sym_write] = ACTIONS(25),
+    [anon_sym_q] = ACTIONS(27),
+    [anon_sym_quit] = ACTIONS(29),
+    [sym_write_quit] = ACTIONS(31),
+    [anon_sym_hi] = ACTIONS(33),
+    [anon_sym_highlight] = ACTIONS(35),
+    [anon_sym_s] = ACTIONS(37),
+    [anon_sym_substitute] = ACTIONS(39),
+    [anon_sym_b] = ACTIONS(41),
+    [anon_sym_buffer] = ACTIONS(41),
+    [anon_sym_ls] = ACTIONS(43),
+    [anon_sym_buffers] = ACTIONS(43),
+    [anon_sym_StripWhitespace] = ACTIONS(45),
+    [anon_sym_cope] = ACTIONS(47),
+    [anon_sym_copen] = ACTIONS(49),
+    [anon_sym_cn] = ACTIONS(51),
+    [anon_sym_cnext] = ACTIONS(53),
+    [anon_sym_cp] = ACTIONS(55),
+    [anon_sym_cprev] = ACTIONS(57),
+    [anon_sym_cN] = ACTIONS(55),
+    [anon_sym_cNext] = ACTIONS(57),
+  },
+  [3] = {
+    [sym_cmd] = STATE(35),
+    [sym_set] = STATE(12),
+    [sym_edit] = STATE(12),
+    [sym_goto] = STATE(12),
+    [sym_write] = STATE(12),
+    [sym_quit] = STATE(12),
+    [sym_highlight] = STATE(12),
+    [sym_substitute] = STATE(12),
+    [sym_buffer] = STATE(12),
+    [sym_buffers] = STATE(12),
+    [sym_strip_whitespace] = STATE(12),
+    [sym_copen] = STATE(12),
+    [sym_cnext] = STATE(12),
+    [sym_cprev] = STATE(12),
+    [ts_builtin_sym_end] = ACTIONS(59),
+    [sym_newline] = ACTIONS(3),
+    [anon_sym_se] = ACTIONS(11),
+    [anon_sym_set] = ACTIONS(13),
+    [anon_sym_e] = ACTIONS(15),
+    [anon_sym_edit] = ACTIONS(17),
+    [anon_sym_go] = ACTIONS(19),
+    [anon_sym_goto] = ACTIONS(21),
+    [anon_sym_w] = ACTIONS(23),
+    [anon_sym_write] = ACTIONS(25),
+    [anon_sym_q] = ACTIONS(27),
+    [anon_sym_quit] = ACTIONS(29),
+    [sym_write_quit] = ACTIONS(31),
+    [anon_sym_hi] = ACTIONS(33),
+    [anon_sym_highlight] = ACTIONS(35),
+    [anon_sym_s] = ACTIONS(37),
+    [anon_sym_substitute] = ACTIONS(39),
+    [anon_sym_b] = ACTIONS(41),
+    [anon_sym_buffer] = ACTIONS(41),
+    [anon_sym_ls] = ACTIONS(43),
+    [anon_sym_buffers] = ACTIONS(43),
+    [anon_sym_StripWhitespace] = ACTIONS(45),
+    [anon_sym_cope] = ACTIONS(47),
+    [anon_sym_copen] = ACTIONS(49),
+    [anon_sym_cn] = ACTIONS(51),
+    [anon_sym_cnext] = ACTIONS(53),
+    [anon_sym_cp] = ACTIONS(55),
+    [anon_sym_cprev] = ACTIONS(57),
+    [anon_sym_cN] = ACTIONS(55),
+    [anon_sym_cNext] = ACTIONS(57),
+  },
+  [4] = {
+    [ts_builtin_sym_end] = ACTIONS(61),
+    [sym_newline] = ACTIONS(3),
+    [anon_sym_COMMA] = ACTIONS(63),
+    [anon_sym_se] = ACTIONS(65),
+    [anon_sym_set] = ACTIONS(61),
+    [anon_sym_e] = ACTIONS(65),
+    [anon_sym_edit] = ACTIONS(61),
+    [anon_sym_go] = ACTIONS(65),
+    [anon_sym_goto] = ACTIONS(61),
+    [anon_sym_w] = ACTIONS(65),
+    [anon_sym_write] = ACTIONS(61),
+    [anon_sym_q] = ACTIONS(65),
+    [anon_sym_quit] = ACTIONS(61),
+    [sym_write_quit] = ACTIONS(61),
+    [anon_sym_hi] = ACTIONS(65),
+    [anon_sym_highlight] = ACTIONS(61),
+    [anon_sym_s] = ACTIONS(65),
+    [anon_sym_substitute] = ACTIONS(61),
+    [anon_sym_b] = ACTIONS(65),
+    [anon_sym_buffer] = ACTIONS(65),
+    [anon_sym_ls] = ACTIONS(61),
+    [anon_sym_buffers] = ACTIONS(61),
+    [anon_sym_StripWhitespace] = ACTIONS(61),
+    [anon_sym_cope] = ACTIONS(65),
+    [anon_sym_copen] = ACTIONS(61),
+    [anon_sym_cn] = ACTIONS(65),
+    [anon_sym_cnext] = ACTIONS(61),
+    [anon_sym_cp] = ACTIONS(65),
+    [anon_sym_cprev] = ACTIONS(61),
+    [anon_sym_cN] = ACTIONS(65),
+    [anon_sym_cNext] = ACTIONS(61),
+  },
+  [5] = {
+    [ts_builtin_sym_end] = ACTIONS(67),
+    [sym_newline] = ACTIONS(3),
+    [anon_sym_se] = ACTIONS(69),
+    [anon_sym_set] = ACTIONS(67),
+    [anon_sym_e] = ACTIONS(69),
+    [anon_sym_edit] = ACTIONS(67),
+    [anon_sym_go] = ACTIONS(69),
+    [anon_sym_goto] = ACTIONS(67),
+    [anon_sym_w] = ACTIONS(69),
+    [anon_sym_write] = ACTIONS(67),
+    [anon_sym_q] = ACTIONS(69),
+    [anon_sym_quit] = ACTIONS(67),
+    [sym_write_quit] = ACTIONS(67),
+    [anon_sym_hi] = ACTIONS(69),
+    [anon_sym_highlight] = ACTIONS(67),
+    [anon_sym_s] = ACTIONS(69),
+    [anon_sym_substitute] = ACTIONS(67),
+    [anon_sym_b] = ACTIONS(69),
+    [anon_sym_buffer] = ACTIONS(69),
+    [anon_sym_ls] = ACTIONS(67),
+    [anon_sym_buffers] = ACTIONS(67),
+    [anon_sym_StripWhitespace] = ACTIONS(67),
+    [anon_sym_cope] = ACTIONS(69),
+    [anon_sym_copen] = ACTIONS(67),
+    [anon_sym_cn] = ACTIONS(69),
+    [anon_sym_cnext] = ACTIONS(67),
+    [anon_sym_cp] = ACTIONS(69),
+    [anon_sym_cprev] = ACTIONS(67),
+    [anon_sym_cN] = ACTIONS(69),
+    [anon_sym_cNext] = ACTIONS(67),
   },
 };
 
 static uint16_t ts_small_parse_table[] = {
-  [0] = 8,
+  [0] = 3,
+    ACTIONS(3), 1,
+      sym_newline,
+    ACTIONS(71), 1,
+      ts_builtin_sym_end,
+    ACTIONS(73), 1,
+      aux_sym_goto_token1,
+  [10] = 3,
     ACTIONS(3), 1,
       sym_newline,
-    ACTIONS(11), 1,
-      anon_sym_set,
-    ACTIONS(13), 1,
-      anon_sym_edit,
-    ACTIONS(15), 1,
-      anon_sym_buffer,
-    ACTIONS(17), 1,
-      anon_sym_buffers,
-    ACTIONS(19), 1,
+    ACTIONS(75), 1,
       ts_builtin_sym_end,
-    STATE(18), 1,
-      sym_cmd,
-    STATE(12), 4,
-      sym_set,
-      sym_edit,
-      sym_buffer,
-      sym_buffers,
-  [28] = 4,
+    ACTIONS(77), 1,
+      anon_sym_BANG,
+  [20] = 2,
     ACTIONS(3), 1,
       sym_newline,
-    ACTIONS(23), 1,
-      anon_sym_COMMA,
-    ACTIONS(25), 1,
-      anon_sym_buffer,
-    ACTIONS(21), 4,
+    ACTIONS(79), 1,
       ts_builtin_sym_end,
-      anon_sym_set,
-      anon_sym_edit,
-      anon_sym_buffers,
-  [44] = 3,
+  [27] = 2,
+    ACTIONS(81), 1,
+      sym_newline,
+    ACTIONS(83), 1,
+      aux_sym_set_token1,
+  [34] = 2,
+    ACTIONS(81), 1,
+      sym_newline,
+    ACTIONS(85), 1,
+      aux_sym_set_token1,
+  [41] = 2,
+    ACTIONS(81), 1,
+      sym_newline,
+    ACTIONS(87), 1,
+      aux_sym_set_token1,
+  [48] = 2,
     ACTIONS(3), 1,
       sym_newline,
-    ACTIONS(29), 1,
-      anon_sym_buffer,
-    ACTIONS(27), 4,
+    ACTIONS(89), 1,
       ts_builtin_sym_end,
-      anon_sym_set,
-      anon_sym_edit,
-      anon_sym_buffers,
-  [57] = 3,
+  [55] = 2,
+    ACTIONS(81), 1,
+      sym_newline,
+    ACTIONS(91), 1,
+      aux_sym_set_token1,
+  [62] = 2,
+    ACTIONS(81), 1,
+      sym_newline,
+    ACTIONS(93), 1,
+      aux_sym_set_token1,
+  [69] = 2,
     ACTIONS(3), 1,
       sym_newline,
-    STATE(15), 1,
-      sym_config_param,
-    ACTIONS(31), 2,
-      anon_sym_wrap,
-      anon_sym_nowrap,
-  [68] = 2,
+    ACTIONS(95), 1,
+      aux_sym_goto_token1,
+  [76] = 2,
     ACTIONS(3), 1,
       sym_newline,
-    ACTIONS(33), 1,
+    ACTIONS(97), 1,
       ts_builtin_sym_end,
-  [75] = 2,
-    ACTIONS(35), 1,
+  [83] = 2,
+    ACTIONS(81), 1,
       sym_newline,
-    ACTIONS(37), 1,
-      aux_sym_edit_token1,
-  [82] = 2,
+    ACTIONS(99), 1,
+      aux_sym_set_token1,
+  [90] = 2,
+    ACTIONS(81), 1,
+      sym_newline,
+    ACTIONS(101), 1,
+      aux_sym_set_token1,
+  [97] = 2,
+    ACTIONS(81), 1,
+      sym_newline,
+    ACTIONS(103), 1,
+      aux_sym_set_token1,
+  [104] = 2,
+    ACTIONS(81), 1,
+      sym_newline,
+    ACTIONS(105), 1,
+      aux_sym_set_token1,
+  [111] = 2,
     ACTIONS(3), 1,
       sym_newline,
-    ACTIONS(39), 1,
-      aux_sym_buffer_token1,
-  [89] = 2,
+    ACTIONS(59), 1,
+      ts_builtin_sym_end,
+  [118] = 2,
     ACTIONS(3), 1,
       sym_newline,
-    ACTIONS(41), 1,
+    ACTIONS(107), 1,
+      sym_range_end,
+  [125] = 2,
+    ACTIONS(3), 1,
+      sym_newline,
+    ACTIONS(109), 1,
       ts_builtin_sym_end,
-  [96] = 2,
+  [132] = 2,
     ACTIONS(3), 1,
       sym_newline,
-    ACTIONS(19), 1,
+    ACTIONS(111), 1,
       ts_builtin_sym_end,
-  [103] = 2,
+  [139] = 2,
     ACTIONS(3), 1,
       sym_newline,
-    ACTIONS(43), 1,
+    ACTIONS(113), 1,
       ts_builtin_sym_end,
-  [110] = 2,
+  [146] = 2,
     ACTIONS(3), 1,
       sym_newline,
-    ACTIONS(45), 1,
-      sym_range_end,
-  [117] = 2,
+    ACTIONS(115), 1,
+      ts_builtin_sym_end,
+  [153] = 2,
+    ACTIONS(3), 1,
+      sym_newline,
+    ACTIONS(117), 1,
+      ts_builtin_sym_end,
+  [160] = 2,
+    ACTIONS(3), 1,
+      sym_newline,
+    ACTIONS(119), 1,
+      ts_builtin_sym_end,
+  [167] = 2,
+    ACTIONS(3), 1,
+      sym_newline,
+    ACTIONS(121), 1,
+      ts_builtin_sym_end,
+  [174] = 2,
+    ACTIONS(3), 1,
+      sym_newline,
+    ACTIONS(123), 1,
+      ts_builtin_sym_end,
+  [181] = 2,
     ACTIONS(3), 1,
       sym_newline,
-    ACTIONS(47), 1,
+    ACTIONS(125), 1,
       ts_builtin_sym_end,
-  [124] = 2,
+  [188] = 2,
     ACTIONS(3), 1,
       sym_newline,
-    ACTIONS(49), 1,
+    ACTIONS(127), 1,
       ts_builtin_sym_end,
-  [131] = 2,
+  [195] = 2,
     ACTIONS(3), 1,
       sym_newline,
-    ACTIONS(51), 1,
+    ACTIONS(129), 1,
       ts_builtin_sym_end,
-  [138] = 2,
+  [202] = 2,
     ACTIONS(3), 1,
       sym_newline,
-    ACTIONS(53), 1,
+    ACTIONS(131), 1,
       ts_builtin_sym_end,
-  [145] = 2,
+  [209] = 2,
     ACTIONS(3), 1,
       sym_newline,
-    ACTIONS(55), 1,
+    ACTIONS(133), 1,
       ts_builtin_sym_end,
 };
 
 static uint32_t ts_small_parse_table_map[] = {
-  [SMALL_STATE(3)] = 0,
-  [SMALL_STATE(4)] = 28,
-  [SMALL_STATE(5)] = 44,
-  [SMALL_STATE(6)] = 57,
-  [SMALL_STATE(7)] = 68,
-  [SMALL_STATE(8)] = 75,
-  [SMALL_STATE(9)] = 82,
-  [SMALL_STATE(10)] = 89,
-  [SMALL_STATE(11)] = 96,
-  [SMALL_STATE(12)] = 103,
-  [SMALL_STATE(13)] = 110,
-  [SMALL_STATE(14)] = 117,
-  [SMALL_STATE(15)] = 124,
-  [SMALL_STATE(16)] = 131,
-  [SMALL_STATE(17)] = 138,
-  [SMALL_STATE(18)] = 145,
+  [SMALL_STATE(6)] = 0,
+  [SMALL_STATE(7)] = 10,
+  [SMALL_STATE(8)] = 20,
+  [SMALL_STATE(9)] = 27,
+  [SMALL_STATE(10)] = 34,
+  [SMALL_STATE(11)] = 41,
+  [SMALL_STATE(12)] = 48,
+  [SMALL_STATE(13)] = 55,
+  [SMALL_STATE(14)] = 62,
+  [SMALL_STATE(15)] = 69,
+  [SMALL_STATE(16)] = 76,
+  [SMALL_STATE(17)] = 83,
+  [SMALL_STATE(18)] = 90,
+  [SMALL_STATE(19)] = 97,
+  [SMALL_STATE(20)] = 104,
+  [SMALL_STATE(21)] = 111,
+  [SMALL_STATE(22)] = 118,
+  [SMALL_STATE(23)] = 125,
+  [SMALL_STATE(24)] = 132,
+  [SMALL_STATE(25)] = 139,
+  [SMALL_STATE(26)] = 146,
+  [SMALL_STATE(27)] = 153,
+  [SMALL_STATE(28)] = 160,
+  [SMALL_STATE(29)] = 167,
+  [SMALL_STATE(30)] = 174,
+  [SMALL_STATE(31)] = 181,
+  [SMALL_STATE(32)] = 188,
+  [SMALL_STATE(33)] = 195,
+  [SMALL_STATE(34)] = 202,
+  [SMALL_STATE(35)] = 209,
 };
 
 static TSParseActionEntry ts_parse_actions[] = {
@@ -635,29 +1368,68 @@ static TSParseActionEntry ts_parse_actions[] = {
   [5] = {.entry = {.count = 1, .reusable = true}}, SHIFT(2),
   [7] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_s, 1),
   [9] = {.entry = {.count = 1, .reusable = true}}, SHIFT(4),
-  [11] = {.entry = {.count = 1, .reusable = true}}, SHIFT(6),
-  [13] = {.entry = {.count = 1, .reusable = true}}, SHIFT(8),
-  [15] = {.entry = {.count = 1, .reusable = false}}, SHIFT(9),
+  [11] = {.entry = {.count = 1, .reusable = false}}, SHIFT(9),
+  [13] = {.entry = {.count = 1, .reusable = true}}, SHIFT(9),
+  [15] = {.entry = {.count = 1, .reusable = false}}, SHIFT(10),
   [17] = {.entry = {.count = 1, .reusable = true}}, SHIFT(10),
-  [19] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_s, 2),
-  [21] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_range, 1),
-  [23] = {.entry = {.count = 1, .reusable = true}}, SHIFT(13),
-  [25] = {.entry = {.count = 1, .reusable = false}}, REDUCE(sym_range, 1),
-  [27] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_range, 3),
-  [29] = {.entry = {.count = 1, .reusable = false}}, REDUCE(sym_range, 3),
-  [31] = {.entry = {.count = 1, .reusable = true}}, SHIFT(14),
-  [33] = {.entry = {.count = 1, .reusable = true}},  ACCEPT_INPUT(),
-  [35] = {.entry = {.count = 1, .reusable = false}}, SHIFT_EXTRA(),
-  [37] = {.entry = {.count = 1, .reusable = false}}, SHIFT(16),
-  [39] = {.entry = {.count = 1, .reusable = true}}, SHIFT(17),
-  [41] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_buffers, 1),
-  [43] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_cmd, 1),
-  [45] = {.entry = {.count = 1, .reusable = true}}, SHIFT(5),
-  [47] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_config_param, 1),
-  [49] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_set, 2),
-  [51] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_edit, 2),
-  [53] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_buffer, 2),
-  [55] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_s, 3),
+  [19] = {.entry = {.count = 1, .reusable = false}}, SHIFT(6),
+  [21] = {.entry = {.count = 1, .reusable = true}}, SHIFT(6),
+  [23] = {.entry = {.count = 1, .reusable = false}}, SHIFT(11),
+  [25] = {.entry = {.count = 1, .reusable = true}}, SHIFT(11),
+  [27] = {.entry = {.count = 1, .reusable = false}}, SHIFT(7),
+  [29] = {.entry = {.count = 1, .reusable = true}}, SHIFT(7),
+  [31] = {.entry = {.count = 1, .reusable = true}}, SHIFT(12),
+  [33] = {.entry = {.count = 1, .reusable = false}}, SHIFT(13),
+  [35] = {.entry = {.count = 1, .reusable = true}}, SHIFT(13),
+  [37] = {.entry = {.count = 1, .reusable = false}}, SHIFT(14),
+  [39] = {.entry = {.count = 1, .reusable = true}}, SHIFT(14),
+  [41] = {.entry = {.count = 1, .reusable = false}}, SHIFT(15),
+  [43] = {.entry = {.count = 1, .reusable = true}}, SHIFT(16),
+  [45] = {.entry = {.count = 1, .reusable = true}}, SHIFT(17),
+  [47] = {.entry = {.count = 1, .reusable = false}}, SHIFT(18),
+  [49] = {.entry = {.count = 1, .reusable = true}}, SHIFT(18),
+  [51] = {.entry = {.count = 1, .reusable = false}}, SHIFT(19),
+  [53] = {.entry = {.count = 1, .reusable = true}}, SHIFT(19),
+  [55] = {.entry = {.count = 1, .reusable = false}}, SHIFT(20),
+  [57] = {.entry = {.count = 1, .reusable = true}}, SHIFT(20),
+  [59] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_s, 2),
+  [61] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_range, 1),
+  [63] = {.entry = {.count = 1, .reusable = true}}, SHIFT(22),
+  [65] = {.entry = {.count = 1, .reusable = false}}, REDUCE(sym_range, 1),
+  [67] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_range, 3),
+  [69] = {.entry = {.count = 1, .reusable = false}}, REDUCE(sym_range, 3),
+  [71] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_goto, 1),
+  [73] = {.entry = {.count = 1, .reusable = true}}, SHIFT(25),
+  [75] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_quit, 1),
+  [77] = {.entry = {.count = 1, .reusable = true}}, SHIFT(27),
+  [79] = {.entry = {.count = 1, .reusable = true}},  ACCEPT_INPUT(),
+  [81] = {.entry = {.count = 1, .reusable = false}}, SHIFT_EXTRA(),
+  [83] = {.entry = {.count = 1, .reusable = false}}, SHIFT(23),
+  [85] = {.entry = {.count = 1, .reusable = false}}, SHIFT(24),
+  [87] = {.entry = {.count = 1, .reusable = false}}, SHIFT(26),
+  [89] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_cmd, 1),
+  [91] = {.entry = {.count = 1, .reusable = false}}, SHIFT(28),
+  [93] = {.entry = {.count = 1, .reusable = false}}, SHIFT(29),
+  [95] = {.entry = {.count = 1, .reusable = true}}, SHIFT(30),
+  [97] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_buffers, 1),
+  [99] = {.entry = {.count = 1, .reusable = false}}, SHIFT(31),
+  [101] = {.entry = {.count = 1, .reusable = false}}, SHIFT(32),
+  [103] = {.entry = {.count = 1, .reusable = false}}, SHIFT(33),
+  [105] = {.entry = {.count = 1, .reusable = false}}, SHIFT(34),
+  [107] = {.entry = {.count = 1, .reusable = true}}, SHIFT(5),
+  [109] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_set, 2),
+  [111] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_edit, 2),
+  [113] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_goto, 2),
+  [115] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_write, 2),
+  [117] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_quit, 2),
+  [119] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_highlight, 2),
+  [121] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_substitute, 2),
+  [123] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_buffer, 2),
+  [125] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_strip_whitespace, 2),
+  [127] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_copen, 2),
+  [129] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_cnext, 2),
+  [131] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_cprev, 2),
+  [133] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_s, 3),
 };
 
 #ifdef __cplusplus
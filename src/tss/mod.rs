@@ -13,7 +13,7 @@ use std::{
 use crate::{
     buffer::Buffer,
     colors::{ColorScheme, Highlight},
-    term, Error, Result,
+    syntax, term, Error, Result,
 };
 
 /// Ted style sheet for `toml` format.
@@ -29,9 +29,11 @@ macro_rules! wrap_edge {
     ($edge:expr, $varn:ident) => {{
         *$edge = match mem::replace($edge, Default::default()) {
             e @ Edge::Kind(_) => Edge::$varn(Box::new(e.clone())),
+            e @ Edge::Field(_) => Edge::$varn(Box::new(e.clone())),
+            e @ Edge::KindField(_, _) => Edge::$varn(Box::new(e.clone())),
             _ => err_at!(Fatal, msg: format!("unexpected wrap_edge"))?,
         };
-        Ok(())
+        Ok::<(), Error>(())
     }};
 }
 
@@ -41,6 +43,7 @@ extern "C" {
 
 pub struct Token {
     pub kind: String,
+    pub field: Option<String>, // field name of the node, within its parent
     pub depth: usize,
     pub sibling: usize,
     pub a: usize, // charactor position, inclusive
@@ -51,20 +54,28 @@ impl fmt::Display for Token {
     fn fmt(&self, f: &mut fmt::Formatter) -> result::Result<(), fmt::Error> {
         write!(
             f,
-            "Token<{},{},{},{}..{}>",
-            self.kind, self.depth, self.sibling, self.a, self.z
+            "Token<{},{:?},{},{},{}..{}>",
+            self.kind, self.field, self.depth, self.sibling, self.a, self.z
         )
     }
 }
 
 impl Token {
-    pub fn from_node(buf: &Buffer, nd: &ts::Node, d: usize, s: usize) -> Token {
+    pub fn from_node(
+        buf: &Buffer,
+        nd: &ts::Node,
+        field: Option<&str>,
+        d: usize,
+        s: usize,
+    ) -> Token {
         let kind = nd.kind().to_string();
+        let field = field.map(|f| f.to_string());
         let a = buf.byte_to_char(nd.start_byte());
         let z = buf.byte_to_char(nd.end_byte());
         // trace!("{:?} {} {}", nd, nd.start_byte(), nd.end_byte());
         Token {
             kind,
+            field,
             depth: d,
             sibling: s,
             a,
@@ -129,7 +140,11 @@ pub struct Automata {
     name: String,
     patterns: Vec<Rc<Node>>,
     patterns_trie: KindTrie,
+    // patterns starting with field selector, offset into `patterns`.
+    patterns_field: Vec<usize>,
     open_nodes: Vec<Node>,
+    // default style for segments not matched by any rule.
+    canvas: term::Style,
 }
 
 impl fmt::Display for Automata {
@@ -162,6 +177,7 @@ impl Automata {
         let mut tc = root.walk();
         let mut patterns = vec![];
         let mut kinds = vec![];
+        let mut patterns_field = vec![];
         for i in 0..root.child_count() {
             let child = root.child(i).unwrap();
             if child.kind() != "hl_rule" {
@@ -180,13 +196,15 @@ impl Automata {
                     .collect()
             };
             for n_sel in n_selectors.into_iter() {
-                let style = style.clone();
-                let node = Node::compile_pattern(n_sel, tss, style, &mut tc)?;
+                let end = Node::End(style.clone(), Node::to_specificity(n_sel));
+                let node = Node::compile_pattern(n_sel, tss, end, &mut tc)?;
                 match &node {
                     Node::Pattern(Edge::Kind(k), _) => {
                         let s = k.as_text()?.to_string();
-                        kinds.push(s);
+                        kinds.push((patterns.len(), s));
                     }
+                    Node::Pattern(Edge::Field(_), _) => patterns_field.push(patterns.len()),
+                    Node::Pattern(Edge::KindField(_, _), _) => patterns_field.push(patterns.len()),
                     _ => (),
                 };
                 patterns.push(Rc::new(node))
@@ -197,33 +215,57 @@ impl Automata {
             name: name.to_string(),
             patterns,
             patterns_trie: Self::build_trie(kinds),
+            patterns_field,
             open_nodes: Vec::default(),
+            canvas: scheme.to_style(Highlight::Canvas),
         })
     }
 
-    fn build_trie(kinds: Vec<String>) -> KindTrie {
+    fn build_trie(kinds: Vec<(usize, String)>) -> KindTrie {
         let initial = KindTrie::default();
         kinds
             .into_iter()
-            .enumerate()
             .fold(initial, |trie, item| trie.merge(item.into()))
     }
 }
 
 impl Automata {
+    /// Highlight characters `a..z` in `buf`, walk the syntax nodes in
+    /// `tree` overlapping the range, shift them into the automata and
+    /// return the styled spans. Segments not matched by any rule are
+    /// styled with the color-scheme's canvas.
+    pub fn to_spanline(
+        &mut self,
+        buf: &Buffer,
+        tree: &ts::Tree,
+        a: usize,
+        z: usize,
+    ) -> Result<term::Spanline> {
+        self.open_nodes.clear();
+        let canvas = self.canvas.clone();
+        syntax::highlight_canvas(buf, canvas, tree, self, a, z)
+    }
+
+    /// Shift `token` into the automata and return the style for the token,
+    /// if any of the rules match.
+    ///
+    /// When more than one rule matches the same token, the rule with more
+    /// selectors wins, say `hl_rule selector sel_kind` wins over `s sel_kind`,
+    /// which in turn wins over `sel_kind`. Between rules with same number of
+    /// selectors, the rule that started matching earlier in the tree wins.
     pub fn shift_in(&mut self, token: &Token) -> Result<Option<term::Style>> {
         // check whether there is a match with open-patterns.
-        let mut style1: Option<term::Style> = None;
+        let mut style1: Option<(term::Style, usize)> = None;
         let mut ops = vec![];
 
         // trace!("open_nodes: {:?}", self.open_nodes);
         for (off, open_node) in self.open_nodes.iter().enumerate() {
             style1 = match open_node.is_match(token)? {
-                (Some(Node::End(style)), true) => {
+                (Some(Node::End(style, spec)), true) => {
                     ops.push((off, None));
-                    Some(style1.unwrap_or(style))
+                    Self::to_specific(style1, Some((style, spec)))
                 }
-                (Some(Node::End(style)), _) => Some(style1.unwrap_or(style)),
+                (Some(Node::End(style, spec)), _) => Self::to_specific(style1, Some((style, spec))),
                 (Some(next), _) => {
                     ops.push((off, Some(next)));
                     style1
@@ -246,10 +288,9 @@ impl Automata {
 
         let msg = format!("unreachable");
         let style2 = match self.match_pattern(&token) {
-            Some(Node::End(style)) => Some(style),
-            Some(Node::Pattern(_, n)) => {
-                let n: &Node = n.borrow();
-                self.open_nodes.push(n.to_open_node(token)?);
+            Some(Node::End(style, spec)) => Some((style, spec)),
+            Some(node @ Node::Pattern(_, _)) => {
+                self.open_nodes.push(node.to_open_node(token)?);
                 None
             }
             Some(Node::Twin { .. }) => err_at!(Fatal, msg: msg)?,
@@ -259,20 +300,42 @@ impl Automata {
             None => None,
         };
 
-        if let Some(style) = style1 {
-            Ok(Some(style))
-        } else {
-            Ok(style2)
+        Ok(Self::to_specific(style1, style2).map(|(style, _)| style))
+    }
+
+    // pick the style from the more specific rule, `a` wins the tie.
+    fn to_specific(
+        a: Option<(term::Style, usize)>,
+        b: Option<(term::Style, usize)>,
+    ) -> Option<(term::Style, usize)> {
+        match (a, b) {
+            (Some(a), Some(b)) => Some(if_else!(b.1 > a.1, b, a)),
+            (Some(a), None) => Some(a),
+            (None, b) => b,
         }
     }
 
     fn match_pattern(&self, token: &Token) -> Option<Node> {
-        let chars: Vec<char> = token.kind.chars().collect();
-        let node: &Node = match self.patterns_trie.lookup(&chars) {
-            Trie::None => None,
-            Trie::Index(i) => Some(self.patterns[i].borrow()),
-            Trie::Table(_) => None,
-            Trie::IndexTable(i, _) => Some(self.patterns[i].borrow()),
+        // field selectors are more specific than kind selectors.
+        let node: Option<&Node> = {
+            let iter = self.patterns_field.iter().rev();
+            iter.map(|i| self.patterns[*i].borrow())
+                .find(|node: &&Node| match node {
+                    Node::Pattern(edge, _) => edge.is_match(token).unwrap_or(false),
+                    _ => false,
+                })
+        };
+        let node: &Node = match node {
+            Some(node) => Some(node),
+            None => {
+                let chars: Vec<char> = token.kind.chars().collect();
+                match self.patterns_trie.lookup(&chars) {
+                    Trie::None => None,
+                    Trie::Index(i) => Some(self.patterns[i].borrow()),
+                    Trie::Table(_) => None,
+                    Trie::IndexTable(i, _) => Some(self.patterns[i].borrow()),
+                }
+            }
         }?;
         match node {
             Node::Pattern(_, n) => {
@@ -287,6 +350,8 @@ impl Automata {
 #[derive(Clone)]
 enum Edge {
     Kind(Span),
+    Field(Span),
+    KindField(Span, Span),
     Twin(Box<Edge>),
     Sibling(Box<Edge>),
     Child(Box<Edge>),
@@ -301,10 +366,12 @@ impl Default for Edge {
 
 impl fmt::Display for Edge {
     fn fmt(&self, f: &mut fmt::Formatter) -> result::Result<(), fmt::Error> {
-        use Edge::{Child, Descendant, Kind, Sibling, Twin};
+        use Edge::{Child, Descendant, Field, Kind, KindField, Sibling, Twin};
 
         match self {
             Kind(span) => write!(f, "e-kind<{}>", span),
+            Field(span) => write!(f, "e-field<{}>", span),
+            KindField(kind, field) => write!(f, "e-kind-field<{},{}>", kind, field),
             Twin(edge) => write!(f, "e-twin<{}>", edge),
             Sibling(edge) => write!(f, "e-sibling<{}>", edge),
             Child(edge) => write!(f, "e-child<{}>", edge),
@@ -315,10 +382,15 @@ impl fmt::Display for Edge {
 
 impl Edge {
     fn is_match(&self, token: &Token) -> Result<bool> {
-        use Edge::{Child, Descendant, Kind, Sibling, Twin};
+        use Edge::{Child, Descendant, Field, Kind, KindField, Sibling, Twin};
 
         match self {
             Kind(k) => Ok(token.kind == k.as_text()?),
+            Field(f) => Ok(token.field.as_deref() == Some(f.as_text()?)),
+            KindField(k, f) => {
+                let ok = token.field.as_deref() == Some(f.as_text()?);
+                Ok(ok && token.kind == k.as_text()?)
+            }
             Twin(_) => err_at!(Fatal, msg: format!("unreachable")),
             Sibling(_) => err_at!(Fatal, msg: format!("unreachable")),
             Child(_) => err_at!(Fatal, msg: format!("unreachable")),
@@ -354,7 +426,8 @@ enum Node {
         depth: usize,
         nth_child: usize,
     },
-    End(term::Style),
+    // style and specificity, number of selectors, of the rule.
+    End(term::Style, usize),
 }
 
 impl fmt::Display for Node {
@@ -395,7 +468,7 @@ impl fmt::Display for Node {
                 "Descendant<{},{},{}> -> {}",
                 edge, depth, nth_child, next
             ),
-            End(style) => write!(f, "End<{}>", style),
+            End(style, spec) => write!(f, "End<{},{}>", style, spec),
         }
     }
 }
@@ -411,6 +484,8 @@ impl Node {
         match self {
             Node::Pattern(edge, next) => match edge {
                 Edge::Kind(_) => err_at!(Fatal, msg: format!("unreachable")),
+                Edge::Field(_) => err_at!(Fatal, msg: format!("unreachable")),
+                Edge::KindField(_, _) => err_at!(Fatal, msg: format!("unreachable")),
                 Edge::Twin(ne) => Ok(Node::Twin {
                     edge: ne.as_ref().clone(),
                     next: Rc::clone(next),
@@ -436,7 +511,7 @@ impl Node {
                     depth: token.depth,
                 }),
             },
-            node @ Node::End(_) => Ok(node.clone()),
+            node @ Node::End(_, _) => Ok(node.clone()),
             Node::Twin { .. } => err_at!(Fatal, msg: format!("unreachable")),
             Node::Sibling { .. } => err_at!(Fatal, msg: format!("unreachable")),
             Node::Child { .. } => err_at!(Fatal, msg: format!("unreachable")),
@@ -483,7 +558,7 @@ impl Node {
                 let ok3 = edge.is_match(token)?;
                 (ok1 && ok3, false, next)
             }
-            Node::End(_) => return Ok((None, false)),
+            Node::End(_, _) => return Ok((None, false)),
         };
 
         // trace!("node.is_match {} {}", ok, drop);
@@ -503,7 +578,7 @@ impl Node {
             Sibling { edge, .. } => edge,
             Child { edge, .. } => edge,
             Descendant { edge, .. } => edge,
-            End(_) => unreachable!(),
+            End(_, _) => unreachable!(),
         }
     }
 }
@@ -514,7 +589,7 @@ impl Node {
         tss: &str,
         tc: &mut ts::TreeCursor<'a>,
         scheme: &ColorScheme,
-    ) -> Result<Node> {
+    ) -> Result<term::Style> {
         let style = match ts_node.kind() {
             "highlight" => {
                 let cont = {
@@ -565,7 +640,19 @@ impl Node {
             kind => err_at!(Fatal, msg: format!("unexpected {:?}", kind))?,
         };
 
-        Ok(Node::End(style))
+        Ok(style)
+    }
+
+    // count the number of selectors in a pattern, like `a > b c` has
+    // three selectors.
+    fn to_specificity(ts_node: ts::Node) -> usize {
+        match ts_node.kind() {
+            "sel_kind" | "sel_field" | "sel_symbol_field" => 1,
+            _ => (0..ts_node.child_count())
+                .filter_map(|i| ts_node.child(i))
+                .map(Self::to_specificity)
+                .sum(),
+        }
     }
 
     fn compile_pattern<'a>(
@@ -605,6 +692,24 @@ impl Node {
                 let edge = Edge::Kind(Span::from_node(&chd).pos_to_text(tss));
                 Ok(Node::Pattern(edge, Rc::new(next)))
             }
+            "sel_field" => {
+                // skip the `.` prefix.
+                let field = tss[chd.start_byte()..chd.end_byte()].trim_start_matches('.');
+                let edge = Edge::Field(Span::Text(field.to_string()));
+                Ok(Node::Pattern(edge, Rc::new(next)))
+            }
+            "sel_symbol_field" => {
+                let txt = &tss[chd.start_byte()..chd.end_byte()];
+                let (kind, field) = match txt.find('.') {
+                    Some(off) => (&txt[..off], &txt[off + 1..]),
+                    None => err_at!(Fatal, msg: format!("unexpected {}", txt))?,
+                };
+                let edge = {
+                    let (kind, field) = (kind.to_string(), field.to_string());
+                    Edge::KindField(Span::Text(kind), Span::Text(field))
+                };
+                Ok(Node::Pattern(edge, Rc::new(next)))
+            }
             "sel_twins" => {
                 next = Self::compile_sel(chd.child(2).unwrap(), tss, next, tc)?;
                 wrap_edge!(next.as_mut_edge(), Twin)?;
@@ -18,11 +18,15 @@ module.exports = grammar({
     selector: $ => repeat1($.sel_symbol),
     sel_symbol: $ => choice(
         $.sel_kind,
+        $.sel_field,
+        $.sel_symbol_field,
         $.sel_twins,
         $.sel_siblings,
         $.sel_child,
     ),
     sel_kind: $ => /[a-z][0-9a-zA-Z-_]+/,
+    sel_field: $ => /\.[a-z][0-9a-zA-Z-_]+/,
+    sel_symbol_field: $ => /[a-z][0-9a-zA-Z-_]+\.[a-z][0-9a-zA-Z-_]+/,
     sel_twins: $ => prec.left(2, seq($.sel_symbol, '+', $.sel_symbol)),
     sel_siblings: $ => prec.left(2, seq($.sel_symbol, '~', $.sel_symbol)),
     sel_child: $ => prec.left(2, seq($.sel_symbol, '>', $.sel_symbol)),
@@ -7,3 +7,141 @@ use test::Bencher;
 fn bench_token(b: &mut Bencher) {
     b.iter(|| 1);
 }
+
+#[test]
+fn test_token_from_node() {
+    use crate::{colors::ColorScheme, location::Location, syntax::CodeCmd};
+
+    let txt = ":s/αβ/γδ/g";
+    let buf = {
+        let loc = Location::new_ted("test-token", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+    let tree = {
+        let syn = CodeCmd::new(txt, ColorScheme::default().unwrap()).unwrap();
+        syn.into_parse_tree().unwrap()
+    };
+
+    let root = tree.root_node();
+    let tok = Token::from_node(&buf, &root, None, 0, 0);
+    assert_eq!((tok.a, tok.z), (0, txt.chars().count()));
+
+    let cmd = root.child(root.child_count() - 1).unwrap();
+    let tok = Token::from_node(&buf, &cmd, None, 1, 1);
+    assert!(tok.z > tok.a, "{}", tok);
+    assert_eq!((tok.a, tok.z), (1, txt.chars().count()));
+}
+
+#[test]
+fn test_edge_field() {
+    let token = |kind: &str, field: Option<&str>| Token {
+        kind: kind.to_string(),
+        field: field.map(|f| f.to_string()),
+        depth: 1,
+        sibling: 0,
+        a: 0,
+        z: 1,
+    };
+    let text = |s: &str| Span::Text(s.to_string());
+
+    let edge = Edge::Field(text("key"));
+    assert!(edge.is_match(&token("bare_key", Some("key"))).unwrap());
+    assert!(edge.is_match(&token("string", Some("key"))).unwrap());
+    assert!(!edge.is_match(&token("bare_key", Some("value"))).unwrap());
+    assert!(!edge.is_match(&token("bare_key", None)).unwrap());
+
+    let edge = Edge::KindField(text("bare_key"), text("key"));
+    assert!(edge.is_match(&token("bare_key", Some("key"))).unwrap());
+    assert!(!edge.is_match(&token("string", Some("key"))).unwrap());
+    assert!(!edge.is_match(&token("bare_key", None)).unwrap());
+
+    let edge = Edge::Kind(text("bare_key"));
+    assert!(edge.is_match(&token("bare_key", Some("key"))).unwrap());
+}
+
+#[test]
+fn test_to_spanline() {
+    use crate::{colors::ColorScheme, location::Location};
+
+    let txt = "key: string;\n";
+    let buf = {
+        let loc = Location::new_ted("test-spanline", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+    let tree = {
+        let mut p = ts::Parser::new();
+        p.set_language(unsafe { tree_sitter_tss() }).unwrap();
+        p.parse(txt, None).unwrap()
+    };
+
+    let scheme = ColorScheme::default().unwrap();
+    let tss = "sel_kind: keyword;\nhighlight: string;";
+    let mut atmt = Automata::from_str("test", tss, &scheme).unwrap();
+
+    let refn = r#"L("S(\"key\"),S(\": \"),S(\"string\"),S(\";\")")"#;
+    let line = atmt.to_spanline(&buf, &tree, 0, 12).unwrap();
+    assert_eq!(format!("{:?}", line), refn);
+    // shifting the same range again shall yield the same spans.
+    let line = atmt.to_spanline(&buf, &tree, 0, 12).unwrap();
+    assert_eq!(format!("{:?}", line), refn);
+
+    let refn = r#"L("S(\"ey\"),S(\": \"),S(\"st\")")"#;
+    let line = atmt.to_spanline(&buf, &tree, 1, 7).unwrap();
+    assert_eq!(format!("{:?}", line), refn);
+}
+
+#[test]
+fn test_shift_in_specificity() {
+    use crate::colors::{ColorScheme, Highlight};
+
+    let token = |kind: &str, depth: usize| Token {
+        kind: kind.to_string(),
+        field: None,
+        depth,
+        sibling: 0,
+        a: 0,
+        z: 1,
+    };
+    let tokens = vec![
+        token("s", 0),
+        token("hl_rule", 1),
+        token("selectors", 2),
+        token("selector", 3),
+        token("sel_symbol", 4),
+        token("sel_kind", 5),
+    ];
+
+    let scheme = ColorScheme::default().unwrap();
+    let (comment, keyword, string) = (
+        scheme.to_style(Highlight::Comment),
+        scheme.to_style(Highlight::Keyword),
+        scheme.to_style(Highlight::Str),
+    );
+    assert!(comment.fg != keyword.fg && keyword.fg != string.fg);
+
+    // `hl_rule sel_kind` starts matching ahead of `selectors selector sel_kind`,
+    // yet the later has more selectors, hence more specific.
+    let tss = "sel_kind: string;\n\
+        hl_rule sel_kind: comment;\n\
+        selectors selector sel_kind: keyword;";
+    let mut atmt = Automata::from_str("test", tss, &scheme).unwrap();
+    let styles: Vec<Option<term::Style>> = {
+        let iter = tokens.iter().map(|t| atmt.shift_in(t).unwrap());
+        iter.collect()
+    };
+    assert!(styles[..5].iter().all(|s| s.is_none()));
+    assert!(styles[5].as_ref().unwrap().fg == keyword.fg);
+
+    // same number of selectors, the rule that started matching earlier wins.
+    let tss = "sel_kind: string;\n\
+        hl_rule sel_kind: comment;\n\
+        selectors sel_kind: keyword;";
+    let mut atmt = Automata::from_str("test", tss, &scheme).unwrap();
+    let style = tokens.iter().map(|t| atmt.shift_in(t).unwrap()).last();
+    assert!(style.unwrap().unwrap().fg == comment.fg);
+
+    // bare kind selector.
+    let mut atmt = Automata::from_str("test", "sel_kind: string;", &scheme).unwrap();
+    let style = tokens.iter().map(|t| atmt.shift_in(t).unwrap()).last();
+    assert!(style.unwrap().unwrap().fg == string.fg);
+}
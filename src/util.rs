@@ -43,8 +43,8 @@ macro_rules! limite {
 #[macro_export]
 macro_rules! err_at {
     ($e:expr) => {{
+        use Error::{BadCommand, FailBuffer, FailConvert, FailParse};
         use Error::{BadPattern, Fatal, IOError, Invalid, NoTopic, IPC};
-        use Error::{FailBuffer, FailConvert, FailParse};
 
         let p = format!("{}:{}", file!(), line!());
         match $e {
@@ -58,6 +58,7 @@ macro_rules! err_at {
             Err(FailConvert(_, s)) => Err(FailConvert(p, s)),
             Err(FailParse(_, s)) => Err(FailParse(p, s)),
             Err(FailBuffer(_, s)) => Err(FailBuffer(p, s)),
+            Err(BadCommand(_, s)) => Err(BadCommand(p, s)),
         }
     }};
     ($v:ident, msg:$m:expr) => {{
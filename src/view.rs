@@ -8,10 +8,16 @@ use crate::{
     buffer::{self},
     col_nu::{ColKind, ColNu},
     colors::Highlight,
+    term::{Sink, Span, Spanline, Style},
     window::{Coord, Cursor, Render, WinBuffer, Window},
     Error, Result,
 };
 
+// display glyphs, in list mode, for tab, trailing space and line end.
+const LIST_TAB: char = '\u{25b8}';
+const LIST_TRAIL: char = '\u{b7}';
+const LIST_EOL: char = '\u{b6}';
+
 /// Type to position and render a buffer in wrap mode. Takes the following
 /// as input.
 ///
@@ -31,6 +37,10 @@ pub struct Wrap {
     obc_xy: buffer::Cursor,
     scroll_off: u16,
     line_number: bool,
+    relative_number: bool,
+    highlight_trailing: bool,
+    list: bool,
+    cursor_line: bool,
     edit_lines: Vec<ScrLine>,
 }
 
@@ -57,10 +67,14 @@ where
     fn try_from((w, obc_xy): (&'a W, buffer::Cursor)) -> Result<Wrap> {
         let cursor = {
             let e = Error::Invalid(String::default(), "no-cursor".to_string());
-            err_at!(w.to_cursor().ok_or(e))?
+            err_at!(w.to_view_cursor().ok_or(e))?
         };
         let scroll_off = w.config_scroll_offset();
         let line_number = w.config_line_number();
+        let relative_number = w.config_relative_number();
+        let highlight_trailing = w.config_highlight_trailing();
+        let list = w.config_list();
+        let cursor_line = w.config_cursor_line();
         Ok(Wrap {
             name: w.to_name(),
             coord: w.to_coord(),
@@ -68,6 +82,10 @@ where
             obc_xy,
             scroll_off,
             line_number,
+            relative_number,
+            highlight_trailing,
+            list,
+            cursor_line,
             edit_lines: Vec::default(),
         })
     }
@@ -130,69 +148,111 @@ impl Wrap {
     }
 
     /// consume this wrap-view and render the screen content.
-    pub fn render<R>(self, buf: &R::Buf, r: &R, old_screen: Option<Vec<ScrLine>>) -> Result<Cursor>
+    /// Render the view into `sink`, either the terminal or a headless
+    /// [Screen][crate::term::Screen]. `old_lines` are the lines rendered
+    /// by the previous call, only lines that differ are queued into `sink`
+    /// and `old_lines` is updated with this frame. Pass an empty vector to
+    /// render the view afresh.
+    pub fn render<R, S>(
+        self,
+        buf: &R::Buf,
+        r: &R,
+        old_lines: &mut Vec<Spanline>,
+        sink: &mut S,
+    ) -> Result<Cursor>
     where
         R: Render,
         <R as Render>::Buf: WinBuffer,
+        S: Sink,
     {
-        self.refresh(buf, r, old_screen)
+        self.refresh(buf, r, old_lines, sink)
     }
 
-    fn refresh<R>(self, buf: &R::Buf, r: &R, old_screen: Option<Vec<ScrLine>>) -> Result<Cursor>
+    fn refresh<R, S>(
+        self,
+        buf: &R::Buf,
+        r: &R,
+        old_lines: &mut Vec<Spanline>,
+        sink: &mut S,
+    ) -> Result<Cursor>
     where
         R: Render,
         <R as Render>::Buf: WinBuffer,
+        S: Sink,
     {
         debug!("WRAP-REFRESH {}", self);
 
-        let canvas = {
+        let (canvas, whitespace, non_text, cursor_line, folded) = {
             let scheme = r.as_color_scheme();
-            scheme.to_style(Highlight::Canvas)
+            (
+                scheme.to_style(Highlight::Canvas),
+                scheme.to_style(Highlight::Whitespace),
+                scheme.to_style(Highlight::NonText),
+                scheme.to_style(Highlight::CursorLine),
+                scheme.to_style(Highlight::Folded),
+            )
         };
         let (col, row) = self.coord.to_origin_cursor();
         let edit_lines = self.to_edit_lines(buf);
 
-        let (mut nu, nu_wth) = to_nu_width(&self.edit_lines, self.line_number);
+        let cursor_row = buf.to_xy_cursor(None).row;
+        let (mut nu, nu_wth) = {
+            let (ln, rn) = (self.line_number, self.relative_number);
+            to_nu_width(&self.edit_lines, cursor_row, ln, rn)
+        };
         nu.set_color_scheme(r.as_color_scheme());
 
+        let mut new_lines = Vec::with_capacity(edit_lines.len());
         let rows = row..(row + self.coord.hgt);
-        let iter = rows.zip(edit_lines.into_iter().enumerate());
-        for (row, (i, sline)) in iter {
-            match old_screen.as_ref() {
-                Some(old_screen) if sline == old_screen[i] => continue,
-                _ => (),
-            }
-
-            let nu_span = {
-                let mut span = nu.to_span(sline.colk);
-                span.set_cursor(Cursor { col, row });
-                span
-            };
-            let mut line_span = {
-                let (a, z) = (sline.bc, sline.bc + (sline.n as usize));
-                r.to_span_line(buf, a, z)?
+        for (row, sline) in rows.zip(edit_lines) {
+            let nu_span = nu.to_span(sline.colk);
+            let wth = self.coord.wth.saturating_sub(nu_wth);
+            let mut line_span = match sline.fold {
+                Some(n) => fold_line(buf, sline.line_idx, n, wth, &folded),
+                None => {
+                    let mut line_span = {
+                        let (a, z) = (sline.bc, sline.bc + (sline.n as usize));
+                        r.to_span_line(buf, a, z)?
+                    };
+                    line_span.trim_newline();
+                    let eol = {
+                        let trail = if_else!(self.highlight_trailing, Some(&whitespace), None);
+                        let list = if_else!(self.list, Some(&non_text), None);
+                        decorate_line(buf, &sline, &mut line_span, trail, list)
+                    };
+                    if self.list {
+                        // tabs take a single column in wrap-view.
+                        line_span.replace_chars('\t', LIST_TAB);
+                    }
+                    let padding = {
+                        let n = wrap_width(sline.text.as_deref().unwrap_or("")) as u16;
+                        let n = n + if_else!(eol, 1, 0);
+                        wth.saturating_sub(n)
+                    };
+                    line_span.right_padding(padding);
+                    if self.cursor_line && is_cursor_line(&sline, cursor_row) {
+                        line_span.set_canvas_bg(&canvas, cursor_line.bg.clone());
+                    }
+                    line_span.optimize_spans(canvas.clone());
+                    line_span
+                }
             };
-            let padding = {
-                let n = sline.n.saturating_sub(line_span.trim_newline() as u16);
-                self.coord.wth.saturating_sub(nu_wth + n)
-            };
-            line_span.right_padding(padding);
-            line_span.optimize_spans(canvas.clone());
-            match &canvas.bg {
-                Some(bg) => err_at!(Fatal, termbg!(bg.clone()))?,
-                None => (),
-            };
-            err_at!(Fatal, termqu!(nu_span, line_span))?;
-
+            line_span
+                .prepend_span(nu_span)
+                .truncate_width(self.coord.wth as usize)
+                .set_cursor(Cursor { col, row });
             trace!(
-                "  to_span_line row:{} {} w:{} {} {:?}",
+                "  to_span_line row:{} {} w:{} {}",
                 row,
                 sline,
                 line_span.to_width(),
                 line_span,
-                nu_span.cursor
             );
+
+            queue_line(sink, &canvas, old_lines.get(new_lines.len()), &line_span)?;
+            new_lines.push(line_span);
         }
+        *old_lines = new_lines;
 
         Ok(self.cursor)
     }
@@ -206,6 +266,13 @@ pub struct NoWrap {
     obc_xy: buffer::Cursor,
     scroll_off: u16,
     line_number: bool,
+    relative_number: bool,
+    highlight_trailing: bool,
+    list: bool,
+    cursor_line: bool,
+    tab_stop: usize,
+    // visual column of buffer, at the left edge of the viewport.
+    vcol: usize,
     edit_lines: Vec<ScrLine>,
 }
 
@@ -228,10 +295,15 @@ where
     fn try_from((w, obc_xy): (&'a W, buffer::Cursor)) -> Result<NoWrap> {
         let cursor = {
             let e = Error::Invalid(String::default(), "no-cursor".to_string());
-            err_at!(w.to_cursor().ok_or(e))?
+            err_at!(w.to_view_cursor().ok_or(e))?
         };
         let line_number = w.config_line_number();
+        let relative_number = w.config_relative_number();
+        let highlight_trailing = w.config_highlight_trailing();
+        let list = w.config_list();
+        let cursor_line = w.config_cursor_line();
         let scroll_off = w.config_scroll_offset();
+        let tab_stop = w.config_tab_stop();
         Ok(NoWrap {
             name: w.to_name(),
             coord: w.to_coord(),
@@ -239,6 +311,12 @@ where
             obc_xy,
             scroll_off,
             line_number,
+            relative_number,
+            highlight_trailing,
+            list,
+            cursor_line,
+            tab_stop,
+            vcol: 0,
             edit_lines: Vec::default(),
         })
     }
@@ -253,6 +331,12 @@ impl NoWrap {
         Cursor { row: 0, col }
     }
 
+    /// Return the visual column of buffer, at the left edge of the viewport.
+    #[inline]
+    pub fn to_vcol(&self) -> usize {
+        self.vcol
+    }
+
     #[inline]
     pub fn to_edit_lines<B>(&self, buf: &B) -> Vec<ScrLine>
     where
@@ -276,20 +360,26 @@ impl NoWrap {
         B: WinBuffer,
     {
         let nbc_xy = buf.to_xy_cursor(None);
-        let (diff_col, diff_row) = self.obc_xy.diff(&nbc_xy);
+        // lines within a closed fold are rendered as a single line.
+        let diff_row = {
+            let hgt = self.coord.hgt as usize;
+            visible_diff(buf, self.obc_xy.row, nbc_xy.row, hgt)
+        };
         let nc_row = self.cursor.add_row(diff_row, self.coord, self.scroll_off);
+        // columns are visual columns, tabs expand upto the next tab-stop.
+        let (ovcol, nvcol) = (
+            to_vcol(buf, self.obc_xy, self.tab_stop),
+            to_vcol(buf, nbc_xy, self.tab_stop),
+        );
+        let diff_col = (nvcol as isize) - (ovcol as isize);
 
         let lines: Vec<usize> = {
-            let from = nbc_xy.row.saturating_sub(nc_row as usize);
-            let to = {
-                let to = from + (self.coord.hgt as usize);
-                cmp::max(to, buf.n_lines())
-            };
-            (from..to).collect()
+            let from = visible_up(buf, nbc_xy.row, nc_row as usize);
+            visible_lines(buf, from, self.coord.hgt as usize)
         };
         let nu_wth = {
-            let row = lines.clone().into_iter().max().unwrap_or(0);
-            ColNu::new(row, self.line_number).to_width()
+            let (ln, rn) = (self.line_number, self.relative_number);
+            to_col_nu(&lines, nbc_xy.row, ln, rn).to_width()
         };
         let wth = self.coord.wth.saturating_sub(nu_wth);
 
@@ -302,11 +392,11 @@ impl NoWrap {
             row: nc_row,
         };
 
-        let edit_lines = {
+        let vcol = {
             let col = cursor.col.saturating_sub(nu_wth);
-            let col = nbc_xy.col.saturating_sub(col as usize);
-            nowrap_lines(buf, lines, col, nu_wth, wth)
+            nvcol.saturating_sub(col as usize)
         };
+        let edit_lines = nowrap_lines(buf, lines, vcol, self.tab_stop, nu_wth, wth);
         let lines = padd_lines(edit_lines, self.coord, nu_wth);
 
         debug!(
@@ -320,65 +410,120 @@ impl NoWrap {
 
         // update this wrap-view.
         self.cursor = cursor;
+        self.vcol = vcol;
         self.edit_lines = lines;
 
         Ok(self.cursor)
     }
 
-    pub fn render<R>(self, buf: &R::Buf, r: &R, old_screen: Option<Vec<ScrLine>>) -> Result<Cursor>
+    /// Render the view into `sink`, either the terminal or a headless
+    /// [Screen][crate::term::Screen]. `old_lines` are the lines rendered
+    /// by the previous call, only lines that differ are queued into `sink`
+    /// and `old_lines` is updated with this frame. Pass an empty vector to
+    /// render the view afresh.
+    pub fn render<R, S>(
+        self,
+        buf: &R::Buf,
+        r: &R,
+        old_lines: &mut Vec<Spanline>,
+        sink: &mut S,
+    ) -> Result<Cursor>
     where
         R: Render,
         <R as Render>::Buf: WinBuffer,
+        S: Sink,
     {
-        self.refresh(buf, r, old_screen)
+        self.refresh(buf, r, old_lines, sink)
     }
 
-    fn refresh<R>(self, buf: &R::Buf, r: &R, old_screen: Option<Vec<ScrLine>>) -> Result<Cursor>
+    fn refresh<R, S>(
+        self,
+        buf: &R::Buf,
+        r: &R,
+        old_lines: &mut Vec<Spanline>,
+        sink: &mut S,
+    ) -> Result<Cursor>
     where
         R: Render,
         <R as Render>::Buf: WinBuffer,
+        S: Sink,
     {
+        use crate::text;
+
         debug!("NOWRAP-REFRESH {}", self);
 
-        let canvas = {
+        let (canvas, whitespace, non_text, cursor_line, folded) = {
             let scheme = r.as_color_scheme();
-            scheme.to_style(Highlight::Canvas)
+            (
+                scheme.to_style(Highlight::Canvas),
+                scheme.to_style(Highlight::Whitespace),
+                scheme.to_style(Highlight::NonText),
+                scheme.to_style(Highlight::CursorLine),
+                scheme.to_style(Highlight::Folded),
+            )
         };
         let (col, row) = self.coord.to_origin_cursor();
         let edit_lines = self.to_edit_lines(buf);
 
-        let (mut nu, nu_wth) = to_nu_width(&self.edit_lines, self.line_number);
+        let cursor_row = buf.to_xy_cursor(None).row;
+        let (mut nu, nu_wth) = {
+            let (ln, rn) = (self.line_number, self.relative_number);
+            to_nu_width(&self.edit_lines, cursor_row, ln, rn)
+        };
         nu.set_color_scheme(r.as_color_scheme());
 
+        let mut new_lines = Vec::with_capacity(edit_lines.len());
         let rows = row..(row + self.coord.hgt);
-        let iter = rows.zip(edit_lines.into_iter().enumerate());
-        for (row, (i, sline)) in iter {
-            match old_screen.as_ref() {
-                Some(old_screen) if sline == old_screen[i] => continue,
-                _ => (),
-            }
-
-            let nu_span = {
-                let mut span = nu.to_span(sline.colk);
-                span.set_cursor(Cursor { col, row });
-                span
-            };
-            let mut line_span = {
-                let (a, z) = (sline.bc, sline.bc + (sline.n as usize));
-                r.to_span_line(buf, a, z)?
-            };
-            let padding = {
-                let n = sline.n.saturating_sub(line_span.trim_newline() as u16);
-                self.coord.wth.saturating_sub(nu_wth + n)
+        for (row, sline) in rows.zip(edit_lines) {
+            let nu_span = nu.to_span(sline.colk);
+            let wth = self.coord.wth.saturating_sub(nu_wth);
+            let mut line_span = match sline.fold {
+                Some(n) => fold_line(buf, sline.line_idx, n, wth, &folded),
+                None => {
+                    let mut line_span = {
+                        let (a, z) = (sline.bc, sline.bc + (sline.n as usize));
+                        r.to_span_line(buf, a, z)?
+                    };
+                    line_span.trim_newline();
+                    {
+                        let trail = if_else!(self.highlight_trailing, Some(&whitespace), None);
+                        let list = if_else!(self.list, Some(&non_text), None);
+                        decorate_line(buf, &sline, &mut line_span, trail, list);
+                    }
+                    // a tab or a wide character, partially scrolled out on
+                    // the left, is padded.
+                    let lpad = match sline.colk {
+                        ColKind::Empty => 0,
+                        _ => {
+                            let col = sline.bc - buf.line_to_char(sline.line_idx);
+                            let line = buf.line(sline.line_idx);
+                            let vcol = text::to_vcol(&line, col, self.tab_stop);
+                            let mark = if_else!(self.list, LIST_TAB, ' ');
+                            line_span.expand_tabs_with(vcol, self.tab_stop, mark);
+                            vcol.saturating_sub(self.vcol) as u16
+                        }
+                    };
+                    let padding = {
+                        let n = lpad + (line_span.to_width() as u16);
+                        wth.saturating_sub(n)
+                    };
+                    line_span.left_padding(lpad).right_padding(padding);
+                    if self.cursor_line && is_cursor_line(&sline, cursor_row) {
+                        line_span.set_canvas_bg(&canvas, cursor_line.bg.clone());
+                    }
+                    line_span.optimize_spans(canvas.clone());
+                    line_span
+                }
             };
-            line_span.right_padding(padding);
-            line_span.optimize_spans(canvas.clone());
-            match &canvas.bg {
-                Some(bg) => err_at!(Fatal, termbg!(bg.clone()))?,
-                None => (),
-            };
-            err_at!(Fatal, termqu!(nu_span, line_span))?;
+            line_span
+                .prepend_span(nu_span)
+                .truncate_width(self.coord.wth as usize)
+                .set_cursor(Cursor { col, row });
+
+            queue_line(sink, &canvas, old_lines.get(new_lines.len()), &line_span)?;
+            new_lines.push(line_span);
         }
+        *old_lines = new_lines;
 
         Ok(self.cursor)
     }
@@ -390,6 +535,7 @@ struct WrapView {
     obc_xy: buffer::Cursor,
     scroll_off: u16,
     line_number: bool,
+    relative_number: bool,
 }
 
 impl From<Wrap> for WrapView {
@@ -400,6 +546,7 @@ impl From<Wrap> for WrapView {
             obc_xy: w.obc_xy,
             scroll_off: w.scroll_off,
             line_number: w.line_number,
+            relative_number: w.relative_number,
         }
     }
 }
@@ -421,9 +568,8 @@ impl WrapView {
             } else {
                 (nbc_xy.row, self.obc_xy.row)
             };
-            let (from, to) = (from.saturating_sub(hgt), to.saturating_add(hgt));
-            let to = cmp::min(buf.to_last_line_idx(), to);
-            (from..=to).collect()
+            let from = visible_up(buf, from, hgt);
+            visible_lines(buf, from, (to - from + 1).saturating_add(hgt))
         };
 
         // screen-lines around/between obc and nbc, crop and padd.
@@ -434,7 +580,10 @@ impl WrapView {
         let lines = padd_lines(edit_lines, self.coord, nu_wth);
 
         assert_eq!(lines.len(), hgt as usize);
-        let (_, max_wth) = to_nu_width(&lines, self.line_number);
+        let (_, max_wth) = {
+            let (ln, rn) = (self.line_number, self.relative_number);
+            to_nu_width(&lines, nbc_xy.row, ln, rn)
+        };
         debug!(
             "pivot:{} cursor:{} nbc:{} {} {}",
             pivot, cursor, nbc, nu_wth, max_wth
@@ -494,13 +643,40 @@ impl WrapView {
 
         // debug!("<< rows:{:?} row:{} col:{}", rows, row, col);
         let col = {
-            let col = nbc_xy.col % (wth as usize);
+            let bc = {
+                let slines = wrap_line(buf, nbc_xy.row, nu_wth, wth);
+                let iter = slines.into_iter().take_while(|sline| sline.bc <= nbc);
+                iter.last().map(|sline| sline.bc).unwrap_or(nbc)
+            };
+            let col = wrap_width(&buf.slice(bc..nbc)) % cmp::max(wth as usize, 1);
             col.saturating_add(nu_wth as usize) as u16
         };
         Cursor { col, row }
     }
 }
 
+// queue `line` into `sink`, unless it is same as the `old` line rendered
+// on the same row.
+fn queue_line<S>(
+    sink: &mut S,
+    canvas: &Style,
+    old: Option<&Spanline>,
+    line: &Spanline,
+) -> Result<()>
+where
+    S: Sink,
+{
+    match old {
+        Some(old) if old == line => Ok(()),
+        _ => {
+            if let Some(bg) = &canvas.bg {
+                sink.queue_bg(bg.clone())?
+            }
+            sink.queue_line(line.clone())
+        }
+    }
+}
+
 pub fn cursor_line(edit_lines: &[ScrLine], bc: usize) -> Option<usize> {
     let item = {
         let iter = edit_lines.iter().enumerate();
@@ -535,16 +711,212 @@ pub fn padd_lines(mut edit_lines: Vec<ScrLine>, coord: Coord, nu_wth: u16) -> Ve
     edit_lines
 }
 
-fn nowrap_lines<B>(buf: &B, lines: Vec<usize>, col: usize, nu_wth: u16, wth: u16) -> Vec<ScrLine>
+// `vcol` is the visual column at the left edge of the viewport, refer
+// [text::to_vcol] for details.
+fn nowrap_lines<B>(
+    buf: &B,
+    lines: Vec<usize>,
+    vcol: usize,
+    ts: usize,
+    nu_wth: u16,
+    wth: u16,
+) -> Vec<ScrLine>
 where
     B: WinBuffer,
 {
+    use crate::text;
+
     lines
         .into_iter()
-        .map(|line_idx| nowrap_line(buf, line_idx, col, wth, nu_wth))
+        .filter_map(|line_idx| match buf.to_closed_fold(line_idx) {
+            Some((a, z)) if a == line_idx => {
+                let bc = buf.line_to_char(line_idx);
+                Some(ScrLine::new_fold(nu_wth, line_idx, bc, z - a + 1))
+            }
+            Some(_) => None,
+            None => {
+                let col = match line_idx < buf.n_lines() {
+                    true => text::from_vcol(&buf.line(line_idx), vcol, ts),
+                    false => vcol,
+                };
+                Some(nowrap_line(buf, line_idx, col, nu_wth, wth))
+            }
+        })
         .collect()
 }
 
+// Return upto `n` lines, as line-index, starting from `line_idx`. Lines
+// hidden within a closed fold are skipped.
+fn visible_lines<B>(buf: &B, line_idx: usize, n: usize) -> Vec<usize>
+where
+    B: WinBuffer,
+{
+    let mut lines = vec![];
+    let mut line_idx = line_idx;
+    while lines.len() < n && line_idx < buf.n_lines() {
+        lines.push(line_idx);
+        line_idx = to_fold_range(buf, line_idx).1 + 1;
+    }
+    lines
+}
+
+// Return the line rendered `n` screen-lines above `line_idx`, a closed
+// fold is rendered as a single line, by its first line.
+fn visible_up<B>(buf: &B, line_idx: usize, n: usize) -> usize
+where
+    B: WinBuffer,
+{
+    let mut line_idx = to_fold_range(buf, line_idx).0;
+    for _ in 0..n {
+        match line_idx {
+            0 => break,
+            _ => line_idx = to_fold_range(buf, line_idx - 1).0,
+        }
+    }
+    line_idx
+}
+
+// Return the number of screen-lines from line `from` to line `to`, negative
+// if `to` is above `from`. Counting stops at `max` lines.
+fn visible_diff<B>(buf: &B, from: usize, to: usize, max: usize) -> isize
+where
+    B: WinBuffer,
+{
+    let (a, z) = (cmp::min(from, to), cmp::max(from, to));
+    let z = to_fold_range(buf, z).0;
+
+    let (mut n, mut line_idx) = (0, to_fold_range(buf, a).0);
+    while line_idx < z && n < max {
+        line_idx = to_fold_range(buf, line_idx).1 + 1;
+        n += 1;
+    }
+    if_else!(from <= to, n as isize, -(n as isize))
+}
+
+// Return the first and last line of the closed fold containing `line_idx`,
+// if `line_idx` is not within a closed fold return (line_idx, line_idx).
+fn to_fold_range<B>(buf: &B, line_idx: usize) -> (usize, usize)
+where
+    B: WinBuffer,
+{
+    buf.to_closed_fold(line_idx).unwrap_or((line_idx, line_idx))
+}
+
+// Render the closed fold, of `n` lines starting from `line_idx`, as a single
+// summary line `wth` columns wide, using `style`.
+fn fold_line<B>(buf: &B, line_idx: usize, n: usize, wth: u16, style: &Style) -> Spanline
+where
+    B: WinBuffer,
+{
+    use crate::text;
+
+    let txt = {
+        let line = buf.line(line_idx).replace('\t', " ");
+        format!("+--{:>3} lines: {}", n, text::visual_line(&line).trim())
+    };
+    let padding = (wth as usize).saturating_sub(wrap_width(&txt));
+    let span: Span = format!("{}{}", txt, " ".repeat(padding)).into();
+    Spanline::from(span.using(style.clone()))
+}
+
+// whether screen-line `sline` renders the line under cursor, `cursor_row`.
+fn is_cursor_line(sline: &ScrLine, cursor_row: usize) -> bool {
+    match sline.colk {
+        ColKind::Empty => false,
+        _ => sline.line_idx == cursor_row,
+    }
+}
+
+// Decorate `line_span` rendered for the screen-line `sline`. Trailing
+// whitespace is highlighted using `trail` style, if supplied. In list mode,
+// when `list` style is supplied, trailing spaces are shown as LIST_TRAIL
+// and the line end as LIST_EOL, using `list` style. Display glyphs take
+// the same width as the characters they replace, and LIST_EOL is appended
+// after the line. Return whether LIST_EOL was appended.
+fn decorate_line<B>(
+    buf: &B,
+    sline: &ScrLine,
+    line_span: &mut Spanline,
+    trail: Option<&Style>,
+    list: Option<&Style>,
+) -> bool
+where
+    B: WinBuffer,
+{
+    use crate::text;
+
+    let n = trailing_whitespace(buf, sline);
+    if list.is_some() {
+        line_span.replace_suffix(n, ' ', LIST_TRAIL);
+    }
+    if let Some(style) = trail {
+        line_span.using_suffix(n, style.clone());
+    }
+
+    match (list, sline.colk) {
+        (_, ColKind::Empty) | (None, _) => false,
+        (Some(style), _) => {
+            let end = {
+                let n = text::visual_line_n(&buf.line(sline.line_idx));
+                buf.line_to_char(sline.line_idx) + n
+            };
+            match (sline.bc + (sline.n as usize)) == end {
+                true => {
+                    let span: Span = LIST_EOL.to_string().into();
+                    line_span.add_span(span.using(style.clone()));
+                    true
+                }
+                false => false,
+            }
+        }
+    }
+}
+
+// number of trailing whitespace characters, spaces and tabs, within the
+// screen-line `sline`. Trailing whitespace, if any, is always a suffix of
+// the screen-line.
+fn trailing_whitespace<B>(buf: &B, sline: &ScrLine) -> usize
+where
+    B: WinBuffer,
+{
+    use crate::text;
+
+    if let ColKind::Empty = sline.colk {
+        return 0;
+    }
+
+    let line = buf.line(sline.line_idx);
+    let line = text::visual_line(&line);
+    let bc = buf.line_to_char(sline.line_idx);
+    let (from, to) = {
+        let txt = line.trim_end_matches(&[' ', '\t'][..]);
+        (bc + txt.chars().count(), bc + line.chars().count())
+    };
+    let (a, z) = (sline.bc, sline.bc + (sline.n as usize));
+    cmp::min(z, to).saturating_sub(cmp::max(a, from))
+}
+
+// display width of `txt` in wrap-view, tabs are not expanded and take a
+// single column, refer [text::split_width] for details.
+fn wrap_width(txt: &str) -> usize {
+    use crate::text;
+
+    text::to_vcol(txt, txt.chars().count(), 1)
+}
+
+// visual column of buffer cursor `xy`, refer [text::to_vcol] for details.
+fn to_vcol<B>(buf: &B, xy: buffer::Cursor, tab_stop: usize) -> usize
+where
+    B: WinBuffer,
+{
+    use crate::text;
+
+    match xy.row < buf.n_lines() {
+        true => text::to_vcol(&buf.line(xy.row), xy.col, tab_stop),
+        false => xy.col,
+    }
+}
+
 pub fn nowrap_line<B>(buf: &B, line_idx: usize, col: usize, nu_wth: u16, wth: u16) -> ScrLine
 where
     B: WinBuffer,
@@ -554,7 +926,7 @@ where
     let bc = buf.line_to_char(line_idx);
     let n = {
         let n = text::visual_line_n(&buf.line(line_idx));
-        cmp::max(wth as usize, n.saturating_sub(col)) as u16
+        cmp::min(wth as usize, n.saturating_sub(col)) as u16
     };
     ScrLine::new_nu(nu_wth, line_idx, bc + col, n)
 }
@@ -567,7 +939,14 @@ where
 
     lines
         .into_iter()
-        .map(|line_idx| wrap_line(buf, line_idx, nu_wth, wth).into_iter())
+        .map(|line_idx| match buf.to_closed_fold(line_idx) {
+            Some((a, z)) if a == line_idx => {
+                let bc = buf.line_to_char(line_idx);
+                vec![ScrLine::new_fold(nu_wth, line_idx, bc, z - a + 1)].into_iter()
+            }
+            Some(_) => vec![].into_iter(),
+            None => wrap_line(buf, line_idx, nu_wth, wth).into_iter(),
+        })
         .flat_map(convert::identity)
         .collect()
 }
@@ -577,14 +956,10 @@ where
     B: WinBuffer,
 {
     use crate::text;
-    use std::iter::repeat;
 
     let bc = buf.line_to_char(line_idx);
-    let w = wth as usize;
-    let (m, n) = {
-        let line = buf.line(line_idx);
-        (line.chars().count(), text::visual_line_n(&line))
-    };
+    let line = buf.line(line_idx);
+    let (m, n) = (line.chars().count(), text::visual_line_n(&line));
     //debug!(
     //    "... {} {} {}",
     //    line_idx,
@@ -594,20 +969,18 @@ where
     match n {
         0 if line_idx == 0 || m > 0 => vec![ScrLine::new_nu(nu_wth, line_idx, bc, 0)],
         0 if m == 0 => vec![],
-        n => {
-            let mut ns: Vec<u16> = repeat(wth).take(n / w).collect();
-            match n % w {
-                0 => (),
-                r => ns.push(r as u16),
-            }
-            let mut slines = vec![ScrLine::new_nu(nu_wth, line_idx, bc, ns.remove(0))];
-            let iter = ns
-                .into_iter()
-                .enumerate()
-                .into_iter()
-                .map(|(i, n)| (bc + ((i + 1) * w), n));
-            for (bc, n) in iter {
-                slines.push(ScrLine::new_wrap(nu_wth, line_idx, bc, n))
+        _ => {
+            // wide characters do not straddle across screen lines.
+            let ns = text::split_width(text::visual_line(&line), wth as usize);
+            let mut slines = vec![];
+            let mut bc = bc;
+            for (i, n) in ns.into_iter().enumerate() {
+                let sline = match i {
+                    0 => ScrLine::new_nu(nu_wth, line_idx, bc, n as u16),
+                    _ => ScrLine::new_wrap(nu_wth, line_idx, bc, n as u16),
+                };
+                slines.push(sline);
+                bc += n;
             }
             slines
         }
@@ -622,6 +995,8 @@ pub struct ScrLine {
     pub bc: usize,
     pub n: u16,
     pub text: Option<String>,
+    // number of lines, when this screen-line renders a closed fold.
+    pub fold: Option<usize>,
 }
 
 impl fmt::Display for ScrLine {
@@ -645,6 +1020,7 @@ impl ScrLine {
             bc,
             n,
             text: None,
+            fold: None,
         }
     }
 
@@ -656,6 +1032,7 @@ impl ScrLine {
             bc,
             n,
             text: None,
+            fold: None,
         }
     }
 
@@ -667,15 +1044,54 @@ impl ScrLine {
             bc: usize::default(),
             n: u16::default(),
             text: None,
+            fold: None,
+        }
+    }
+
+    fn new_fold(nu_wth: u16, line_idx: usize, bc: usize, n_lines: usize) -> Self {
+        ScrLine {
+            nu_wth,
+            colk: ColKind::Nu(line_idx + 1),
+            line_idx,
+            bc,
+            n: 0,
+            text: None,
+            fold: Some(n_lines),
         }
     }
 }
 
-pub fn to_nu_width(lines: &[ScrLine], line_number: bool) -> (ColNu, u16) {
-    let nu = match lines.iter().map(|x| x.line_idx).max() {
-        Some(line_idx) => ColNu::new(line_idx, line_number),
-        None => ColNu::new(0, line_number),
+/// Line-number column for screen `lines`, with cursor on buffer line
+/// `cursor_row`. Both layout and render compute the column width from here,
+/// so that text is rendered where the cursor is placed.
+pub fn to_nu_width(
+    lines: &[ScrLine],
+    cursor_row: usize,
+    line_number: bool,
+    relative_number: bool,
+) -> (ColNu, u16) {
+    let lines: Vec<usize> = {
+        let iter = lines.iter().filter(|x| !x.colk.is_empty());
+        iter.map(|x| x.line_idx).collect()
     };
+    let nu = to_col_nu(&lines, cursor_row, line_number, relative_number);
     let nu_wth = nu.to_width();
     (nu, nu_wth)
 }
+
+// line-number column for buffer `lines`, in relative mode the width is from
+// the cursor line and the largest distance of `lines` from the cursor line.
+fn to_col_nu(lines: &[usize], cursor_row: usize, line_number: bool, relative: bool) -> ColNu {
+    let mut nu = ColNu::new(lines.iter().max().copied().unwrap_or(0), line_number);
+    if relative {
+        let iter = lines
+            .iter()
+            .map(|r| cmp::max(*r, cursor_row) - cmp::min(*r, cursor_row));
+        nu.set_relative(cursor_row, iter.max().unwrap_or(0));
+    }
+    nu
+}
+
+#[cfg(test)]
+#[path = "view_test.rs"]
+mod view_test;
@@ -0,0 +1,477 @@
+use super::*;
+
+use crate::{
+    buffer::Buffer,
+    colors::ColorScheme,
+    location::Location,
+    term::{Screen, Spanline},
+};
+
+// render buffer content as plain text, without syntax highlighting.
+struct PlainRender(ColorScheme);
+
+impl Render for PlainRender {
+    type Buf = Buffer;
+
+    fn as_color_scheme(&self) -> &ColorScheme {
+        &self.0
+    }
+
+    fn to_span_line(&self, buf: &Buffer, a: usize, z: usize) -> Result<Spanline> {
+        Ok(buf.slice(a..z).into())
+    }
+}
+
+#[test]
+fn test_wrap_lines() {
+    let buf = {
+        let txt = "abcdefghij\n\nxyz";
+        let loc = Location::new_ted("test-wrap-lines", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+
+    let lines = wrap_lines(&buf, vec![0, 1, 2], 0, 4);
+    let cells: Vec<(ColKind, usize, usize, u16)> = lines
+        .iter()
+        .map(|sl| (sl.colk, sl.line_idx, sl.bc, sl.n))
+        .collect();
+    assert!(
+        cells
+            == vec![
+                (ColKind::Nu(1), 0, 0, 4),
+                (ColKind::Wrap, 0, 4, 4),
+                (ColKind::Wrap, 0, 8, 2),
+                (ColKind::Nu(2), 1, 11, 0),
+                (ColKind::Nu(3), 2, 12, 3),
+            ]
+    );
+
+    assert_eq!(cursor_line(&lines, 0), Some(0));
+    assert_eq!(cursor_line(&lines, 9), Some(2));
+    assert_eq!(cursor_line(&lines, 11), Some(3));
+    assert_eq!(cursor_line(&lines, 14), Some(4));
+}
+
+#[test]
+fn test_nowrap_tabs() {
+    use crate::text;
+
+    let buf = {
+        let txt = "\tab\nx\ty\n";
+        let loc = Location::new_ted("test-nowrap-tabs", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+
+    assert_eq!(text::to_vcol("x\ty", 2, 8), 8);
+    assert_eq!(text::to_vcol("x\ty", 2, 4), 4);
+    assert_eq!(text::from_vcol("x\ty", 4, 8), 2);
+    assert_eq!(text::from_vcol("x\ty", 1, 8), 1);
+    assert_eq!(text::expand_tabs("x\ty", 0, 4), ("x   y".to_string(), 5));
+    assert_eq!(text::expand_tabs("\ty", 2, 4), ("  y".to_string(), 5));
+
+    let lines = nowrap_lines(&buf, vec![0, 1], 0, 8, 0, 10);
+    let cells: Vec<usize> = lines.iter().map(|sl| sl.bc).collect();
+    assert_eq!(cells, vec![0, 4]);
+
+    // viewport starts mid-tab, first character after the tab is visible.
+    let lines = nowrap_lines(&buf, vec![0, 1], 4, 8, 0, 10);
+    let cells: Vec<usize> = lines.iter().map(|sl| sl.bc).collect();
+    assert_eq!(cells, vec![1, 6]);
+}
+
+#[test]
+fn test_wide_chars() {
+    use crate::text;
+
+    // ASCII, full-width character and a combining mark.
+    let line = "a世e\u{301}b\n";
+    assert_eq!(text::to_vcol(line, 2, 8), 3);
+    assert_eq!(text::to_vcol(line, 3, 8), 4);
+    assert_eq!(text::to_vcol(line, 4, 8), 4);
+    assert_eq!(text::to_vcol(line, 5, 8), 5);
+    assert_eq!(text::from_vcol(line, 2, 8), 2);
+    assert_eq!(text::from_vcol(line, 4, 8), 4);
+
+    assert_eq!(text::split_width("abcde", 2), vec![2, 2, 1]);
+    assert_eq!(text::split_width(text::visual_line(line), 2), vec![1, 1, 3]);
+
+    let buf = {
+        let loc = Location::new_ted("test-wide-chars", line.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+    let cells: Vec<(usize, u16)> = {
+        let iter = wrap_line(&buf, 0, 0, 2).into_iter();
+        iter.map(|sl| (sl.bc, sl.n)).collect()
+    };
+    assert_eq!(cells, vec![(0, 1), (1, 1), (2, 3)]);
+}
+
+fn new_nowrap(name: &str, coord: Coord) -> NoWrap {
+    NoWrap {
+        name: name.to_string(),
+        coord,
+        cursor: NoWrap::initial_cursor(true),
+        obc_xy: buffer::Cursor::default(),
+        scroll_off: 0,
+        line_number: true,
+        relative_number: false,
+        highlight_trailing: false,
+        list: false,
+        cursor_line: false,
+        tab_stop: 8,
+        vcol: 0,
+        edit_lines: Vec::default(),
+    }
+}
+
+#[test]
+fn test_render_nowrap() {
+    let buf = {
+        let loc = Location::new_ted("test-render-nowrap", "hello\nworld".as_bytes(), false);
+        Buffer::from_reader(loc.unwrap()).unwrap()
+    };
+    let r = PlainRender(ColorScheme::default().unwrap());
+
+    let mut v = new_nowrap("test-render-nowrap", Coord::new(2, 1, 4, 12));
+    v.shift_cursor(&buf).unwrap();
+
+    let mut screen = Screen::new(14, 5);
+    let cursor = v.render(&buf, &r, &mut vec![], &mut screen).unwrap();
+    assert_eq!(cursor, Cursor::new(4, 0));
+    assert_eq!(
+        screen.to_lines(),
+        vec![
+            "   1 hello    ",
+            "   2 world    ",
+            " ~            ",
+            " ~            ",
+            "              ",
+        ]
+    );
+}
+
+#[test]
+fn test_render_dirty_lines() {
+    let mut buf = {
+        let loc = Location::new_ted("test-render-dirty", "hello\nworld".as_bytes(), false);
+        Buffer::from_reader(loc.unwrap()).unwrap()
+    };
+    let r = PlainRender(ColorScheme::default().unwrap());
+    let coord = Coord::new(1, 1, 3, 12);
+
+    let mut old_lines = vec![];
+    let mut v = new_nowrap("test-render-dirty", coord);
+    v.shift_cursor(&buf).unwrap();
+    v.render(&buf, &r, &mut old_lines, &mut Screen::new(12, 3))
+        .unwrap();
+    assert_eq!(old_lines.len(), 3);
+
+    // only the edited line is rendered again.
+    buf.insert_str(5, "!").unwrap();
+    let mut v = new_nowrap("test-render-dirty", coord);
+    v.shift_cursor(&buf).unwrap();
+    let mut screen = Screen::new(12, 3);
+    v.render(&buf, &r, &mut old_lines, &mut screen).unwrap();
+    assert_eq!(
+        screen.to_lines(),
+        vec!["  1 hello!  ", "            ", "            "]
+    );
+
+    // nothing has changed, nothing is rendered.
+    let mut v = new_nowrap("test-render-dirty", coord);
+    v.shift_cursor(&buf).unwrap();
+    let mut screen = Screen::new(12, 3);
+    v.render(&buf, &r, &mut old_lines, &mut screen).unwrap();
+    assert!(screen.to_lines().iter().all(|l| l.trim().is_empty()));
+}
+
+#[test]
+fn test_render_wrap() {
+    let buf = {
+        let loc = Location::new_ted("test-render-wrap", "abcdefghij\nxy".as_bytes(), false);
+        Buffer::from_reader(loc.unwrap()).unwrap()
+    };
+    let r = PlainRender(ColorScheme::default().unwrap());
+
+    let mut v = Wrap {
+        name: "test-render-wrap".to_string(),
+        coord: Coord::new(1, 1, 4, 10),
+        cursor: Wrap::initial_cursor(true),
+        obc_xy: buffer::Cursor::default(),
+        scroll_off: 0,
+        line_number: true,
+        relative_number: false,
+        highlight_trailing: false,
+        list: false,
+        cursor_line: false,
+        edit_lines: Vec::default(),
+    };
+    v.shift_cursor(&buf).unwrap();
+
+    let mut screen = Screen::new(10, 4);
+    v.render(&buf, &r, &mut vec![], &mut screen).unwrap();
+    assert_eq!(
+        screen.to_lines(),
+        vec!["  1 abcdef", "    ghij  ", "  2 xy    ", "~         "]
+    );
+}
+
+#[test]
+fn test_render_narrow() {
+    let buf = {
+        let txt = "a long line that does not fit\nshort";
+        let loc = Location::new_ted("test-render-narrow", txt.as_bytes(), false);
+        Buffer::from_reader(loc.unwrap()).unwrap()
+    };
+    let r = PlainRender(ColorScheme::default().unwrap());
+
+    // window is resized narrower, even narrower than the number column.
+    for wth in [40, 12, 5, 3, 1].iter().cloned() {
+        let coord = Coord::new(1, 1, 3, wth);
+
+        let mut v = new_nowrap("test-render-narrow", coord);
+        v.shift_cursor(&buf).unwrap();
+        let mut screen = Screen::new(wth + 4, 3);
+        v.render(&buf, &r, &mut vec![], &mut screen).unwrap();
+        for line in screen.to_lines().into_iter() {
+            let (a, z) = line.split_at(wth as usize);
+            assert!(z.trim().is_empty(), "nowrap {} {:?}", wth, a);
+        }
+
+        let mut v = Wrap {
+            name: "test-render-narrow".to_string(),
+            coord,
+            cursor: Wrap::initial_cursor(true),
+            obc_xy: buffer::Cursor::default(),
+            scroll_off: 0,
+            line_number: true,
+            relative_number: false,
+            highlight_trailing: false,
+            list: false,
+            cursor_line: false,
+            edit_lines: Vec::default(),
+        };
+        v.shift_cursor(&buf).unwrap();
+        let mut screen = Screen::new(wth + 4, 3);
+        v.render(&buf, &r, &mut vec![], &mut screen).unwrap();
+        for line in screen.to_lines().into_iter() {
+            let (a, z) = line.split_at(wth as usize);
+            assert!(z.trim().is_empty(), "wrap {} {:?}", wth, a);
+        }
+    }
+}
+
+#[test]
+fn test_trailing_whitespace() {
+    let buf = {
+        let txt = "ab \t\n  \nxyz\n";
+        let loc = Location::new_ted("test-trailing", txt.as_bytes(), false);
+        Buffer::from_reader(loc.unwrap()).unwrap()
+    };
+
+    let lines = wrap_lines(&buf, vec![0, 1, 2], 0, 3);
+    let ns: Vec<usize> = lines
+        .iter()
+        .map(|sl| trailing_whitespace(&buf, sl))
+        .collect();
+    assert_eq!(ns, vec![1, 1, 2, 0]);
+
+    // viewport scrolled right, trailing whitespace cut on the right.
+    let n = trailing_whitespace(&buf, &nowrap_line(&buf, 0, 1, 0, 2));
+    assert_eq!(n, 1);
+    let n = trailing_whitespace(&buf, &nowrap_line(&buf, 0, 3, 0, 2));
+    assert_eq!(n, 1);
+    let n = trailing_whitespace(&buf, &ScrLine::new_empty(0));
+    assert_eq!(n, 0);
+}
+
+#[test]
+fn test_render_list() {
+    let buf = {
+        let loc = Location::new_ted("test-render-list", "a\tb  \nxy".as_bytes(), false);
+        Buffer::from_reader(loc.unwrap()).unwrap()
+    };
+    let r = PlainRender(ColorScheme::default().unwrap());
+
+    let mut v = new_nowrap("test-render-list", Coord::new(1, 1, 3, 20));
+    v.list = true;
+    v.shift_cursor(&buf).unwrap();
+    let mut screen = Screen::new(20, 3);
+    let cursor = v.render(&buf, &r, &mut vec![], &mut screen).unwrap();
+    // cursor column is computed from buffer, not from display glyphs.
+    assert_eq!(cursor, Cursor::new(4, 0));
+    assert_eq!(
+        screen.to_lines(),
+        vec![
+            "  1 a\u{25b8}      b\u{b7}\u{b7}\u{b6}    ",
+            "  2 xy\u{b6}             ",
+            "~                   ",
+        ]
+    );
+
+    let buf = {
+        let txt = "abcdefgh \t\nxy";
+        let loc = Location::new_ted("test-render-list", txt.as_bytes(), false);
+        Buffer::from_reader(loc.unwrap()).unwrap()
+    };
+    let mut v = Wrap {
+        name: "test-render-list".to_string(),
+        coord: Coord::new(1, 1, 4, 10),
+        cursor: Wrap::initial_cursor(true),
+        obc_xy: buffer::Cursor::default(),
+        scroll_off: 0,
+        line_number: true,
+        relative_number: false,
+        highlight_trailing: true,
+        list: true,
+        cursor_line: false,
+        edit_lines: Vec::default(),
+    };
+    v.shift_cursor(&buf).unwrap();
+    let mut screen = Screen::new(10, 4);
+    v.render(&buf, &r, &mut vec![], &mut screen).unwrap();
+    assert_eq!(
+        screen.to_lines(),
+        vec![
+            "  1 abcdef",
+            "    gh\u{b7}\u{25b8}\u{b6} ",
+            "  2 xy\u{b6}   ",
+            "~         "
+        ]
+    );
+}
+
+#[test]
+fn test_render_folds() {
+    let mut buf = {
+        let txt = "one\n\ttwo\nthree\nfour\nfive";
+        let loc = Location::new_ted("test-render-folds", txt.as_bytes(), false);
+        Buffer::from_reader(loc.unwrap()).unwrap()
+    };
+    buf.add_fold(1, 2);
+    let r = PlainRender(ColorScheme::default().unwrap());
+    let lines = vec![
+        "  1 one               ",
+        "  2 +--  2 lines: two ",
+        "  4 four              ",
+        "  5 five              ",
+    ];
+
+    // closed fold is rendered as a single line, below the cursor.
+    buf.set_cursor(15);
+    let mut v = new_nowrap("test-render-folds", Coord::new(1, 1, 4, 22));
+    v.shift_cursor(&buf).unwrap();
+    let mut screen = Screen::new(22, 4);
+    let cursor = v.render(&buf, &r, &mut vec![], &mut screen).unwrap();
+    assert_eq!(cursor, Cursor::new(4, 2));
+    assert_eq!(screen.to_lines(), lines);
+
+    let mut v = Wrap {
+        name: "test-render-folds".to_string(),
+        coord: Coord::new(1, 1, 4, 22),
+        cursor: Wrap::initial_cursor(true),
+        obc_xy: buffer::Cursor::default(),
+        scroll_off: 0,
+        line_number: true,
+        relative_number: false,
+        highlight_trailing: false,
+        list: false,
+        cursor_line: false,
+        edit_lines: Vec::default(),
+    };
+    v.shift_cursor(&buf).unwrap();
+    let mut screen = Screen::new(22, 4);
+    let cursor = v.render(&buf, &r, &mut vec![], &mut screen).unwrap();
+    assert_eq!(cursor, Cursor::new(4, 2));
+    assert_eq!(screen.to_lines(), lines);
+}
+
+#[test]
+fn test_render_cursor_line() {
+    let mut buf = {
+        let loc = Location::new_ted("test-cursor-line", "hello\nworld".as_bytes(), false);
+        Buffer::from_reader(loc.unwrap()).unwrap()
+    };
+    let r = PlainRender(ColorScheme::default().unwrap());
+    let coord = Coord::new(1, 1, 3, 12);
+
+    let render = |buf: &Buffer, cursor_line: bool| -> Vec<Spanline> {
+        let mut v = new_nowrap("test-cursor-line", coord);
+        v.cursor_line = cursor_line;
+        v.shift_cursor(buf).unwrap();
+        let mut lines = vec![];
+        v.render(buf, &r, &mut lines, &mut Screen::new(12, 3))
+            .unwrap();
+        lines
+    };
+
+    let plain = render(&buf, false);
+    let lines = render(&buf, true);
+    assert!(lines[0] != plain[0]);
+    assert!(lines[1] == plain[1] && lines[2] == plain[2]);
+
+    // highlight follows the cursor, previous line is rendered plain.
+    buf.set_cursor(6);
+    let lines = render(&buf, true);
+    assert!(lines[0] == plain[0] && lines[2] == plain[2]);
+    assert!(lines[1] != plain[1]);
+}
+
+#[test]
+fn test_render_relative_number() {
+    let text: String = (1..=1005).map(|_| "ab\n").collect();
+    let mut buf = {
+        let loc = Location::new_ted("test-render-relative", text.as_bytes(), false);
+        Buffer::from_reader(loc.unwrap()).unwrap()
+    };
+    let r = PlainRender(ColorScheme::default().unwrap());
+    let coord = Coord::new(1, 1, 4, 10);
+    // cursor on 'b' in line 999, with lines 1000 and 1001 on screen.
+    buf.set_cursor(buf.line_to_char(998) + 1);
+    let obc_xy = buffer::Cursor { row: 998, col: 0 };
+
+    let mut v = new_nowrap("test-render-relative", coord);
+    v.relative_number = true;
+    v.obc_xy = obc_xy;
+    v.cursor = Cursor::new(4, 1);
+    let cursor = v.shift_cursor(&buf).unwrap();
+
+    let mut screen = Screen::new(10, 4);
+    v.render(&buf, &r, &mut vec![], &mut screen).unwrap();
+    let lines = screen.to_lines();
+    assert_eq!(
+        lines,
+        vec!["  1 ab    ", "999 ab    ", "  1 ab    ", "  2 ab    "]
+    );
+    assert_eq!(
+        lines[cursor.row as usize].chars().nth(cursor.col as usize),
+        Some('b')
+    );
+
+    let mut v = Wrap {
+        name: "test-render-relative".to_string(),
+        coord,
+        cursor: Cursor::new(4, 1),
+        obc_xy,
+        scroll_off: 0,
+        line_number: true,
+        relative_number: true,
+        highlight_trailing: false,
+        list: false,
+        cursor_line: false,
+        edit_lines: Vec::default(),
+    };
+    let cursor = v.shift_cursor(&buf).unwrap();
+
+    let mut screen = Screen::new(10, 4);
+    v.render(&buf, &r, &mut vec![], &mut screen).unwrap();
+    let lines = screen.to_lines();
+    assert_eq!(
+        lines,
+        vec!["  1 ab    ", "999 ab    ", "  1 ab    ", "  2 ab    "]
+    );
+    assert_eq!(
+        lines[cursor.row as usize].chars().nth(cursor.col as usize),
+        Some('b')
+    );
+}
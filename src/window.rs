@@ -6,6 +6,7 @@ pub use crate::window_less::WindowLess;
 pub use crate::window_prompt::WindowPrompt;
 pub use crate::window_status::WindowStatus;
 pub use crate::window_suggt::WindowSuggest;
+pub use crate::window_tabs::WindowTabs;
 
 use crate::{
     buffer::{self, Buffer},
@@ -33,14 +34,37 @@ pub trait Window {
 
     fn to_coord(&self) -> Coord;
 
+    /// Return the cursor position on the screen, starts from (0, 0).
     fn to_cursor(&self) -> Option<Cursor>;
 
+    /// Return the cursor position within window's viewport, starts from
+    /// (0, 0).
+    fn to_view_cursor(&self) -> Option<Cursor> {
+        let (col, row) = self.to_coord().to_origin_cursor();
+        let cursor = self.to_cursor()?;
+        Some(cursor!(
+            cursor.col.saturating_sub(col),
+            cursor.row.saturating_sub(row)
+        ))
+    }
+
     fn config_wrap(&self) -> bool;
 
     fn config_line_number(&self) -> bool;
 
+    fn config_relative_number(&self) -> bool;
+
+    fn config_highlight_trailing(&self) -> bool;
+
+    fn config_list(&self) -> bool;
+
+    fn config_cursor_line(&self) -> bool;
+
     fn config_scroll_offset(&self) -> u16;
 
+    /// Number of columns that a tab character expands to, when rendered.
+    fn config_tab_stop(&self) -> usize;
+
     fn on_event(&mut self, app: &mut Self::App, evnt: Event) -> Result<Event>;
 
     fn on_refresh(&mut self, app: &mut Self::App) -> Result<()>;
@@ -69,6 +93,17 @@ pub trait WinBuffer {
         dp: DP,
     ) -> Result<Box<dyn Iterator<Item = String> + 'a>>;
 
+    /// Return an iterator over lines in `[a, z)`, specified as line-index.
+    /// `dp` can either be [DP::Right] or [DP::Left], in the forward
+    /// direction iteration start from `a`, in reverse direction iteration
+    /// start from `z-1`. Out of bound range is clipped to the buffer.
+    fn lines_between<'a>(
+        &'a self,
+        a: usize,
+        z: usize,
+        dp: DP,
+    ) -> Result<Box<dyn Iterator<Item = String> + 'a>>;
+
     /// Return an iterator starting from char_idx. `dp` can either be
     /// [DP::Right] or [DP::Left] for either forward iteration or reverse
     /// iteration. In the forward direction, iteration will start from
@@ -102,6 +137,11 @@ pub trait WinBuffer {
 
     /// Return the number of characters in line `line_idx`, starts from ZERO.
     fn len_line(&self, line_idx: usize) -> usize;
+
+    /// Return the outermost closed fold containing `line_idx`, as
+    /// (first-line, last-line) specified in line-index. Lines within a
+    /// closed fold are rendered as a single line.
+    fn to_closed_fold(&self, line_idx: usize) -> Option<(usize, usize)>;
 }
 
 /// Render trait for window objects.
@@ -198,6 +238,50 @@ impl Coord {
     pub fn to_size(&self) -> (u16, u16) {
         (self.hgt, self.wth)
     }
+
+    /// Split the viewport horizontally, return the top half and the
+    /// bottom half.
+    pub fn split_h(&self) -> (Coord, Coord) {
+        let hgt = self.hgt / 2;
+        let top = Coord::new(self.col, self.row, hgt, self.wth);
+        let bottom = Coord::new(self.col, self.row + hgt, self.hgt - hgt, self.wth);
+        (top, bottom)
+    }
+
+    /// Split the viewport vertically, return the left half and the
+    /// right half.
+    pub fn split_v(&self) -> (Coord, Coord) {
+        let wth = self.wth / 2;
+        let left = Coord::new(self.col, self.row, self.hgt, wth);
+        let right = Coord::new(self.col + wth, self.row, self.hgt, self.wth - wth);
+        (left, right)
+    }
+
+    /// Viewport is a tile within `old` viewport, return the corresponding
+    /// tile within `new` viewport. Edges are scaled proportionally, so
+    /// that adjacent tiles continue to share their edges.
+    pub fn rescale(&self, old: Coord, new: Coord) -> Coord {
+        let scale = |off: u16, o: u16, n: u16| -> u16 {
+            match o {
+                0 => 0,
+                o => (((off as u32) * (n as u32)) / (o as u32)) as u16,
+            }
+        };
+
+        let top = self.row.saturating_sub(old.row);
+        let left = self.col.saturating_sub(old.col);
+        let (bottom, right) = (top + self.hgt, left + self.wth);
+
+        let (top, bottom) = (
+            scale(top, old.hgt, new.hgt),
+            scale(bottom, old.hgt, new.hgt),
+        );
+        let (left, right) = (
+            scale(left, old.wth, new.wth),
+            scale(right, old.wth, new.wth),
+        );
+        Coord::new(new.col + left, new.row + top, bottom - top, right - left)
+    }
 }
 
 // Cursor within the Window object, starts from (0, 0)
@@ -483,3 +567,7 @@ impl Jump {
         }
     }
 }
+
+#[cfg(test)]
+#[path = "window_test.rs"]
+mod window_test;
@@ -5,13 +5,15 @@ use std::{convert::TryInto, fmt, mem, result};
 
 use crate::{
     buffer::Buffer,
-    colors::ColorScheme,
+    colors::{ColorScheme, Highlight},
     event::Event,
     keymap::Keymap,
     location::Location,
-    syntax, view,
+    syntax,
+    term::Span,
+    text, view,
     window::{Coord, Cursor},
-    Result,
+    Error, Result,
 };
 
 #[derive(Clone)]
@@ -69,6 +71,13 @@ impl WindowLess {
         self.wrap = wrap;
         self
     }
+
+    /// Move this window to `coord`, say when the terminal is resized.
+    pub fn set_coord(&mut self, coord: Coord) -> &mut Self {
+        self.coord = coord;
+        self.old_screen = None;
+        self
+    }
 }
 
 impl WindowLess {
@@ -89,7 +98,7 @@ impl WindowLess {
 
     pub fn on_event(&mut self, evnt: Event) -> Result<Event> {
         match evnt {
-            evnt @ Event::Esc => Ok(evnt),
+            Event::Esc | Event::Enter(_) => Ok(Event::Esc),
             evnt => {
                 let mut km = mem::replace(&mut self.keymap, Keymap::default());
                 let evnt = km.fold(&self.buffer, evnt)?;
@@ -101,23 +110,24 @@ impl WindowLess {
     }
 
     pub fn on_refresh(&mut self) -> Result<()> {
-        //use crate::Error;
-        //use crossterm::queue;
-        //use std::io::{self, Write};
-
-        //let (col, row_iter) = {
-        //    let (col, _) = self.coord.to_origin_cursor();
-        //    let start = self.coord.hgt.saturating_sub(
-        //        self.span_lines.len() as u16
-        //    );
-        //    (col, start..self.coord.hgt)
-        //};
-        //for (row, line) in row_iter.zip(self.span_lines.iter_mut()) {
-        //    line.set_cursor(Cursor { col, row });
-        //    err_at!(Fatal, termqu!(line))?;
-        //}
-        //let span: Span = self.buffer.to_string().into();
-        //err_at!(Fatal, termqu!(span))?;
+        use std::iter::FromIterator;
+
+        let canvas = self.scheme.to_style(Highlight::Canvas);
+        let (col, row) = self.coord.to_origin_cursor();
+        let wth = self.coord.wth as usize;
+
+        let content = self.buffer.to_string();
+        let mut lines = content.lines();
+        for row in row..(row + self.coord.hgt) {
+            let line = {
+                let iter = text::take_width(lines.next().unwrap_or("").chars(), wth);
+                String::from_iter(iter)
+            };
+            let padding = wth.saturating_sub(text::width(line.chars()));
+            let mut span: Span = format!("{}{}", line, " ".repeat(padding)).into();
+            span.set_cursor(Cursor { col, row });
+            err_at!(Fatal, termqu!(span.using(canvas.clone())))?;
+        }
         Ok(())
     }
 }
@@ -71,6 +71,12 @@ impl WindowPrompt {
 }
 
 impl WindowPrompt {
+    /// Move this window to `coord`, say when the terminal is resized.
+    pub fn set_coord(&mut self, coord: Coord) -> &mut Self {
+        self.coord = coord;
+        self
+    }
+
     pub fn set_options(&mut self, options: Vec<Regex>) {
         self.options.extend(options.into_iter());
     }
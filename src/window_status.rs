@@ -4,15 +4,21 @@ use log::trace;
 use std::{fmt, iter::FromIterator, result};
 
 use crate::{
+    buffer::Buffer,
+    colors::{ColorScheme, Highlight},
     event::Event,
     term::{Span, Spanline},
-    window::{Coord, Cursor},
+    text,
+    window::{Coord, Cursor, WinBuffer},
     Error, Result,
 };
 
 pub struct WindowStatus {
     coord: Coord,
+    scheme: ColorScheme,
     spans: Vec<Span>,
+    // (left, right) status for the buffer under edit.
+    status: Option<(String, String)>,
 }
 
 impl fmt::Display for WindowStatus {
@@ -22,12 +28,21 @@ impl fmt::Display for WindowStatus {
 }
 
 impl WindowStatus {
-    pub fn new(coord: Coord) -> Self {
+    pub fn new(coord: Coord, scheme: ColorScheme) -> Self {
         WindowStatus {
             coord,
+            scheme,
             spans: Vec::default(),
+            status: None,
         }
     }
+
+    /// Show status of `buf`, the buffer under edit, on next refresh.
+    /// `viewport` is the first and last line-index displayed for `buf`.
+    pub fn set_buffer(&mut self, buf: &Buffer, viewport: Option<(usize, usize)>) -> &mut Self {
+        self.status = Some(to_buffer_status(buf, viewport));
+        self
+    }
 }
 
 impl WindowStatus {
@@ -52,6 +67,20 @@ impl WindowStatus {
 
     pub fn on_refresh(&mut self) -> Result<()> {
         let mut line = Spanline::from_iter(self.spans.drain(..));
+        if let Some((left, right)) = self.status.take() {
+            let style = self.scheme.to_style(Highlight::StatusLine);
+            let wth = (self.coord.wth as usize).saturating_sub(line.to_width());
+            let right_wth = text::width(right.chars());
+            let left: String = {
+                let wth = wth.saturating_sub(right_wth + 1);
+                text::take_width(left.chars(), wth).collect()
+            };
+            let padding = wth.saturating_sub(text::width(left.chars()) + right_wth);
+
+            line.add_span(Span::from(left).using(style.clone()));
+            line.add_span(" ".repeat(padding).into());
+            line.add_span(Span::from(right).using(style));
+        }
         let padding = self.coord.wth.saturating_sub(line.to_width() as u16);
 
         line.set_cursor(self.coord.to_origin_cursor().into())
@@ -61,3 +90,34 @@ impl WindowStatus {
         Ok(())
     }
 }
+
+/// Return status for `buf` as (left, right) text. Left part is the buffer
+/// id, `[+]` if modified, and its mode. Right part is the total number of
+/// lines and a ruler, `line:col` followed by position of the `viewport`
+/// within the buffer, like vim's `Top`, `Bot`, `All` or `NN%`. Column is
+/// counted in display columns.
+pub fn to_buffer_status(buf: &Buffer, viewport: Option<(usize, usize)>) -> (String, String) {
+    let modified = if_else!(buf.is_modified(), " [+]", "");
+    let left = format!("{}{} {}", buf.to_id(), modified, buf.to_mode());
+
+    let xy = buf.to_xy_cursor(None);
+    let vcol = text::to_vcol(&buf.line(xy.row), xy.col, buf.tab_stop);
+    let last = buf.to_last_line_idx();
+    let pos = match viewport {
+        Some((0, bottom)) if bottom >= last => "All".to_string(),
+        Some((0, _)) => "Top".to_string(),
+        Some((_, bottom)) if bottom >= last => "Bot".to_string(),
+        Some((top, bottom)) => {
+            let (above, below) = (top, last - bottom);
+            format!("{}%", (above * 100) / (above + below))
+        }
+        None => format!("{}%", ((xy.row + 1) * 100) / (last + 1)),
+    };
+    let right = format!("{}L {}:{} {}", last + 1, xy.row + 1, vcol + 1, pos);
+
+    (left, right)
+}
+
+#[cfg(test)]
+#[path = "window_status_test.rs"]
+mod window_status_test;
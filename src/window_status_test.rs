@@ -0,0 +1,45 @@
+use super::*;
+
+use crate::location::Location;
+
+#[test]
+fn test_buffer_status() {
+    let mut buf = {
+        let txt: String = (1..=10).map(|i| format!("line{}\n", i)).collect();
+        let loc = Location::new_ted("test-status", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+    let id = buf.to_id();
+
+    let (left, right) = to_buffer_status(&buf, None);
+    assert_eq!(left, format!("{} normal", id));
+    assert_eq!(right, "10L 1:1 10%");
+
+    buf.set_cursor(buf.line_to_char(4) + 3);
+    assert_eq!(to_buffer_status(&buf, None).1, "10L 5:4 50%");
+
+    buf.insert_str(0, "x").unwrap();
+    buf.set_insert_mode();
+    assert_eq!(to_buffer_status(&buf, None).0, format!("{} [+] insert", id));
+}
+
+#[test]
+fn test_buffer_ruler() {
+    let mut buf = {
+        let txt: String = (1..=20).map(|i| format!("\tlïne{}\n", i)).collect();
+        let loc = Location::new_ted("test-ruler", txt.as_bytes(), false).unwrap();
+        Buffer::from_reader(loc).unwrap()
+    };
+    buf.set_tab_stop(8);
+
+    let ruler = |buf: &Buffer, viewport| to_buffer_status(buf, Some(viewport)).1;
+    assert_eq!(ruler(&buf, (0, 19)), "20L 1:1 All");
+    assert_eq!(ruler(&buf, (0, 9)), "20L 1:1 Top");
+    assert_eq!(ruler(&buf, (10, 19)), "20L 1:1 Bot");
+    assert_eq!(ruler(&buf, (5, 14)), "20L 1:1 50%");
+    assert_eq!(ruler(&buf, (2, 11)), "20L 1:1 20%");
+
+    // display columns, tab expanded.
+    buf.set_cursor(buf.line_to_char(1) + 4);
+    assert_eq!(ruler(&buf, (0, 9)), "20L 2:12 Top");
+}
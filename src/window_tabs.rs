@@ -0,0 +1,145 @@
+#[allow(unused_imports)]
+use log::trace;
+
+use std::{cmp, fmt, iter::FromIterator, result};
+
+use crate::{
+    buffer::Buffer,
+    colors::ColorScheme,
+    state::TabTitle,
+    term::Spanline,
+    text,
+    window::{Coord, Cursor},
+    Error, Result,
+};
+
+/// Tab line, list buffers opened by the application, one tab for each
+/// buffer, on the top row of the edit area.
+pub struct WindowTabs {
+    coord: Coord,
+    scheme: ColorScheme,
+    // (buffer-id, title) for each tab, ordered by buffer number.
+    tabs: Vec<(String, TabTitle)>,
+}
+
+impl fmt::Display for WindowTabs {
+    fn fmt(&self, f: &mut fmt::Formatter) -> result::Result<(), fmt::Error> {
+        write!(f, "WindowTabs<{},{}>", self.coord, self.tabs.len())
+    }
+}
+
+impl WindowTabs {
+    pub fn new(coord: Coord, scheme: ColorScheme) -> Self {
+        WindowTabs {
+            coord,
+            scheme,
+            tabs: Vec::default(),
+        }
+    }
+
+    /// List `bufs` as tabs on next refresh, `active` is the buffer-id under
+    /// edit.
+    pub fn set_buffers(&mut self, bufs: &[Buffer], active: &str) -> &mut Self {
+        let wth = match bufs.len() {
+            0 => 0,
+            n => cmp::min((self.coord.wth as usize) / n, 16),
+        };
+        self.tabs = to_tab_titles(bufs, active, wth);
+        self
+    }
+
+    /// Return the buffer-id of the tab at screen position (`col`, `row`),
+    /// position starts from (0, 0).
+    pub fn to_buffer_at(&self, col: u16, row: u16) -> Option<String> {
+        let (x, y) = self.coord.to_origin_cursor();
+        if row != y || col < x || col >= (x + self.coord.wth) {
+            return None;
+        }
+
+        let mut off = (col - x) as usize;
+        for (id, tab) in self.tabs.iter() {
+            let wth = text::width(tab.text.chars());
+            if off < wth {
+                return Some(id.clone());
+            }
+            off -= wth;
+        }
+        None
+    }
+}
+
+impl WindowTabs {
+    #[inline]
+    pub fn to_name(&self) -> String {
+        "window-tabs".to_string()
+    }
+
+    #[inline]
+    pub fn to_coord(&self) -> Coord {
+        self.coord
+    }
+
+    #[inline]
+    pub fn to_cursor(&self) -> Option<Cursor> {
+        None
+    }
+
+    pub fn on_refresh(&mut self) -> Result<()> {
+        let mut line = {
+            let wth = self.coord.wth as usize;
+            let iter = self.tabs.iter().scan(0, |acc, (_, tab)| {
+                let text: String =
+                    text::take_width(tab.text.chars(), wth.saturating_sub(*acc)).collect();
+                *acc += text::width(text.chars());
+                let tab = TabTitle {
+                    text,
+                    modified: tab.modified,
+                    active: tab.active,
+                };
+                Some(tab.into_span(&self.scheme))
+            });
+            Spanline::from_iter(iter)
+        };
+        let padding = self.coord.wth.saturating_sub(line.to_width() as u16);
+
+        line.set_cursor(self.coord.to_origin_cursor().into())
+            .right_padding(padding);
+
+        err_at!(Fatal, termqu!(line))?;
+        Ok(())
+    }
+}
+
+/// Return a tab title for each buffer in `bufs`, ordered by buffer number,
+/// along with its buffer-id. Buffer name is shrunk to `wth`, and suffixed
+/// with `[+]` if modified and `[RO]` if read-only. Tab for `active`
+/// buffer-id is marked active.
+pub fn to_tab_titles(bufs: &[Buffer], active: &str, wth: usize) -> Vec<(String, TabTitle)> {
+    let mut bufs: Vec<&Buffer> = bufs.iter().collect();
+    bufs.sort_by_key(|b| b.to_num());
+
+    bufs.into_iter()
+        .map(|buf| {
+            let id = buf.to_id();
+            let name = match buf.to_location().to_title(wth) {
+                Ok(name) => name.trim().to_string(),
+                Err(_) => {
+                    let iter = text::take_width(id.chars().rev(), wth);
+                    String::from_iter(iter.rev())
+                }
+            };
+            let modified = if_else!(buf.is_modified(), " [+]", "");
+            let read_only = if_else!(buf.is_read_only(), " [RO]", "");
+            let tab = TabTitle {
+                text: format!(" {}{}{} ", name, modified, read_only),
+                modified: buf.is_modified(),
+                active: id == active,
+            };
+            (id, tab)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[path = "window_tabs_test.rs"]
+mod window_tabs_test;
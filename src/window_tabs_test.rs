@@ -0,0 +1,50 @@
+use super::*;
+
+use crate::location::Location;
+
+fn new_buffer(name: &str, read_only: bool) -> Buffer {
+    let loc = Location::new_ted(name, "hello world\n".as_bytes(), read_only).unwrap();
+    Buffer::from_reader(loc).unwrap()
+}
+
+#[test]
+fn test_tab_titles() {
+    let one = new_buffer("test-tabs-one", true);
+    let mut two = new_buffer("test-tabs-two", false);
+    two.insert_str(0, "x").unwrap();
+    let (id_one, id_two) = (one.to_id(), two.to_id());
+
+    // buffers are in the order of use, most recent last, tabs by buffer number.
+    let bufs = vec![one, two];
+    let tabs = to_tab_titles(&bufs, &id_two, 16);
+    assert_eq!(tabs.len(), 2);
+
+    assert_eq!(tabs[0].0, id_one);
+    assert_eq!(tabs[0].1.text, " T([test-tabs-one]) [RO] ");
+    assert!(!tabs[0].1.modified && !tabs[0].1.active);
+
+    assert_eq!(tabs[1].0, id_two);
+    assert_eq!(tabs[1].1.text, " T([test-tabs-two]) [+] ");
+    assert!(tabs[1].1.modified && tabs[1].1.active);
+}
+
+#[test]
+fn test_tab_click() {
+    let bufs = vec![
+        new_buffer("test-tabs-one", false),
+        new_buffer("test-tabs-two", false),
+    ];
+    let (id_one, id_two) = (bufs[0].to_id(), bufs[1].to_id());
+
+    let scheme = ColorScheme::default().unwrap();
+    let mut wtabs = WindowTabs::new(Coord::new(1, 1, 1, 80), scheme);
+    wtabs.set_buffers(&bufs, &id_one);
+
+    // each tab is 20 columns wide.
+    assert_eq!(wtabs.to_buffer_at(0, 0), Some(id_one.clone()));
+    assert_eq!(wtabs.to_buffer_at(19, 0), Some(id_one));
+    assert_eq!(wtabs.to_buffer_at(20, 0), Some(id_two.clone()));
+    assert_eq!(wtabs.to_buffer_at(39, 0), Some(id_two));
+    assert_eq!(wtabs.to_buffer_at(40, 0), None);
+    assert_eq!(wtabs.to_buffer_at(1, 1), None);
+}
@@ -0,0 +1,43 @@
+use super::*;
+
+#[test]
+fn test_coord_split() {
+    let coord = Coord::new(1, 1, 25, 81);
+
+    let (top, bottom) = coord.split_h();
+    assert_eq!(top, Coord::new(1, 1, 12, 81));
+    assert_eq!(bottom, Coord::new(1, 13, 13, 81));
+
+    let (left, right) = coord.split_v();
+    assert_eq!(left, Coord::new(1, 1, 25, 40));
+    assert_eq!(right, Coord::new(41, 1, 25, 41));
+
+    let (left, right) = bottom.split_v();
+    assert_eq!(left, Coord::new(1, 13, 13, 40));
+    assert_eq!(right, Coord::new(41, 13, 13, 41));
+}
+
+#[test]
+fn test_coord_rescale() {
+    let old = Coord::new(1, 1, 24, 80);
+    let new = Coord::new(1, 1, 30, 100);
+
+    let (left, right) = old.split_v();
+    assert_eq!(left.rescale(old, new), Coord::new(1, 1, 30, 50));
+    assert_eq!(right.rescale(old, new), Coord::new(51, 1, 30, 50));
+
+    let (top, bottom) = old.split_h();
+    assert_eq!(top.rescale(old, new), Coord::new(1, 1, 15, 100));
+    assert_eq!(bottom.rescale(old, new), Coord::new(1, 16, 15, 100));
+
+    // shrinking, adjacent tiles continue to share their edges.
+    let new = Coord::new(1, 1, 17, 33);
+    let (a, b) = (left.rescale(old, new), right.rescale(old, new));
+    assert_eq!(a.col + a.wth, b.col);
+    assert_eq!(a.wth + b.wth, new.wth);
+    let (a, b) = (top.rescale(old, new), bottom.rescale(old, new));
+    assert_eq!(a.row + a.hgt, b.row);
+    assert_eq!(a.hgt + b.hgt, new.hgt);
+
+    assert_eq!(old.rescale(old, new), new);
+}